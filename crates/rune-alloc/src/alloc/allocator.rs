@@ -3,6 +3,7 @@
 use core::alloc::Layout;
 use core::fmt;
 
+use crate::limit;
 use crate::ptr::{self, invalid_mut, NonNull};
 
 use ::rust_alloc::alloc::{alloc, alloc_zeroed, dealloc};
@@ -11,15 +12,51 @@ use ::rust_alloc::alloc::{alloc, alloc_zeroed, dealloc};
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub struct AllocError {
     pub(crate) layout: Layout,
+    pub(crate) limited: bool,
+}
+
+impl AllocError {
+    #[inline]
+    pub(crate) const fn new(layout: Layout) -> Self {
+        Self {
+            layout,
+            limited: false,
+        }
+    }
+
+    #[inline]
+    pub(crate) const fn limited(layout: Layout) -> Self {
+        Self {
+            layout,
+            limited: true,
+        }
+    }
+
+    /// Test if the allocation failed because it would have exceeded the
+    /// memory limit set through [`limit::with`].
+    ///
+    /// [`limit::with`]: crate::limit::with
+    #[inline]
+    pub fn is_limit_exceeded(&self) -> bool {
+        self.limited
+    }
 }
 
 impl fmt::Display for AllocError {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        write!(
-            f,
-            "Failed to allocate {} bytes of memory",
-            self.layout.size()
-        )
+        if self.limited {
+            write!(
+                f,
+                "Memory limit exceeded while allocating {} bytes of memory",
+                self.layout.size()
+            )
+        } else {
+            write!(
+                f,
+                "Failed to allocate {} bytes of memory",
+                self.layout.size()
+            )
+        }
     }
 }
 
@@ -346,6 +383,10 @@ impl Global {
             0 => Ok(NonNull::slice_from_raw_parts(dangling(&layout), 0)),
             // SAFETY: `layout` is non-zero in size,
             size => unsafe {
+                if !limit::take(size) {
+                    return Err(AllocError::limited(layout));
+                }
+
                 let raw_ptr = if zeroed {
                     alloc_zeroed(layout)
                 } else {
@@ -353,7 +394,8 @@ impl Global {
                 };
 
                 let Some(ptr) = NonNull::new(raw_ptr) else {
-                    return Err(AllocError { layout });
+                    limit::release(size);
+                    return Err(AllocError::new(layout));
                 };
 
                 Ok(NonNull::slice_from_raw_parts(ptr, size))
@@ -378,6 +420,7 @@ unsafe impl Allocator for Global {
             // SAFETY: `layout` is non-zero in size,
             // other conditions must be upheld by the caller
             unsafe { dealloc(ptr.as_ptr(), layout) }
+            limit::release(layout.size());
         }
     }
 }
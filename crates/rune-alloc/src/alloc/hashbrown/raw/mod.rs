@@ -3160,7 +3160,7 @@ impl<T: TryClone, A: Allocator + Clone> RawTable<T, A> {
         // cloned so far.
         let mut guard = guard((0, &mut *self), |(index, self_)| {
             if T::NEEDS_DROP {
-                for i in 0..=*index {
+                for i in 0..*index {
                     if self_.is_bucket_full(i) {
                         self_.bucket(i).drop();
                     }
@@ -3174,7 +3174,7 @@ impl<T: TryClone, A: Allocator + Clone> RawTable<T, A> {
             to.write(from.as_ref().try_clone()?);
 
             // Update the index in case we need to unwind.
-            guard.0 = index;
+            guard.0 = index + 1;
         }
 
         // Successfully cloned all items, no need to clean up.
@@ -4309,4 +4309,47 @@ mod test_map {
         // All allocator clones should already be dropped.
         assert_eq!(dropped.load(Ordering::SeqCst), 1);
     }
+
+    /// CHECKING THAT AN ERROR RAISED WHEN CLONING THE FIRST ELEMENT DOESN'T
+    /// DROP THE UNINITIALIZED BUCKET IT WAS SUPPOSED TO BE WRITTEN TO.
+    #[test]
+    fn test_error_clone_first_element() {
+        use core::sync::atomic::{AtomicUsize, Ordering};
+
+        static DROPS: AtomicUsize = AtomicUsize::new(0);
+
+        struct FailingClone;
+
+        impl TryClone for FailingClone {
+            fn try_clone(&self) -> Result<Self, Error> {
+                Err(Error::CapacityOverflow)
+            }
+        }
+
+        impl Drop for FailingClone {
+            fn drop(&mut self) {
+                DROPS.fetch_add(1, Ordering::SeqCst);
+            }
+        }
+
+        let mut table = RawTable::new();
+
+        for idx in 0..3u64 {
+            table
+                .insert(
+                    &mut (),
+                    idx,
+                    (idx, FailingClone),
+                    |_: &mut (), (k, _): &(u64, _)| Ok::<_, Infallible>(*k),
+                )
+                .abort();
+        }
+
+        assert!(unsafe { table.is_bucket_full(0) });
+        assert!(table.try_clone().is_err());
+        assert_eq!(DROPS.load(Ordering::SeqCst), 0);
+
+        drop(table);
+        assert_eq!(DROPS.load(Ordering::SeqCst), 3);
+    }
 }
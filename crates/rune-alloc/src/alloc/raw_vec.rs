@@ -4,7 +4,7 @@ use core::mem::{self, ManuallyDrop, MaybeUninit};
 use core::slice;
 
 use crate::alloc::boxed::Box;
-use crate::alloc::{AllocError, Allocator, Error, Global, SizedTypeProperties};
+use crate::alloc::{Allocator, Error, Global, SizedTypeProperties};
 use crate::ptr::Unique;
 use crate::ptr::{self, NonNull};
 
@@ -369,9 +369,7 @@ impl<T, A: Allocator> RawVec<T, A> {
                 // overflowed earlier when capacity was larger.
                 let new_size = mem::size_of::<T>().wrapping_mul(cap);
                 let new_layout = Layout::from_size_align_unchecked(new_size, layout.align());
-                self.alloc
-                    .shrink(ptr, layout, new_layout)
-                    .map_err(|_| AllocError { layout: new_layout })?
+                self.alloc.shrink(ptr, layout, new_layout)?
             };
             self.set_ptr_and_cap(ptr, cap);
         }
@@ -408,7 +406,7 @@ where
         alloc.allocate(new_layout)
     };
 
-    memory.map_err(|_| AllocError { layout: new_layout }.into())
+    memory.map_err(Error::from)
 }
 
 #[cfg(not(rune_nightly))]
@@ -1,6 +1,16 @@
 //! Raw extension utilities of std for Rune.
 //!
 //! Note that there is lots of unsafety in here. Use with caution.
+//!
+//! ## Features
+//!
+//! * `std` - Use the standard library, which stores memory limits in
+//!   thread-local storage.
+//! * `alloc` - Use the `alloc` crate. This is currently required.
+//! * `extern-memory-limit` - In no-std environments, store memory limits
+//!   through functions which must be provided by the implementor. Without it
+//!   [limits][limit] have no effect unless `std` is enabled. See [limit] for
+//!   details.
 
 // Quite a few parts copied from the Rust Project under the MIT license.
 //
@@ -81,6 +91,8 @@ pub mod iter;
 pub use self::fmt::TryWrite;
 pub mod fmt;
 
+pub mod limit;
+
 pub(crate) mod hint;
 pub(crate) mod ptr;
 pub(crate) mod slice;
@@ -0,0 +1,254 @@
+//! Memory limits for Rune.
+//!
+//! This module contains methods which allows for limiting the memory use of
+//! the virtual machine to abide by the specified budget.
+//!
+//! By default memory limits are disabled, but can be enabled by wrapping your
+//! function call or future in [with].
+//!
+//! Only allocations which goes through the [Global] allocator are accounted
+//! for. Allocations which exceed the limit are reported as an [AllocError]
+//! for which [AllocError::is_limit_exceeded] returns `true`.
+//!
+//! Memory which is freed while a limit is in effect is returned to it, but
+//! never past the limit that was configured. So freeing memory which was
+//! allocated before the limit was set doesn't raise it.
+//!
+//! In no-std environments, the limit is only tracked if the
+//! `extern-memory-limit` feature is enabled, in which case the implementor
+//! must provide the following functions which store it:
+//!
+//! ```text
+//! extern "C" fn __rune_alloc_memory_get() -> usize;
+//! extern "C" fn __rune_alloc_memory_replace(value: usize) -> usize;
+//! extern "C" fn __rune_alloc_memory_limit_get() -> usize;
+//! extern "C" fn __rune_alloc_memory_limit_replace(value: usize) -> usize;
+//! ```
+//!
+//! Without it, limits have no effect.
+//!
+//! [Global]: crate::Global
+//! [AllocError]: crate::AllocError
+//! [AllocError::is_limit_exceeded]: crate::AllocError::is_limit_exceeded
+//!
+//! # Examples
+//!
+//! ```
+//! use rune_alloc::{limit, Vec};
+//!
+//! let f = limit::with(1024, || {
+//!     let mut vec = Vec::<u32>::try_with_capacity(128)?;
+//!     assert!(vec.try_reserve(1024).is_err());
+//!     Ok::<_, rune_alloc::Error>(())
+//! });
+//!
+//! f.call()?;
+//! # Ok::<_, rune_alloc::Error>(())
+//! ```
+
+#[cfg_attr(feature = "std", path = "limit/std.rs")]
+#[cfg_attr(
+    all(not(feature = "std"), not(feature = "extern-memory-limit")),
+    path = "limit/disabled.rs"
+)]
+mod no_std;
+
+use core::alloc::Layout;
+use core::future::Future;
+use core::pin::Pin;
+use core::task::{Context, Poll};
+
+use crate::alloc::AllocError;
+
+/// Something being limited.
+pub struct Memory<T> {
+    /// The remaining memory.
+    memory: usize,
+    /// The configured memory limit, which freed memory is never returned past.
+    limit: usize,
+    /// The thing being limited.
+    value: T,
+}
+
+/// Wrap the given value with a memory limit measured in bytes.
+pub fn with<T>(memory: usize, value: T) -> Memory<T> {
+    Memory {
+        memory,
+        limit: memory,
+        value,
+    }
+}
+
+/// Get the remaining memory in bytes which may be allocated within the
+/// current limit.
+///
+/// This returns [`usize::MAX`] if no limit is in effect.
+pub fn get() -> usize {
+    self::no_std::rune_memory_get()
+}
+
+/// Check that the given amount of memory in bytes could be allocated within
+/// the current limit, without taking it from the limit.
+///
+/// This can be used to bound memory which is allocated outside of [Global],
+/// such as by types which use the standard allocator.
+///
+/// [Global]: crate::Global
+///
+/// # Examples
+///
+/// ```
+/// use rune_alloc::limit;
+///
+/// let f = limit::with(1024, || {
+///     assert!(limit::check(512).is_ok());
+///     assert!(limit::check(2048).unwrap_err().is_limit_exceeded());
+/// });
+///
+/// f.call();
+/// assert!(limit::check(usize::MAX).is_ok());
+/// ```
+pub fn check(amount: usize) -> Result<(), AllocError> {
+    if amount <= self::no_std::rune_memory_get() {
+        return Ok(());
+    }
+
+    // NB: sizes up to `isize::MAX` are always valid for an alignment of one.
+    let layout = Layout::from_size_align(amount.min(isize::MAX as usize), 1)
+        .unwrap_or_else(|_| Layout::new::<u8>());
+    Err(AllocError::limited(layout))
+}
+
+/// Take the given amount of memory from the current limit, indicating with
+/// `true` if the limit is maintained.
+pub(crate) fn take(amount: usize) -> bool {
+    let memory = self::no_std::rune_memory_get();
+
+    if memory == usize::MAX {
+        return true;
+    }
+
+    if memory < amount {
+        return false;
+    }
+
+    self::no_std::rune_memory_replace(memory - amount);
+    true
+}
+
+/// Take the given amount of memory in bytes from the current limit, or error
+/// if doing so would exceed it.
+///
+/// This can be used to account for memory which is allocated outside of
+/// [Global], such as by types which use the standard allocator. The memory
+/// should be given back with [release] once it's freed.
+///
+/// [Global]: crate::Global
+///
+/// # Examples
+///
+/// ```
+/// use rune_alloc::limit;
+///
+/// let f = limit::with(1024, || {
+///     assert!(limit::try_take(768).is_ok());
+///     assert!(limit::try_take(512).unwrap_err().is_limit_exceeded());
+///     limit::release(768);
+///     assert_eq!(limit::get(), 1024);
+/// });
+///
+/// f.call();
+/// ```
+pub fn try_take(amount: usize) -> Result<(), AllocError> {
+    check(amount)?;
+    let _ = take(amount);
+    Ok(())
+}
+
+/// Release the given amount of memory back to the current limit, without
+/// raising it past the limit which was configured.
+///
+/// This is the counterpart of [try_take].
+pub fn release(amount: usize) {
+    let memory = self::no_std::rune_memory_get();
+
+    if memory != usize::MAX {
+        // NB: `usize::MAX` is reserved to indicate that no limit is in effect.
+        let limit = self::no_std::rune_memory_limit_get().min(usize::MAX - 1);
+        self::no_std::rune_memory_replace(memory.saturating_add(amount).min(limit));
+    }
+}
+
+/// Install the given remaining memory and limit, restoring the previous ones
+/// when dropped.
+struct MemoryGuard {
+    memory: usize,
+    limit: usize,
+}
+
+impl MemoryGuard {
+    fn new(memory: usize, limit: usize) -> Self {
+        Self {
+            memory: self::no_std::rune_memory_replace(memory),
+            limit: self::no_std::rune_memory_limit_replace(limit),
+        }
+    }
+}
+
+impl Drop for MemoryGuard {
+    fn drop(&mut self) {
+        let _ = self::no_std::rune_memory_replace(self.memory);
+        let _ = self::no_std::rune_memory_limit_replace(self.limit);
+    }
+}
+
+impl<T> Memory<T> {
+    /// Start out with only the given amount of memory remaining out of the
+    /// limit, as if the rest had already been allocated.
+    ///
+    /// This is used to continue a limit across multiple calls, since memory
+    /// allocated in an earlier call can be freed in a later one.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rune_alloc::limit;
+    ///
+    /// let f = limit::with(1024, || limit::get()).with_remaining(256);
+    /// assert_eq!(f.call(), 256);
+    /// ```
+    pub fn with_remaining(mut self, memory: usize) -> Self {
+        self.memory = memory.min(self.limit);
+        self
+    }
+}
+
+impl<T, O> Memory<T>
+where
+    T: FnOnce() -> O,
+{
+    /// Call the wrapped function.
+    pub fn call(self) -> O {
+        let _guard = MemoryGuard::new(self.memory, self.limit);
+        (self.value)()
+    }
+}
+
+impl<T> Future for Memory<T>
+where
+    T: Future,
+{
+    type Output = T::Output;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        // SAFETY: We're not moving the value out of the pinned reference, and
+        // the memory field is never pinned.
+        let this = unsafe { self.get_unchecked_mut() };
+        let value = unsafe { Pin::new_unchecked(&mut this.value) };
+
+        let _guard = MemoryGuard::new(this.memory, this.limit);
+        let poll = value.poll(cx);
+        this.memory = self::no_std::rune_memory_get();
+        poll
+    }
+}
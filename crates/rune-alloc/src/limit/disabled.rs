@@ -0,0 +1,18 @@
+// Used in no-std environments without the `extern-memory-limit` feature, where
+// there is no storage for the memory limit. Limits have no effect.
+
+pub(super) fn rune_memory_get() -> usize {
+    usize::MAX
+}
+
+pub(super) fn rune_memory_replace(_: usize) -> usize {
+    usize::MAX
+}
+
+pub(super) fn rune_memory_limit_get() -> usize {
+    usize::MAX
+}
+
+pub(super) fn rune_memory_limit_replace(_: usize) -> usize {
+    usize::MAX
+}
@@ -0,0 +1,46 @@
+// In no-std environments, the implementor must define these functions.
+//
+// Normally these make use of thread-local storage, but if you want them to be
+// completed disabled simply return dummy values or store it in static storage
+// (if singlethreaded).
+extern "C" {
+    /// Get the remaining memory limit for the current thread. A value of
+    /// `usize::MAX` indicates that no limit is in effect.
+    pub(super) fn __rune_alloc_memory_get() -> usize;
+
+    /// Replace the memory limit for the current thread and return the one
+    /// which was previously set.
+    pub(super) fn __rune_alloc_memory_replace(value: usize) -> usize;
+
+    /// Get the configured memory limit for the current thread, which freed
+    /// memory is never returned past.
+    pub(super) fn __rune_alloc_memory_limit_get() -> usize;
+
+    /// Replace the configured memory limit for the current thread and return
+    /// the one which was previously set.
+    pub(super) fn __rune_alloc_memory_limit_replace(value: usize) -> usize;
+}
+
+pub(super) fn rune_memory_get() -> usize {
+    // SAFETY: implementor is expected to have read the documentation and
+    // implemented this correctly.
+    unsafe { __rune_alloc_memory_get() }
+}
+
+pub(super) fn rune_memory_replace(value: usize) -> usize {
+    // SAFETY: implementor is expected to have read the documentation and
+    // implemented this correctly.
+    unsafe { __rune_alloc_memory_replace(value) }
+}
+
+pub(super) fn rune_memory_limit_get() -> usize {
+    // SAFETY: implementor is expected to have read the documentation and
+    // implemented this correctly.
+    unsafe { __rune_alloc_memory_limit_get() }
+}
+
+pub(super) fn rune_memory_limit_replace(value: usize) -> usize {
+    // SAFETY: implementor is expected to have read the documentation and
+    // implemented this correctly.
+    unsafe { __rune_alloc_memory_limit_replace(value) }
+}
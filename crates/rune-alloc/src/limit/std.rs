@@ -0,0 +1,20 @@
+use core::cell::Cell;
+
+rust_std::thread_local!(static MEMORY: Cell<usize> = const { Cell::new(usize::MAX) });
+rust_std::thread_local!(static LIMIT: Cell<usize> = const { Cell::new(usize::MAX) });
+
+pub(super) fn rune_memory_get() -> usize {
+    MEMORY.with(|tls| tls.get())
+}
+
+pub(super) fn rune_memory_replace(value: usize) -> usize {
+    MEMORY.with(|tls| tls.replace(value))
+}
+
+pub(super) fn rune_memory_limit_get() -> usize {
+    LIMIT.with(|tls| tls.get())
+}
+
+pub(super) fn rune_memory_limit_replace(value: usize) -> usize {
+    LIMIT.with(|tls| tls.replace(value))
+}
@@ -12,3 +12,48 @@ fn test_vec_macro() -> Result<(), Error> {
     assert_eq!(vec, []);
     Ok(())
 }
+
+#[test]
+fn test_limit() -> Result<(), Error> {
+    use crate::limit;
+
+    let f = limit::with(64, || {
+        let mut vec = Vec::<u8>::new();
+        vec.try_reserve_exact(32)?;
+        assert_eq!(limit::get(), 32);
+
+        let error = vec.try_reserve_exact(128).unwrap_err();
+        assert!(matches!(error, Error::AllocError { error } if error.is_limit_exceeded()));
+
+        drop(vec);
+        assert_eq!(limit::get(), 64);
+        Ok::<_, Error>(())
+    });
+
+    f.call()?;
+    assert_eq!(limit::get(), usize::MAX);
+    Ok(())
+}
+
+#[test]
+fn test_limit_release_outside() -> Result<(), Error> {
+    use crate::limit;
+
+    let vec = Vec::<u8>::try_with_capacity(1024)?;
+
+    let f = limit::with(64, || {
+        // Memory allocated before the limit was set doesn't raise it when
+        // it's freed.
+        drop(vec);
+        assert_eq!(limit::get(), 64);
+
+        let mut vec = Vec::<u8>::new();
+        let error = vec.try_reserve_exact(128).unwrap_err();
+        assert!(matches!(error, Error::AllocError { error } if error.is_limit_exceeded()));
+        Ok::<_, Error>(())
+    });
+
+    f.call()?;
+    assert_eq!(limit::get(), usize::MAX);
+    Ok(())
+}
@@ -24,20 +24,1122 @@
 //! fn main() {
 //!     let file = fs::read_to_string("file.txt").await?;
 //!     println(`{file}`);
+//!
+//!     fs::create_dir_all("out").await?;
+//!     fs::write("out/copy.txt", file).await?;
+//!
+//!     let entries = fs::read_dir("out").await?;
+//!
+//!     while let Some(entry) = entries.next().await {
+//!         let entry = entry?;
+//!         let metadata = entry.metadata().await?;
+//!         println(`{entry.path()}: {metadata.len()} bytes`);
+//!     }
+//!
+//!     match fs::remove_file("missing.txt").await {
+//!         Err(error) => match error.kind() {
+//!             fs::ErrorKind::NotFound => println("nothing to remove"),
+//!             _ => return Err(error),
+//!         },
+//!         Ok(()) => (),
+//!     }
 //! }
 //! ```
+//!
+//! ## Sandboxing
+//!
+//! The module constructed with [`module`] can access the whole filesystem. To
+//! limit scripts to a set of directories, construct it with
+//! [`module_with_capability`] instead:
+//!
+//! ```rust,no_run
+//! use rune_modules::fs::{Access, Capability};
+//!
+//! let capability = Capability::new()
+//!     .with_root("assets", Access::ReadOnly)?
+//!     .with_root("scratch", Access::ReadWrite)?;
+//!
+//! let mut context = rune::Context::with_default_modules()?;
+//! context.install(rune_modules::fs::module_with_capability(true, &capability)?)?;
+//! # Ok::<_, Box<dyn std::error::Error>>(())
+//! ```
+//!
+//! Accessing a path outside of the permitted roots fails with an error of the
+//! `fs::ErrorKind::PermissionDenied` kind, which scripts can handle like any
+//! other error.
 
 use std::io;
+use std::path::{Component, Path, PathBuf};
+use std::sync::Arc;
+use std::time::UNIX_EPOCH;
+
+use rune::alloc::TryWrite;
+use rune::runtime::{Bytes, Formatter, Mut, Ref, Stream, Value, VmResult};
+use rune::{Any, ContextError, Module, Vm};
 use tokio::fs;
-use rune::{Module, ContextError};
+use tokio::io::{AsyncBufReadExt, AsyncReadExt, AsyncWriteExt, BufStream};
+
+/// Construct the `fs` module, giving scripts access to the whole
+/// filesystem.
+pub fn module(stdio: bool) -> Result<Module, ContextError> {
+    module_with_capability(stdio, &Capability::unrestricted())
+}
+
+/// Register a function which is passed a clone of the given capability as its
+/// first argument.
+macro_rules! capability_fn {
+    ($module:ident, $capability:ident, $name:expr, $f:ident($($arg:ident: $ty:ty),*)) => {{
+        let c = $capability.clone();
+        $module.function($name, move |$($arg: $ty),*| $f(c.clone(), $($arg),*))?
+    }};
+}
 
-/// Construct the `fs` module.
-pub fn module(_stdio: bool) -> Result<Module, ContextError> {
+/// Construct the `fs` module, where scripts can only access paths permitted
+/// by the given [`Capability`].
+pub fn module_with_capability(
+    _stdio: bool,
+    capability: &Capability,
+) -> Result<Module, ContextError> {
     let mut module = Module::with_crate("fs");
-    module.function(["read_to_string"], read_to_string)?;
+
+    module.ty::<Error>()?;
+    module.ty::<ErrorKind>()?;
+    module.ty::<Metadata>()?;
+    module.ty::<FileType>()?;
+    module.ty::<DirEntry>()?;
+    module.ty::<File>()?;
+
+    capability_fn!(module, capability, ["read_to_string"], read_to_string(path: Ref<str>))
+        .docs(["Read the entire contents of a file into a string."]);
+
+    capability_fn!(module, capability, ["read"], read(path: Ref<str>))
+        .docs(["Read the entire contents of a file into bytes."]);
+
+    capability_fn!(module, capability, ["write"], write(path: Ref<str>, contents: Value))
+        .docs(["Write a string or bytes to a file, replacing its contents if it exists."]);
+
+    capability_fn!(module, capability, ["create_dir"], create_dir(path: Ref<str>))
+        .docs(["Create a new, empty directory."]);
+
+    capability_fn!(module, capability, ["create_dir_all"], create_dir_all(path: Ref<str>))
+        .docs(["Create a directory and all of its missing parents."]);
+
+    capability_fn!(module, capability, ["remove_file"], remove_file(path: Ref<str>))
+        .docs(["Remove a file."]);
+
+    capability_fn!(module, capability, ["remove_dir"], remove_dir(path: Ref<str>))
+        .docs(["Remove an empty directory."]);
+
+    capability_fn!(module, capability, ["remove_dir_all"], remove_dir_all(path: Ref<str>))
+        .docs(["Remove a directory and all of its contents."]);
+
+    capability_fn!(module, capability, ["rename"], rename(from: Ref<str>, to: Ref<str>))
+        .docs(["Rename a file or directory, replacing the destination if it exists."]);
+
+    capability_fn!(module, capability, ["copy"], copy(from: Ref<str>, to: Ref<str>))
+        .docs([
+            "Copy the contents of a file to another, returning the number of bytes",
+            "copied.",
+        ]);
+
+    capability_fn!(module, capability, ["metadata"], metadata(path: Ref<str>))
+        .docs(["Get the metadata for a path, following symbolic links."]);
+
+    capability_fn!(module, capability, ["read_dir"], read_dir(path: Ref<str>))
+        .docs([
+            "Read the entries of a directory, as a stream which produces a result",
+            "for each entry.",
+        ]);
+
+    capability_fn!(module, capability, ["File", "open"], file_open(path: Ref<str>))
+        .docs(["Open a file in read-only mode."]);
+
+    capability_fn!(module, capability, ["File", "create"], file_create(path: Ref<str>))
+        .docs([
+            "Open a file in write-only mode, creating it if it doesn't exist and",
+            "truncating it if it does.",
+        ]);
+
+    capability_fn!(module, capability, ["File", "append"], file_append(path: Ref<str>))
+        .docs(["Open a file for appending, creating it if it doesn't exist."]);
+
+    module.function_meta(Error::kind)?;
+    module.function_meta(Error::string_display)?;
+    module.function_meta(Error::string_debug)?;
+    module.function_meta(ErrorKind::partial_eq)?;
+    module.function_meta(ErrorKind::string_debug)?;
+
+    module.function_meta(Metadata::file_type)?;
+    module.function_meta(Metadata::is_file)?;
+    module.function_meta(Metadata::is_dir)?;
+    module.function_meta(Metadata::is_symlink)?;
+    module.function_meta(Metadata::len)?;
+    module.function_meta(Metadata::is_readonly)?;
+    module.function_meta(Metadata::modified)?;
+
+    module.function_meta(FileType::is_file)?;
+    module.function_meta(FileType::is_dir)?;
+    module.function_meta(FileType::is_symlink)?;
+
+    module.function_meta(DirEntry::path)?;
+    module.function_meta(DirEntry::file_name)?;
+    module.function_meta(dir_entry_metadata)?;
+    module.function_meta(dir_entry_file_type)?;
+
+    module.function_meta(file_read_to_string)?;
+    module.function_meta(file_read_to_end)?;
+    module.function_meta(file_read_line)?;
+    module.function_meta(file_write)?;
+    module.function_meta(file_flush)?;
     Ok(module)
 }
 
-async fn read_to_string(path: String) -> io::Result<String> {
-    fs::read_to_string(&path).await
+/// How paths under a root of a [`Capability`] can be accessed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum Access {
+    /// Paths can only be read.
+    ReadOnly,
+    /// Paths can be both read and written.
+    ReadWrite,
+}
+
+#[derive(Debug, Clone)]
+struct Root {
+    path: PathBuf,
+    access: Access,
+}
+
+/// The capability to access the filesystem, which limits the paths scripts
+/// can access to a set of root directories.
+///
+/// Paths are canonicalized before they are checked against the roots, so
+/// neither `..` components nor symbolic links can be used to escape them. A
+/// path which isn't permitted causes the operation to fail with an error of
+/// the `PermissionDenied` kind.
+///
+/// # Limitations
+///
+/// Paths are checked before the operation which uses them is performed, and
+/// the operation itself is performed on the resolved path. Anything else
+/// which can modify the filesystem under a root at the same time, such as
+/// another process or a script running concurrently, can therefore race the
+/// check by replacing a directory with a symbolic link after it has been
+/// checked. Roots should only contain directories which aren't writable by
+/// anything less trusted than the scripts themselves.
+#[derive(Debug, Clone)]
+pub struct Capability {
+    /// Permitted roots, or `None` if access is unrestricted.
+    roots: Option<Arc<[Root]>>,
+}
+
+impl Capability {
+    /// Construct a capability which doesn't permit access to any path.
+    pub fn new() -> Self {
+        Self {
+            roots: Some(Arc::from([])),
+        }
+    }
+
+    /// Construct a capability which permits access to every path.
+    pub fn unrestricted() -> Self {
+        Self { roots: None }
+    }
+
+    /// Permit access to the given root directory and everything under it.
+    ///
+    /// If this is called on an [unrestricted][Capability::unrestricted]
+    /// capability, the returned capability only permits access to the given
+    /// root.
+    ///
+    /// This errors if the root can't be canonicalized, such as when it doesn't
+    /// exist.
+    pub fn with_root<P>(self, path: P, access: Access) -> io::Result<Self>
+    where
+        P: AsRef<Path>,
+    {
+        let path = std::fs::canonicalize(path)?;
+
+        let mut roots = self.roots.map(|roots| roots.to_vec()).unwrap_or_default();
+        roots.push(Root { path, access });
+
+        Ok(Self {
+            roots: Some(roots.into()),
+        })
+    }
+
+    /// Resolve a path provided by a script, checking that it's permitted to be
+    /// accessed with the given access.
+    ///
+    /// If `follow` is set and the path is a symbolic link, the target of the
+    /// link has to be permitted as well.
+    async fn resolve(&self, path: &str, access: Access, follow: bool) -> io::Result<PathBuf> {
+        let Some(roots) = &self.roots else {
+            return Ok(PathBuf::from(path));
+        };
+
+        let path = std::env::current_dir()?.join(path);
+
+        let (parent, name) = match path.components().next_back() {
+            Some(Component::Normal(name)) => (path.parent().unwrap_or(&path), Some(name)),
+            _ => (path.as_path(), None),
+        };
+
+        let mut resolved = canonicalize_existing(parent).await?;
+
+        if let Some(name) = name {
+            resolved.push(name);
+
+            if follow
+                && fs::symlink_metadata(&resolved)
+                    .await
+                    .map_or(false, |m| m.is_symlink())
+            {
+                // NB: Links which can't be resolved are denied, since writing
+                // through them would create their target.
+                resolved = match fs::canonicalize(&resolved).await {
+                    Ok(target) => target,
+                    Err(..) => return Err(denied(&path)),
+                };
+            }
+        }
+
+        let permitted = roots.iter().any(|root| {
+            resolved.starts_with(&root.path)
+                && (access == Access::ReadOnly || root.access == Access::ReadWrite)
+        });
+
+        if !permitted {
+            return Err(denied(&path));
+        }
+
+        Ok(resolved)
+    }
+}
+
+impl Default for Capability {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Canonicalize the longest prefix of the given absolute path which exists,
+/// and append the rest of it.
+async fn canonicalize_existing(path: &Path) -> io::Result<PathBuf> {
+    let mut existing = path;
+    let mut rest = Vec::new();
+
+    let mut canonical = loop {
+        match fs::canonicalize(existing).await {
+            Ok(canonical) => break canonical,
+            Err(error) if error.kind() == io::ErrorKind::NotFound => {
+                let (Some(parent), Some(last)) =
+                    (existing.parent(), existing.components().next_back())
+                else {
+                    return Err(error);
+                };
+
+                rest.push(last);
+                existing = parent;
+            }
+            Err(error) => return Err(error),
+        }
+    };
+
+    for component in rest.into_iter().rev() {
+        match component {
+            Component::Normal(name) => canonical.push(name),
+            Component::CurDir => {}
+            // Components which don't exist can't be traversed out of.
+            _ => return Err(io::Error::from(io::ErrorKind::NotFound)),
+        }
+    }
+
+    Ok(canonical)
+}
+
+/// Construct the error raised when a path isn't permitted.
+fn denied(path: &Path) -> io::Error {
+    io::Error::new(
+        io::ErrorKind::PermissionDenied,
+        format!("access to `{}` is not permitted", path.display()),
+    )
+}
+
+/// An error raised by a filesystem operation.
+#[derive(Debug, Any)]
+#[rune(item = ::fs)]
+pub struct Error {
+    inner: io::Error,
+}
+
+impl From<io::Error> for Error {
+    fn from(inner: io::Error) -> Self {
+        Self { inner }
+    }
+}
+
+impl Error {
+    /// Get the kind of the error.
+    ///
+    /// # Examples
+    ///
+    /// ```rune,no_run
+    /// match fs::read_to_string("missing.txt").await {
+    ///     Err(error) if error.kind() == fs::ErrorKind::NotFound => (),
+    ///     _ => panic!("expected file to be missing"),
+    /// }
+    /// ```
+    #[rune::function]
+    fn kind(&self) -> ErrorKind {
+        ErrorKind::from(self.inner.kind())
+    }
+
+    #[rune::function(protocol = STRING_DISPLAY)]
+    fn string_display(&self, f: &mut Formatter) -> VmResult<()> {
+        rune::vm_write!(f, "{}", self.inner);
+        VmResult::Ok(())
+    }
+
+    #[rune::function(protocol = STRING_DEBUG)]
+    fn string_debug(&self, f: &mut Formatter) -> VmResult<()> {
+        rune::vm_write!(f, "{:?}", self.inner);
+        VmResult::Ok(())
+    }
+}
+
+/// The kind of a filesystem [`Error`], which can be matched over.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Any)]
+#[rune(item = ::fs)]
+pub enum ErrorKind {
+    /// An entity was not found.
+    #[rune(constructor)]
+    NotFound,
+    /// The operation lacked the necessary privileges to complete.
+    #[rune(constructor)]
+    PermissionDenied,
+    /// An entity already exists.
+    #[rune(constructor)]
+    AlreadyExists,
+    /// A parameter was incorrect.
+    #[rune(constructor)]
+    InvalidInput,
+    /// Data not valid for the operation were encountered, like a file which
+    /// isn't valid UTF-8 being read as a string.
+    #[rune(constructor)]
+    InvalidData,
+    /// An operation could not be completed because an end of file was
+    /// reached prematurely.
+    #[rune(constructor)]
+    UnexpectedEof,
+    /// The operation was interrupted.
+    #[rune(constructor)]
+    Interrupted,
+    /// The operation is not supported on this platform.
+    #[rune(constructor)]
+    Unsupported,
+    /// Any other error.
+    #[rune(constructor)]
+    Other,
+}
+
+impl From<io::ErrorKind> for ErrorKind {
+    fn from(kind: io::ErrorKind) -> Self {
+        match kind {
+            io::ErrorKind::NotFound => Self::NotFound,
+            io::ErrorKind::PermissionDenied => Self::PermissionDenied,
+            io::ErrorKind::AlreadyExists => Self::AlreadyExists,
+            io::ErrorKind::InvalidInput => Self::InvalidInput,
+            io::ErrorKind::InvalidData => Self::InvalidData,
+            io::ErrorKind::UnexpectedEof => Self::UnexpectedEof,
+            io::ErrorKind::Interrupted => Self::Interrupted,
+            io::ErrorKind::Unsupported => Self::Unsupported,
+            _ => Self::Other,
+        }
+    }
+}
+
+impl ErrorKind {
+    #[rune::function(protocol = PARTIAL_EQ)]
+    fn partial_eq(&self, other: &Self) -> bool {
+        self == other
+    }
+
+    #[rune::function(protocol = STRING_DEBUG)]
+    fn string_debug(&self, f: &mut Formatter) -> VmResult<()> {
+        rune::vm_write!(f, "{:?}", self);
+        VmResult::Ok(())
+    }
+}
+
+/// Metadata information about a file or directory.
+#[derive(Debug, Any)]
+#[rune(item = ::fs)]
+struct Metadata {
+    inner: std::fs::Metadata,
+}
+
+impl Metadata {
+    /// Get the type of the file.
+    #[rune::function]
+    fn file_type(&self) -> FileType {
+        FileType {
+            inner: self.inner.file_type(),
+        }
+    }
+
+    /// Test if this metadata is for a regular file.
+    #[rune::function]
+    fn is_file(&self) -> bool {
+        self.inner.is_file()
+    }
+
+    /// Test if this metadata is for a directory.
+    #[rune::function]
+    fn is_dir(&self) -> bool {
+        self.inner.is_dir()
+    }
+
+    /// Test if this metadata is for a symbolic link.
+    #[rune::function]
+    fn is_symlink(&self) -> bool {
+        self.inner.is_symlink()
+    }
+
+    /// The size of the file in bytes.
+    #[rune::function]
+    fn len(&self) -> u64 {
+        self.inner.len()
+    }
+
+    /// Test if the file is read-only.
+    #[rune::function]
+    fn is_readonly(&self) -> bool {
+        self.inner.permissions().readonly()
+    }
+
+    /// The last modification time of the file, as the number of seconds since
+    /// the UNIX epoch.
+    #[rune::function]
+    fn modified(&self) -> Result<f64, Error> {
+        let modified = self.inner.modified()?;
+
+        let seconds = match modified.duration_since(UNIX_EPOCH) {
+            Ok(duration) => duration.as_secs_f64(),
+            Err(error) => -error.duration().as_secs_f64(),
+        };
+
+        Ok(seconds)
+    }
+}
+
+/// The type of a file.
+#[derive(Debug, Clone, Copy, Any)]
+#[rune(item = ::fs)]
+struct FileType {
+    inner: std::fs::FileType,
+}
+
+impl FileType {
+    /// Test if this is a regular file.
+    #[rune::function]
+    fn is_file(&self) -> bool {
+        self.inner.is_file()
+    }
+
+    /// Test if this is a directory.
+    #[rune::function]
+    fn is_dir(&self) -> bool {
+        self.inner.is_dir()
+    }
+
+    /// Test if this is a symbolic link.
+    #[rune::function]
+    fn is_symlink(&self) -> bool {
+        self.inner.is_symlink()
+    }
+}
+
+/// An entry in a directory.
+#[derive(Debug, Any)]
+#[rune(item = ::fs)]
+struct DirEntry {
+    inner: fs::DirEntry,
+}
+
+impl DirEntry {
+    /// The full path to the entry.
+    #[rune::function]
+    fn path(&self) -> String {
+        self.inner.path().to_string_lossy().into_owned()
+    }
+
+    /// The name of the entry, without the leading path.
+    #[rune::function]
+    fn file_name(&self) -> String {
+        self.inner.file_name().to_string_lossy().into_owned()
+    }
+}
+
+/// Get the metadata for the entry.
+#[rune::function(instance, path = metadata)]
+async fn dir_entry_metadata(this: Ref<DirEntry>) -> Result<Metadata, Error> {
+    let inner = this.inner.metadata().await?;
+    Ok(Metadata { inner })
+}
+
+/// Get the type of the entry.
+#[rune::function(instance, path = file_type)]
+async fn dir_entry_file_type(this: Ref<DirEntry>) -> Result<FileType, Error> {
+    let inner = this.inner.file_type().await?;
+    Ok(FileType { inner })
+}
+
+/// An open file with buffered reads and writes.
+///
+/// Writes are buffered, so `flush` has to be called to make sure that
+/// everything written reaches the file.
+#[derive(Debug, Any)]
+#[rune(item = ::fs)]
+struct File {
+    inner: BufStream<fs::File>,
+}
+
+impl File {
+    fn new(file: fs::File) -> Self {
+        Self {
+            inner: BufStream::new(file),
+        }
+    }
+}
+
+/// Read the rest of the file into a string.
+#[rune::function(instance, path = read_to_string)]
+async fn file_read_to_string(mut this: Mut<File>) -> Result<String, Error> {
+    let mut string = String::new();
+    this.inner.read_to_string(&mut string).await?;
+    Ok(string)
+}
+
+/// Read the rest of the file into bytes.
+#[rune::function(instance, path = read_to_end)]
+async fn file_read_to_end(mut this: Mut<File>) -> Result<Bytes, Error> {
+    let mut bytes = Vec::new();
+    this.inner.read_to_end(&mut bytes).await?;
+    Ok(Bytes::from_vec(bytes))
+}
+
+/// Read the next line from the file, including the line ending. Returns `None`
+/// once the end of the file has been reached.
+#[rune::function(instance, path = read_line)]
+async fn file_read_line(mut this: Mut<File>) -> Result<Option<String>, Error> {
+    let mut line = String::new();
+
+    if this.inner.read_line(&mut line).await? == 0 {
+        return Ok(None);
+    }
+
+    Ok(Some(line))
+}
+
+/// Write a string or bytes to the file.
+#[rune::function(instance, path = write)]
+async fn file_write(mut this: Mut<File>, contents: Value) -> VmResult<Result<(), Error>> {
+    let contents = rune::vm_try!(contents_to_vec(contents));
+
+    if let Err(error) = this.inner.write_all(&contents).await {
+        return VmResult::Ok(Err(error.into()));
+    }
+
+    VmResult::Ok(Ok(()))
+}
+
+/// Flush any buffered writes to the file.
+#[rune::function(instance, path = flush)]
+async fn file_flush(mut this: Mut<File>) -> Result<(), Error> {
+    this.inner.flush().await?;
+    Ok(())
+}
+
+async fn read_to_string(capability: Capability, path: Ref<str>) -> Result<String, Error> {
+    let path = capability.resolve(&path, Access::ReadOnly, true).await?;
+    Ok(fs::read_to_string(path).await?)
+}
+
+async fn read(capability: Capability, path: Ref<str>) -> Result<Bytes, Error> {
+    let path = capability.resolve(&path, Access::ReadOnly, true).await?;
+    Ok(Bytes::from_vec(fs::read(path).await?))
+}
+
+async fn write(
+    capability: Capability,
+    path: Ref<str>,
+    contents: Value,
+) -> VmResult<Result<(), Error>> {
+    let contents = rune::vm_try!(contents_to_vec(contents));
+
+    let result = match capability.resolve(&path, Access::ReadWrite, true).await {
+        Ok(path) => fs::write(path, contents).await,
+        Err(error) => Err(error),
+    };
+
+    VmResult::Ok(result.map_err(Error::from))
+}
+
+async fn create_dir(capability: Capability, path: Ref<str>) -> Result<(), Error> {
+    let path = capability.resolve(&path, Access::ReadWrite, true).await?;
+    Ok(fs::create_dir(path).await?)
+}
+
+async fn create_dir_all(capability: Capability, path: Ref<str>) -> Result<(), Error> {
+    let path = capability.resolve(&path, Access::ReadWrite, true).await?;
+    Ok(fs::create_dir_all(path).await?)
+}
+
+async fn remove_file(capability: Capability, path: Ref<str>) -> Result<(), Error> {
+    let path = capability.resolve(&path, Access::ReadWrite, false).await?;
+    Ok(fs::remove_file(path).await?)
+}
+
+async fn remove_dir(capability: Capability, path: Ref<str>) -> Result<(), Error> {
+    let path = capability.resolve(&path, Access::ReadWrite, false).await?;
+    Ok(fs::remove_dir(path).await?)
+}
+
+async fn remove_dir_all(capability: Capability, path: Ref<str>) -> Result<(), Error> {
+    let path = capability.resolve(&path, Access::ReadWrite, false).await?;
+    Ok(fs::remove_dir_all(path).await?)
+}
+
+async fn rename(capability: Capability, from: Ref<str>, to: Ref<str>) -> Result<(), Error> {
+    let from = capability.resolve(&from, Access::ReadWrite, false).await?;
+    let to = capability.resolve(&to, Access::ReadWrite, false).await?;
+    Ok(fs::rename(from, to).await?)
+}
+
+async fn copy(capability: Capability, from: Ref<str>, to: Ref<str>) -> Result<u64, Error> {
+    let from = capability.resolve(&from, Access::ReadOnly, true).await?;
+    let to = capability.resolve(&to, Access::ReadWrite, true).await?;
+    Ok(fs::copy(from, to).await?)
+}
+
+async fn metadata(capability: Capability, path: Ref<str>) -> Result<Metadata, Error> {
+    let path = capability.resolve(&path, Access::ReadOnly, true).await?;
+    let inner = fs::metadata(path).await?;
+    Ok(Metadata { inner })
+}
+
+async fn read_dir(capability: Capability, path: Ref<str>) -> Result<Stream<Vm>, Error> {
+    let path = capability.resolve(&path, Access::ReadOnly, true).await?;
+    let read_dir = fs::read_dir(path).await?;
+
+    // NB: the stream completes after the first error, which it produces as a
+    // value.
+    let stream = futures_util::stream::unfold(Some(read_dir), |read_dir| async move {
+        let mut read_dir = read_dir?;
+
+        let (entry, read_dir) = match read_dir.next_entry().await {
+            Ok(Some(inner)) => (Ok(DirEntry { inner }), Some(read_dir)),
+            Ok(None) => return None,
+            Err(error) => (Err(Error::from(error)), None),
+        };
+
+        Some((VmResult::Ok(entry), read_dir))
+    });
+
+    Ok(Stream::from_stream(stream))
+}
+
+async fn file_open(capability: Capability, path: Ref<str>) -> Result<File, Error> {
+    let path = capability.resolve(&path, Access::ReadOnly, true).await?;
+    let file = fs::File::open(path).await?;
+    Ok(File::new(file))
+}
+
+async fn file_create(capability: Capability, path: Ref<str>) -> Result<File, Error> {
+    let path = capability.resolve(&path, Access::ReadWrite, true).await?;
+    let file = fs::File::create(path).await?;
+    Ok(File::new(file))
+}
+
+async fn file_append(capability: Capability, path: Ref<str>) -> Result<File, Error> {
+    let path = capability.resolve(&path, Access::ReadWrite, true).await?;
+
+    let file = fs::OpenOptions::new()
+        .append(true)
+        .create(true)
+        .open(path)
+        .await?;
+
+    Ok(File::new(file))
+}
+
+/// Convert the contents to write into a vector of bytes.
+fn contents_to_vec(contents: Value) -> VmResult<Vec<u8>> {
+    match contents {
+        Value::String(string) => {
+            VmResult::Ok(rune::vm_try!(string.borrow_ref()).as_bytes().to_vec())
+        }
+        Value::Bytes(bytes) => VmResult::Ok(rune::vm_try!(bytes.borrow_ref()).to_vec()),
+        actual => VmResult::expected::<String>(rune::vm_try!(actual.type_info())),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::error::Error;
+    use std::io;
+    use std::path::Path;
+    use std::sync::Arc;
+
+    use rune::{Context, FromValue, Source, Sources, Vm};
+
+    use super::{Access, Capability};
+
+    /// Run the `main` function of the given script, passing it the given
+    /// directory.
+    async fn run<T>(capability: &Capability, dir: &Path, source: &str) -> Result<T, Box<dyn Error>>
+    where
+        T: FromValue,
+    {
+        let mut context = Context::with_default_modules()?;
+        context.install(super::module_with_capability(true, capability)?)?;
+
+        let mut sources = Sources::new();
+        sources.insert(Source::memory(source));
+
+        let unit = rune::prepare(&mut sources).with_context(&context).build()?;
+        let mut vm = Vm::new(Arc::new(context.runtime()), Arc::new(unit));
+
+        let dir = dir.to_str().unwrap().to_owned();
+        Ok(rune::from_value(vm.async_call(["main"], (dir,)).await?)?)
+    }
+
+    /// Resolve the given path, which is relative to the given directory.
+    async fn resolve(
+        capability: &Capability,
+        dir: &Path,
+        path: &str,
+        access: Access,
+        follow: bool,
+    ) -> io::Result<()> {
+        let path = dir.join(path);
+        capability
+            .resolve(path.to_str().unwrap(), access, follow)
+            .await?;
+        Ok(())
+    }
+
+    fn is_denied(result: io::Result<()>) -> bool {
+        matches!(result, Err(error) if error.kind() == io::ErrorKind::PermissionDenied)
+    }
+
+    #[tokio::test]
+    async fn test_parent_traversal() -> io::Result<()> {
+        let dir = tempfile::tempdir()?;
+        std::fs::create_dir(dir.path().join("root"))?;
+        std::fs::write(dir.path().join("secret.txt"), "secret")?;
+
+        let capability = Capability::new().with_root(dir.path().join("root"), Access::ReadWrite)?;
+        let dir = dir.path();
+
+        assert!(
+            resolve(&capability, dir, "root/file.txt", Access::ReadOnly, true)
+                .await
+                .is_ok()
+        );
+        std::fs::create_dir(dir.join("root/a"))?;
+        assert!(resolve(
+            &capability,
+            dir,
+            "root/./a/../file.txt",
+            Access::ReadOnly,
+            true
+        )
+        .await
+        .is_ok());
+
+        // Directories which don't exist can't be traversed out of.
+        let result = resolve(
+            &capability,
+            dir,
+            "root/b/../file.txt",
+            Access::ReadOnly,
+            true,
+        )
+        .await;
+        assert!(matches!(result, Err(e) if e.kind() == io::ErrorKind::NotFound));
+
+        for path in [
+            "root/../secret.txt",
+            "root/..",
+            "root/missing/../../secret.txt",
+        ] {
+            let result = resolve(&capability, dir, path, Access::ReadOnly, true).await;
+            assert!(result.is_err(), "{path}");
+        }
+
+        assert!(is_denied(
+            resolve(
+                &capability,
+                dir,
+                "root/../secret.txt",
+                Access::ReadOnly,
+                true
+            )
+            .await
+        ));
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_absolute_paths() -> io::Result<()> {
+        let dir = tempfile::tempdir()?;
+        let root = dir.path().join("root");
+        std::fs::create_dir(&root)?;
+
+        let capability = Capability::new().with_root(&root, Access::ReadOnly)?;
+        let root = root.canonicalize()?;
+
+        let inside = root.join("file.txt");
+        let resolved = capability
+            .resolve(inside.to_str().unwrap(), Access::ReadOnly, true)
+            .await?;
+        assert_eq!(resolved, inside);
+
+        let outside = dir.path().join("file.txt");
+        let result = capability
+            .resolve(outside.to_str().unwrap(), Access::ReadOnly, true)
+            .await;
+        assert!(matches!(result, Err(e) if e.kind() == io::ErrorKind::PermissionDenied));
+
+        let sibling = format!("{}-sibling/file.txt", root.display());
+        let result = capability.resolve(&sibling, Access::ReadOnly, true).await;
+        assert!(result.is_err());
+        Ok(())
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_symlink_escape() -> io::Result<()> {
+        let dir = tempfile::tempdir()?;
+        let root = dir.path().join("root");
+        std::fs::create_dir(&root)?;
+        std::fs::write(dir.path().join("secret.txt"), "secret")?;
+        std::fs::write(root.join("inner.txt"), "inner")?;
+
+        std::os::unix::fs::symlink(dir.path().join("secret.txt"), root.join("escape"))?;
+        std::os::unix::fs::symlink(dir.path(), root.join("parent"))?;
+        std::os::unix::fs::symlink(root.join("inner.txt"), root.join("inner"))?;
+        std::os::unix::fs::symlink(dir.path().join("missing"), root.join("dangling"))?;
+
+        let capability = Capability::new().with_root(&root, Access::ReadWrite)?;
+        let dir = dir.path();
+
+        // Following a link which points outside of the root is denied.
+        assert!(is_denied(
+            resolve(&capability, dir, "root/escape", Access::ReadOnly, true).await
+        ));
+        assert!(is_denied(
+            resolve(&capability, dir, "root/dangling", Access::ReadWrite, true).await
+        ));
+
+        // Links to directories are resolved as parents, regardless of
+        // whether the last component is followed.
+        for follow in [true, false] {
+            assert!(is_denied(
+                resolve(
+                    &capability,
+                    dir,
+                    "root/parent/secret.txt",
+                    Access::ReadOnly,
+                    follow
+                )
+                .await
+            ));
+        }
+
+        // Without following, the link itself can be operated on.
+        assert!(
+            resolve(&capability, dir, "root/escape", Access::ReadWrite, false)
+                .await
+                .is_ok()
+        );
+
+        assert!(
+            resolve(&capability, dir, "root/inner", Access::ReadOnly, true)
+                .await
+                .is_ok()
+        );
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_read_only_root() -> io::Result<()> {
+        let dir = tempfile::tempdir()?;
+        std::fs::create_dir(dir.path().join("assets"))?;
+        std::fs::create_dir(dir.path().join("scratch"))?;
+
+        let capability = Capability::new()
+            .with_root(dir.path().join("assets"), Access::ReadOnly)?
+            .with_root(dir.path().join("scratch"), Access::ReadWrite)?;
+
+        let dir = dir.path();
+
+        assert!(
+            resolve(&capability, dir, "assets/file.txt", Access::ReadOnly, true)
+                .await
+                .is_ok()
+        );
+        assert!(is_denied(
+            resolve(&capability, dir, "assets/file.txt", Access::ReadWrite, true).await
+        ));
+        assert!(resolve(
+            &capability,
+            dir,
+            "scratch/file.txt",
+            Access::ReadWrite,
+            true
+        )
+        .await
+        .is_ok());
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_restricting_unrestricted() -> io::Result<()> {
+        let dir = tempfile::tempdir()?;
+        std::fs::create_dir(dir.path().join("root"))?;
+
+        let unrestricted = Capability::unrestricted();
+        assert!(resolve(
+            &unrestricted,
+            dir.path(),
+            "file.txt",
+            Access::ReadWrite,
+            true
+        )
+        .await
+        .is_ok());
+
+        let capability = unrestricted.with_root(dir.path().join("root"), Access::ReadOnly)?;
+        let dir = dir.path();
+
+        assert!(
+            resolve(&capability, dir, "root/file.txt", Access::ReadOnly, true)
+                .await
+                .is_ok()
+        );
+        assert!(is_denied(
+            resolve(&capability, dir, "file.txt", Access::ReadOnly, true).await
+        ));
+        assert!(is_denied(
+            resolve(&capability, dir, "root/file.txt", Access::ReadWrite, true).await
+        ));
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_script() -> Result<(), Box<dyn Error>> {
+        let dir = tempfile::tempdir()?;
+
+        let output: (Option<String>, String, u64, Vec<String>) = run(
+            &Capability::unrestricted(),
+            dir.path(),
+            r#"
+            pub async fn main(dir) {
+                fs::create_dir_all(`${dir}/out/nested`).await?;
+                fs::write(`${dir}/out/a.txt`, "hello\nworld\n").await?;
+                fs::copy(`${dir}/out/a.txt`, `${dir}/out/b.txt`).await?;
+                fs::rename(`${dir}/out/b.txt`, `${dir}/out/c.txt`).await?;
+
+                let file = fs::File::append(`${dir}/out/c.txt`).await?;
+                file.write(b"again\n").await?;
+                file.flush().await?;
+
+                let file = fs::File::open(`${dir}/out/c.txt`).await?;
+                let first = file.read_line().await?;
+
+                let names = [];
+                let entries = fs::read_dir(`${dir}/out`).await?;
+
+                while let Some(entry) = entries.next().await {
+                    let entry = entry?;
+
+                    if entry.file_type().await?.is_dir() {
+                        names.push(`${entry.file_name()}/`);
+                    } else {
+                        names.push(entry.file_name());
+                    }
+                }
+
+                names.sort();
+
+                let contents = fs::read_to_string(`${dir}/out/c.txt`).await?;
+                let metadata = fs::metadata(`${dir}/out/c.txt`).await?;
+                (first, contents, metadata.len(), names)
+            }
+            "#,
+        )
+        .await?;
+
+        assert_eq!(
+            output,
+            (
+                Some(String::from("hello\n")),
+                String::from("hello\nworld\nagain\n"),
+                18,
+                vec![
+                    String::from("a.txt"),
+                    String::from("c.txt"),
+                    String::from("nested/")
+                ],
+            )
+        );
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_script_error_kind() -> Result<(), Box<dyn Error>> {
+        let dir = tempfile::tempdir()?;
+        std::fs::create_dir(dir.path().join("root"))?;
+
+        let capability = Capability::new().with_root(dir.path().join("root"), Access::ReadOnly)?;
+
+        let output: Vec<String> = run(
+            &capability,
+            dir.path(),
+            r#"
+            fn kind(result) {
+                match result {
+                    Ok(..) => "ok",
+                    Err(error) => match error.kind() {
+                        fs::ErrorKind::NotFound => "not found",
+                        fs::ErrorKind::PermissionDenied => "permission denied",
+                        _ => "other",
+                    },
+                }
+            }
+
+            pub async fn main(dir) {
+                [
+                    kind(fs::read_to_string(`${dir}/root/missing.txt`).await),
+                    kind(fs::read_dir(`${dir}/root/missing`).await),
+                    kind(fs::read_dir(`${dir}/root`).await),
+                    kind(fs::write(`${dir}/root/file.txt`, "denied").await),
+                    kind(fs::read_dir(dir).await),
+                ]
+            }
+            "#,
+        )
+        .await?;
+
+        assert_eq!(
+            output,
+            ["not found", "not found", "ok", "permission denied", "permission denied"]
+        );
+
+        Ok(())
+    }
 }
@@ -47,10 +47,77 @@
 //!     dbg(response);
 //! }
 //! ```
+//!
+//! ## Server
+//!
+//! The `http::Server` type serves HTTP requests using handler functions
+//! written in Rune. Requests are accepted in the background on the tokio
+//! runtime which runs the script, but handlers are called one at a time by
+//! the script itself while it awaits `serve` or `serve_one`.
+//!
+//! A handler receives an `http::ServerRequest` and can return:
+//! * An `http::ServerResponse` to control the status, headers and body.
+//! * A string, which is sent as `text/plain` with status 200.
+//! * Bytes, which are sent as `application/octet-stream` with status 200.
+//! * `()`, which is sent as an empty response with status 204.
+//! * Any other value, which is sent as JSON with status 200.
+//!
+//! A handler which returns `Ok(value)` is treated as if it returned `value`,
+//! while `Err(error)` results in an empty response with status 500.
+//!
+//! Requests with a body larger than 2 MiB are rejected with status 413 before
+//! they reach a handler.
+//!
+//! ```rust,ignore
+//! use http::{Server, ServerResponse};
+//!
+//! async fn hello(request) {
+//!     let name = request.query().get("name").unwrap_or("world");
+//!     `Hello ${name}!`
+//! }
+//!
+//! async fn webhook(request) {
+//!     let event = request.json()?;
+//!     println!("got event: {:?}", event);
+//!     Ok(ServerResponse::new(202))
+//! }
+//!
+//! pub async fn main() {
+//!     let server = Server::bind("127.0.0.1:8080").await?;
+//!     server.get("/hello", hello);
+//!     server.post("/webhook", webhook);
+//!     server.serve().await;
+//! }
+//! ```
+//!
+//! Since a server can be bound to port `0`, and `serve_one` only serves a
+//! single request, a script can test its handlers against localhost:
+//!
+//! ```rust,ignore
+//! pub async fn main() {
+//!     let server = http::Server::bind("127.0.0.1:0").await?;
+//!     server.get("/hello", hello);
+//!
+//!     let url = `http://${server.local_addr()}/hello?name=rune`;
+//!     let (_, response) = std::future::join((server.serve_one(), http::get(url))).await;
+//!
+//!     assert_eq!(response?.text().await?, "Hello rune!");
+//! }
+//! ```
 
+use std::collections::HashMap;
+use std::convert::Infallible;
+use std::io;
+use std::net::SocketAddr;
+
+use hyper::body::HttpBody;
+use hyper::header::{HeaderName, HeaderValue, CONTENT_TYPE};
+use hyper::service::{make_service_fn, service_fn};
+use hyper::{Body, StatusCode as HyperStatusCode};
 use rune::{Any, Module, Value, ContextError};
-use rune::runtime::{Bytes, Ref, Formatter, VmResult};
+use rune::runtime::{Bytes, Function, Mut, Ref, Formatter, VmResult};
 use rune::alloc::TryWrite;
+use tokio::sync::{mpsc, oneshot};
 
 /// Construct the `http` module.
 pub fn module(_stdio: bool) -> Result<Module, ContextError> {
@@ -78,18 +145,92 @@ pub fn module(_stdio: bool) -> Result<Module, ContextError> {
 
     module.function_meta(Error::string_display)?;
     module.function_meta(StatusCode::string_display)?;
+
+    module.ty::<Server>()?;
+    module.ty::<ServerRequest>()?;
+    module.ty::<ServerResponse>()?;
+
+    module.function_meta(Server::bind)?;
+    module.function_meta(Server::local_addr)?;
+    module.function_meta(Server::route)?;
+    module.function_meta(Server::get)?;
+    module.function_meta(Server::post)?;
+    module.function_meta(Server::fallback)?;
+    module.function_meta(server_serve)?;
+    module.function_meta(server_serve_one)?;
+
+    module.function_meta(ServerRequest::method)?;
+    module.function_meta(ServerRequest::path)?;
+    module.function_meta(ServerRequest::query)?;
+    module.function_meta(ServerRequest::header)?;
+    module.function_meta(ServerRequest::headers)?;
+    module.function_meta(ServerRequest::body)?;
+    module.function_meta(ServerRequest::text)?;
+    module.function_meta(ServerRequest::json)?;
+
+    module.function_meta(ServerResponse::new)?;
+    module.function_meta(ServerResponse::text__meta)?;
+    module.function_meta(ServerResponse::bytes__meta)?;
+    module.function_meta(ServerResponse::json__meta)?;
+    module.function_meta(ServerResponse::header)?;
+    module.function_meta(ServerResponse::body_bytes)?;
     Ok(module)
 }
 
 #[derive(Debug, Any)]
 #[rune(item = ::http)]
 pub struct Error {
-    inner: reqwest::Error,
+    inner: ErrorKind,
+}
+
+#[derive(Debug)]
+enum ErrorKind {
+    Reqwest(reqwest::Error),
+    Hyper(hyper::Error),
+    Io(io::Error),
+    Json(serde_json::Error),
+    Utf8(std::string::FromUtf8Error),
+}
+
+impl std::fmt::Display for ErrorKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ErrorKind::Reqwest(error) => error.fmt(f),
+            ErrorKind::Hyper(error) => error.fmt(f),
+            ErrorKind::Io(error) => error.fmt(f),
+            ErrorKind::Json(error) => error.fmt(f),
+            ErrorKind::Utf8(error) => error.fmt(f),
+        }
+    }
 }
 
 impl From<reqwest::Error> for Error {
     fn from(inner: reqwest::Error) -> Self {
-        Self { inner }
+        Self { inner: ErrorKind::Reqwest(inner) }
+    }
+}
+
+impl From<hyper::Error> for Error {
+    fn from(inner: hyper::Error) -> Self {
+        Self { inner: ErrorKind::Hyper(inner) }
+    }
+}
+
+impl From<io::Error> for Error {
+    fn from(inner: io::Error) -> Self {
+        Self { inner: ErrorKind::Io(inner) }
+    }
+}
+
+impl From<serde_json::Error> for Error {
+    fn from(inner: serde_json::Error) -> Self {
+        Self { inner: ErrorKind::Json(inner) }
+    }
+}
+
+impl From<std::string::FromUtf8Error> for Error {
+    fn from(inner: std::string::FromUtf8Error) -> Self {
+        Self { inner: ErrorKind::Utf8(inner) }
     }
 }
 
@@ -269,3 +410,586 @@ async fn get(url: Ref<str>) -> Result<Response, Error> {
         response: reqwest::get(url.as_ref()).await?,
     })
 }
+
+/// The largest request body the server accepts, in bytes.
+const MAX_BODY_SIZE: usize = 2 * 1024 * 1024;
+
+/// A request waiting for the script to respond to it.
+struct Incoming {
+    request: ServerRequest,
+    respond: oneshot::Sender<hyper::Response<Body>>,
+}
+
+/// A handler registered for a path.
+struct Route {
+    /// The method of the route, or `None` if it matches any method.
+    method: Option<String>,
+    path: String,
+    handler: Function,
+}
+
+/// An HTTP server which serves requests using handler functions.
+///
+/// Connections are accepted on the tokio runtime in the background once the
+/// server has been bound, but requests are only handled while the script is
+/// awaiting [`Server::serve`] or [`Server::serve_one`]. The server stops
+/// accepting connections once it's dropped.
+#[derive(Any)]
+#[rune(item = ::http)]
+struct Server {
+    local_addr: SocketAddr,
+    requests: mpsc::Receiver<Incoming>,
+    routes: Vec<Route>,
+    fallback: Option<Function>,
+    // Dropping the sender shuts down the background server.
+    _shutdown: oneshot::Sender<()>,
+}
+
+/// Hand a request over to the script and wait for its response.
+async fn dispatch(
+    sender: mpsc::Sender<Incoming>,
+    request: hyper::Request<Body>,
+) -> hyper::Response<Body> {
+    let (parts, body) = request.into_parts();
+
+    let body = match read_body(body).await {
+        Ok(body) => body,
+        Err(status) => return empty_response(status),
+    };
+
+    let headers = parts
+        .headers
+        .iter()
+        .flat_map(|(name, value)| Some((name.as_str().to_owned(), value.to_str().ok()?.to_owned())))
+        .collect();
+
+    let request = ServerRequest {
+        method: parts.method.as_str().to_owned(),
+        path: parts.uri.path().to_owned(),
+        query: parts.uri.query().map(str::to_owned),
+        headers,
+        body,
+    };
+
+    let (respond, response) = oneshot::channel();
+
+    if sender.send(Incoming { request, respond }).await.is_err() {
+        return empty_response(HyperStatusCode::SERVICE_UNAVAILABLE);
+    }
+
+    match response.await {
+        Ok(response) => response,
+        Err(..) => empty_response(HyperStatusCode::INTERNAL_SERVER_ERROR),
+    }
+}
+
+/// Read the body of a request, up to [`MAX_BODY_SIZE`] bytes.
+///
+/// On failure, the status to respond with is returned.
+async fn read_body(mut body: Body) -> Result<Vec<u8>, HyperStatusCode> {
+    if body.size_hint().lower() > MAX_BODY_SIZE as u64 {
+        return Err(HyperStatusCode::PAYLOAD_TOO_LARGE);
+    }
+
+    let mut bytes = Vec::new();
+
+    while let Some(chunk) = body.data().await {
+        let Ok(chunk) = chunk else {
+            return Err(HyperStatusCode::BAD_REQUEST);
+        };
+
+        if bytes.len() + chunk.len() > MAX_BODY_SIZE {
+            return Err(HyperStatusCode::PAYLOAD_TOO_LARGE);
+        }
+
+        bytes.extend_from_slice(&chunk);
+    }
+
+    Ok(bytes)
+}
+
+impl Server {
+    /// Bind a server to the given address, such as `"127.0.0.1:8080"`.
+    ///
+    /// Binding to port `0` picks a free port, which can be found with
+    /// `local_addr`.
+    #[rune::function(path = Self::bind)]
+    async fn bind(addr: Ref<str>) -> Result<Self, Error> {
+        let Some(addr) = tokio::net::lookup_host(addr.as_ref()).await?.next() else {
+            return Err(Error::from(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "address did not resolve",
+            )));
+        };
+
+        let (sender, requests) = mpsc::channel(16);
+
+        let make_service = make_service_fn(move |_| {
+            let sender = sender.clone();
+
+            async move {
+                Ok::<_, Infallible>(service_fn(move |request| {
+                    let sender = sender.clone();
+                    async move { Ok::<_, Infallible>(dispatch(sender, request).await) }
+                }))
+            }
+        });
+
+        let server = hyper::Server::try_bind(&addr)?.serve(make_service);
+        let local_addr = server.local_addr();
+        let (shutdown, signal) = oneshot::channel::<()>();
+
+        tokio::spawn(server.with_graceful_shutdown(async move {
+            let _ = signal.await;
+        }));
+
+        Ok(Self {
+            local_addr,
+            requests,
+            routes: Vec::new(),
+            fallback: None,
+            _shutdown: shutdown,
+        })
+    }
+
+    /// The address the server is bound to.
+    #[rune::function]
+    fn local_addr(&self) -> String {
+        self.local_addr.to_string()
+    }
+
+    /// Register a handler for requests with the given method and path.
+    ///
+    /// The method `"*"` matches any method.
+    #[rune::function]
+    fn route(&mut self, method: &str, path: &str, handler: Function) {
+        let method = (method != "*").then(|| method.to_ascii_uppercase());
+
+        self.routes.push(Route {
+            method,
+            path: path.to_owned(),
+            handler,
+        });
+    }
+
+    /// Register a handler for `GET` requests to the given path.
+    #[rune::function]
+    fn get(&mut self, path: &str, handler: Function) {
+        self.routes.push(Route {
+            method: Some(String::from("GET")),
+            path: path.to_owned(),
+            handler,
+        });
+    }
+
+    /// Register a handler for `POST` requests to the given path.
+    #[rune::function]
+    fn post(&mut self, path: &str, handler: Function) {
+        self.routes.push(Route {
+            method: Some(String::from("POST")),
+            path: path.to_owned(),
+            handler,
+        });
+    }
+
+    /// Register a handler for requests which don't match any route.
+    ///
+    /// Without a fallback, such requests get an empty response with status
+    /// 404, or 405 if the path matches a route with a different method.
+    #[rune::function]
+    fn fallback(&mut self, handler: Function) {
+        self.fallback = Some(handler);
+    }
+
+    /// Respond to a single request.
+    async fn handle(&self, incoming: Incoming) -> VmResult<()> {
+        let Incoming { request, respond } = incoming;
+
+        let mut path_matched = false;
+        let mut handler = None;
+
+        for route in &self.routes {
+            if route.path != request.path {
+                continue;
+            }
+
+            path_matched = true;
+
+            if route.method.as_deref().map_or(true, |m| m == request.method) {
+                handler = Some(&route.handler);
+                break;
+            }
+        }
+
+        let Some(handler) = handler.or(self.fallback.as_ref()) else {
+            let status = if path_matched {
+                HyperStatusCode::METHOD_NOT_ALLOWED
+            } else {
+                HyperStatusCode::NOT_FOUND
+            };
+
+            let _ = respond.send(empty_response(status));
+            return VmResult::Ok(());
+        };
+
+        let response = match call_handler(handler, request).await {
+            VmResult::Ok(value) => into_response(value),
+            VmResult::Err(error) => VmResult::Err(error),
+        };
+
+        match response {
+            VmResult::Ok(response) => {
+                let _ = respond.send(response);
+                VmResult::Ok(())
+            }
+            VmResult::Err(error) => {
+                let _ = respond.send(empty_response(HyperStatusCode::INTERNAL_SERVER_ERROR));
+                VmResult::Err(error)
+            }
+        }
+    }
+}
+
+/// Call a handler, awaiting its result if it's an async function.
+async fn call_handler(handler: &Function, request: ServerRequest) -> VmResult<Value> {
+    match rune::vm_try!(handler.call::<_, Value>((request,))) {
+        Value::Future(future) => rune::vm_try!(future.take()).await,
+        value => VmResult::Ok(value),
+    }
+}
+
+/// Serve requests until the server is dropped.
+///
+/// If a handler raises an error, such as a panic, the request is responded
+/// to with status 500 and the server keeps serving.
+#[rune::function(instance, path = serve)]
+async fn server_serve(mut this: Mut<Server>) -> VmResult<()> {
+    while let Some(incoming) = this.requests.recv().await {
+        // NB: the failed request has already been responded to, and one
+        // broken handler shouldn't take down the whole server.
+        let _ = this.handle(incoming).await;
+    }
+
+    VmResult::Ok(())
+}
+
+/// Wait for and serve a single request.
+///
+/// Unlike `serve`, errors raised by the handler are returned after the
+/// request has been responded to with status 500.
+#[rune::function(instance, path = serve_one)]
+async fn server_serve_one(mut this: Mut<Server>) -> VmResult<()> {
+    if let Some(incoming) = this.requests.recv().await {
+        rune::vm_try!(this.handle(incoming).await);
+    }
+
+    VmResult::Ok(())
+}
+
+/// A request received by a [`Server`].
+#[derive(Debug, Any)]
+#[rune(item = ::http)]
+struct ServerRequest {
+    method: String,
+    path: String,
+    query: Option<String>,
+    headers: Vec<(String, String)>,
+    body: Vec<u8>,
+}
+
+impl ServerRequest {
+    /// The method of the request, such as `"GET"`.
+    #[rune::function]
+    fn method(&self) -> String {
+        self.method.clone()
+    }
+
+    /// The path of the request, without the query string.
+    #[rune::function]
+    fn path(&self) -> String {
+        self.path.clone()
+    }
+
+    /// The decoded parameters of the query string.
+    ///
+    /// If a parameter is repeated, the last value is used.
+    #[rune::function]
+    fn query(&self) -> VmResult<HashMap<rune::alloc::String, String>> {
+        let query = self.query.as_deref().unwrap_or_default();
+        let mut params = HashMap::new();
+
+        for (key, value) in form_urlencoded::parse(query.as_bytes()) {
+            let key = rune::vm_try!(rune::alloc::String::try_from(key.as_ref()));
+            params.insert(key, value.into_owned());
+        }
+
+        VmResult::Ok(params)
+    }
+
+    /// Get the value of the header with the given name, ignoring case.
+    #[rune::function]
+    fn header(&self, name: &str) -> Option<String> {
+        self.headers
+            .iter()
+            .find(|(key, _)| key.eq_ignore_ascii_case(name))
+            .map(|(_, value)| value.clone())
+    }
+
+    /// All headers of the request as pairs of lowercase names and values.
+    #[rune::function]
+    fn headers(&self) -> Vec<(String, String)> {
+        self.headers.clone()
+    }
+
+    /// The body of the request.
+    #[rune::function]
+    fn body(&self) -> Bytes {
+        Bytes::from_slice(&self.body)
+    }
+
+    /// The body of the request as a string.
+    #[rune::function]
+    fn text(&self) -> Result<String, Error> {
+        Ok(String::from_utf8(self.body.clone())?)
+    }
+
+    /// The body of the request as a Rune value decoded from JSON.
+    #[rune::function]
+    fn json(&self) -> Result<Value, Error> {
+        Ok(serde_json::from_slice(&self.body)?)
+    }
+}
+
+/// A response returned by a handler of a [`Server`].
+#[derive(Debug, Any)]
+#[rune(item = ::http)]
+struct ServerResponse {
+    status: u16,
+    headers: Vec<(String, String)>,
+    body: Vec<u8>,
+}
+
+impl ServerResponse {
+    /// Construct an empty response with the given status code.
+    ///
+    /// # Examples
+    ///
+    /// ```rune
+    /// let response = http::ServerResponse::new(404);
+    /// ```
+    #[rune::function(path = Self::new)]
+    fn new(status: u16) -> Self {
+        Self {
+            status,
+            headers: Vec::new(),
+            body: Vec::new(),
+        }
+    }
+
+    /// Construct a `text/plain` response with status 200.
+    #[rune::function(keep, path = Self::text)]
+    fn text(body: &str) -> Self {
+        Self {
+            status: 200,
+            headers: vec![(CONTENT_TYPE.to_string(), String::from("text/plain; charset=utf-8"))],
+            body: body.as_bytes().to_vec(),
+        }
+    }
+
+    /// Construct an `application/octet-stream` response with status 200.
+    #[rune::function(keep, path = Self::bytes)]
+    fn bytes(body: Bytes) -> Self {
+        Self {
+            status: 200,
+            headers: vec![(CONTENT_TYPE.to_string(), String::from("application/octet-stream"))],
+            body: body.into_vec(),
+        }
+    }
+
+    /// Construct an `application/json` response with status 200 from a value.
+    #[rune::function(keep, path = Self::json)]
+    fn json(value: Value) -> Result<Self, Error> {
+        Ok(Self {
+            status: 200,
+            headers: vec![(CONTENT_TYPE.to_string(), String::from("application/json"))],
+            body: serde_json::to_vec(&value)?,
+        })
+    }
+
+    /// Set a header in the response.
+    ///
+    /// # Examples
+    ///
+    /// ```rune
+    /// let response = http::ServerResponse::text("hello")
+    ///     .header("x-powered-by", "rune");
+    /// ```
+    #[rune::function]
+    fn header(mut self, key: &str, value: &str) -> Self {
+        self.headers.retain(|(k, _)| !k.eq_ignore_ascii_case(key));
+        self.headers.push((key.to_owned(), value.to_owned()));
+        self
+    }
+
+    /// Set the body of the response from bytes.
+    #[rune::function]
+    fn body_bytes(mut self, bytes: Bytes) -> Self {
+        self.body = bytes.into_vec();
+        self
+    }
+
+    fn build(self) -> hyper::Response<Body> {
+        let Ok(status) = HyperStatusCode::from_u16(self.status) else {
+            return empty_response(HyperStatusCode::INTERNAL_SERVER_ERROR);
+        };
+
+        let mut response = hyper::Response::new(Body::from(self.body));
+        *response.status_mut() = status;
+
+        for (key, value) in self.headers {
+            let (Ok(key), Ok(value)) = (HeaderName::try_from(key), HeaderValue::try_from(value)) else {
+                return empty_response(HyperStatusCode::INTERNAL_SERVER_ERROR);
+            };
+
+            response.headers_mut().insert(key, value);
+        }
+
+        response
+    }
+}
+
+/// Convert the value returned by a handler into a response.
+fn into_response(value: Value) -> VmResult<hyper::Response<Body>> {
+    let response = match value {
+        Value::EmptyTuple => empty_response(HyperStatusCode::NO_CONTENT),
+        Value::String(string) => {
+            ServerResponse::text(&rune::vm_try!(string.borrow_ref())).build()
+        }
+        Value::Bytes(bytes) => {
+            ServerResponse::bytes(rune::vm_try!(bytes.borrow_ref()).clone()).build()
+        }
+        Value::Result(result) => match &*rune::vm_try!(result.borrow_ref()) {
+            Ok(value) => return into_response(value.clone()),
+            Err(..) => empty_response(HyperStatusCode::INTERNAL_SERVER_ERROR),
+        },
+        Value::Any(..) => match rune::from_value::<ServerResponse>(value) {
+            Ok(response) => response.build(),
+            Err(..) => empty_response(HyperStatusCode::INTERNAL_SERVER_ERROR),
+        },
+        value => match ServerResponse::json(value) {
+            Ok(response) => response.build(),
+            Err(..) => empty_response(HyperStatusCode::INTERNAL_SERVER_ERROR),
+        },
+    };
+
+    VmResult::Ok(response)
+}
+
+fn empty_response(status: HyperStatusCode) -> hyper::Response<Body> {
+    let mut response = hyper::Response::new(Body::empty());
+    *response.status_mut() = status;
+    response
+}
+
+#[cfg(test)]
+mod tests {
+    use std::error::Error;
+    use std::sync::Arc;
+
+    use rune::{Context, Vm};
+
+    use super::{Server, MAX_BODY_SIZE};
+
+    #[tokio::test]
+    async fn test_server() -> Result<(), Box<dyn Error>> {
+        let mut context = Context::with_default_modules()?;
+        context.install(super::module(true)?)?;
+
+        let mut sources = rune::sources! {
+            entry => {
+                use http::{Server, ServerResponse};
+
+                fn hello(request) {
+                    let name = request.query().get("name").unwrap_or("world");
+                    format!("Hello {}!", name)
+                }
+
+                async fn echo(request) {
+                    ServerResponse::text(request.text()?).header("x-echo", "yes")
+                }
+
+                fn fail(request) {
+                    panic!("handler failed")
+                }
+
+                pub async fn bind() {
+                    Server::bind("127.0.0.1:0").await?
+                }
+
+                pub async fn serve(server) {
+                    server.get("/hello", hello);
+                    server.post("/echo", echo);
+                    server.route("*", "/fail", fail);
+                    server.serve().await
+                }
+            }
+        };
+
+        let unit = rune::prepare(&mut sources).with_context(&context).build()?;
+        let mut vm = Vm::new(Arc::new(context.runtime()), Arc::new(unit));
+
+        let server: Server = rune::from_value(vm.async_call(["bind"], ()).await?)?;
+        let base = format!("http://{}", server.local_addr);
+
+        let requests = async {
+            let client = reqwest::Client::new();
+
+            let response = client
+                .get(format!("{base}/hello?name=rune"))
+                .send()
+                .await?;
+            assert_eq!(response.status(), 200);
+            assert_eq!(response.text().await?, "Hello rune!");
+
+            let response = client
+                .post(format!("{base}/echo"))
+                .body("ping")
+                .send()
+                .await?;
+            assert_eq!(response.status(), 200);
+            assert_eq!(response.headers()["x-echo"], "yes");
+            assert_eq!(response.text().await?, "ping");
+
+            let response = client.get(format!("{base}/missing")).send().await?;
+            assert_eq!(response.status(), 404);
+
+            let response = client.post(format!("{base}/hello")).send().await?;
+            assert_eq!(response.status(), 405);
+
+            let response = client
+                .post(format!("{base}/echo"))
+                .body(vec![b'a'; MAX_BODY_SIZE + 1])
+                .send()
+                .await?;
+            assert_eq!(response.status(), 413);
+
+            // A failing handler doesn't stop the server.
+            let response = client.get(format!("{base}/fail")).send().await?;
+            assert_eq!(response.status(), 500);
+
+            let response = client.get(format!("{base}/hello")).send().await?;
+            assert_eq!(response.status(), 200);
+            assert_eq!(response.text().await?, "Hello world!");
+            Ok::<_, reqwest::Error>(())
+        };
+
+        tokio::select! {
+            result = vm.async_call(["serve"], (server,)) => {
+                panic!("server stopped: {:?}", result);
+            }
+            result = requests => result?,
+        }
+
+        Ok(())
+    }
+}
@@ -21,25 +21,52 @@
 //! Use it in Rune:
 //!
 //! ```rust,ignore
-//! use process::Command;
+//! use process::{Command, Stdio};
 //!
-//! fn main() {
+//! async fn main() {
 //!     let command = Command::new("ls");
-//!     command.run().await;
+//!     command.current_dir("/tmp");
+//!     command.env("LC_ALL", "C");
+//!     command.stdout(Stdio::piped());
+//!
+//!     let child = command.spawn()?;
+//!     let stdout = child.stdout().unwrap();
+//!
+//!     while let Some(chunk) = stdout.next().await {
+//!         print!("{}", String::from_utf8(chunk?)?);
+//!     }
+//!
+//!     let status = child.wait().await?;
+//!
+//!     if !status.success() {
+//!         println!("ls failed: {}", status);
+//!     }
 //! }
 //! ```
+//!
+//! ## Streaming output
+//!
+//! The output of a child process can be read incrementally, since
+//! `Child::stdout` and `Child::stderr` return streams. Each value produced by
+//! them is a result containing the next chunk of output as bytes, and they
+//! complete once the child has closed its output.
 
-use rune::{Any, Module, ContextError};
-use rune::runtime::{Bytes, Shared, Value, VmResult, Formatter};
-use rune::alloc::TryWrite;
 use std::io;
+use std::process::Stdio as StdStdio;
+
+use rune::alloc::TryWrite;
+use rune::runtime::{Bytes, Formatter, Mut, Shared, Stream, Value, VmResult};
+use rune::{Any, ContextError, Module, Vm};
+use tokio::io::{AsyncBufRead, AsyncBufReadExt, AsyncWriteExt, BufReader};
 use tokio::process;
 
 /// Construct the `process` module.
 pub fn module(_stdio: bool) -> Result<Module, ContextError> {
     let mut module = Module::with_crate("process");
     module.ty::<Command>()?;
+    module.ty::<Stdio>()?;
     module.ty::<Child>()?;
+    module.ty::<ChildStdin>()?;
     module.ty::<ExitStatus>()?;
     module.ty::<Output>()?;
 
@@ -47,9 +74,38 @@ pub fn module(_stdio: bool) -> Result<Module, ContextError> {
     module.function_meta(Command::spawn)?;
     module.function_meta(Command::arg)?;
     module.function_meta(Command::args)?;
+    module.function_meta(Command::env)?;
+    module.function_meta(Command::env_remove)?;
+    module.function_meta(Command::env_clear)?;
+    module.function_meta(Command::current_dir)?;
+    module.function_meta(Command::stdin)?;
+    module.function_meta(Command::stdout)?;
+    module.function_meta(Command::stderr)?;
+    module.function_meta(command_status)?;
+    module.function_meta(command_output)?;
+
+    module.function_meta(Stdio::piped)?;
+    module.function_meta(Stdio::inherit)?;
+    module.function_meta(Stdio::null)?;
+
+    module.function_meta(Child::id)?;
+    module.function_meta(Child::stdin)?;
+    module.function_meta(Child::stdout)?;
+    module.function_meta(Child::stderr)?;
+    module.function_meta(Child::start_kill)?;
+    module.function_meta(Child::try_wait)?;
     module.function_meta(Child::wait_with_output)?;
+    module.function_meta(child_kill)?;
+    module.function_meta(child_wait)?;
+
+    module.function_meta(child_stdin_write)?;
+    module.function_meta(child_stdin_flush)?;
+    module.function_meta(ChildStdin::close)?;
+
     module.function_meta(ExitStatus::string_display)?;
+    module.function_meta(ExitStatus::success)?;
     module.function_meta(ExitStatus::code)?;
+    module.function_meta(ExitStatus::signal)?;
     Ok(module)
 }
 
@@ -91,6 +147,50 @@ impl Command {
         self.inner.arg(arg);
     }
 
+    /// Set an environment variable for the command.
+    #[rune::function(instance)]
+    fn env(&mut self, key: &str, value: &str) {
+        self.inner.env(key, value);
+    }
+
+    /// Remove an environment variable which the command would otherwise
+    /// inherit.
+    #[rune::function(instance)]
+    fn env_remove(&mut self, key: &str) {
+        self.inner.env_remove(key);
+    }
+
+    /// Clear all environment variables, including the ones which the command
+    /// would otherwise inherit.
+    #[rune::function(instance)]
+    fn env_clear(&mut self) {
+        self.inner.env_clear();
+    }
+
+    /// Set the working directory of the command.
+    #[rune::function(instance)]
+    fn current_dir(&mut self, dir: &str) {
+        self.inner.current_dir(dir);
+    }
+
+    /// Configure the standard input of the command.
+    #[rune::function(instance)]
+    fn stdin(&mut self, stdio: &Stdio) {
+        self.inner.stdin(stdio.to_std());
+    }
+
+    /// Configure the standard output of the command.
+    #[rune::function(instance)]
+    fn stdout(&mut self, stdio: &Stdio) {
+        self.inner.stdout(stdio.to_std());
+    }
+
+    /// Configure the standard error of the command.
+    #[rune::function(instance)]
+    fn stderr(&mut self, stdio: &Stdio) {
+        self.inner.stderr(stdio.to_std());
+    }
+
     /// Spawn the command.
     #[rune::function(instance)]
     fn spawn(mut self) -> io::Result<Child> {
@@ -100,6 +200,79 @@ impl Command {
     }
 }
 
+/// Run the command to completion and return its exit status.
+///
+/// Standard input, output and error are inherited unless they have been
+/// configured otherwise.
+#[rune::function(instance, path = status)]
+async fn command_status(mut this: Mut<Command>) -> io::Result<ExitStatus> {
+    let status = this.inner.status().await?;
+    Ok(ExitStatus { status })
+}
+
+/// Run the command to completion and collect its output.
+///
+/// Standard output and error are always captured, regardless of how they have
+/// been configured.
+#[rune::function(instance, path = output)]
+async fn command_output(mut this: Mut<Command>) -> VmResult<io::Result<Output>> {
+    let output = match this.inner.output().await {
+        Ok(output) => output,
+        Err(error) => return VmResult::Ok(Err(error)),
+    };
+
+    VmResult::Ok(Ok(rune::vm_try!(Output::new(output))))
+}
+
+/// Configuration for the standard input, output or error of a [`Command`].
+#[derive(Debug, Clone, Copy, Any)]
+#[rune(item = ::process)]
+struct Stdio {
+    kind: StdioKind,
+}
+
+#[derive(Debug, Clone, Copy)]
+enum StdioKind {
+    Piped,
+    Inherit,
+    Null,
+}
+
+impl Stdio {
+    /// Connect the stream to the parent process through a pipe, which can be
+    /// accessed through `Child::stdin`, `Child::stdout` or `Child::stderr`.
+    #[rune::function(path = Self::piped)]
+    fn piped() -> Self {
+        Self {
+            kind: StdioKind::Piped,
+        }
+    }
+
+    /// Inherit the stream from the parent process.
+    #[rune::function(path = Self::inherit)]
+    fn inherit() -> Self {
+        Self {
+            kind: StdioKind::Inherit,
+        }
+    }
+
+    /// Ignore the stream, like redirecting it to `/dev/null`.
+    #[rune::function(path = Self::null)]
+    fn null() -> Self {
+        Self {
+            kind: StdioKind::Null,
+        }
+    }
+
+    fn to_std(self) -> StdStdio {
+        match self.kind {
+            StdioKind::Piped => StdStdio::piped(),
+            StdioKind::Inherit => StdStdio::inherit(),
+            StdioKind::Null => StdStdio::null(),
+        }
+    }
+}
+
 #[derive(Any)]
 #[rune(item = ::process)]
 struct Child {
@@ -111,6 +284,67 @@ struct Child {
 }
 
 impl Child {
+    fn inner(&mut self) -> VmResult<&mut process::Child> {
+        match &mut self.inner {
+            Some(inner) => VmResult::Ok(inner),
+            None => VmResult::panic("already completed"),
+        }
+    }
+
+    /// The operating system identifier of the child process, or `None` if it
+    /// has been polled to completion.
+    #[rune::function(instance)]
+    fn id(&self) -> Option<u32> {
+        self.inner.as_ref()?.id()
+    }
+
+    /// Take the handle for writing to the standard input of the child.
+    ///
+    /// This is only available if standard input was configured with
+    /// `Stdio::piped()`, and returns `None` if it has already been taken.
+    #[rune::function(instance)]
+    fn stdin(&mut self) -> VmResult<Option<ChildStdin>> {
+        let stdin = rune::vm_try!(self.inner()).stdin.take();
+        VmResult::Ok(stdin.map(|inner| ChildStdin { inner: Some(inner) }))
+    }
+
+    /// Take a stream of the standard output of the child, which produces
+    /// results containing chunks of bytes until the child closes it.
+    ///
+    /// This is only available if standard output was configured with
+    /// `Stdio::piped()`, and returns `None` if it has already been taken.
+    #[rune::function(instance)]
+    fn stdout(&mut self) -> VmResult<Option<Stream<Vm>>> {
+        let stdout = rune::vm_try!(self.inner()).stdout.take();
+        VmResult::Ok(stdout.map(chunks))
+    }
+
+    /// Take a stream of the standard error of the child, which produces
+    /// results containing chunks of bytes until the child closes it.
+    ///
+    /// This is only available if standard error was configured with
+    /// `Stdio::piped()`, and returns `None` if it has already been taken.
+    #[rune::function(instance)]
+    fn stderr(&mut self) -> VmResult<Option<Stream<Vm>>> {
+        let stderr = rune::vm_try!(self.inner()).stderr.take();
+        VmResult::Ok(stderr.map(chunks))
+    }
+
+    /// Ask the operating system to kill the child without waiting for it to
+    /// exit.
+    #[rune::function(instance)]
+    fn start_kill(&mut self) -> VmResult<io::Result<()>> {
+        VmResult::Ok(rune::vm_try!(self.inner()).start_kill())
+    }
+
+    /// Check if the child has exited without waiting for it, returning its
+    /// exit status if it has.
+    #[rune::function(instance)]
+    fn try_wait(&mut self) -> VmResult<io::Result<Option<ExitStatus>>> {
+        let status = rune::vm_try!(self.inner()).try_wait();
+        VmResult::Ok(status.map(|status| status.map(|status| ExitStatus { status })))
+    }
+
     // Returns a future that will resolve to an Output, containing the exit
     // status, stdout, and stderr of the child process.
     #[rune::function(instance)]
@@ -127,14 +361,110 @@ impl Child {
             Err(error) => return VmResult::Ok(Err(error)),
         };
 
-        VmResult::Ok(Ok(Output {
-            status: ExitStatus { status: output.status },
-            stdout: rune::vm_try!(Shared::new(Bytes::from_vec(output.stdout))),
-            stderr: rune::vm_try!(Shared::new(Bytes::from_vec(output.stderr))),
-        }))
+        VmResult::Ok(Ok(rune::vm_try!(Output::new(output))))
     }
 }
 
+/// Kill the child and wait for it to exit.
+#[rune::function(instance, path = kill)]
+async fn child_kill(mut this: Mut<Child>) -> VmResult<io::Result<()>> {
+    VmResult::Ok(rune::vm_try!(this.inner()).kill().await)
+}
+
+/// Wait for the child to exit and return its exit status.
+///
+/// The standard input of the child is closed before waiting, so that it
+/// doesn't wait forever for input.
+#[rune::function(instance, path = wait)]
+async fn child_wait(mut this: Mut<Child>) -> VmResult<io::Result<ExitStatus>> {
+    let status = rune::vm_try!(this.inner()).wait().await;
+    VmResult::Ok(status.map(|status| ExitStatus { status }))
+}
+
+/// A handle for writing to the standard input of a child process.
+#[derive(Any)]
+#[rune(item = ::process)]
+struct ChildStdin {
+    inner: Option<process::ChildStdin>,
+}
+
+impl ChildStdin {
+    fn inner(&mut self) -> io::Result<&mut process::ChildStdin> {
+        match &mut self.inner {
+            Some(inner) => Ok(inner),
+            None => Err(io::Error::new(io::ErrorKind::BrokenPipe, "stdin is closed")),
+        }
+    }
+
+    /// Close the standard input of the child, signalling that no more input
+    /// will be written.
+    #[rune::function(instance)]
+    fn close(&mut self) {
+        self.inner = None;
+    }
+}
+
+/// Write a string or bytes to the standard input of the child.
+#[rune::function(instance, path = write)]
+async fn child_stdin_write(mut this: Mut<ChildStdin>, contents: Value) -> VmResult<io::Result<()>> {
+    let contents = match contents {
+        Value::String(string) => rune::vm_try!(string.borrow_ref()).as_bytes().to_vec(),
+        Value::Bytes(bytes) => rune::vm_try!(bytes.borrow_ref()).to_vec(),
+        actual => return VmResult::expected::<String>(rune::vm_try!(actual.type_info())),
+    };
+
+    let inner = match this.inner() {
+        Ok(inner) => inner,
+        Err(error) => return VmResult::Ok(Err(error)),
+    };
+
+    VmResult::Ok(inner.write_all(&contents).await)
+}
+
+/// Flush any buffered input to the child.
+#[rune::function(instance, path = flush)]
+async fn child_stdin_flush(mut this: Mut<ChildStdin>) -> io::Result<()> {
+    this.inner()?.flush().await
+}
+
+/// Construct a stream which reads the given output in chunks.
+///
+/// The stream completes after the first error, which it produces as a value.
+fn chunks<R>(reader: R) -> Stream<Vm>
+where
+    R: tokio::io::AsyncRead + Unpin + 'static,
+{
+    let stream = futures_util::stream::unfold(Some(BufReader::new(reader)), |reader| async move {
+        let mut reader = reader?;
+
+        let (chunk, reader) = match read_chunk(&mut reader).await {
+            Ok(Some(chunk)) => (Ok(chunk), Some(reader)),
+            Ok(None) => return None,
+            Err(error) => (Err(error), None),
+        };
+
+        Some((VmResult::Ok(chunk), reader))
+    });
+
+    Stream::from_stream(stream)
+}
+
+async fn read_chunk<R>(reader: &mut R) -> io::Result<Option<Bytes>>
+where
+    R: AsyncBufRead + Unpin,
+{
+    let chunk = reader.fill_buf().await?;
+
+    if chunk.is_empty() {
+        return Ok(None);
+    }
+
+    let bytes = Bytes::from_slice(chunk);
+    let len = chunk.len();
+    reader.consume(len);
+    Ok(Some(bytes))
+}
+
 #[derive(Any)]
 #[rune(item = ::process)]
 struct Output {
@@ -146,6 +476,18 @@ struct Output {
     stderr: Shared<Bytes>,
 }
 
+impl Output {
+    fn new(output: std::process::Output) -> VmResult<Self> {
+        VmResult::Ok(Self {
+            status: ExitStatus {
+                status: output.status,
+            },
+            stdout: rune::vm_try!(Shared::new(Bytes::from_vec(output.stdout))),
+            stderr: rune::vm_try!(Shared::new(Bytes::from_vec(output.stderr))),
+        })
+    }
+}
+
 #[derive(Clone, Copy, Any)]
 #[rune(item = ::process)]
 struct ExitStatus {
@@ -159,8 +501,179 @@ impl ExitStatus {
         VmResult::Ok(())
     }
 
+    /// Test if the process exited successfully, which means that it exited
+    /// with a zero exit code.
+    #[rune::function]
+    fn success(&self) -> bool {
+        self.status.success()
+    }
+
+    /// The exit code of the process, or `None` if it was terminated by a
+    /// signal.
     #[rune::function]
     fn code(&self) -> Option<i32> {
         self.status.code()
     }
+
+    /// The signal which terminated the process, or `None` if it exited
+    /// normally. This is always `None` on platforms without signals.
+    #[rune::function]
+    fn signal(&self) -> Option<i32> {
+        #[cfg(unix)]
+        {
+            std::os::unix::process::ExitStatusExt::signal(&self.status)
+        }
+
+        #[cfg(not(unix))]
+        {
+            None
+        }
+    }
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use std::error::Error;
+    use std::sync::Arc;
+
+    use rune::{Context, FromValue, Source, Sources, Vm};
+
+    /// Run the `main` function of the given script.
+    async fn run<T>(source: &str) -> Result<T, Box<dyn Error>>
+    where
+        T: FromValue,
+    {
+        let mut context = Context::with_default_modules()?;
+        context.install(super::module(true)?)?;
+
+        let mut sources = Sources::new();
+        sources.insert(Source::memory(source));
+
+        let unit = rune::prepare(&mut sources).with_context(&context).build()?;
+        let mut vm = Vm::new(Arc::new(context.runtime()), Arc::new(unit));
+        Ok(rune::from_value(vm.async_call(["main"], ()).await?)?)
+    }
+
+    #[tokio::test]
+    async fn test_output() -> Result<(), Box<dyn Error>> {
+        let output: (bool, String, String) = run(r#"
+            use process::Command;
+
+            pub async fn main() {
+                let command = Command::new("sh");
+                command.args(["-c", "printf '%s %s' \"$GREETING\" \"$(pwd)\"; printf oops >&2"]);
+                command.env("GREETING", "hello");
+                command.current_dir("/");
+
+                let output = command.output().await?;
+                (output.status.success(), String::from_utf8(output.stdout)?, String::from_utf8(output.stderr)?)
+            }
+        "#)
+        .await?;
+
+        assert_eq!(output, (true, String::from("hello /"), String::from("oops")));
+
+        let output: String = run(r#"
+            use process::Command;
+
+            pub async fn main() {
+                let command = Command::new("sh");
+                command.args(["-c", "printf '%s' \"${HOME:-none}\""]);
+                command.env_clear();
+
+                let output = command.output().await?;
+                String::from_utf8(output.stdout)?
+            }
+        "#)
+        .await?;
+
+        assert_eq!(output, "none");
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_status() -> Result<(), Box<dyn Error>> {
+        let status: (bool, Option<i64>, Option<i64>, String) = run(r#"
+            use process::Command;
+
+            pub async fn main() {
+                let command = Command::new("sh");
+                command.args(["-c", "exit 3"]);
+
+                let status = command.status().await?;
+                (status.success(), status.code(), status.signal(), `${status}`)
+            }
+        "#)
+        .await?;
+
+        assert_eq!(status, (false, Some(3), None, String::from("exit status: 3")));
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_stdin_and_stdout() -> Result<(), Box<dyn Error>> {
+        let output: (bool, bool, String) = run(r#"
+            use process::{Command, Stdio};
+
+            pub async fn main() {
+                let command = Command::new("cat");
+                command.stdin(Stdio::piped());
+                command.stdout(Stdio::piped());
+                command.stderr(Stdio::null());
+
+                let child = command.spawn()?;
+                assert!(child.stderr().is_none());
+                let stdin = child.stdin().unwrap();
+                stdin.write("hello ").await?;
+                stdin.write(b"world").await?;
+                stdin.flush().await?;
+                stdin.close();
+
+                let stdout = child.stdout().unwrap();
+                let taken = child.stdout().is_none();
+                let output = String::new();
+
+                while let Some(chunk) = stdout.next().await {
+                    output.push_str(String::from_utf8(chunk?)?);
+                }
+
+                let status = child.wait().await?;
+                (status.success(), taken, output)
+            }
+        "#)
+        .await?;
+
+        assert_eq!(output, (true, true, String::from("hello world")));
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_kill() -> Result<(), Box<dyn Error>> {
+        let output: (bool, bool, Option<i64>, Option<i64>, Option<i64>) = run(r#"
+            use process::Command;
+
+            pub async fn main() {
+                let command = Command::new("sleep");
+                command.arg("10");
+
+                let child = command.spawn()?;
+                let running = child.try_wait()?.is_none();
+                child.kill().await?;
+                let status = child.wait().await?;
+
+                let command = Command::new("sleep");
+                command.arg("10");
+
+                let child = command.spawn()?;
+                child.start_kill()?;
+                let signal = child.wait().await?.signal();
+
+                (running, status.success(), status.code(), status.signal(), signal)
+            }
+        "#)
+        .await?;
+
+        assert_eq!(output, (true, false, None, Some(9), Some(9)));
+        Ok(())
+    }
 }
@@ -570,7 +570,7 @@ fn chain(p: &mut Parser<'_>, mut expr: Expr, callable: Callable) -> Result<Expr>
             }
             // Chained function call.
             K!['('] if is_callable => {
-                let args = p.parse::<ast::Parenthesized<Expr, T![,]>>()?;
+                let args = p.parse::<ast::Parenthesized<ast::CallArg, T![,]>>()?;
 
                 expr = Expr::Call(ast::ExprCall {
                     id: Default::default(),
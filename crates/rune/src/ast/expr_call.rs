@@ -6,6 +6,14 @@ fn ast_parse() {
 
     rt::<ast::ExprCall>("test()");
     rt::<ast::ExprCall>("(foo::bar)()");
+    rt::<ast::ExprCall>("connect(host, port: 9000)");
+    rt::<ast::ExprCall>("connect(host: \"localhost\", port: a::b)");
+
+    let arg = rt::<ast::CallArg>("port: 9000");
+    assert!(arg.name.is_some());
+
+    let arg = rt::<ast::CallArg>("port::DEFAULT");
+    assert!(arg.name.is_none());
 }
 
 /// A call expression.
@@ -23,7 +31,35 @@ pub struct ExprCall {
     /// The name of the function being called.
     pub expr: Box<ast::Expr>,
     /// The arguments of the function call.
-    pub args: ast::Parenthesized<ast::Expr, T![,]>,
+    pub args: ast::Parenthesized<ast::CallArg, T![,]>,
 }
 
 expr_parse!(Call, ExprCall, "call expression");
+
+/// A single argument in a call expression.
+///
+/// * `<expr>`.
+/// * `<ident>: <expr>`.
+#[derive(Debug, Clone, PartialEq, Eq, ToTokens, Spanned)]
+#[non_exhaustive]
+pub struct CallArg {
+    /// The name of the argument if it is passed by name.
+    #[rune(iter)]
+    pub name: Option<(ast::Ident, T![:])>,
+    /// The value of the argument.
+    pub expr: ast::Expr,
+}
+
+impl Parse for CallArg {
+    fn parse(p: &mut Parser<'_>) -> Result<Self> {
+        let name = match (p.nth(0)?, p.nth(1)?) {
+            (K![ident], K![:]) => Some((p.parse()?, p.parse()?)),
+            _ => None,
+        };
+
+        Ok(Self {
+            name,
+            expr: p.parse()?,
+        })
+    }
+}
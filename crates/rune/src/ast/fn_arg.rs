@@ -7,23 +7,95 @@ fn ast_parse() {
     rt::<ast::FnArg>("self");
     rt::<ast::FnArg>("_");
     rt::<ast::FnArg>("abc");
+    rt::<ast::FnArg>("abc: i64");
+    rt::<ast::FnArg>("(a, b): (i64, String)");
+
+    let arg = rt::<ast::FnArg>("abc: std::string::String");
+    assert!(matches!(arg, ast::FnArg::Typed(..)));
+
+    let arg = rt::<ast::FnArg>("port = 8080");
+    assert!(matches!(arg, ast::FnArg::Default(..)));
+
+    let arg = rt::<ast::FnArg>("port: i64 = 8080");
+    assert!(matches!(arg, ast::FnArg::Default(..)));
 }
 
 /// A single argument in a closure.
 #[derive(Debug, Clone, PartialEq, Eq, ToTokens, Spanned)]
 #[non_exhaustive]
+#[allow(clippy::large_enum_variant)]
 pub enum FnArg {
     /// The `self` parameter.
     SelfValue(T![self]),
     /// Function argument is a pattern binding.
     Pat(ast::Pat),
+    /// Function argument is a pattern binding with a type annotation.
+    Typed(ast::PatType),
+    /// Function argument with a default value.
+    Default(ast::FnArgDefault),
 }
 
-impl Parse for FnArg {
-    fn parse(p: &mut Parser<'_>) -> Result<Self> {
+/// A function argument with a default value.
+///
+/// * `<pat> = <expr>`.
+/// * `<pat>: <type> = <expr>`.
+#[derive(Debug, Clone, PartialEq, Eq, ToTokens, Spanned)]
+#[non_exhaustive]
+pub struct FnArgDefault {
+    /// The pattern being bound.
+    pub pat: ast::Pat,
+    /// The optional type annotation of the argument.
+    #[rune(iter)]
+    pub ty: Option<(T![:], ast::Type)>,
+    /// The equals sign.
+    pub eq: T![=],
+    /// The default value of the argument.
+    pub value: ast::Expr,
+}
+
+impl FnArg {
+    /// Parse a closure argument, which does not support default values.
+    ///
+    /// The closing `|` of a closure would otherwise be parsed as part of the
+    /// default value expression.
+    pub(crate) fn parse_closure(p: &mut Parser<'_>) -> Result<Self> {
+        Self::parse_with_default(p, false)
+    }
+
+    fn parse_with_default(p: &mut Parser<'_>, default: bool) -> Result<Self> {
         Ok(match p.nth(0)? {
             K![self] => Self::SelfValue(p.parse()?),
-            _ => Self::Pat(p.parse()?),
+            _ => {
+                let pat = ast::Pat::parse_without_or(p)?;
+                let ty = p.parse::<Option<(T![:], ast::Type)>>()?;
+
+                if let Some(eq) = p.parse::<Option<T![=]>>()? {
+                    if !default {
+                        return Err(compile::Error::new(
+                            eq,
+                            ErrorKind::UnsupportedArgumentDefault,
+                        ));
+                    }
+
+                    return Ok(Self::Default(ast::FnArgDefault {
+                        pat,
+                        ty,
+                        eq,
+                        value: p.parse()?,
+                    }));
+                }
+
+                match ty {
+                    Some((colon, ty)) => Self::Typed(ast::PatType { pat, colon, ty }),
+                    None => Self::Pat(pat),
+                }
+            }
         })
     }
 }
+
+impl Parse for FnArg {
+    fn parse(p: &mut Parser<'_>) -> Result<Self> {
+        Self::parse_with_default(p, true)
+    }
+}
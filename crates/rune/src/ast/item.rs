@@ -19,6 +19,8 @@ pub enum Item {
     Struct(ast::ItemStruct),
     /// An impl declaration.
     Impl(ast::ItemImpl),
+    /// A trait declaration.
+    Trait(ast::ItemTrait),
     /// A module declaration.
     Mod(ast::ItemMod),
     /// A const declaration.
@@ -36,6 +38,7 @@ impl Item {
             Self::Enum(item) => &item.attributes,
             Self::Struct(item) => &item.attributes,
             Self::Impl(item) => &item.attributes,
+            Self::Trait(item) => &item.attributes,
             Self::Mod(item) => &item.attributes,
             Self::Const(item) => &item.attributes,
             Self::MacroCall(item) => &item.attributes,
@@ -49,6 +52,7 @@ impl Item {
             Self::Enum(item) => &mut item.attributes,
             Self::Struct(item) => &mut item.attributes,
             Self::Impl(item) => &mut item.attributes,
+            Self::Trait(item) => &mut item.attributes,
             Self::Mod(item) => &mut item.attributes,
             Self::Const(item) => &mut item.attributes,
             Self::MacroCall(item) => &mut item.attributes,
@@ -72,6 +76,7 @@ impl Item {
             K![enum] => true,
             K![struct] => true,
             K![impl] => true,
+            K![trait] => true,
             K![async] => matches!(p.nth(1), K![fn]),
             K![fn] => true,
             K![mod] => true,
@@ -117,6 +122,11 @@ impl Item {
                     p,
                     take(&mut attributes),
                 )?),
+                K![trait] => Self::Trait(ast::ItemTrait::parse_with_meta(
+                    p,
+                    take(&mut attributes),
+                    take(&mut visibility),
+                )?),
                 K![fn] => Self::Fn(ast::ItemFn::parse_with_meta(
                     p,
                     take(&mut attributes),
@@ -144,7 +154,7 @@ impl Item {
                 _ => {
                     return Err(compile::Error::expected(
                         p.tok_at(0)?,
-                        "`fn`, `mod`, `struct`, `enum`, `use`, or macro call",
+                        "`fn`, `mod`, `struct`, `enum`, `trait`, `use`, or macro call",
                     ))
                 }
             };
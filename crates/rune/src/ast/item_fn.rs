@@ -36,6 +36,14 @@ fn ast_parse() {
     assert_eq!(item.attributes.len(), 1);
     assert!(item.async_token.is_none());
     assert!(item.const_token.is_some());
+
+    let item = rt::<ast::ItemFn>("fn hello(foo: i64, bar) -> String {}");
+    assert_eq!(item.args.len(), 2);
+    assert!(matches!(
+        &item.args.first(),
+        Some((ast::FnArg::Typed(..), _))
+    ));
+    assert!(item.output.is_some());
 }
 
 /// A function item.
@@ -64,6 +72,9 @@ pub struct ItemFn {
     pub name: ast::Ident,
     /// The arguments of the function.
     pub args: ast::Parenthesized<ast::FnArg, T![,]>,
+    /// The optional return type of the function.
+    #[rune(iter)]
+    pub output: Option<(T![->], ast::Type)>,
     /// The body of the function.
     pub body: ast::Block,
 }
@@ -72,10 +83,15 @@ impl ItemFn {
     /// Get the descriptive span of this item, e.g. `pub fn foo()` instead of
     /// the span for the whole function declaration, body included.
     pub(crate) fn descriptive_span(&self) -> Span {
+        let end = match &self.output {
+            Some((_, ty)) => ty.span(),
+            None => self.args.span(),
+        };
+
         if let Some(async_token) = &self.async_token {
-            async_token.span().join(self.args.span())
+            async_token.span().join(end)
         } else {
-            self.fn_token.span().join(self.args.span())
+            self.fn_token.span().join(end)
         }
     }
 
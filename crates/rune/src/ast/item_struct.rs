@@ -8,6 +8,7 @@ fn ast_parse() {
     rt::<ast::ItemStruct>("struct Foo ( a, b, c )");
     rt::<ast::ItemStruct>("struct Foo { a, b, c }");
     rt::<ast::ItemStruct>("struct Foo { #[default_value = 1] a, b, c }");
+    rt::<ast::ItemStruct>("struct Foo { a: i64, b: std::string::String, c }");
     rt::<ast::ItemStruct>("#[alpha] struct Foo ( #[default_value = \"x\" ] a, b, c )");
 
     rt::<ast::Fields>("");
@@ -24,6 +25,7 @@ fn ast_parse() {
 
     rt::<ast::Field>("a");
     rt::<ast::Field>("#[x] a");
+    rt::<ast::Field>("#[x] a: i64");
 }
 
 /// A struct item.
@@ -70,4 +72,7 @@ pub struct Field {
     pub visibility: ast::Visibility,
     /// Name of the field.
     pub name: ast::Ident,
+    /// The optional type annotation of the field.
+    #[rune(iter)]
+    pub ty: Option<(T![:], ast::Type)>,
 }
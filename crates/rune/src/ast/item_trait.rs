@@ -0,0 +1,166 @@
+use crate::ast::prelude::*;
+
+#[test]
+fn ast_parse() {
+    use crate::testing::rt;
+
+    rt::<ast::ItemTrait>("trait Foo {}");
+    rt::<ast::ItemTrait>("pub trait Foo { fn test(self); }");
+    rt::<ast::ItemTrait>("trait Foo { fn test(self); fn other(self) { self.test() } }");
+    rt::<ast::ItemTrait>("trait Foo { async fn test(self, a, b); fn new(); }");
+    rt::<ast::ItemTrait>("trait Foo { fn test(self, a: i64) -> String; fn new() -> Foo {} }");
+    rt::<ast::ItemTrait>("#[doc = \"x\"] trait Foo { #[doc = \"y\"] fn test(self); }");
+
+    let item = rt::<ast::ItemTrait>("trait Foo { fn a(self); fn b(self) {} }");
+    assert!(matches!(&item.items[0], ast::TraitItem::Required(..)));
+    assert!(matches!(&item.items[1], ast::TraitItem::Provided(..)));
+}
+
+/// A trait item.
+///
+/// * `trait <ident> { <items>* }`.
+#[derive(Debug, Clone, PartialEq, Eq, ToTokens, Spanned)]
+#[non_exhaustive]
+pub struct ItemTrait {
+    /// The attributes of the trait.
+    #[rune(iter)]
+    pub attributes: Vec<ast::Attribute>,
+    /// The visibility of the trait.
+    #[rune(option)]
+    pub visibility: ast::Visibility,
+    /// The `trait` keyword.
+    pub trait_token: T![trait],
+    /// The name of the trait.
+    pub ident: ast::Ident,
+    /// The open brace.
+    pub open: T!['{'],
+    /// The functions declared by the trait.
+    #[rune(iter)]
+    pub items: Vec<TraitItem>,
+    /// The close brace.
+    pub close: T!['}'],
+}
+
+impl ItemTrait {
+    /// Parse a `trait` item with the given meta.
+    pub(crate) fn parse_with_meta(
+        parser: &mut Parser<'_>,
+        attributes: Vec<ast::Attribute>,
+        visibility: ast::Visibility,
+    ) -> Result<Self> {
+        let trait_token = parser.parse()?;
+        let ident = parser.parse()?;
+        let open = parser.parse()?;
+
+        let mut items = Vec::new();
+
+        while !parser.peek::<ast::CloseBrace>()? {
+            items.push(parser.parse()?);
+        }
+
+        let close = parser.parse()?;
+
+        Ok(Self {
+            attributes,
+            visibility,
+            trait_token,
+            ident,
+            open,
+            items,
+            close,
+        })
+    }
+}
+
+item_parse!(Trait, ItemTrait, "trait item");
+
+/// A function declared in a trait.
+#[derive(Debug, Clone, PartialEq, Eq, ToTokens, Spanned)]
+#[non_exhaustive]
+pub enum TraitItem {
+    /// A function which has to be implemented, like `fn test(self);`.
+    Required(TraitFn),
+    /// A function with a default implementation.
+    Provided(ast::ItemFn),
+}
+
+impl TraitItem {
+    /// The name of the function.
+    pub(crate) fn name(&self) -> &ast::Ident {
+        match self {
+            TraitItem::Required(f) => &f.name,
+            TraitItem::Provided(f) => &f.name,
+        }
+    }
+
+    /// The arguments of the function.
+    pub(crate) fn args(&self) -> &ast::Parenthesized<ast::FnArg, T![,]> {
+        match self {
+            TraitItem::Required(f) => &f.args,
+            TraitItem::Provided(f) => &f.args,
+        }
+    }
+}
+
+impl Parse for TraitItem {
+    fn parse(p: &mut Parser<'_>) -> Result<Self> {
+        let attributes = p.parse()?;
+        let async_token = p.parse()?;
+        let fn_token = p.parse()?;
+        let name = p.parse()?;
+        let args = p.parse()?;
+        let output = p.parse()?;
+
+        if let Some(semi) = p.parse()? {
+            return Ok(TraitItem::Required(TraitFn {
+                attributes,
+                async_token,
+                fn_token,
+                name,
+                args,
+                output,
+                semi,
+            }));
+        }
+
+        let body = p.parse()?;
+
+        Ok(TraitItem::Provided(ast::ItemFn {
+            id: Default::default(),
+            attributes,
+            visibility: ast::Visibility::Inherited,
+            const_token: None,
+            async_token,
+            fn_token,
+            name,
+            args,
+            output,
+            body,
+        }))
+    }
+}
+
+/// The declaration of a function in a trait without a default implementation.
+///
+/// * `fn <name>(<args>);`.
+#[derive(Debug, Clone, PartialEq, Eq, ToTokens, Spanned)]
+#[non_exhaustive]
+pub struct TraitFn {
+    /// The attributes of the function.
+    #[rune(iter)]
+    pub attributes: Vec<ast::Attribute>,
+    /// The optional `async` keyword.
+    #[rune(iter)]
+    pub async_token: Option<T![async]>,
+    /// The `fn` token.
+    pub fn_token: T![fn],
+    /// The name of the function.
+    pub name: ast::Ident,
+    /// The arguments of the function.
+    pub args: ast::Parenthesized<ast::FnArg, T![,]>,
+    /// The optional return type of the function.
+    #[rune(iter)]
+    pub output: Option<(T![->], ast::Type)>,
+    /// The terminating semicolon.
+    pub semi: T![;],
+}
@@ -10,6 +10,7 @@ fn ast_parse() {
     rt::<ast::LitNumber>("42.42");
     rt::<ast::LitNumber>("0.42");
     rt::<ast::LitNumber>("0.42e10");
+    rt::<ast::LitNumber>("42n");
 }
 
 /// A number literal.
@@ -80,6 +81,7 @@ impl<'a> Resolve<'a> for LitNumber {
             "i64" => Some(ast::NumberSuffix::Int(text.suffix)),
             "f64" => Some(ast::NumberSuffix::Float(text.suffix)),
             "u8" => Some(ast::NumberSuffix::Byte(text.suffix)),
+            "n" => Some(ast::NumberSuffix::BigInt(text.suffix)),
             "" => None,
             _ => {
                 return Err(compile::Error::new(
@@ -1,3 +1,5 @@
+use core::iter;
+
 use crate::ast::prelude::*;
 
 #[test]
@@ -16,6 +18,19 @@ fn ast_parse() {
     rt::<ast::Pat>("var");
     rt::<ast::Pat>("_");
     rt::<ast::Pat>("Foo(n)");
+    rt::<ast::Pat>("1 | 2 | 3");
+    rt::<ast::Pat>("Some(1 | 2) | None");
+    rt::<ast::Pat>("(a, 'a' | 'b')");
+    rt::<ast::Pat>("1..10");
+    rt::<ast::Pat>("-10..=10");
+    rt::<ast::Pat>("'a'..='z'");
+    rt::<ast::Pat>("b'0'..=b'9'");
+    rt::<ast::Pat>("10..");
+    rt::<ast::Pat>("..=10");
+    rt::<ast::Pat>("[1..=3, ..]");
+
+    let pat = rt::<ast::Pat>("'a' | 'b'..='z'");
+    assert!(matches!(&pat, ast::Pat::Or(or) if matches!(&or.rest[0].1, ast::Pat::Range(..))));
 }
 
 /// A pattern match.
@@ -38,24 +53,66 @@ pub enum Pat {
     Binding(PatBinding),
     /// The rest pattern `..`.
     Rest(PatRest),
+    /// An or-pattern `a | b`.
+    Or(PatOr),
+    /// A range pattern `a..b` or `a..=b`.
+    Range(PatRange),
 }
 
 impl Parse for Pat {
     fn parse(p: &mut Parser<'_>) -> Result<Self> {
+        Self::parse_with(p, true)
+    }
+}
+
+impl Pat {
+    /// Parse a pattern which is not a binding like `a: pattern`.
+    ///
+    /// This is used for `let` statements, where a `:` introduces a type
+    /// annotation.
+    pub(crate) fn parse_without_binding(p: &mut Parser<'_>) -> Result<Self> {
+        Self::parse_with(p, false)
+    }
+
+    /// Parse a pattern which is neither an or-pattern nor a binding.
+    ///
+    /// This is used for function and closure arguments, where a `|` would be
+    /// ambiguous with the end of the closure arguments and a `:` introduces a
+    /// type annotation.
+    pub(crate) fn parse_without_or(p: &mut Parser<'_>) -> Result<Self> {
+        Self::parse_single(p, false)
+    }
+
+    fn parse_with(p: &mut Parser<'_>, binding: bool) -> Result<Self> {
+        let first = Self::parse_single(p, binding)?;
+
+        if !p.peek::<T![|]>()? {
+            return Ok(first);
+        }
+
+        let mut rest = Vec::new();
+
+        while let Some(pipe) = p.parse::<Option<T![|]>>()? {
+            rest.push((pipe, Self::parse_single(p, binding)?));
+        }
+
+        Ok(Self::Or(PatOr {
+            first: Box::new(first),
+            rest,
+        }))
+    }
+
+    fn parse_single(p: &mut Parser<'_>, binding: bool) -> Result<Self> {
         let attributes = p.parse::<Vec<ast::Attribute>>()?;
 
         match p.nth(0)? {
             K![byte] => {
-                return Ok(Self::Lit(PatLit {
-                    attributes,
-                    expr: Box::new(ast::Expr::from_lit(ast::Lit::Byte(p.parse()?))),
-                }));
+                let expr = Box::new(ast::Expr::from_lit(ast::Lit::Byte(p.parse()?)));
+                return Self::parse_lit_or_range(p, attributes, expr);
             }
             K![char] => {
-                return Ok(Self::Lit(PatLit {
-                    attributes,
-                    expr: Box::new(ast::Expr::from_lit(ast::Lit::Char(p.parse()?))),
-                }));
+                let expr = Box::new(ast::Expr::from_lit(ast::Lit::Char(p.parse()?)));
+                return Self::parse_lit_or_range(p, attributes, expr);
             }
             K![bytestr] => {
                 return Ok(Self::Lit(PatLit {
@@ -71,7 +128,7 @@ impl Parse for Pat {
             }
             K![str] => {
                 return Ok(match p.nth(1)? {
-                    K![:] => Self::Binding(PatBinding {
+                    K![:] if binding => Self::Binding(PatBinding {
                         attributes,
                         key: ast::ObjectKey::LitStr(p.parse()?),
                         colon: p.parse()?,
@@ -84,10 +141,8 @@ impl Parse for Pat {
                 });
             }
             K![number] => {
-                return Ok(Self::Lit(PatLit {
-                    attributes,
-                    expr: Box::new(ast::Expr::from_lit(ast::Lit::Number(p.parse()?))),
-                }));
+                let expr = Box::new(ast::Expr::from_lit(ast::Lit::Number(p.parse()?)));
+                return Self::parse_lit_or_range(p, attributes, expr);
             }
             K![..] => {
                 return Ok(Self::Rest(PatRest {
@@ -120,15 +175,25 @@ impl Parse for Pat {
                 }))
             }
             K![-] => {
-                let expr: ast::Expr = p.parse()?;
+                let expr = ast::Expr::parse_with(
+                    p,
+                    ast::expr::EAGER_BRACE,
+                    ast::expr::NOT_EAGER_BINARY,
+                    ast::expr::CALLABLE,
+                )?;
 
                 if expr.is_lit() {
-                    return Ok(Self::Lit(PatLit {
-                        attributes,
-                        expr: Box::new(expr),
-                    }));
+                    return Self::parse_lit_or_range(p, attributes, Box::new(expr));
                 }
             }
+            K![..=] => {
+                return Ok(Self::Range(PatRange {
+                    attributes,
+                    start: None,
+                    limits: ast::ExprRangeLimits::Closed(p.parse()?),
+                    end: Some(parse_range_bound(p)?),
+                }))
+            }
             K![_] => {
                 return Ok(Self::Ignore(PatIgnore {
                     attributes,
@@ -149,7 +214,7 @@ impl Parse for Pat {
                         ident: ast::ObjectIdent::Named(path),
                         items: p.parse()?,
                     }),
-                    K![:] => Self::Binding(PatBinding {
+                    K![:] if binding => Self::Binding(PatBinding {
                         attributes,
                         key: ast::ObjectKey::Path(path),
                         colon: p.parse()?,
@@ -163,6 +228,64 @@ impl Parse for Pat {
 
         Err(compile::Error::expected(p.tok_at(0)?, "pattern"))
     }
+
+    /// Parse the remainder of a literal pattern, which might be the start of a
+    /// range pattern.
+    fn parse_lit_or_range(
+        p: &mut Parser<'_>,
+        attributes: Vec<ast::Attribute>,
+        expr: Box<ast::Expr>,
+    ) -> Result<Self> {
+        let limits = match p.nth(0)? {
+            K![..] => ast::ExprRangeLimits::HalfOpen(p.parse()?),
+            K![..=] => ast::ExprRangeLimits::Closed(p.parse()?),
+            _ => return Ok(Self::Lit(PatLit { attributes, expr })),
+        };
+
+        let end = match limits {
+            ast::ExprRangeLimits::Closed(..) => Some(parse_range_bound(p)?),
+            ast::ExprRangeLimits::HalfOpen(..) => {
+                if matches!(p.nth(0)?, K![byte] | K![char] | K![number] | K![-]) {
+                    Some(parse_range_bound(p)?)
+                } else {
+                    None
+                }
+            }
+        };
+
+        Ok(Self::Range(PatRange {
+            attributes,
+            start: Some(expr),
+            limits,
+            end,
+        }))
+    }
+}
+
+/// Parse the bound of a range pattern, which has to be a literal.
+fn parse_range_bound(p: &mut Parser<'_>) -> Result<Box<ast::Expr>> {
+    let expr = match p.nth(0)? {
+        K![byte] => ast::Expr::from_lit(ast::Lit::Byte(p.parse()?)),
+        K![char] => ast::Expr::from_lit(ast::Lit::Char(p.parse()?)),
+        K![number] => ast::Expr::from_lit(ast::Lit::Number(p.parse()?)),
+        K![-] => {
+            let expr = ast::Expr::parse_with(
+                p,
+                ast::expr::EAGER_BRACE,
+                ast::expr::NOT_EAGER_BINARY,
+                ast::expr::CALLABLE,
+            )?;
+
+            if !expr.is_lit() {
+                return Err(compile::Error::new(expr, ErrorKind::UnsupportedPatternExpr));
+            }
+
+            expr
+        }
+        _ => return Err(compile::Error::expected(p.tok_at(0)?, "literal")),
+    };
+
+    Ok(Box::new(expr))
 }
 
 impl Peek for Pat {
@@ -173,6 +296,7 @@ impl Peek for Pat {
             K![#] => matches!(p.nth(1), K!['{']),
             K![_] => true,
             K![..] => true,
+            K![..=] => true,
             K![byte] | K![char] | K![number] | K![str] => true,
             K![true] | K![false] => true,
             K![-] => matches!(p.nth(1), K![number]),
@@ -192,6 +316,53 @@ pub struct PatLit {
     pub expr: Box<ast::Expr>,
 }
 
+/// An or-pattern, matching if any of its alternatives match.
+///
+/// * `<pat> | <pat>`.
+#[derive(Debug, Clone, PartialEq, Eq, ToTokens, Spanned)]
+#[non_exhaustive]
+pub struct PatOr {
+    /// The first alternative.
+    pub first: Box<ast::Pat>,
+    /// The remaining alternatives, each preceded by a `|`.
+    #[rune(iter)]
+    pub rest: Vec<(T![|], ast::Pat)>,
+}
+
+impl PatOr {
+    /// Iterate over all alternatives of the pattern.
+    pub(crate) fn alternatives(&self) -> impl Iterator<Item = &ast::Pat> {
+        iter::once(&*self.first).chain(self.rest.iter().map(|(_, pat)| pat))
+    }
+
+    /// Iterate mutably over all alternatives of the pattern.
+    pub(crate) fn alternatives_mut(&mut self) -> impl Iterator<Item = &mut ast::Pat> {
+        iter::once(&mut *self.first).chain(self.rest.iter_mut().map(|(_, pat)| pat))
+    }
+}
+
+/// A range pattern over integers, bytes or characters.
+///
+/// * `<lit>..<lit>`.
+/// * `<lit>..=<lit>`.
+/// * `<lit>..`.
+/// * `..=<lit>`.
+#[derive(Debug, Clone, PartialEq, Eq, ToTokens, Spanned)]
+#[non_exhaustive]
+pub struct PatRange {
+    /// Attributes associated with the pattern.
+    #[rune(iter)]
+    pub attributes: Vec<ast::Attribute>,
+    /// The start of the range.
+    #[rune(iter)]
+    pub start: Option<Box<ast::Expr>>,
+    /// The range limits.
+    pub limits: ast::ExprRangeLimits,
+    /// The end of the range.
+    #[rune(iter)]
+    pub end: Option<Box<ast::Expr>>,
+}
+
 /// The rest pattern `..` and associated attributes.
 #[derive(Debug, Clone, PartialEq, Eq, ToTokens, Spanned)]
 #[non_exhaustive]
@@ -256,6 +427,20 @@ pub struct PatBinding {
     pub pat: Box<ast::Pat>,
 }
 
+/// A pattern with a type annotation, as used in function arguments.
+///
+/// * `<pat>: <type>`.
+#[derive(Debug, Clone, PartialEq, Eq, ToTokens, Spanned)]
+#[non_exhaustive]
+pub struct PatType {
+    /// The pattern being annotated.
+    pub pat: ast::Pat,
+    /// The colon separator.
+    pub colon: T![:],
+    /// The type of the pattern.
+    pub ty: ast::Type,
+}
+
 /// A path pattern.
 #[derive(Debug, Clone, PartialEq, Eq, ToTokens, Spanned)]
 #[non_exhaustive]
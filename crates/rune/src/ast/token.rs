@@ -199,6 +199,8 @@ pub enum NumberSuffix {
     Float(Span),
     /// The `u8` suffix.
     Byte(Span),
+    /// The `n` suffix, used for arbitrary-precision integers.
+    BigInt(Span),
 }
 
 /// A resolved number literal.
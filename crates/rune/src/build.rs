@@ -4,13 +4,27 @@ use core::mem::take;
 
 use crate::no_std::prelude::*;
 
+#[cfg(feature = "cache")]
+use serde::de::DeserializeOwned;
+#[cfg(feature = "cache")]
+use serde::Serialize;
+
 use crate::ast::{Span, Spanned};
 use crate::compile;
+#[cfg(feature = "cache")]
+use crate::compile::unit_cache::{self, LoadedSource, RecordingSourceLoader};
+#[cfg(feature = "cache")]
+use crate::compile::UnitCache;
 use crate::compile::{
-    CompileVisitor, FileSourceLoader, Located, MetaError, Options, Pool, SourceLoader,
+    CompileVisitor, ContextSignature, FileSourceLoader, Located, MetaError, Options, Pool,
+    SourceLoader,
 };
+#[cfg(feature = "cache")]
+use crate::runtime::unit::UnitStorage;
 use crate::runtime::unit::{DefaultStorage, UnitEncoder};
 use crate::runtime::Unit;
+#[cfg(feature = "cache")]
+use crate::Hash;
 use crate::{Context, Diagnostics, SourceId, Sources};
 
 /// Error raised when we failed to load sources.
@@ -85,10 +99,13 @@ where
     Build {
         sources,
         context: None,
+        context_signature: None,
         diagnostics: None,
         options: None,
         visitors: Vec::new(),
         source_loader: None,
+        #[cfg(feature = "cache")]
+        cache: None,
         _unit_storage: PhantomData,
     }
 }
@@ -97,13 +114,34 @@ where
 pub struct Build<'a, S> {
     sources: &'a mut Sources,
     context: Option<&'a Context>,
+    context_signature: Option<&'a ContextSignature>,
     diagnostics: Option<&'a mut Diagnostics>,
     options: Option<&'a Options>,
     visitors: Vec<&'a mut dyn compile::CompileVisitor>,
     source_loader: Option<&'a mut dyn SourceLoader>,
+    #[cfg(feature = "cache")]
+    cache: Option<BuildCache<'a, S>>,
     _unit_storage: PhantomData<S>,
 }
 
+/// A cache configured through [Build::with_cache], together with the
+/// functions used to load and store units in it.
+///
+/// The functions are captured when the cache is configured, so that only
+/// building with a cache requires the unit storage to be serializable.
+#[cfg(feature = "cache")]
+struct BuildCache<'a, S> {
+    cache: &'a mut dyn UnitCache,
+    load: fn(
+        &mut dyn UnitCache,
+        Hash,
+        &Context,
+        &mut Sources,
+        &mut dyn SourceLoader,
+    ) -> Option<Unit<S>>,
+    store: fn(&mut dyn UnitCache, Hash, Vec<LoadedSource>, Unit<S>) -> Unit<S>,
+}
+
 /// Wraps a collection of CompileVisitor
 struct CompileVisitorGroup<'a> {
     visitors: Vec<&'a mut dyn compile::CompileVisitor>,
@@ -141,6 +179,24 @@ impl<'a> compile::CompileVisitor for CompileVisitorGroup<'a> {
         }
     }
 
+    fn visit_declaration(
+        &mut self,
+        location: &dyn Located,
+        kind: compile::DeclarationKind,
+        name: &str,
+        name_span: &dyn Spanned,
+    ) {
+        for v in self.visitors.iter_mut() {
+            v.visit_declaration(location, kind, name, name_span)
+        }
+    }
+
+    fn visit_block(&mut self, location: &dyn Located) {
+        for v in self.visitors.iter_mut() {
+            v.visit_block(location)
+        }
+    }
+
     fn visit_doc_comment(
         &mut self,
         location: &dyn Located,
@@ -180,6 +236,53 @@ impl<'a, S> Build<'a, S> {
         self
     }
 
+    /// Modify the current [Build] to compile and link check against the
+    /// given [ContextSignature] instead of a [Context].
+    ///
+    /// This allows checking scripts against a context without access to the
+    /// native modules which it was constructed from, by exporting its
+    /// signature with [Context::signature] ahead of time. Calling native
+    /// functions in a unit built this way results in an error, so the unit
+    /// should be rebuilt with the real [Context] before it's run.
+    ///
+    /// If a [Context] is also specified with [Build::with_context], it takes
+    /// precedence over the signature.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rune::compile::ContextSignature;
+    /// use rune::{Context, Module, Source, Sources};
+    ///
+    /// let mut module = Module::with_item(["app"]);
+    /// module.function(["add"], |a: i64, b: i64| a + b)?;
+    ///
+    /// let mut context = Context::with_default_modules()?;
+    /// context.install(module)?;
+    ///
+    /// // The signature is exported by an application which has access to
+    /// // its native modules...
+    /// let json = serde_json::to_string(&context.signature())?;
+    ///
+    /// // ...and used to check scripts elsewhere.
+    /// let signature: ContextSignature = serde_json::from_str(&json)?;
+    ///
+    /// let mut sources = Sources::new();
+    /// sources.insert(Source::new("entry", "pub fn main() { app::add(1, 2) }"));
+    ///
+    /// let unit = rune::prepare(&mut sources)
+    ///     .with_context_signature(&signature)
+    ///     .build();
+    ///
+    /// assert!(unit.is_ok());
+    /// # Ok::<_, Box<dyn std::error::Error>>(())
+    /// ```
+    #[inline]
+    pub fn with_context_signature(mut self, signature: &'a ContextSignature) -> Self {
+        self.context_signature = Some(signature);
+        self
+    }
+
     /// Modify the current [Build] to use the given [Diagnostics] collection.
     #[inline]
     pub fn with_diagnostics(mut self, diagnostics: &'a mut Diagnostics) -> Self {
@@ -215,16 +318,107 @@ impl<'a, S> Build<'a, S> {
         self
     }
 
+    /// Modify the current [Build] to use the given [UnitCache].
+    ///
+    /// If the cache has an entry for the sources, [Context] and [Options]
+    /// being built, the unit is loaded from it instead of being compiled.
+    /// Modules are loaded through the configured [SourceLoader] and compared
+    /// against the ones used when the entry was stored, so changing any of
+    /// them causes the unit to be compiled again.
+    ///
+    /// Note that units loaded from the cache are not compiled, so no
+    /// diagnostics are reported and compile visitors are not called.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rune::compile::UnitCache;
+    /// use rune::{Context, Hash, Source, Sources};
+    /// use std::collections::HashMap;
+    /// use std::io;
+    ///
+    /// #[derive(Default)]
+    /// struct MemoryCache {
+    ///     entries: HashMap<Hash, Vec<u8>>,
+    ///     hits: usize,
+    /// }
+    ///
+    /// impl UnitCache for MemoryCache {
+    ///     fn load(&mut self, key: Hash) -> io::Result<Option<Vec<u8>>> {
+    ///         let entry = self.entries.get(&key).cloned();
+    ///         self.hits += usize::from(entry.is_some());
+    ///         Ok(entry)
+    ///     }
+    ///
+    ///     fn store(&mut self, key: Hash, entry: &[u8]) -> io::Result<()> {
+    ///         self.entries.insert(key, entry.to_vec());
+    ///         Ok(())
+    ///     }
+    /// }
+    ///
+    /// let context = Context::with_default_modules()?;
+    /// let mut cache = MemoryCache::default();
+    ///
+    /// for _ in 0..2 {
+    ///     let mut sources = Sources::new();
+    ///     sources.insert(Source::new("entry", "pub fn main() { 42 }"));
+    ///
+    ///     let unit = rune::prepare(&mut sources)
+    ///         .with_context(&context)
+    ///         .with_cache(&mut cache)
+    ///         .build()?;
+    /// }
+    ///
+    /// assert_eq!(cache.entries.len(), 1);
+    /// assert_eq!(cache.hits, 1);
+    /// # Ok::<_, rune::Error>(())
+    /// ```
+    #[cfg(feature = "cache")]
+    #[inline]
+    pub fn with_cache(mut self, cache: &'a mut dyn UnitCache) -> Self
+    where
+        S: UnitStorage + Serialize + DeserializeOwned,
+    {
+        self.cache = Some(BuildCache {
+            cache,
+            load: unit_cache::load::<S>,
+            store: unit_cache::store::<S>,
+        });
+        self
+    }
+
     /// Build a [`Unit`] with the current configuration.
     pub fn build(mut self) -> Result<Unit<S>, BuildError>
     where
         S: Default + UnitEncoder,
     {
-        let default_context;
+        let mut default_diagnostics;
 
-        let context = match self.context.take() {
-            Some(context) => context,
+        let diagnostics = match self.diagnostics.take() {
+            Some(diagnostics) => diagnostics,
             None => {
+                default_diagnostics = Diagnostics::new();
+                &mut default_diagnostics
+            }
+        };
+
+        let default_context;
+
+        let context = match (self.context.take(), self.context_signature.take()) {
+            (Some(context), _) => context,
+            (None, Some(signature)) => {
+                default_context = match Context::from_signature(signature) {
+                    Ok(context) => context,
+                    Err(error) => {
+                        diagnostics
+                            .error(SourceId::empty(), compile::Error::msg(Span::empty(), error));
+                        return Err(BuildError);
+                    }
+                };
+
+                &default_context
+            }
+            (None, None) => {
                 default_context = Context::new();
                 &default_context
             }
@@ -238,16 +432,6 @@ impl<'a, S> Build<'a, S> {
             compile::Prelude::default()
         };
 
-        let mut default_diagnostics;
-
-        let diagnostics = match self.diagnostics.take() {
-            Some(diagnostics) => diagnostics,
-            None => {
-                default_diagnostics = Diagnostics::new();
-                &mut default_diagnostics
-            }
-        };
-
         let default_options;
 
         let options = match self.options.take() {
@@ -282,6 +466,29 @@ impl<'a, S> Build<'a, S> {
             }
         };
 
+        #[cfg(feature = "cache")]
+        let cache = match self.cache.take() {
+            Some(BuildCache { cache, load, store }) => {
+                let key = unit_cache::key::<S>(self.sources, context, options);
+
+                if let Some(unit) = load(cache, key, context, self.sources, source_loader) {
+                    return Ok(unit);
+                }
+
+                Some((cache, key, store))
+            }
+            None => None,
+        };
+
+        #[cfg(feature = "cache")]
+        let mut recording = None;
+
+        #[cfg(feature = "cache")]
+        let source_loader: &mut dyn SourceLoader = match &cache {
+            Some(..) => recording.insert(RecordingSourceLoader::new(source_loader)),
+            None => source_loader,
+        };
+
         let mut pool = Pool::default();
         let mut unit_storage = S::default();
 
@@ -311,6 +518,14 @@ impl<'a, S> Build<'a, S> {
         }
 
         match unit.build(Span::empty(), unit_storage) {
+            #[cfg(feature = "cache")]
+            Ok(unit) => match (cache, recording) {
+                (Some((cache, key, store)), Some(recording)) => {
+                    Ok(store(cache, key, recording.loaded, unit))
+                }
+                _ => Ok(unit),
+            },
+            #[cfg(not(feature = "cache"))]
             Ok(unit) => Ok(unit),
             Err(error) => {
                 diagnostics.error(SourceId::empty(), error);
@@ -8,10 +8,12 @@
 
 mod benches;
 mod check;
+mod debug_adapter;
 mod doc;
 mod format;
 mod languageserver;
 mod loader;
+mod repl;
 mod run;
 mod tests;
 mod visitor;
@@ -375,23 +377,29 @@ enum Command {
     Bench(CommandShared<benches::Flags>),
     /// Run the designated script
     Run(CommandShared<run::Flags>),
+    /// Start an interactive session
+    Repl(CommandShared<repl::Flags>),
     /// Format the provided file
     Fmt(CommandShared<format::Flags>),
     /// Run a language server.
     LanguageServer(SharedFlags),
+    /// Run a debug adapter which speaks the Debug Adapter Protocol over stdio.
+    DebugAdapter(SharedFlags),
     /// Helper command to generate type hashes.
     Hash(HashFlags),
 }
 
 impl Command {
-    const ALL: [&'static str; 8] = [
+    const ALL: [&'static str; 10] = [
         "check",
         "doc",
         "test",
         "bench",
         "run",
+        "repl",
         "fmt",
         "languageserver",
+        "debug-adapter",
         "hash",
     ];
 
@@ -402,8 +410,10 @@ impl Command {
             Command::Test(shared) => (&mut shared.shared, &mut shared.command),
             Command::Bench(shared) => (&mut shared.shared, &mut shared.command),
             Command::Run(shared) => (&mut shared.shared, &mut shared.command),
+            Command::Repl(shared) => (&mut shared.shared, &mut shared.command),
             Command::Fmt(shared) => (&mut shared.shared, &mut shared.command),
             Command::LanguageServer(..) => return None,
+            Command::DebugAdapter(..) => return None,
             Command::Hash(..) => return None,
         };
 
@@ -418,7 +428,10 @@ impl Command {
             Command::Bench(shared) => (&shared.shared, &shared.command),
             Command::Run(shared) => (&shared.shared, &shared.command),
             Command::Fmt(shared) => (&shared.shared, &shared.command),
+            // The interactive session doesn't operate over any sources.
+            Command::Repl(..) => return None,
             Command::LanguageServer(..) => return None,
+            Command::DebugAdapter(..) => return None,
             Command::Hash(..) => return None,
         };
 
@@ -870,10 +883,26 @@ where
                 }
             }
         }
+        Command::Repl(f) => {
+            let options = f.options()?;
+            return repl::run(io, entry, c, &f.shared, &options).await;
+        }
         Command::LanguageServer(shared) => {
             let context = shared.context(entry, c, None)?;
             languageserver::run(context).await?;
         }
+        Command::DebugAdapter(shared) => {
+            let mut options = Options::default();
+            options.debug_info(true);
+
+            for option in &shared.compiler_options {
+                options.parse_option(option)?;
+            }
+
+            let capture = CaptureIo::new();
+            let context = shared.context(entry, c, Some(&capture))?;
+            debug_adapter::run(context, capture, options).await?;
+        }
         Command::Hash(args) => {
             use rand::prelude::*;
 
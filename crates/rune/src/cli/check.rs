@@ -1,11 +1,12 @@
+use std::fs;
 use std::io::Write;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 
 use anyhow::{Context, Result};
 use clap::Parser;
 
 use crate::cli::{visitor, Config, Entry, ExitCode, Io, SharedFlags, CommandBase, AssetKind};
-use crate::compile::FileSourceLoader;
+use crate::compile::{ContextSignature, FileSourceLoader};
 use crate::{Diagnostics, Options, Source, Sources};
 
 #[derive(Parser, Debug)]
@@ -13,6 +14,15 @@ pub(super) struct Flags {
     /// Exit with a non-zero exit-code even for warnings
     #[arg(long)]
     warnings_are_errors: bool,
+    /// Check against a context signature previously written with
+    /// `--emit-context-signature`, instead of the native modules available
+    /// to this binary.
+    #[arg(long)]
+    context_signature: Option<PathBuf>,
+    /// Write the signature of the context used for checking to the given
+    /// path, so that it can be used with `--context-signature`.
+    #[arg(long)]
+    emit_context_signature: Option<PathBuf>,
 }
 
 impl CommandBase for Flags {
@@ -45,6 +55,23 @@ pub(super) fn run(
 
     let context = shared.context(entry, c, None)?;
 
+    if let Some(out) = &flags.emit_context_signature {
+        let signature = serde_json::to_vec(&context.signature())?;
+        fs::write(out, signature)
+            .with_context(|| format!("writing context signature: {}", out.display()))?;
+    }
+
+    let signature = match &flags.context_signature {
+        Some(path) => {
+            let signature = fs::read(path)
+                .with_context(|| format!("reading context signature: {}", path.display()))?;
+            let signature: ContextSignature = serde_json::from_slice(&signature)
+                .with_context(|| format!("parsing context signature: {}", path.display()))?;
+            Some(signature)
+        }
+        None => None,
+    };
+
     let source =
         Source::from_path(path).with_context(|| format!("reading file: {}", path.display()))?;
 
@@ -61,8 +88,14 @@ pub(super) fn run(
     let mut test_finder = visitor::FunctionVisitor::new(visitor::Attribute::None);
     let mut source_loader = FileSourceLoader::new();
 
-    let _ = crate::prepare(&mut sources)
-        .with_context(&context)
+    let build = crate::prepare(&mut sources);
+
+    let build = match &signature {
+        Some(signature) => build.with_context_signature(signature),
+        None => build.with_context(&context),
+    };
+
+    let _ = build
         .with_diagnostics(&mut diagnostics)
         .with_options(options)
         .with_visitor(&mut test_finder)
@@ -0,0 +1,876 @@
+//! A debug adapter which speaks the [Debug Adapter Protocol] over stdio.
+//!
+//! Requests are only processed while the script is paused, so pausing a
+//! running script is not supported.
+//!
+//! [Debug Adapter Protocol]: https://microsoft.github.io/debug-adapter-protocol/
+
+use std::fs;
+use std::io::{self, BufRead, Write};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use crate::no_std::prelude::*;
+
+use anyhow::{anyhow, bail, Context as _, Result};
+use serde_json::{json, Value as Json};
+
+use crate::modules::capture_io::CaptureIo;
+use crate::runtime::debugger::{DebugFrame, DebugState, Debugger, Resume};
+use crate::runtime::{Formatter, RuntimeContext, VmExecution, VmResult};
+use crate::termcolor::Buffer;
+use crate::{Context, Diagnostics, Options, Source, SourceId, Sources, Value, Vm};
+
+/// The identifier of the only thread we report.
+const THREAD_ID: u64 = 1;
+
+/// The function which is executed when a program is launched.
+const MAIN: &str = "main";
+
+pub(super) async fn run(context: Context, capture: CaptureIo, options: Options) -> Result<()> {
+    let mut connection = Connection {
+        input: io::stdin().lock(),
+        output: io::stdout().lock(),
+        seq: 0,
+    };
+
+    let mut session = Session {
+        runtime: Arc::new(context.runtime()),
+        context,
+        capture,
+        options,
+        lines: Lines { start_at1: true },
+        program: None,
+    };
+
+    while let Some(request) = connection.read()? {
+        let command = request["command"].as_str().unwrap_or_default();
+
+        match session.handle(&mut connection, command, &request).await {
+            Ok(Handled::Continue) => {}
+            Ok(Handled::Exit) => break,
+            Err(error) => {
+                connection.fail(&request, &error.to_string())?;
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// The outcome of handling a request.
+enum Handled {
+    /// Continue processing requests.
+    Continue,
+    /// Stop the adapter.
+    Exit,
+}
+
+/// A framed connection, which is normally over stdio.
+struct Connection<R, W> {
+    input: R,
+    output: W,
+    /// Sequence number of the last message sent.
+    seq: u64,
+}
+
+impl<R, W> Connection<R, W>
+where
+    R: BufRead,
+    W: Write,
+{
+    /// Read the next message, returning `None` when the input is closed.
+    fn read(&mut self) -> Result<Option<Json>> {
+        let mut content_length = None;
+        let mut line = String::new();
+
+        loop {
+            line.clear();
+
+            if self.input.read_line(&mut line)? == 0 {
+                return Ok(None);
+            }
+
+            let line = line.trim_end();
+
+            if line.is_empty() {
+                break;
+            }
+
+            if let Some((key, value)) = line.split_once(':') {
+                if key.eq_ignore_ascii_case("content-length") {
+                    content_length = Some(value.trim().parse::<usize>()?);
+                }
+            }
+        }
+
+        let Some(length) = content_length else {
+            bail!("missing content-length");
+        };
+
+        let mut content = vec![0u8; length];
+        self.input.read_exact(&mut content)?;
+        Ok(Some(serde_json::from_slice(&content)?))
+    }
+
+    /// Send a message, assigning it the next sequence number.
+    fn send(&mut self, mut message: Json) -> Result<()> {
+        self.seq += 1;
+        message["seq"] = json!(self.seq);
+
+        let content = serde_json::to_vec(&message)?;
+        write!(self.output, "Content-Length: {}\r\n\r\n", content.len())?;
+        self.output.write_all(&content)?;
+        self.output.flush()?;
+        Ok(())
+    }
+
+    /// Respond successfully to the given request.
+    fn respond(&mut self, request: &Json, body: Json) -> Result<()> {
+        self.send(json!({
+            "type": "response",
+            "request_seq": request["seq"],
+            "success": true,
+            "command": request["command"],
+            "body": body,
+        }))
+    }
+
+    /// Respond to the given request with an error.
+    fn fail(&mut self, request: &Json, message: &str) -> Result<()> {
+        self.send(json!({
+            "type": "response",
+            "request_seq": request["seq"],
+            "success": false,
+            "command": request["command"],
+            "message": message,
+        }))
+    }
+
+    /// Send an event.
+    fn event(&mut self, event: &str, body: Json) -> Result<()> {
+        self.send(json!({
+            "type": "event",
+            "event": event,
+            "body": body,
+        }))
+    }
+
+    /// Send an output event.
+    fn output(&mut self, category: &str, output: &str) -> Result<()> {
+        if output.is_empty() {
+            return Ok(());
+        }
+
+        self.event("output", json!({ "category": category, "output": output }))
+    }
+}
+
+/// A program which has been launched.
+struct Program {
+    sources: Sources,
+    debugger: Debugger,
+    execution: VmExecution<Vm>,
+    stop_on_entry: bool,
+}
+
+struct Session {
+    context: Context,
+    runtime: Arc<RuntimeContext>,
+    capture: CaptureIo,
+    options: Options,
+    /// How lines are reported to and from the client.
+    lines: Lines,
+    /// The launched program.
+    program: Option<Program>,
+}
+
+impl Session {
+    async fn handle<R, W>(
+        &mut self,
+        c: &mut Connection<R, W>,
+        command: &str,
+        request: &Json,
+    ) -> Result<Handled>
+    where
+        R: BufRead,
+        W: Write,
+    {
+        let arguments = &request["arguments"];
+        let lines = self.lines;
+
+        match command {
+            "initialize" => {
+                self.lines.start_at1 = arguments["linesStartAt1"].as_bool().unwrap_or(true);
+
+                c.respond(
+                    request,
+                    json!({
+                        "supportsConfigurationDoneRequest": true,
+                        "supportsEvaluateForHovers": true,
+                    }),
+                )?;
+            }
+            "launch" => {
+                let program = arguments["program"]
+                    .as_str()
+                    .context("missing `program` to launch")?;
+
+                let stop_on_entry = arguments["stopOnEntry"].as_bool().unwrap_or_default();
+
+                match self.launch(Path::new(program), stop_on_entry) {
+                    Ok(program) => {
+                        self.program = Some(program);
+                        c.respond(request, Json::Null)?;
+                        // Breakpoints can only be verified once the program
+                        // has been compiled, so we ask for them now.
+                        c.event("initialized", Json::Null)?;
+                    }
+                    Err((error, diagnostics)) => {
+                        c.output("stderr", &diagnostics)?;
+                        c.fail(request, &error.to_string())?;
+                    }
+                }
+            }
+            "setBreakpoints" => {
+                let path = arguments["source"]["path"].as_str().unwrap_or_default();
+
+                let requested = arguments["breakpoints"]
+                    .as_array()
+                    .into_iter()
+                    .flatten()
+                    .map(|b| b["line"].as_u64().unwrap_or_default() as usize)
+                    .collect::<Vec<_>>();
+
+                let placed = match &mut self.program {
+                    Some(program) => match find_source(&program.sources, Path::new(path)) {
+                        Some(source_id) => {
+                            let requested = requested.iter().map(|&line| lines.decode(line));
+                            program.debugger.set_breakpoints(source_id, requested)
+                        }
+                        None => vec![None; requested.len()],
+                    },
+                    None => vec![None; requested.len()],
+                };
+
+                let breakpoints = requested
+                    .iter()
+                    .zip(placed)
+                    .map(|(&line, placed)| match placed {
+                        Some(placed) => json!({
+                            "verified": true,
+                            "line": lines.encode(placed),
+                        }),
+                        None => json!({ "verified": false, "line": line }),
+                    })
+                    .collect::<Vec<_>>();
+
+                c.respond(request, json!({ "breakpoints": breakpoints }))?;
+            }
+            "configurationDone" => {
+                c.respond(request, Json::Null)?;
+
+                let program = self.program()?;
+
+                let entry = program
+                    .debugger
+                    .location(program.execution.vm().ip())
+                    .filter(|&l| program.debugger.has_breakpoint(l));
+
+                if program.stop_on_entry {
+                    c.event("stopped", stopped("entry"))?;
+                } else if entry.is_some() {
+                    c.event("stopped", stopped("breakpoint"))?;
+                } else {
+                    self.resume(c, Resume::Continue).await?;
+                }
+            }
+            "threads" => {
+                c.respond(
+                    request,
+                    json!({ "threads": [{ "id": THREAD_ID, "name": MAIN }] }),
+                )?;
+            }
+            "stackTrace" => {
+                let program = self.program()?;
+                let vm = program.execution.vm();
+                let frames = program.debugger.frames(vm);
+
+                let mut stack_frames = Vec::new();
+
+                for (id, frame) in frames.iter().enumerate() {
+                    let name = match program.debugger.function_at(frame.ip) {
+                        Some(signature) => signature.to_string(),
+                        None => String::from("<unknown>"),
+                    };
+
+                    let mut stack_frame = json!({
+                        "id": id,
+                        "name": name,
+                        "line": 0,
+                        "column": 0,
+                    });
+
+                    if let Some(location) = program.debugger.location(frame.ip) {
+                        if let Some(source) = program.sources.get(location.source_id) {
+                            stack_frame["source"] = json!({
+                                "name": source.name(),
+                                "path": source.path(),
+                            });
+                            stack_frame["line"] = json!(lines.encode(location.line));
+                            stack_frame["column"] = json!(lines.encode(0));
+                        }
+                    }
+
+                    stack_frames.push(stack_frame);
+                }
+
+                c.respond(
+                    request,
+                    json!({
+                        "stackFrames": stack_frames,
+                        "totalFrames": frames.len(),
+                    }),
+                )?;
+            }
+            "scopes" => {
+                let id = arguments["frameId"].as_u64().unwrap_or_default();
+
+                c.respond(
+                    request,
+                    json!({
+                        "scopes": [{
+                            "name": "Locals",
+                            "variablesReference": id + 1,
+                            "expensive": false,
+                        }],
+                    }),
+                )?;
+            }
+            "variables" => {
+                let reference = arguments["variablesReference"].as_u64().unwrap_or_default();
+
+                let program = self.program()?;
+                let frame = reference
+                    .checked_sub(1)
+                    .and_then(|id| frame(program, id))
+                    .context("no such frame")?;
+
+                let locals = program
+                    .debugger
+                    .locals(program.execution.vm(), &frame)
+                    .into_iter()
+                    .map(|(name, value)| (name.to_owned(), value))
+                    .collect::<Vec<_>>();
+
+                let mut variables = Vec::new();
+
+                for (name, value) in locals {
+                    variables.push(json!({
+                        "name": name,
+                        "value": describe(program.execution.vm_mut(), &value),
+                        "type": type_name(&value),
+                        "variablesReference": 0,
+                    }));
+                }
+
+                c.respond(request, json!({ "variables": variables }))?;
+            }
+            "continue" => {
+                c.respond(request, json!({ "allThreadsContinued": true }))?;
+                self.resume(c, Resume::Continue).await?;
+            }
+            "next" => {
+                c.respond(request, Json::Null)?;
+                self.resume(c, Resume::StepOver).await?;
+            }
+            "stepIn" => {
+                c.respond(request, Json::Null)?;
+                self.resume(c, Resume::StepInto).await?;
+            }
+            "stepOut" => {
+                c.respond(request, Json::Null)?;
+                self.resume(c, Resume::StepOut).await?;
+            }
+            "evaluate" => {
+                let expression = arguments["expression"].as_str().unwrap_or_default();
+                let id = arguments["frameId"].as_u64().unwrap_or_default();
+
+                let program = self
+                    .program
+                    .as_mut()
+                    .context("the program is not running")?;
+                let frame = frame(program, id).context("no such frame")?;
+
+                let value = program
+                    .debugger
+                    .evaluate(&self.context, program.execution.vm(), &frame, expression)
+                    .map_err(|error| anyhow!("{error}"))?;
+
+                c.respond(
+                    request,
+                    json!({
+                        "result": describe(program.execution.vm_mut(), &value),
+                        "type": type_name(&value),
+                        "variablesReference": 0,
+                    }),
+                )?;
+            }
+            "disconnect" => {
+                c.respond(request, Json::Null)?;
+                return Ok(Handled::Exit);
+            }
+            command => {
+                bail!("unsupported request `{command}`");
+            }
+        }
+
+        Ok(Handled::Continue)
+    }
+
+    /// Compile and prepare the program at the given path for execution.
+    fn launch(&self, path: &Path, stop_on_entry: bool) -> Result<Program, (anyhow::Error, String)> {
+        let source = Source::from_path(path)
+            .with_context(|| anyhow!("cannot read file: {}", path.display()))
+            .map_err(|error| (error, String::new()))?;
+
+        let mut sources = Sources::new();
+        sources.insert(source);
+
+        let mut diagnostics = Diagnostics::new();
+
+        let result = crate::prepare(&mut sources)
+            .with_context(&self.context)
+            .with_diagnostics(&mut diagnostics)
+            .with_options(&self.options)
+            .build();
+
+        let mut buffer = Buffer::no_color();
+        let _ = diagnostics.emit(&mut buffer, &sources);
+        let diagnostics = String::from_utf8_lossy(buffer.as_slice()).into_owned();
+
+        let unit = Arc::new(result.map_err(|error| (error.into(), diagnostics.clone()))?);
+        let debugger = Debugger::new(unit.clone(), &sources);
+
+        let mut vm = Vm::new(self.runtime.clone(), unit);
+
+        let execution = vm
+            .execute([MAIN], ())
+            .map_err(|error| (error.into(), diagnostics))?
+            .into_owned();
+
+        Ok(Program {
+            sources,
+            debugger,
+            execution,
+            stop_on_entry,
+        })
+    }
+
+    /// Resume the program and report where it stopped.
+    async fn resume<R, W>(&mut self, c: &mut Connection<R, W>, resume: Resume) -> Result<()>
+    where
+        R: BufRead,
+        W: Write,
+    {
+        let program = self
+            .program
+            .as_mut()
+            .context("the program is not running")?;
+
+        let result = program
+            .execution
+            .async_debug(&program.debugger, resume)
+            .await;
+
+        c.output("stdout", &String::from_utf8_lossy(&self.capture.drain()))?;
+
+        let exit_code = match result {
+            VmResult::Ok(DebugState::Breakpoint) => {
+                c.event("stopped", stopped("breakpoint"))?;
+                return Ok(());
+            }
+            VmResult::Ok(DebugState::Step) => {
+                c.event("stopped", stopped("step"))?;
+                return Ok(());
+            }
+            VmResult::Ok(DebugState::Complete(..)) => 0,
+            VmResult::Err(error) => {
+                let mut buffer = Buffer::no_color();
+
+                match error.emit(&mut buffer, &program.sources) {
+                    Ok(()) => {
+                        c.output("stderr", &String::from_utf8_lossy(buffer.as_slice()))?;
+                    }
+                    Err(..) => {
+                        c.output("stderr", &format!("{error}\n"))?;
+                    }
+                }
+
+                1
+            }
+        };
+
+        self.program = None;
+        c.event("exited", json!({ "exitCode": exit_code }))?;
+        c.event("terminated", Json::Null)?;
+        Ok(())
+    }
+
+    fn program(&mut self) -> Result<&mut Program> {
+        self.program.as_mut().context("the program is not running")
+    }
+}
+
+/// How lines are reported to and from the client.
+#[derive(Clone, Copy)]
+struct Lines {
+    /// Whether lines start at 1 rather than 0.
+    start_at1: bool,
+}
+
+impl Lines {
+    /// Convert a zero-based line into one reported to the client.
+    fn encode(self, line: usize) -> usize {
+        if self.start_at1 {
+            line + 1
+        } else {
+            line
+        }
+    }
+
+    /// Convert a line reported by the client into a zero-based line.
+    fn decode(self, line: usize) -> usize {
+        if self.start_at1 {
+            line.saturating_sub(1)
+        } else {
+            line
+        }
+    }
+}
+
+/// The body of a stopped event with the given reason.
+fn stopped(reason: &str) -> Json {
+    json!({
+        "reason": reason,
+        "threadId": THREAD_ID,
+        "allThreadsStopped": true,
+    })
+}
+
+/// Get the frame with the given identifier, which is its index counting from
+/// the innermost frame.
+fn frame(program: &Program, id: u64) -> Option<DebugFrame> {
+    let frames = program.debugger.frames(program.execution.vm());
+    frames.get(usize::try_from(id).ok()?).copied()
+}
+
+/// Find the source which was loaded from the given path.
+fn find_source(sources: &Sources, path: &Path) -> Option<SourceId> {
+    let path = canonicalize(path);
+
+    sources.source_ids().find(|&id| {
+        sources
+            .get(id)
+            .and_then(Source::path)
+            .is_some_and(|p| canonicalize(p) == path)
+    })
+}
+
+fn canonicalize(path: &Path) -> PathBuf {
+    fs::canonicalize(path).unwrap_or_else(|_| path.to_owned())
+}
+
+/// Describe a value using its debug representation.
+fn describe(vm: &mut Vm, value: &Value) -> String {
+    let mut f = Formatter::new();
+
+    match vm.with(|| value.string_debug(&mut f)) {
+        VmResult::Ok(()) => f.as_str().to_owned(),
+        VmResult::Err(..) => format!("{value:?}"),
+    }
+}
+
+/// The name of the type of a value.
+fn type_name(value: &Value) -> String {
+    match value.type_info() {
+        VmResult::Ok(type_info) => type_info.to_string(),
+        VmResult::Err(..) => String::new(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+    use std::path::PathBuf;
+    use std::sync::Arc;
+
+    use crate::no_std::prelude::*;
+
+    use anyhow::Result;
+    use serde_json::{json, Value as Json};
+
+    use super::{Connection, Lines, Session};
+    use crate::modules::capture_io::{self, CaptureIo};
+    use crate::{Context, Options};
+
+    const SCRIPT: &str = r#"fn add(a, b) {
+    a + b
+}
+
+pub fn main() {
+    let x = 1;
+    let y = add(x, 2);
+    println!("{}", y);
+    y * 2
+}
+"#;
+
+    /// Frame the given messages.
+    fn frame(messages: &[&str]) -> Vec<u8> {
+        let mut out = Vec::new();
+
+        for message in messages {
+            out.extend(format!("Content-Length: {}\r\n\r\n{message}", message.len()).bytes());
+        }
+
+        out
+    }
+
+    /// Read every framed message from the given output.
+    fn messages(output: &[u8]) -> Result<Vec<Json>> {
+        let mut connection = Connection {
+            input: output,
+            output: Vec::new(),
+            seq: 0,
+        };
+
+        let mut messages = Vec::new();
+
+        while let Some(message) = connection.read()? {
+            messages.push(message);
+        }
+
+        Ok(messages)
+    }
+
+    /// A client which drives a session directly.
+    struct Client {
+        session: Session,
+        connection: Connection<&'static [u8], Vec<u8>>,
+        seq: u64,
+        path: PathBuf,
+    }
+
+    impl Client {
+        fn new(name: &str) -> Result<Self> {
+            let path = std::env::temp_dir().join(format!(
+                "rune-debug-adapter-{name}-{}.rn",
+                std::process::id()
+            ));
+
+            fs::write(&path, SCRIPT)?;
+
+            let capture = CaptureIo::new();
+            let mut context = Context::with_config(false)?;
+            context.install(capture_io::module(&capture)?)?;
+
+            let mut options = Options::default();
+            options.debug_info(true);
+
+            Ok(Self {
+                session: Session {
+                    runtime: Arc::new(context.runtime()),
+                    context,
+                    capture,
+                    options,
+                    lines: Lines { start_at1: true },
+                    program: None,
+                },
+                connection: Connection {
+                    input: &[],
+                    output: Vec::new(),
+                    seq: 0,
+                },
+                seq: 0,
+                path,
+            })
+        }
+
+        /// Send a request and collect the messages sent in response.
+        fn request(&mut self, command: &str, arguments: Json) -> Result<Vec<Json>> {
+            self.seq += 1;
+
+            let request = json!({
+                "seq": self.seq,
+                "type": "request",
+                "command": command,
+                "arguments": arguments,
+            });
+
+            let handled = futures_executor::block_on(self.session.handle(
+                &mut self.connection,
+                command,
+                &request,
+            ));
+
+            if let Err(error) = handled {
+                self.connection.fail(&request, &error.to_string())?;
+            }
+
+            messages(&std::mem::take(&mut self.connection.output))
+        }
+
+        /// Launch the script and stop on the given lines.
+        fn launch(&mut self, lines: &[usize]) -> Result<Vec<Json>> {
+            self.request("initialize", json!({ "linesStartAt1": true }))?;
+
+            let path = self.path.clone();
+            let messages = self.request("launch", json!({ "program": path }))?;
+            assert_eq!(messages[0]["success"], true);
+            assert_eq!(messages[1]["event"], "initialized");
+
+            let breakpoints = lines
+                .iter()
+                .map(|&line| json!({ "line": line }))
+                .collect::<Vec<_>>();
+
+            let messages = self.request(
+                "setBreakpoints",
+                json!({
+                    "source": { "path": path },
+                    "breakpoints": breakpoints,
+                }),
+            )?;
+
+            assert_eq!(messages.len(), 1);
+            Ok(messages)
+        }
+    }
+
+    impl Drop for Client {
+        fn drop(&mut self) {
+            let _ = fs::remove_file(&self.path);
+        }
+    }
+
+    #[test]
+    fn test_framing() -> Result<()> {
+        let mut input = frame(&[r#"{"seq":1}"#]);
+        input.extend(b"content-length: 9\r\nContent-Type: application/json\r\n\r\n{\"seq\":2}");
+
+        let mut connection = Connection {
+            input: &input[..],
+            output: Vec::new(),
+            seq: 0,
+        };
+
+        assert_eq!(connection.read()?, Some(json!({ "seq": 1 })));
+        assert_eq!(connection.read()?, Some(json!({ "seq": 2 })));
+        assert_eq!(connection.read()?, None);
+
+        connection.event("stopped", json!({ "reason": "step" }))?;
+        connection.event("exited", json!({ "exitCode": 0 }))?;
+
+        let output = String::from_utf8(connection.output.clone())?;
+        assert!(output.starts_with("Content-Length: "));
+
+        let messages = messages(&connection.output)?;
+        assert_eq!(messages.len(), 2);
+        assert_eq!(messages[0]["seq"], 1);
+        assert_eq!(messages[0]["body"]["reason"], "step");
+        assert_eq!(messages[1]["seq"], 2);
+        assert_eq!(messages[1]["event"], "exited");
+
+        let mut connection = Connection {
+            input: &b"Content-Type: application/json\r\n\r\n{}"[..],
+            output: Vec::new(),
+            seq: 0,
+        };
+
+        assert!(connection.read().is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_set_breakpoints() -> Result<()> {
+        let mut client = Client::new("breakpoints")?;
+        let messages = client.launch(&[2, 4, 100])?;
+
+        let breakpoints = &messages[0]["body"]["breakpoints"];
+        assert_eq!(breakpoints[0], json!({ "verified": true, "line": 2 }));
+        assert_eq!(breakpoints[1]["verified"], true);
+        assert!(breakpoints[1]["line"].as_u64().unwrap() > 4);
+        assert_eq!(breakpoints[2], json!({ "verified": false, "line": 100 }));
+
+        let messages = client.request(
+            "setBreakpoints",
+            json!({
+                "source": { "path": "missing.rn" },
+                "breakpoints": [{ "line": 2 }],
+            }),
+        )?;
+
+        assert_eq!(
+            messages[0]["body"]["breakpoints"][0],
+            json!({ "verified": false, "line": 2 })
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_stack_trace_and_variables() -> Result<()> {
+        let mut client = Client::new("variables")?;
+        client.launch(&[2, 8])?;
+
+        let messages = client.request("configurationDone", Json::Null)?;
+        assert_eq!(messages[1]["event"], "stopped");
+        assert_eq!(messages[1]["body"]["reason"], "breakpoint");
+
+        let messages = client.request("stackTrace", json!({ "threadId": 1 }))?;
+        let body = &messages[0]["body"];
+        assert_eq!(body["totalFrames"], 2);
+
+        let frames = body["stackFrames"].as_array().unwrap();
+        assert!(frames[0]["name"].as_str().unwrap().starts_with("add"));
+        assert_eq!(frames[0]["line"], 2);
+        assert!(frames[1]["name"].as_str().unwrap().starts_with("main"));
+        assert_eq!(frames[1]["line"], 7);
+
+        let messages = client.request("variables", json!({ "variablesReference": 1 }))?;
+        let variables = messages[0]["body"]["variables"].as_array().unwrap();
+        let variables = variables
+            .iter()
+            .map(|v| (v["name"].as_str().unwrap(), v["value"].as_str().unwrap()))
+            .collect::<Vec<_>>();
+        assert_eq!(variables, [("a", "1"), ("b", "2")]);
+
+        let messages = client.request("variables", json!({ "variablesReference": 2 }))?;
+        assert_eq!(messages[0]["body"]["variables"][0]["name"], "x");
+
+        let messages = client.request("continue", json!({ "threadId": 1 }))?;
+        assert_eq!(messages[1]["body"]["reason"], "breakpoint");
+
+        for _ in 0..2 {
+            let messages =
+                client.request("evaluate", json!({ "expression": "x + y", "frameId": 0 }))?;
+            assert_eq!(messages[0]["body"]["result"], "4");
+        }
+
+        let messages = client.request("evaluate", json!({ "expression": "z", "frameId": 0 }))?;
+        assert_eq!(messages[0]["success"], false);
+
+        let messages = client.request("continue", json!({ "threadId": 1 }))?;
+        let events = messages
+            .iter()
+            .filter_map(|m| m["event"].as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(events, ["output", "exited", "terminated"]);
+        assert_eq!(messages[1]["body"]["output"], "3\n");
+        assert_eq!(messages[2]["body"]["exitCode"], 0);
+
+        let messages = client.request("stackTrace", json!({ "threadId": 1 }))?;
+        assert_eq!(messages[0]["success"], false);
+        Ok(())
+    }
+}
@@ -23,6 +23,10 @@ pub(super) struct Flags {
     /// Open the generated documentation in a browser.
     #[arg(long)]
     open: bool,
+    /// Emit API declarations as Rune stubs in `declarations.rn` and as JSON
+    /// in `declarations.json` instead of HTML documentation.
+    #[arg(long)]
+    declarations: bool,
 }
 
 impl CommandBase for Flags {
@@ -114,13 +118,21 @@ where
 
     let mut artifacts = Artifacts::new();
 
-    crate::doc::build("root", &mut artifacts, &context, &visitors)?;
+    if flags.declarations {
+        let declarations = crate::doc::declarations(&context, &visitors)?;
+        let stubs = declarations.to_stubs()?;
+        let json = declarations.to_json()?;
+        artifacts.asset(false, "declarations.rn", || Ok(stubs.into_bytes().into()))?;
+        artifacts.asset(false, "declarations.json", || Ok(json.into_bytes().into()))?;
+    } else {
+        crate::doc::build("root", &mut artifacts, &context, &visitors)?;
+    }
 
     for asset in artifacts.assets() {
         asset.build(&root)?;
     }
 
-    if flags.open {
+    if flags.open && !flags.declarations {
         let path = root.join("index.html");
         let _ = webbrowser::open(&path.display().to_string());
     }
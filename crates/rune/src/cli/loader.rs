@@ -10,7 +10,7 @@ use crate::no_std::prelude::*;
 use anyhow::{anyhow, Context as _, Result};
 
 use crate::cli::{visitor, Io, SharedFlags};
-use crate::compile::{FileSourceLoader, ItemBuf};
+use crate::compile::{FileSourceLoader, FileUnitCache, ItemBuf};
 use crate::Diagnostics;
 use crate::{Context, Hash, Options, Source, Sources, Unit};
 
@@ -29,68 +29,41 @@ pub(super) fn load(
     path: &Path,
     attribute: visitor::Attribute,
 ) -> Result<Load> {
-    let bytecode_path = path.with_extension("rnc");
-
     let source =
         Source::from_path(path).with_context(|| anyhow!("cannot read file: {}", path.display()))?;
 
     let mut sources = Sources::new();
     sources.insert(source);
 
-    let use_cache = options.bytecode && should_cache_be_used(path, &bytecode_path)?;
-
-    // TODO: how do we deal with tests discovery for bytecode loading
-    let maybe_unit = if use_cache {
-        let f = fs::File::open(&bytecode_path)?;
+    tracing::trace!("building file: {}", path.display());
 
-        match bincode::deserialize_from::<_, Unit>(f) {
-            Ok(unit) => {
-                tracing::trace!("Using cache: {}", bytecode_path.display());
-                Some(Arc::new(unit))
-            }
-            Err(e) => {
-                tracing::error!("Failed to deserialize: {}: {}", bytecode_path.display(), e);
-                None
-            }
-        }
+    let mut diagnostics = if shared.warnings {
+        Diagnostics::new()
     } else {
-        None
+        Diagnostics::without_warnings()
     };
 
-    let (unit, functions) = match maybe_unit {
-        Some(unit) => (unit, Default::default()),
-        None => {
-            tracing::trace!("building file: {}", path.display());
-
-            let mut diagnostics = if shared.warnings {
-                Diagnostics::new()
-            } else {
-                Diagnostics::without_warnings()
-            };
-
-            let mut functions = visitor::FunctionVisitor::new(attribute);
-            let mut source_loader = FileSourceLoader::new();
+    let mut functions = visitor::FunctionVisitor::new(attribute);
+    let mut source_loader = FileSourceLoader::new();
+    let mut cache = FileUnitCache::new(cache_directory(path));
 
-            let result = crate::prepare(&mut sources)
-                .with_context(context)
-                .with_diagnostics(&mut diagnostics)
-                .with_options(options)
-                .with_visitor(&mut functions)
-                .with_source_loader(&mut source_loader)
-                .build();
+    let mut build = crate::prepare(&mut sources)
+        .with_context(context)
+        .with_diagnostics(&mut diagnostics)
+        .with_options(options)
+        .with_visitor(&mut functions)
+        .with_source_loader(&mut source_loader);
 
-            diagnostics.emit(io.stdout, &sources)?;
-            let unit = result?;
+    // TODO: how do we deal with tests discovery for cached units
+    if options.bytecode {
+        build = build.with_cache(&mut cache);
+    }
 
-            if options.bytecode {
-                tracing::trace!("serializing cache: {}", bytecode_path.display());
-                let f = fs::File::create(&bytecode_path)?;
-                bincode::serialize_into(f, &unit)?;
-            }
+    let result = build.build();
 
-            (Arc::new(unit), functions.into_functions())
-        }
-    };
+    diagnostics.emit(io.stdout, &sources)?;
+    let unit = Arc::new(result?);
+    let functions = functions.into_functions();
 
     Ok(Load {
         unit,
@@ -99,17 +72,11 @@ pub(super) fn load(
     })
 }
 
-/// Test if path `a` is newer than path `b`.
-fn should_cache_be_used(source: &Path, cached: &Path) -> io::Result<bool> {
-    let source = fs::metadata(source)?;
-
-    let cached = match fs::metadata(cached) {
-        Ok(cached) => cached,
-        Err(error) if error.kind() == io::ErrorKind::NotFound => return Ok(false),
-        Err(error) => return Err(error),
-    };
-
-    Ok(source.modified()? < cached.modified()?)
+/// The directory where cached units for the script at `path` are stored.
+fn cache_directory(path: &Path) -> PathBuf {
+    path.parent()
+        .unwrap_or_else(|| Path::new(""))
+        .join(".rune-cache")
 }
 
 pub(super) fn recurse_paths(
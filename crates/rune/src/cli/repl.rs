@@ -0,0 +1,656 @@
+use std::io::{self, BufRead, Write};
+use std::sync::Arc;
+
+use crate::no_std::prelude::*;
+
+use anyhow::Result;
+use clap::Parser;
+
+use crate::ast::{self, Spanned};
+use crate::cli::{run, CommandBase, Config, Entry, ExitCode, Io, SharedFlags};
+use crate::compile::{self, ErrorKind};
+use crate::parse::{Lexer, Parser as AstParser};
+use crate::runtime::{Formatter, RuntimeContext, UnitStorage, VmResult};
+use crate::termcolor::{Color, ColorSpec, WriteColor};
+use crate::{Diagnostics, Options, Source, SourceId, Sources, Unit, Value, Vm};
+
+/// The name of the function which input is evaluated in.
+const ENTRY: &str = "__repl";
+
+/// Help text for meta-commands.
+const HELP: &str = "\
+:help  - Show this help.
+:dump  - Dump the instructions of the last evaluated input.
+:unit  - Dump the functions and static data of the last evaluated input.
+:reset - Forget all variables and items declared in this session.
+:quit  - Exit the session.";
+
+#[derive(Parser, Debug)]
+pub(super) struct Flags {}
+
+impl CommandBase for Flags {
+    #[inline]
+    fn is_debug(&self) -> bool {
+        true
+    }
+}
+
+/// An item declared in the session.
+struct Declaration {
+    /// The name of the declared item, used to replace it when it's redeclared.
+    name: Option<String>,
+    /// The source of the item.
+    source: String,
+}
+
+/// The last successfully evaluated input.
+struct Last {
+    unit: Arc<Unit>,
+    sources: Sources,
+}
+
+/// The state of a session, which carries over between inputs.
+#[derive(Default)]
+struct Session {
+    /// Items declared so far, such as functions, types and imports.
+    declarations: Vec<Declaration>,
+    /// Variables bound so far, in the order they were bound.
+    variables: Vec<(String, Value)>,
+    /// The last successfully evaluated input.
+    last: Option<Last>,
+}
+
+/// Input which has been split into parts that are kept across inputs and
+/// parts which are evaluated.
+struct Input {
+    /// Items declared in the input.
+    declarations: Vec<Declaration>,
+    /// Statements to evaluate.
+    statements: Vec<String>,
+    /// The trailing expression whose value is the result of the input.
+    tail: Option<String>,
+    /// Names of variables bound by `let` statements.
+    variables: Vec<String>,
+}
+
+pub(super) async fn run(
+    io: &mut Io<'_>,
+    entry: &mut Entry<'_>,
+    c: &Config,
+    shared: &SharedFlags,
+    options: &Options,
+) -> Result<ExitCode> {
+    let context = shared.context(entry, c, None)?;
+    let interactive = atty::is(atty::Stream::Stdin);
+
+    let stdin = io::stdin();
+    let mut stdin = stdin.lock();
+
+    repl(
+        io.stdout,
+        &mut stdin,
+        interactive,
+        &context,
+        shared.warnings,
+        options,
+    )
+    .await?;
+    Ok(ExitCode::Success)
+}
+
+/// Run a session which reads input from `stdin` until it's exhausted or the
+/// user quits, writing everything to `out`.
+async fn repl<O, R>(
+    out: &mut O,
+    stdin: &mut R,
+    interactive: bool,
+    context: &crate::Context,
+    warnings: bool,
+    options: &Options,
+) -> Result<()>
+where
+    O: WriteColor,
+    R: BufRead,
+{
+    let runtime = Arc::new(context.runtime());
+
+    if interactive {
+        writeln!(out, "Type `:help` for help, or `:quit` to exit.")?;
+    }
+
+    let mut session = Session::default();
+
+    while let Some(input) = read_input(out, stdin, interactive)? {
+        let trimmed = input.trim();
+
+        if trimmed.is_empty() {
+            continue;
+        }
+
+        if let Some(command) = trimmed.strip_prefix(':') {
+            match command {
+                "help" => {
+                    writeln!(out, "{HELP}")?;
+                }
+                "quit" | "exit" => break,
+                "reset" => {
+                    session = Session::default();
+                }
+                "dump" | "unit" => {
+                    let Some(last) = &session.last else {
+                        writeln!(out, "Nothing has been evaluated yet")?;
+                        continue;
+                    };
+
+                    if command == "dump" {
+                        writeln!(out, "# instructions")?;
+                        last.unit.emit_instructions(out, &last.sources, true)?;
+                    } else {
+                        let unit = &last.unit;
+                        writeln!(out, "Unit size: {} bytes", unit.instructions().bytes())?;
+                        run::dump_unit(out, unit, true, true)?;
+                    }
+                }
+                command => {
+                    writeln!(out, "Unknown command `:{command}`, try `:help`")?;
+                }
+            }
+
+            continue;
+        }
+
+        eval(
+            out,
+            context,
+            &runtime,
+            warnings,
+            options,
+            &mut session,
+            &input,
+        )
+        .await?;
+    }
+
+    Ok(())
+}
+
+/// Read a single input, which might span multiple lines.
+///
+/// Returns `None` once there is no more input to read.
+fn read_input<O, R>(out: &mut O, stdin: &mut R, interactive: bool) -> io::Result<Option<String>>
+where
+    O: Write,
+    R: BufRead,
+{
+    let mut input = String::new();
+
+    loop {
+        if interactive {
+            let prompt = if input.is_empty() { "rune> " } else { "  ... " };
+            write!(out, "{prompt}")?;
+            out.flush()?;
+        }
+
+        let mut line = String::new();
+
+        if stdin.read_line(&mut line)? == 0 {
+            return Ok((!input.is_empty()).then_some(input));
+        }
+
+        // An empty line submits incomplete input, so that errors can be
+        // reported.
+        let submit = line.trim().is_empty() && !input.is_empty();
+        input.push_str(&line);
+
+        if submit || input.trim_start().starts_with(':') {
+            return Ok(Some(input));
+        }
+
+        match parse(&input) {
+            Err(error) if is_incomplete(&input, &error) => {}
+            _ => return Ok(Some(input)),
+        }
+    }
+}
+
+/// Evaluate a single input in the session.
+async fn eval<O>(
+    out: &mut O,
+    context: &crate::Context,
+    runtime: &Arc<RuntimeContext>,
+    warnings: bool,
+    options: &Options,
+    session: &mut Session,
+    input: &str,
+) -> Result<()>
+where
+    O: WriteColor,
+{
+    let input = match parse(input) {
+        Ok(input) => input,
+        Err(error) => {
+            let mut sources = Sources::new();
+            let source_id = sources.insert(Source::new("<repl>", input));
+            let mut diagnostics = Diagnostics::new();
+            diagnostics.error(source_id, error);
+            diagnostics.emit(out, &sources)?;
+            return Ok(());
+        }
+    };
+
+    let mut declarations = Vec::new();
+
+    for declaration in &session.declarations {
+        let redeclared = input
+            .declarations
+            .iter()
+            .any(|d| match (&d.name, &declaration.name) {
+                (Some(a), Some(b)) => a == b,
+                _ => d.source == declaration.source,
+            });
+
+        if !redeclared {
+            declarations.push(declaration);
+        }
+    }
+
+    declarations.extend(&input.declarations);
+
+    // Variables bound by the input shadow existing ones.
+    let mut names = session
+        .variables
+        .iter()
+        .map(|(name, _)| name.as_str())
+        .filter(|name| !input.variables.iter().any(|v| v == name))
+        .collect::<Vec<_>>();
+
+    names.extend(input.variables.iter().map(String::as_str));
+
+    let mut script = String::new();
+
+    for declaration in &declarations {
+        script.push_str(&declaration.source);
+        script.push_str("\n\n");
+    }
+
+    let args = session
+        .variables
+        .iter()
+        .map(|(name, _)| name.as_str())
+        .collect::<Vec<_>>();
+
+    script.push_str(&format!("pub async fn {ENTRY}({}) {{\n", args.join(", ")));
+
+    for statement in &input.statements {
+        script.push_str(statement);
+        script.push('\n');
+    }
+
+    let tail = input.tail.as_deref().unwrap_or("()");
+    script.push_str(&format!("(\n{tail}\n, [{}])\n}}\n", names.join(", ")));
+
+    let mut sources = Sources::new();
+    sources.insert(Source::new("<repl>", script));
+
+    let mut diagnostics = if warnings {
+        Diagnostics::new()
+    } else {
+        Diagnostics::without_warnings()
+    };
+
+    let result = crate::prepare(&mut sources)
+        .with_context(context)
+        .with_diagnostics(&mut diagnostics)
+        .with_options(options)
+        .build();
+
+    diagnostics.emit(out, &sources)?;
+
+    let Ok(unit) = result else {
+        return Ok(());
+    };
+
+    let unit = Arc::new(unit);
+
+    session.declarations = declarations
+        .into_iter()
+        .map(|d| Declaration {
+            name: d.name.clone(),
+            source: d.source.clone(),
+        })
+        .collect();
+
+    let mut vm = Vm::new(runtime.clone(), unit.clone());
+    let values = session
+        .variables
+        .iter()
+        .map(|(_, value)| value.clone())
+        .collect::<Vec<_>>();
+
+    let result = match vm.execute([ENTRY], values) {
+        Ok(mut execution) => complete(execution.async_complete().await).await,
+        Err(error) => VmResult::Err(error),
+    };
+
+    let value = match result {
+        VmResult::Ok(value) => value,
+        VmResult::Err(error) => {
+            error.emit(out, &sources)?;
+            session.last = Some(Last { unit, sources });
+            return Ok(());
+        }
+    };
+
+    // An early return from the input doesn't produce any new variables.
+    let value = match split_output(&value, names.len()) {
+        Some((value, values)) => {
+            session.variables = names.into_iter().map(String::from).zip(values).collect();
+
+            if input.tail.is_none() {
+                session.last = Some(Last { unit, sources });
+                return Ok(());
+            }
+
+            value
+        }
+        None => value,
+    };
+
+    session.last = Some(Last { unit, sources });
+
+    if matches!(value, Value::EmptyTuple) {
+        return Ok(());
+    }
+
+    let mut f = Formatter::new();
+
+    if let VmResult::Err(error) = vm.with(|| value.string_debug(&mut f)) {
+        error.emit(out, &session.last.as_ref().expect("last unit").sources)?;
+        return Ok(());
+    }
+
+    write!(out, "{}", f.as_str())?;
+
+    if let VmResult::Ok(type_info) = value.type_info() {
+        out.set_color(ColorSpec::new().set_fg(Some(Color::Cyan)))?;
+        write!(out, ": {type_info}")?;
+        out.reset()?;
+    }
+
+    writeln!(out)?;
+    Ok(())
+}
+
+/// Complete the result of calling the entry function, which is a future since
+/// it's async.
+async fn complete(result: VmResult<Value>) -> VmResult<Value> {
+    let future = match result {
+        VmResult::Ok(Value::Future(future)) => future,
+        result => return result,
+    };
+
+    match future.take() {
+        Ok(future) => future.await,
+        Err(error) => VmResult::Err(error.into()),
+    }
+}
+
+/// Split the output of the entry function into the value of the input and the
+/// values of the variables in the session.
+///
+/// Returns `None` if the output didn't come from the end of the entry function.
+fn split_output(output: &Value, variables: usize) -> Option<(Value, Vec<Value>)> {
+    let Value::Tuple(tuple) = output else {
+        return None;
+    };
+
+    let tuple = tuple.borrow_ref().ok()?;
+
+    let [value, Value::Vec(values)] = &tuple[..] else {
+        return None;
+    };
+
+    let values = values.borrow_ref().ok()?;
+
+    if values.len() != variables {
+        return None;
+    }
+
+    Some((value.clone(), values.iter().cloned().collect()))
+}
+
+/// Parse input into declarations and statements.
+fn parse(input: &str) -> compile::Result<Input> {
+    let mut parser = AstParser::new(input, SourceId::empty(), true);
+
+    let mut declarations = Vec::new();
+    let mut statements = Vec::new();
+    let mut tail = None;
+    let mut variables = Vec::new();
+
+    let mut parsed = Vec::new();
+
+    while !parser.is_eof()? {
+        parsed.push(parser.parse::<ast::Stmt>()?);
+    }
+
+    // NB: The source of a statement extends up until the next one, since the
+    // span of some items doesn't cover all of their tokens.
+    let starts = parsed
+        .iter()
+        .map(|s| s.span().start.into_usize())
+        .collect::<Vec<_>>();
+
+    for (n, statement) in parsed.iter().enumerate() {
+        let end = starts.get(n + 1).copied().unwrap_or(input.len());
+        let source = input[starts[n]..end].trim_end().to_owned();
+
+        // The last expression is the value of the input, any other expressions
+        // are evaluated as statements.
+        if let Some(tail) = tail.take() {
+            statements.push(tail);
+        }
+
+        match statement {
+            ast::Stmt::Item(item, _) => {
+                let name = match item {
+                    ast::Item::Fn(item) => Some(&item.name),
+                    ast::Item::Enum(item) => Some(&item.name),
+                    ast::Item::Struct(item) => Some(&item.ident),
+                    ast::Item::Mod(item) => Some(&item.name),
+                    ast::Item::Const(item) => Some(&item.name),
+                    _ => None,
+                };
+
+                declarations.push(Declaration {
+                    name: name.map(|name| input[name.span().range()].to_owned()),
+                    source,
+                });
+            }
+            ast::Stmt::Local(local) => {
+                bindings(input, &local.pat, &mut variables);
+                statements.push(source);
+            }
+            ast::Stmt::Expr(..) => {
+                tail = Some(source);
+            }
+            ast::Stmt::Semi(..) => {
+                statements.push(source);
+            }
+        }
+    }
+
+    Ok(Input {
+        declarations,
+        statements,
+        tail,
+        variables,
+    })
+}
+
+/// Collect the names of variables bound by the given pattern.
+fn bindings(input: &str, pat: &ast::Pat, output: &mut Vec<String>) {
+    match pat {
+        ast::Pat::Path(pat) => {
+            if let Some(ident) = pat.path.try_as_ident() {
+                let name = input[ident.span().range()].to_owned();
+
+                if !output.contains(&name) {
+                    output.push(name);
+                }
+            }
+        }
+        ast::Pat::Vec(pat) => {
+            for (pat, _) in &pat.items {
+                bindings(input, pat, output);
+            }
+        }
+        ast::Pat::Tuple(pat) => {
+            for (pat, _) in &pat.items {
+                bindings(input, pat, output);
+            }
+        }
+        ast::Pat::Object(pat) => {
+            for (pat, _) in &pat.items {
+                bindings(input, pat, output);
+            }
+        }
+        ast::Pat::Binding(pat) => {
+            bindings(input, &pat.pat, output);
+        }
+        ast::Pat::Or(pat) => {
+            for pat in pat.alternatives() {
+                bindings(input, pat, output);
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Test if parsing failed because the input is incomplete, in which case more
+/// lines should be read.
+fn is_incomplete(input: &str, error: &compile::Error) -> bool {
+    if matches!(
+        error.kind(),
+        ErrorKind::UnexpectedEof
+            | ErrorKind::UnterminatedStrLit
+            | ErrorKind::UnterminatedByteStrLit
+    ) {
+        return true;
+    }
+
+    let mut lexer = Lexer::new(input, SourceId::empty(), true);
+    let mut depth = 0isize;
+
+    while let Ok(Some(token)) = lexer.next() {
+        match token.kind {
+            ast::Kind::Open(..) => depth += 1,
+            ast::Kind::Close(..) => depth -= 1,
+            _ => {}
+        }
+    }
+
+    depth > 0
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::no_std::prelude::*;
+
+    use anyhow::Result;
+
+    use super::repl;
+    use crate::modules::capture_io::{self, CaptureIo};
+    use crate::termcolor::Buffer;
+    use crate::{Context, Options};
+
+    /// Run a session over the given input, returning what was written to the
+    /// terminal and what the scripts printed.
+    async fn session(input: &str) -> Result<(String, String)> {
+        let capture = CaptureIo::new();
+        let mut context = Context::with_config(false)?;
+        context.install(capture_io::module(&capture)?)?;
+
+        let mut out = Buffer::no_color();
+        let mut stdin = input.as_bytes();
+        repl(
+            &mut out,
+            &mut stdin,
+            false,
+            &context,
+            false,
+            &Options::default(),
+        )
+        .await?;
+
+        Ok((String::from_utf8(out.into_inner())?, capture.drain_utf8()?))
+    }
+
+    #[tokio::test]
+    async fn test_eval() -> Result<()> {
+        let (out, printed) = session("1 + 2\nprintln!(\"hello\");\n\"a\" + \"b\"\n").await?;
+        assert_eq!(out, "3: i64\n\"ab\": String\n");
+        assert_eq!(printed, "hello\n");
+
+        // Multi-line input is read until it's complete.
+        let (out, _) = session("fn add(\n  a, b) {\n  a + b\n}\nadd(1,\n 2)\n").await?;
+        assert_eq!(out, "3: i64\n");
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_state() -> Result<()> {
+        let input = "\
+let a = 40;
+fn add(a, b) { a + b }
+let (b, c) = (1, 2);
+add(a, b + c)
+let a = \"shadowed\";
+a
+fn add(a, b) { a - b }
+add(b, c)
+:reset
+b
+";
+
+        let (out, _) = session(input).await?;
+
+        let mut lines = out.lines();
+        assert_eq!(lines.next(), Some("43: i64"));
+        assert_eq!(lines.next(), Some("\"shadowed\": String"));
+        assert_eq!(lines.next(), Some("-1: i64"));
+        // Variables are forgotten after a reset.
+        assert_eq!(lines.next(), Some("error: No local variable `b`"));
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_error_recovery() -> Result<()> {
+        let input = "\
+let a = 1;
+1 +
+
+b
+let c = a / 0;
+:dump
+c
+a + 1
+:nope
+:quit
+a
+";
+
+        let (out, _) = session(input).await?;
+
+        // Incomplete input is submitted by an empty line.
+        assert!(out.contains("error: Expected `expression`, but got `eof`"));
+        assert!(out.contains("error: No local variable `b`"));
+        assert!(out.contains("error: Division by zero"));
+        // The input which failed at runtime can still be inspected.
+        assert!(out.contains("# instructions\nfn __repl(a)"));
+        // Variables bound by failed input are not kept.
+        assert!(out.contains("error: No local variable `c`"));
+        assert!(out.ends_with("2: i64\nUnknown command `:nope`, try `:help`\n"));
+        Ok(())
+    }
+}
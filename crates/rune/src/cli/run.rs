@@ -1,4 +1,6 @@
-use std::io::Write;
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::path::PathBuf;
 use std::sync::Arc;
 use std::time::Instant;
 
@@ -6,9 +8,13 @@ use anyhow::{anyhow, Result};
 use clap::Parser;
 
 use crate::cli::{Config, ExitCode, Io, CommandBase, AssetKind, SharedFlags};
+use crate::runtime::profiler::{Metric, Profiler};
 use crate::runtime::{VmError, VmExecution, VmResult, UnitStorage};
 use crate::{Context, Sources, Unit, Value, Vm};
 
+/// The number of functions and lines included in a profile report.
+const PROFILE_LIMIT: usize = 20;
+
 #[derive(Parser, Debug)]
 pub(super) struct Flags {
     /// Provide detailed tracing for each instruction executed.
@@ -53,6 +59,14 @@ pub(super) struct Flags {
     /// implies `--trace`.
     #[arg(long)]
     trace_limit: Option<usize>,
+    /// Profile the execution and report the functions and lines where the
+    /// most instructions and time is spent (only available if -O debug-info=true).
+    #[arg(long)]
+    profile: bool,
+    /// Write the profile as folded stacks weighted by time to the given path,
+    /// which can be rendered as a flamegraph. This implies `--profile`.
+    #[arg(long)]
+    profile_folded: Option<PathBuf>,
 }
 
 impl CommandBase for Flags {
@@ -79,6 +93,10 @@ impl CommandBase for Flags {
         if self.trace_limit.is_some() {
             self.trace = true;
         }
+
+        if self.profile_folded.is_some() {
+            self.profile = true;
+        }
     }
 }
 
@@ -153,46 +171,7 @@ pub(super) async fn run(
             unit.emit_instructions(&mut o, sources, args.with_source)?;
         }
 
-        let mut functions = unit.iter_functions().peekable();
-        let mut strings = unit.iter_static_strings().peekable();
-        let mut keys = unit.iter_static_object_keys().peekable();
-        let mut constants = unit.iter_constants().peekable();
-
-        if args.dump_functions && functions.peek().is_some() {
-            writeln!(io.stdout, "# dynamic functions")?;
-
-            for (hash, kind) in functions {
-                if let Some(signature) = unit.debug_info().and_then(|d| d.functions.get(&hash)) {
-                    writeln!(io.stdout, "{} = {}", hash, signature)?;
-                } else {
-                    writeln!(io.stdout, "{} = {}", hash, kind)?;
-                }
-            }
-        }
-
-        if strings.peek().is_some() {
-            writeln!(io.stdout, "# strings")?;
-
-            for string in strings {
-                writeln!(io.stdout, "{} = {:?}", string.hash(), string)?;
-            }
-        }
-
-        if args.dump_constants && constants.peek().is_some() {
-            writeln!(io.stdout, "# constants")?;
-
-            for constant in constants {
-                writeln!(io.stdout, "{} = {:?}", constant.0, constant.1)?;
-            }
-        }
-
-        if keys.peek().is_some() {
-            writeln!(io.stdout, "# object keys")?;
-
-            for (hash, keys) in keys {
-                writeln!(io.stdout, "{} = {:?}", hash, keys)?;
-            }
-        }
+        dump_unit(io.stdout, &unit, args.dump_functions, args.dump_constants)?;
     }
 
     let runtime = Arc::new(context.runtime());
@@ -218,6 +197,19 @@ pub(super) async fn run(
             Err(TraceError::VmError(vm)) => VmResult::Err(vm),
             Err(TraceError::Limited) => return Err(anyhow!("Trace limit reached")),
         }
+    } else if args.profile {
+        let mut profiler = Profiler::new(execution.vm().unit().clone(), sources).with_context(context);
+        let result = execution.async_profile(&mut profiler).await;
+
+        profiler.write_report(io.stdout, PROFILE_LIMIT)?;
+
+        if let Some(path) = &args.profile_folded {
+            let mut o = BufWriter::new(File::create(path)?);
+            profiler.write_folded(&mut o, Metric::Time)?;
+            o.flush()?;
+        }
+
+        result
     } else {
         execution.async_complete().await
     };
@@ -307,6 +299,60 @@ pub(super) async fn run(
     }
 }
 
+/// Dump the functions, static data and constants of a unit.
+pub(super) fn dump_unit<O>(
+    o: &mut O,
+    unit: &Unit,
+    dump_functions: bool,
+    dump_constants: bool,
+) -> std::io::Result<()>
+where
+    O: Write,
+{
+    let mut functions = unit.iter_functions().peekable();
+    let mut strings = unit.iter_static_strings().peekable();
+    let mut keys = unit.iter_static_object_keys().peekable();
+    let mut constants = unit.iter_constants().peekable();
+
+    if dump_functions && functions.peek().is_some() {
+        writeln!(o, "# dynamic functions")?;
+
+        for (hash, kind) in functions {
+            if let Some(signature) = unit.debug_info().and_then(|d| d.functions.get(&hash)) {
+                writeln!(o, "{} = {}", hash, signature)?;
+            } else {
+                writeln!(o, "{} = {}", hash, kind)?;
+            }
+        }
+    }
+
+    if strings.peek().is_some() {
+        writeln!(o, "# strings")?;
+
+        for string in strings {
+            writeln!(o, "{} = {:?}", string.hash(), string)?;
+        }
+    }
+
+    if dump_constants && constants.peek().is_some() {
+        writeln!(o, "# constants")?;
+
+        for constant in constants {
+            writeln!(o, "{} = {:?}", constant.0, constant.1)?;
+        }
+    }
+
+    if keys.peek().is_some() {
+        writeln!(o, "# object keys")?;
+
+        for (hash, keys) in keys {
+            writeln!(o, "{} = {:?}", hash, keys)?;
+        }
+    }
+
+    Ok(())
+}
+
 /// Perform a detailed trace of the program.
 async fn do_trace<T>(
     io: &Io<'_>,
@@ -1,4 +1,6 @@
-use std::io::Write;
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::path::PathBuf;
 use std::sync::Arc;
 use std::time::Instant;
 
@@ -12,6 +14,7 @@ use crate::cli::visitor;
 use crate::cli::naming::Naming;
 use crate::compile::{ItemBuf, FileSourceLoader};
 use crate::modules::capture_io::CaptureIo;
+use crate::runtime::coverage::Coverage;
 use crate::runtime::{Value, Vm, VmError, VmResult, UnitFn};
 use crate::doc::TestParams;
 use crate::{Hash, Sources, Unit, Diagnostics, Source};
@@ -31,6 +34,22 @@ pub(super) struct Flags {
     /// Break on the first test failed.
     #[arg(long)]
     fail_fast: bool,
+    /// Record which lines and branches of the tested sources are executed,
+    /// and print a summary of the coverage.
+    #[arg(long)]
+    coverage: bool,
+    /// Write the coverage in the lcov format to the given path. This implies
+    /// `--coverage`.
+    #[arg(long)]
+    coverage_lcov: Option<PathBuf>,
+    /// Write the coverage as an annotated HTML page to the given path. This
+    /// implies `--coverage`.
+    #[arg(long)]
+    coverage_html: Option<PathBuf>,
+    /// Fail if less than the given percentage of lines are covered. This
+    /// implies `--coverage`.
+    #[arg(long)]
+    coverage_fail_under: Option<f64>,
 }
 
 impl CommandBase for Flags {
@@ -52,6 +71,10 @@ impl CommandBase for Flags {
     #[inline]
     fn propagate(&mut self, c: &mut Config, _: &mut SharedFlags) {
         c.test = true;
+
+        if self.coverage_lcov.is_some() || self.coverage_html.is_some() || self.coverage_fail_under.is_some() {
+            self.coverage = true;
+        }
     }
 }
 
@@ -83,6 +106,7 @@ where
     let mut naming = Naming::default();
 
     let mut include_std = false;
+    let mut coverage = flags.coverage.then(Coverage::new);
 
     for opt in &flags.options {
         match opt.as_str() {
@@ -137,6 +161,10 @@ where
 
         doc_visitors.push(doc_visitor);
 
+        if let Some(coverage) = &mut coverage {
+            coverage.insert(unit.clone(), &sources);
+        }
+
         for (hash, item) in functions.into_functions() {
             cases.push(TestCase::new(hash, item, unit.clone(), sources.clone(), TestParams::default()));
         }
@@ -201,7 +229,7 @@ where
         executed = executed.wrapping_add(1);
 
         let mut vm = Vm::new(runtime.clone(), case.unit.clone());
-        case.execute(&mut vm, &capture).await?;
+        case.execute(&mut vm, &capture, coverage.as_mut()).await?;
 
         if case.outcome.is_ok() {
             if flags.quiet {
@@ -246,7 +274,35 @@ where
         elapsed.as_secs_f64()
     )?;
 
-    if build_errors == 0 && failures == 0 {
+    let mut uncovered = false;
+
+    if let Some(coverage) = &coverage {
+        let summary = coverage.summary();
+        writeln!(io.stdout, "Coverage: {}", summary)?;
+
+        if let Some(path) = &flags.coverage_lcov {
+            let mut o = BufWriter::new(File::create(path).with_context(|| path.display().to_string())?);
+            coverage.write_lcov(&mut o)?;
+            o.flush()?;
+        }
+
+        if let Some(path) = &flags.coverage_html {
+            let mut o = BufWriter::new(File::create(path).with_context(|| path.display().to_string())?);
+            coverage.write_html(&mut o)?;
+            o.flush()?;
+        }
+
+        if let Some(limit) = flags.coverage_fail_under {
+            if summary.line_percent() < limit {
+                io.stdout.set_color(&colors.error)?;
+                writeln!(io.stdout, "Line coverage {:.1}% is below the required {:.1}%", summary.line_percent(), limit)?;
+                io.stdout.reset()?;
+                uncovered = true;
+            }
+        }
+    }
+
+    if build_errors == 0 && failures == 0 && !uncovered {
         Ok(ExitCode::Success)
     } else {
         Ok(ExitCode::Failure)
@@ -295,10 +351,12 @@ impl TestCase {
         &mut self,
         vm: &mut Vm,
         capture_io: &CaptureIo,
+        coverage: Option<&mut Coverage>,
     ) -> Result<()> {
-        let result = match vm.execute(self.hash, ()) {
-            Ok(mut execution) => execution.async_complete().await,
-            Err(err) => VmResult::Err(err),
+        let result = match (vm.execute(self.hash, ()), coverage) {
+            (Ok(mut execution), Some(coverage)) => execution.async_complete_with_coverage(coverage).await,
+            (Ok(mut execution), None) => execution.async_complete().await,
+            (Err(err), _) => VmResult::Err(err),
         };
 
         capture_io.drain_into(&mut self.output)?;
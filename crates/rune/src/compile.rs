@@ -4,7 +4,7 @@
 //! uses this compiler. In here you'll just find compiler-specific types.
 
 mod assembly;
-pub(crate) use self::assembly::{Assembly, AssemblyInst};
+pub(crate) use self::assembly::{Assembly, AssemblyInst, AssemblyVar};
 
 pub(crate) mod attrs;
 
@@ -13,8 +13,8 @@ pub use self::error::{Error, ImportStep, MetaError};
 pub(crate) use self::error::{ErrorKind, IrErrorKind};
 
 mod compile_visitor;
-pub use self::compile_visitor::CompileVisitor;
 pub(crate) use self::compile_visitor::NoopCompileVisitor;
+pub use self::compile_visitor::{CompileVisitor, DeclarationKind};
 
 pub(crate) mod context;
 pub use self::context::Context;
@@ -22,6 +22,9 @@ pub use self::context::Context;
 pub(crate) mod context_error;
 pub use self::context_error::ContextError;
 
+pub(crate) mod context_signature;
+pub use self::context_signature::ContextSignature;
+
 pub(crate) mod meta_info;
 pub use meta_info::MetaInfo;
 
@@ -38,6 +41,11 @@ pub use rune_core::{Component, ComponentRef, IntoComponent, Item, ItemBuf};
 mod source_loader;
 pub use self::source_loader::{FileSourceLoader, NoopSourceLoader, SourceLoader};
 
+#[cfg(feature = "cache")]
+pub(crate) mod unit_cache;
+#[cfg(feature = "cache")]
+pub use self::unit_cache::{FileUnitCache, UnitCache};
+
 mod unit_builder;
 pub use self::unit_builder::LinkerError;
 pub(crate) use self::unit_builder::UnitBuilder;
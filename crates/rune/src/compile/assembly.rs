@@ -22,6 +22,19 @@ pub(crate) enum AssemblyInst {
     Raw { raw: Inst },
 }
 
+/// A named variable which is live over a range of instructions in an assembly.
+#[derive(Debug, Clone)]
+pub(crate) struct AssemblyVar {
+    /// The name of the variable.
+    pub(crate) name: Box<str>,
+    /// The offset of the variable from the bottom of the stack frame.
+    pub(crate) offset: usize,
+    /// The first instruction at which the variable is live.
+    pub(crate) start: usize,
+    /// The instruction at which the variable is no longer live.
+    pub(crate) end: usize,
+}
+
 /// Helper structure to build instructions and maintain certain invariants.
 #[derive(Debug, Clone, Default)]
 pub(crate) struct Assembly {
@@ -37,6 +50,8 @@ pub(crate) struct Assembly {
     pub(crate) label_count: usize,
     /// The collection of functions required by this assembly.
     pub(crate) required_functions: HashMap<Hash, Vec<(Span, SourceId)>>,
+    /// Named variables declared in the assembly.
+    pub(crate) variables: Vec<AssemblyVar>,
 }
 
 impl Assembly {
@@ -49,9 +64,15 @@ impl Assembly {
             comments: Default::default(),
             label_count,
             required_functions: Default::default(),
+            variables: Default::default(),
         }
     }
 
+    /// The position of the next instruction to be pushed.
+    pub(crate) fn pos(&self) -> usize {
+        self.instructions.len()
+    }
+
     /// Construct and return a new label.
     pub(crate) fn new_label(&mut self, name: &'static str) -> Label {
         let label = Label::new(name, self.label_count);
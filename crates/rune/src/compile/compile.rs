@@ -4,8 +4,8 @@ use crate::ast;
 use crate::ast::{Span, Spanned};
 use crate::compile::v1;
 use crate::compile::{
-    self, Assembly, CompileVisitor, Context, ErrorKind, Location, Options, Pool, Prelude,
-    SourceLoader, UnitBuilder,
+    self, Assembly, CompileVisitor, ComponentRef, Context, ErrorKind, Location, Options, Pool,
+    Prelude, SourceLoader, UnitBuilder,
 };
 use crate::hir;
 use crate::indexing::FunctionAst;
@@ -13,6 +13,7 @@ use crate::macros::Storage;
 use crate::parse::Resolve;
 use crate::query::{Build, BuildEntry, GenericsParameters, Query, Used};
 use crate::runtime::unit::UnitEncoder;
+use crate::runtime::UnitFnParameters;
 use crate::shared::{Consts, Gen};
 use crate::worker::{LoadFileKind, Task, Worker};
 use crate::{Diagnostics, Sources};
@@ -234,7 +235,7 @@ impl<'arena> CompileBuildEntry<'_, 'arena> {
                 );
 
                 let hir = match &f.ast {
-                    FunctionAst::Item(ast) => hir::lowering::item_fn(&mut cx, ast)?,
+                    FunctionAst::Item(ast) => hir::lowering::item_fn(&mut cx, ast, f.call)?,
                     FunctionAst::Empty(ast, span) => hir::lowering::empty_fn(&mut cx, ast, &span)?,
                 };
 
@@ -243,8 +244,17 @@ impl<'arena> CompileBuildEntry<'_, 'arena> {
                 let mut c = self.compiler1(location, span, &mut asm);
                 assemble::fn_from_item_fn(&mut c, &hir, f.is_instance)?;
 
+                // Functions prefixed with an underscore are intentionally
+                // unused.
+                let is_silenced = matches!(
+                    self.q.pool.item(item_meta.item).last(),
+                    Some(ComponentRef::Str(name)) if name.starts_with('_')
+                );
+
                 if !self.q.is_used(&item_meta) {
-                    self.q.diagnostics.not_used(location.source_id, span, None);
+                    if !is_silenced {
+                        self.q.diagnostics.not_used(location.source_id, span, None);
+                    }
                 } else {
                     let instance = match (type_hash, &f.ast) {
                         (Some(type_hash), FunctionAst::Item(ast)) => {
@@ -254,11 +264,16 @@ impl<'arena> CompileBuildEntry<'_, 'arena> {
                         _ => None,
                     };
 
+                    let parameters = self
+                        .q
+                        .unit_fn_parameters(span, self.q.pool.item_type_hash(item_meta.item))?;
+
                     self.q.unit.new_function(
                         location,
                         self.q.pool.item(item_meta.item),
                         instance,
                         count,
+                        parameters,
                         asm,
                         f.call,
                         args,
@@ -293,11 +308,27 @@ impl<'arena> CompileBuildEntry<'_, 'arena> {
                     c.q.diagnostics
                         .not_used(location.source_id, &location.span, None);
                 } else {
+                    let mut names = Vec::new();
+
+                    for (arg, _) in closure.ast.args.as_slice() {
+                        names.push(match arg {
+                            ast::FnArg::Pat(pat) => self.q.pat_name(pat)?,
+                            ast::FnArg::Typed(arg) => self.q.pat_name(&arg.pat)?,
+                            _ => None,
+                        });
+                    }
+
+                    let parameters = UnitFnParameters {
+                        names: names.into(),
+                        defaults: Box::default(),
+                    };
+
                     self.q.unit.new_function(
                         location,
                         self.q.pool.item(item_meta.item),
                         None,
                         closure.ast.args.len(),
+                        parameters,
                         asm,
                         closure.call,
                         args,
@@ -334,6 +365,7 @@ impl<'arena> CompileBuildEntry<'_, 'arena> {
                         self.q.pool.item(item_meta.item),
                         None,
                         args,
+                        UnitFnParameters::default(),
                         asm,
                         b.call,
                         Default::default(),
@@ -440,8 +472,8 @@ where
             ast::FnArg::SelfValue(..) => {
                 args.push("self".into());
             }
-            ast::FnArg::Pat(pat) => {
-                let span = pat.span();
+            ast::FnArg::Pat(..) | ast::FnArg::Typed(..) | ast::FnArg::Default(..) => {
+                let span = arg.span();
 
                 if let Some(s) = sources.source(location.source_id, span) {
                     args.push(s.into());
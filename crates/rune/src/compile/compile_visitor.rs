@@ -25,6 +25,24 @@ pub trait CompileVisitor {
     /// Visit something that is a module.
     fn visit_mod(&mut self, _location: &dyn Located) {}
 
+    /// Visit an item declared in a source, such as a function, struct, enum,
+    /// variant, constant, impl block or module.
+    ///
+    /// The `location` covers the whole declaration, while `name_span` only
+    /// covers its name. For impl blocks the name is the path being
+    /// implemented.
+    fn visit_declaration(
+        &mut self,
+        _location: &dyn Located,
+        _kind: DeclarationKind,
+        _name: &str,
+        _name_span: &dyn Spanned,
+    ) {
+    }
+
+    /// Visit a block of statements.
+    fn visit_block(&mut self, _location: &dyn Located) {}
+
     /// Visit anterior `///`-style comments, and interior `//!`-style doc
     /// comments for an item.
     ///
@@ -58,6 +76,28 @@ pub trait CompileVisitor {
     }
 }
 
+/// The kind of a declaration passed to [CompileVisitor::visit_declaration].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum DeclarationKind {
+    /// A function.
+    Function,
+    /// A struct.
+    Struct,
+    /// An enum.
+    Enum,
+    /// A variant of an enum.
+    Variant,
+    /// A constant.
+    Const,
+    /// An impl block.
+    Impl,
+    /// A trait.
+    Trait,
+    /// A module, either inline or loaded from a file.
+    Module,
+}
+
 /// A [CompileVisitor] which does nothing.
 pub(crate) struct NoopCompileVisitor(());
 
@@ -4,20 +4,24 @@ use crate::no_std::collections::{BTreeSet, HashMap, HashSet};
 use crate::no_std::prelude::*;
 use crate::no_std::sync::Arc;
 
+use crate::compile::context_signature::{
+    ConstSignature, ContextSignature, KindSignature, MetaSignature, TypeSignature,
+    SIGNATURE_VERSION,
+};
 use crate::compile::meta;
-#[cfg(feature = "doc")]
-use crate::compile::Docs;
 #[cfg(feature = "emit")]
 use crate::compile::MetaInfo;
+use crate::compile::{self, Docs};
 use crate::compile::{ComponentRef, ContextError, IntoComponent, Item, ItemBuf, Names};
 use crate::hash;
+use crate::macros::{MacroContext, TokenStream};
 use crate::module::{
     Fields, InternalEnum, Module, ModuleAssociated, ModuleAttributeMacro, ModuleConstant,
     ModuleFunction, ModuleMacro, ModuleType, TypeSpecification,
 };
 use crate::runtime::{
-    AttributeMacroHandler, ConstValue, FunctionHandler, MacroHandler, Protocol, RuntimeContext,
-    StaticType, TypeCheck, TypeInfo, VariantRtti,
+    AttributeMacroHandler, ConstValue, FunctionHandler, MacroHandler, Protocol, Rtti,
+    RuntimeContext, StaticType, TypeCheck, TypeInfo, VariantRtti, VmErrorKind, VmResult,
 };
 use crate::Hash;
 
@@ -137,6 +141,7 @@ impl Context {
 
         this.install(crate::modules::num::module()?)?;
         this.install(crate::modules::any::module()?)?;
+        this.install(crate::modules::bigint::module()?)?;
         this.install(crate::modules::bytes::module()?)?;
         this.install(crate::modules::char::module()?)?;
         this.install(crate::modules::hash::module()?)?;
@@ -253,6 +258,54 @@ impl Context {
         })
     }
 
+    /// Iterate over the hashes and names of all named functions in the
+    /// [Context].
+    #[cfg(feature = "std")]
+    pub(crate) fn iter_function_names(&self) -> impl Iterator<Item = (Hash, &Item)> {
+        self.meta.iter().flat_map(|meta| match meta.kind {
+            meta::Kind::Function { .. } => Some((meta.hash, meta.item.as_deref()?)),
+            _ => None,
+        })
+    }
+
+    /// Calculate a hash which identifies everything installed in the
+    /// [Context].
+    #[cfg(feature = "cache")]
+    pub(crate) fn fingerprint(&self) -> Hash {
+        let mut hasher = hash::ParametersBuilder::new();
+        hasher.add(self.has_default_modules);
+
+        for meta in &self.meta {
+            hasher.add(meta.hash);
+            hasher.add(meta.item.as_deref());
+        }
+
+        let mut hashes = Vec::<Hash>::new();
+        hashes.extend(self.functions.keys().copied());
+        hashes.extend(self.macros.keys().copied());
+        hashes.extend(self.attribute_macros.keys().copied());
+        hashes.extend(self.types.keys().copied());
+        hashes.sort();
+        hasher.add(hashes);
+
+        // NB: constants are inlined when compiling, so their values are part
+        // of the unit.
+        let mut constants = self
+            .constants
+            .iter()
+            .map(|(hash, value)| (*hash, ConstSignature::from_const(value)))
+            .collect::<Vec<_>>();
+        constants.sort_by_key(|(hash, _)| *hash);
+        hasher.add(constants);
+        hasher.finish()
+    }
+
+    /// Iterate over all metadata in the [Context].
+    #[cfg(feature = "languageserver")]
+    pub(crate) fn iter_meta(&self) -> impl Iterator<Item = &ContextMeta> {
+        self.meta.iter()
+    }
+
     /// Iterate over all available types in the [Context].
     #[cfg(feature = "cli")]
     pub(crate) fn iter_types(&self) -> impl Iterator<Item = (Hash, &Item)> {
@@ -299,7 +352,6 @@ impl Context {
     }
 
     /// Lookup meta by its hash.
-    #[cfg(feature = "doc")]
     pub(crate) fn lookup_meta_by_hash(
         &self,
         hash: Hash,
@@ -369,6 +421,249 @@ impl Context {
         self.has_default_modules
     }
 
+    /// Construct a [ContextSignature] describing everything installed in the
+    /// [Context].
+    ///
+    /// The signature can be serialized to a file, and scripts can then be
+    /// compiled and link checked against it with
+    /// [Build::with_context_signature][crate::Build::with_context_signature]
+    /// without access to the native modules the [Context] was built from.
+    ///
+    /// Function deprecations and argument counts are only recorded in the
+    /// signature if the `doc` feature is enabled, since they're not available
+    /// otherwise.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rune::{Context, Source, Sources, Diagnostics};
+    ///
+    /// let context = Context::with_default_modules()?;
+    /// let signature = context.signature();
+    ///
+    /// let mut sources = Sources::new();
+    /// sources.insert(Source::new("entry", "pub fn main() { std::i64::max(1, 2) }"));
+    ///
+    /// let unit = rune::prepare(&mut sources)
+    ///     .with_context_signature(&signature)
+    ///     .build();
+    ///
+    /// assert!(unit.is_ok());
+    ///
+    /// let mut sources = Sources::new();
+    /// sources.insert(Source::new("entry", "pub fn main() { std::i64::missing(1, 2) }"));
+    ///
+    /// let mut diagnostics = Diagnostics::new();
+    ///
+    /// let unit = rune::prepare(&mut sources)
+    ///     .with_context_signature(&signature)
+    ///     .with_diagnostics(&mut diagnostics)
+    ///     .build();
+    ///
+    /// assert!(unit.is_err());
+    /// # Ok::<_, rune::Error>(())
+    /// ```
+    pub fn signature(&self) -> ContextSignature {
+        let mut crates = self
+            .crates
+            .iter()
+            .map(|c| c.to_string())
+            .collect::<Vec<_>>();
+        crates.sort();
+
+        let meta = self
+            .meta
+            .iter()
+            .flat_map(|meta| {
+                Some(MetaSignature {
+                    hash: meta.hash,
+                    item: meta.item.clone(),
+                    kind: KindSignature::from_meta(&meta.kind)?,
+                    #[cfg(feature = "doc")]
+                    docs: meta.docs.lines().to_vec(),
+                    #[cfg(not(feature = "doc"))]
+                    docs: Vec::new(),
+                    #[cfg(feature = "doc")]
+                    arguments: meta.docs.args().map(<[_]>::to_vec),
+                    #[cfg(not(feature = "doc"))]
+                    arguments: None,
+                })
+            })
+            .collect();
+
+        let mut types = self
+            .types
+            .values()
+            .map(|ty| TypeSignature {
+                item: ty.item.clone(),
+                hash: ty.hash,
+                type_check: ty.type_check,
+                type_parameters: ty.type_parameters,
+                enum_hash: match &ty.type_info {
+                    TypeInfo::Variant(rtti) => Some(rtti.enum_hash),
+                    _ => None,
+                },
+            })
+            .collect::<Vec<_>>();
+
+        types.sort_by_key(|ty| ty.hash);
+
+        let mut functions = self.functions.keys().copied().collect::<Vec<_>>();
+        functions.sort();
+
+        let mut constants = self
+            .constants
+            .iter()
+            .map(|(hash, value)| (*hash, ConstSignature::from_const(value)))
+            .collect::<Vec<_>>();
+
+        constants.sort_by_key(|(hash, _)| *hash);
+
+        ContextSignature {
+            version: SIGNATURE_VERSION,
+            has_default_modules: self.has_default_modules,
+            crates,
+            meta,
+            types,
+            functions,
+            constants,
+        }
+    }
+
+    /// Construct a [Context] from a [ContextSignature].
+    ///
+    /// Native functions in the constructed context raise an error if they are
+    /// called, since the signature doesn't include their implementation.
+    /// Macros from the default modules are taken from them, while other native
+    /// macros raise a compile error when they are used.
+    pub(crate) fn from_signature(signature: &ContextSignature) -> Result<Self, ContextError> {
+        if signature.version != SIGNATURE_VERSION {
+            return Err(ContextError::UnsupportedSignatureVersion {
+                version: signature.version,
+            });
+        }
+
+        let defaults = if signature.has_default_modules {
+            Some(Self::with_default_modules()?)
+        } else {
+            None
+        };
+
+        let mut this = Self::new();
+        this.has_default_modules = signature.has_default_modules;
+        this.crates.extend(
+            signature
+                .crates
+                .iter()
+                .map(|c| Box::<str>::from(c.as_str())),
+        );
+
+        for ty in &signature.types {
+            let type_info = match ty.enum_hash {
+                Some(enum_hash) => TypeInfo::Variant(Arc::new(VariantRtti {
+                    enum_hash,
+                    hash: ty.hash,
+                    item: ty.item.clone(),
+                })),
+                None => TypeInfo::Typed(Arc::new(Rtti {
+                    hash: ty.hash,
+                    item: ty.item.clone(),
+                })),
+            };
+
+            this.types.insert(
+                ty.hash,
+                ContextType {
+                    item: ty.item.clone(),
+                    hash: ty.hash,
+                    type_check: ty.type_check,
+                    type_info,
+                    type_parameters: ty.type_parameters,
+                },
+            );
+        }
+
+        for &hash in &signature.functions {
+            let handler: Arc<FunctionHandler> =
+                Arc::new(move |_, _| VmResult::err(VmErrorKind::MissingFunction { hash }));
+
+            this.functions.insert(hash, handler);
+        }
+
+        for (hash, value) in &signature.constants {
+            this.constants.insert(*hash, value.to_const());
+        }
+
+        for meta in &signature.meta {
+            let Some(kind) = meta.kind.to_meta() else {
+                continue;
+            };
+
+            match kind {
+                meta::Kind::Macro => {
+                    let handler = match defaults.as_ref().and_then(|d| d.macros.get(&meta.hash)) {
+                        Some(handler) => handler.clone(),
+                        None => {
+                            let item = meta.item.clone().unwrap_or_default();
+
+                            Arc::new(move |cx: &mut MacroContext<'_, '_, '_>, _: &TokenStream| {
+                                Err(compile::Error::msg(
+                                    cx.macro_span(),
+                                    format!("Macro `{item}` can't be expanded using a context signature"),
+                                ))
+                            })
+                        }
+                    };
+
+                    this.macros.insert(meta.hash, handler);
+                }
+                meta::Kind::AttributeMacro => {
+                    let handler = match defaults
+                        .as_ref()
+                        .and_then(|d| d.attribute_macros.get(&meta.hash))
+                    {
+                        Some(handler) => handler.clone(),
+                        None => {
+                            let item = meta.item.clone().unwrap_or_default();
+
+                            Arc::new(
+                                move |cx: &mut MacroContext<'_, '_, '_>,
+                                      _: &TokenStream,
+                                      _: &TokenStream| {
+                                    Err(compile::Error::msg(
+                                        cx.macro_span(),
+                                        format!("Macro `{item}` can't be expanded using a context signature"),
+                                    ))
+                                },
+                            )
+                        }
+                    };
+
+                    this.attribute_macros.insert(meta.hash, handler);
+                }
+                _ => {}
+            }
+
+            #[allow(unused_mut)]
+            let mut docs = Docs::EMPTY;
+            docs.set_docs(&meta.docs);
+
+            if let Some(arguments) = &meta.arguments {
+                docs.set_arguments(arguments);
+            }
+
+            this.install_meta(ContextMeta {
+                hash: meta.hash,
+                item: meta.item.clone(),
+                kind,
+                #[cfg(feature = "doc")]
+                docs,
+            })?;
+        }
+
+        Ok(this)
+    }
+
     /// Install the given meta.
     fn install_meta(&mut self, meta: ContextMeta) -> Result<(), ContextError> {
         if let Some(item) = &meta.item {
@@ -437,7 +732,6 @@ impl Context {
                             let hash = Hash::type_hash(&item);
 
                             let signature = meta::Signature {
-                                #[cfg(feature = "doc")]
                                 is_async: false,
                                 #[cfg(feature = "doc")]
                                 deprecated: None,
@@ -447,9 +741,7 @@ impl Context {
                                     Fields::Unnamed(args) => *args,
                                     Fields::Empty => 0,
                                 }),
-                                #[cfg(feature = "doc")]
                                 return_type: Some(ty.hash),
-                                #[cfg(feature = "doc")]
                                 argument_types: Box::from([]),
                             };
 
@@ -467,7 +759,10 @@ impl Context {
                                     .copied()
                                     .enumerate()
                                     .map(|(position, name)| {
-                                        (Box::<str>::from(name), meta::FieldMeta { position })
+                                        (
+                                            Box::<str>::from(name),
+                                            meta::FieldMeta { position, ty: None },
+                                        )
                                     })
                                     .collect(),
                             }),
@@ -501,7 +796,6 @@ impl Context {
 
                         let constructor = if let Some(c) = &variant.constructor {
                             let signature = meta::Signature {
-                                #[cfg(feature = "doc")]
                                 is_async: false,
                                 #[cfg(feature = "doc")]
                                 deprecated: None,
@@ -511,9 +805,7 @@ impl Context {
                                     Fields::Unnamed(args) => *args,
                                     Fields::Empty => 0,
                                 }),
-                                #[cfg(feature = "doc")]
                                 return_type: Some(ty.hash),
-                                #[cfg(feature = "doc")]
                                 argument_types: Box::from([]),
                             };
 
@@ -539,7 +831,7 @@ impl Context {
                                                 .map(|(position, name)| {
                                                     (
                                                         Box::<str>::from(name),
-                                                        meta::FieldMeta { position },
+                                                        meta::FieldMeta { position, ty: None },
                                                     )
                                                 })
                                                 .collect(),
@@ -618,15 +910,12 @@ impl Context {
         );
 
         let signature = meta::Signature {
-            #[cfg(feature = "doc")]
             is_async: f.is_async,
             #[cfg(feature = "doc")]
             deprecated: f.deprecated.clone(),
             #[cfg(feature = "doc")]
             args: f.args,
-            #[cfg(feature = "doc")]
             return_type: f.return_type.as_ref().map(|f| f.hash),
-            #[cfg(feature = "doc")]
             argument_types: f
                 .argument_types
                 .iter()
@@ -732,15 +1021,12 @@ impl Context {
             .with_function_parameters(assoc.name.function_parameters);
 
         let signature = meta::Signature {
-            #[cfg(feature = "doc")]
             is_async: assoc.is_async,
             #[cfg(feature = "doc")]
             deprecated: assoc.deprecated.clone(),
             #[cfg(feature = "doc")]
             args: assoc.args,
-            #[cfg(feature = "doc")]
             return_type: assoc.return_type.as_ref().map(|f| f.hash),
-            #[cfg(feature = "doc")]
             argument_types: assoc
                 .argument_types
                 .iter()
@@ -851,7 +1137,6 @@ impl Context {
                 self.insert_native_fn(hash, constructor)?;
 
                 Some(meta::Signature {
-                    #[cfg(feature = "doc")]
                     is_async: false,
                     #[cfg(feature = "doc")]
                     deprecated: None,
@@ -861,9 +1146,7 @@ impl Context {
                         Fields::Unnamed(args) => *args,
                         Fields::Empty => 0,
                     }),
-                    #[cfg(feature = "doc")]
                     return_type: Some(enum_hash),
-                    #[cfg(feature = "doc")]
                     argument_types: Box::from([]),
                 })
             } else {
@@ -883,7 +1166,10 @@ impl Context {
                                 .copied()
                                 .enumerate()
                                 .map(|(position, name)| {
-                                    (Box::<str>::from(name), meta::FieldMeta { position })
+                                    (
+                                        Box::<str>::from(name),
+                                        meta::FieldMeta { position, ty: None },
+                                    )
                                 })
                                 .collect(),
                         }),
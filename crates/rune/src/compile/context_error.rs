@@ -3,6 +3,7 @@ use core::fmt;
 use crate::no_std::prelude::*;
 
 use crate::alloc::AllocError;
+use crate::compile::context_signature::SIGNATURE_VERSION;
 use crate::compile::ItemBuf;
 use crate::runtime::{TypeInfo, VmError};
 use crate::Hash;
@@ -113,6 +114,9 @@ pub enum ContextError {
         hash: Hash,
         item_hash: Hash,
     },
+    UnsupportedSignatureVersion {
+        version: u32,
+    },
 }
 
 impl From<AllocError> for ContextError {
@@ -253,6 +257,12 @@ impl fmt::Display for ContextError {
             } => {
                 write!(f,"Type hash mismatch for `{type_info}`, from module is `{hash}` while from item `{item}` is `{item_hash}`. A possibility is that it has the wrong #[rune(item = ..)] setting.")?;
             }
+            ContextError::UnsupportedSignatureVersion { version } => {
+                write!(
+                    f,
+                    "Context signature has version {version}, but only version {SIGNATURE_VERSION} is supported"
+                )?;
+            }
         }
 
         Ok(())
@@ -0,0 +1,424 @@
+use crate::no_std::borrow::Cow;
+use crate::no_std::prelude::*;
+
+use serde::{Deserialize, Serialize};
+
+use crate::compile::{meta, ItemBuf};
+use crate::runtime::{Bytes, ConstValue, Protocol, TypeCheck};
+use crate::Hash;
+
+/// The version of the [ContextSignature] format.
+pub(crate) const SIGNATURE_VERSION: u32 = 1;
+
+/// A serializable description of everything installed in a
+/// [Context][crate::Context].
+///
+/// This contains the items, types, function signatures and documentation of
+/// a context, but none of the native code backing it. Scripts can be compiled
+/// and link checked against it with
+/// [Build::with_context_signature][crate::Build::with_context_signature]
+/// without having access to the native modules which were used to construct
+/// the context.
+///
+/// A signature is constructed with [Context::signature][crate::Context::signature].
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ContextSignature {
+    /// The version of the signature format.
+    pub(crate) version: u32,
+    /// Whether the default modules were installed.
+    pub(crate) has_default_modules: bool,
+    /// Registered crates.
+    pub(crate) crates: Vec<String>,
+    /// Registered metadata, in the order that it was registered.
+    pub(crate) meta: Vec<MetaSignature>,
+    /// Registered types.
+    pub(crate) types: Vec<TypeSignature>,
+    /// Hashes of all native functions.
+    pub(crate) functions: Vec<Hash>,
+    /// Constants visible in the context.
+    pub(crate) constants: Vec<(Hash, ConstSignature)>,
+}
+
+/// Signature of a single item of metadata.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub(crate) struct MetaSignature {
+    pub(crate) hash: Hash,
+    pub(crate) item: Option<ItemBuf>,
+    pub(crate) kind: KindSignature,
+    #[serde(default)]
+    pub(crate) docs: Vec<String>,
+    #[serde(default)]
+    pub(crate) arguments: Option<Vec<String>>,
+}
+
+/// Signature of a registered type.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub(crate) struct TypeSignature {
+    pub(crate) item: ItemBuf,
+    pub(crate) hash: Hash,
+    pub(crate) type_check: Option<TypeCheck>,
+    pub(crate) type_parameters: Hash,
+    /// The enum the type is a variant of, if any.
+    pub(crate) enum_hash: Option<Hash>,
+}
+
+/// Signature of a [meta::Kind].
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub(crate) enum KindSignature {
+    Type {
+        parameters: Hash,
+    },
+    Struct {
+        fields: FieldsSignature,
+        constructor: Option<FunctionSignature>,
+        parameters: Hash,
+    },
+    Variant {
+        enum_hash: Hash,
+        index: usize,
+        fields: FieldsSignature,
+        constructor: Option<FunctionSignature>,
+    },
+    Enum {
+        parameters: Hash,
+    },
+    Macro,
+    AttributeMacro,
+    Function {
+        associated: Option<AssociatedSignature>,
+        signature: FunctionSignature,
+        parameters: Hash,
+        #[serde(default)]
+        container: Option<Hash>,
+        #[serde(default)]
+        parameter_types: Vec<Hash>,
+    },
+    Const,
+    Module,
+}
+
+impl KindSignature {
+    /// Construct the signature of a kind of metadata, returning `None` for
+    /// kinds which can't be part of a context.
+    pub(crate) fn from_meta(kind: &meta::Kind) -> Option<Self> {
+        Some(match kind {
+            meta::Kind::Type { parameters } => Self::Type {
+                parameters: *parameters,
+            },
+            meta::Kind::Struct {
+                fields,
+                constructor,
+                parameters,
+            } => Self::Struct {
+                fields: FieldsSignature::from_meta(fields),
+                constructor: constructor.as_ref().map(FunctionSignature::from_meta),
+                parameters: *parameters,
+            },
+            meta::Kind::Variant {
+                enum_hash,
+                index,
+                fields,
+                constructor,
+            } => Self::Variant {
+                enum_hash: *enum_hash,
+                index: *index,
+                fields: FieldsSignature::from_meta(fields),
+                constructor: constructor.as_ref().map(FunctionSignature::from_meta),
+            },
+            meta::Kind::Enum { parameters } => Self::Enum {
+                parameters: *parameters,
+            },
+            meta::Kind::Macro => Self::Macro,
+            meta::Kind::AttributeMacro => Self::AttributeMacro,
+            meta::Kind::Function {
+                associated,
+                signature,
+                parameters,
+                #[cfg(feature = "doc")]
+                container,
+                #[cfg(feature = "doc")]
+                parameter_types,
+                ..
+            } => Self::Function {
+                associated: associated.as_ref().map(AssociatedSignature::from_meta),
+                signature: FunctionSignature::from_meta(signature),
+                parameters: *parameters,
+                #[cfg(feature = "doc")]
+                container: *container,
+                #[cfg(not(feature = "doc"))]
+                container: None,
+                #[cfg(feature = "doc")]
+                parameter_types: parameter_types.clone(),
+                #[cfg(not(feature = "doc"))]
+                parameter_types: Vec::new(),
+            },
+            meta::Kind::Const => Self::Const,
+            meta::Kind::Module => Self::Module,
+            _ => return None,
+        })
+    }
+
+    /// Convert the signature back into a kind of metadata, returning `None`
+    /// if it refers to a protocol which doesn't exist.
+    pub(crate) fn to_meta(&self) -> Option<meta::Kind> {
+        Some(match self {
+            Self::Type { parameters } => meta::Kind::Type {
+                parameters: *parameters,
+            },
+            Self::Struct {
+                fields,
+                constructor,
+                parameters,
+            } => meta::Kind::Struct {
+                fields: fields.to_meta(),
+                constructor: constructor.as_ref().map(FunctionSignature::to_meta),
+                parameters: *parameters,
+            },
+            Self::Variant {
+                enum_hash,
+                index,
+                fields,
+                constructor,
+            } => meta::Kind::Variant {
+                enum_hash: *enum_hash,
+                index: *index,
+                fields: fields.to_meta(),
+                constructor: constructor.as_ref().map(FunctionSignature::to_meta),
+            },
+            Self::Enum { parameters } => meta::Kind::Enum {
+                parameters: *parameters,
+            },
+            Self::Macro => meta::Kind::Macro,
+            Self::AttributeMacro => meta::Kind::AttributeMacro,
+            Self::Function {
+                associated,
+                signature,
+                parameters,
+                #[cfg_attr(not(feature = "doc"), allow(unused))]
+                container,
+                #[cfg_attr(not(feature = "doc"), allow(unused))]
+                parameter_types,
+            } => meta::Kind::Function {
+                associated: match associated {
+                    Some(associated) => Some(associated.to_meta()?),
+                    None => None,
+                },
+                signature: signature.to_meta(),
+                is_test: false,
+                is_bench: false,
+                parameters: *parameters,
+                #[cfg(feature = "doc")]
+                container: *container,
+                #[cfg(feature = "doc")]
+                parameter_types: parameter_types.clone(),
+            },
+            Self::Const => meta::Kind::Const,
+            Self::Module => meta::Kind::Module,
+        })
+    }
+}
+
+/// Signature of [meta::Fields].
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub(crate) enum FieldsSignature {
+    /// Named fields, in the order they are declared.
+    Named(Vec<String>),
+    Unnamed(usize),
+    Empty,
+}
+
+impl FieldsSignature {
+    fn from_meta(fields: &meta::Fields) -> Self {
+        match fields {
+            meta::Fields::Named(named) => {
+                let mut fields = named.fields.iter().collect::<Vec<_>>();
+                fields.sort_by_key(|(_, field)| field.position);
+                Self::Named(
+                    fields
+                        .into_iter()
+                        .map(|(name, _)| name.to_string())
+                        .collect(),
+                )
+            }
+            meta::Fields::Unnamed(args) => Self::Unnamed(*args),
+            meta::Fields::Empty => Self::Empty,
+        }
+    }
+
+    fn to_meta(&self) -> meta::Fields {
+        match self {
+            Self::Named(names) => meta::Fields::Named(meta::FieldsNamed {
+                fields: names
+                    .iter()
+                    .enumerate()
+                    .map(|(position, name)| {
+                        (
+                            Box::<str>::from(name.as_str()),
+                            meta::FieldMeta { position, ty: None },
+                        )
+                    })
+                    .collect(),
+            }),
+            Self::Unnamed(args) => meta::Fields::Unnamed(*args),
+            Self::Empty => meta::Fields::Empty,
+        }
+    }
+}
+
+/// Signature of a [meta::Signature].
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub(crate) struct FunctionSignature {
+    pub(crate) is_async: bool,
+    pub(crate) deprecated: Option<String>,
+    pub(crate) args: Option<usize>,
+    pub(crate) return_type: Option<Hash>,
+    pub(crate) argument_types: Vec<Option<Hash>>,
+}
+
+impl FunctionSignature {
+    fn from_meta(signature: &meta::Signature) -> Self {
+        #[cfg_attr(not(feature = "doc"), allow(unused_mut))]
+        let mut this = Self {
+            is_async: signature.is_async,
+            return_type: signature.return_type,
+            argument_types: signature.argument_types.to_vec(),
+            ..Self::default()
+        };
+
+        #[cfg(feature = "doc")]
+        {
+            this.deprecated = signature.deprecated.as_deref().map(String::from);
+            this.args = signature.args;
+        }
+
+        this
+    }
+
+    fn to_meta(&self) -> meta::Signature {
+        meta::Signature {
+            is_async: self.is_async,
+            #[cfg(feature = "doc")]
+            deprecated: self.deprecated.as_deref().map(Box::from),
+            #[cfg(feature = "doc")]
+            args: self.args,
+            return_type: self.return_type,
+            argument_types: self.argument_types.iter().copied().collect(),
+        }
+    }
+}
+
+/// Signature of a [meta::AssociatedKind], with protocols identified by their
+/// hash.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub(crate) enum AssociatedSignature {
+    Protocol(Hash),
+    FieldFn(Hash, String),
+    IndexFn(Hash, usize),
+    Instance(String),
+}
+
+impl AssociatedSignature {
+    fn from_meta(kind: &meta::AssociatedKind) -> Self {
+        match kind {
+            meta::AssociatedKind::Protocol(protocol) => Self::Protocol(protocol.hash),
+            meta::AssociatedKind::FieldFn(protocol, field) => {
+                Self::FieldFn(protocol.hash, field.to_string())
+            }
+            meta::AssociatedKind::IndexFn(protocol, index) => Self::IndexFn(protocol.hash, *index),
+            meta::AssociatedKind::Instance(name) => Self::Instance(name.to_string()),
+        }
+    }
+
+    fn to_meta(&self) -> Option<meta::AssociatedKind> {
+        Some(match self {
+            Self::Protocol(hash) => meta::AssociatedKind::Protocol(Protocol::from_hash(*hash)?),
+            Self::FieldFn(hash, field) => meta::AssociatedKind::FieldFn(
+                Protocol::from_hash(*hash)?,
+                Cow::Owned(field.clone()),
+            ),
+            Self::IndexFn(hash, index) => {
+                meta::AssociatedKind::IndexFn(Protocol::from_hash(*hash)?, *index)
+            }
+            Self::Instance(name) => meta::AssociatedKind::Instance(Cow::Owned(name.clone())),
+        })
+    }
+}
+
+/// Signature of a [ConstValue].
+///
+/// Floats are stored by their bits, since formats such as JSON can't represent
+/// constants like `f64::NAN`, and big integers by their signed little-endian
+/// bytes.
+#[derive(Debug, Clone, Hash, Serialize, Deserialize)]
+pub(crate) enum ConstSignature {
+    EmptyTuple,
+    Byte(u8),
+    Char(char),
+    Bool(bool),
+    Integer(i64),
+    Float(u64),
+    BigInt(Vec<u8>),
+    String(String),
+    Bytes(Vec<u8>),
+    Vec(Vec<ConstSignature>),
+    Tuple(Vec<ConstSignature>),
+    Object(Vec<(String, ConstSignature)>),
+    Option(Option<Box<ConstSignature>>),
+}
+
+impl ConstSignature {
+    pub(crate) fn from_const(value: &ConstValue) -> Self {
+        match value {
+            ConstValue::EmptyTuple => Self::EmptyTuple,
+            ConstValue::Byte(b) => Self::Byte(*b),
+            ConstValue::Char(c) => Self::Char(*c),
+            ConstValue::Bool(b) => Self::Bool(*b),
+            ConstValue::Integer(n) => Self::Integer(*n),
+            ConstValue::Float(n) => Self::Float(n.to_bits()),
+            ConstValue::BigInt(n) => Self::BigInt(n.to_signed_bytes_le()),
+            ConstValue::String(string) => Self::String(string.clone()),
+            ConstValue::Bytes(bytes) => Self::Bytes(bytes.as_slice().to_vec()),
+            ConstValue::Vec(values) => Self::Vec(values.iter().map(Self::from_const).collect()),
+            ConstValue::Tuple(values) => Self::Tuple(values.iter().map(Self::from_const).collect()),
+            ConstValue::Object(object) => {
+                let mut object = object
+                    .iter()
+                    .map(|(key, value)| (key.clone(), Self::from_const(value)))
+                    .collect::<Vec<_>>();
+                object.sort_by(|a, b| a.0.cmp(&b.0));
+                Self::Object(object)
+            }
+            ConstValue::Option(value) => Self::Option(
+                value
+                    .as_deref()
+                    .map(|value| Box::new(Self::from_const(value))),
+            ),
+        }
+    }
+
+    pub(crate) fn to_const(&self) -> ConstValue {
+        match self {
+            Self::EmptyTuple => ConstValue::EmptyTuple,
+            Self::Byte(b) => ConstValue::Byte(*b),
+            Self::Char(c) => ConstValue::Char(*c),
+            Self::Bool(b) => ConstValue::Bool(*b),
+            Self::Integer(n) => ConstValue::Integer(*n),
+            Self::Float(bits) => ConstValue::Float(f64::from_bits(*bits)),
+            Self::BigInt(bytes) => ConstValue::BigInt(num::BigInt::from_signed_bytes_le(bytes)),
+            Self::String(string) => ConstValue::String(string.clone()),
+            Self::Bytes(bytes) => ConstValue::Bytes(Bytes::from_vec(bytes.clone())),
+            Self::Vec(values) => ConstValue::Vec(values.iter().map(Self::to_const).collect()),
+            Self::Tuple(values) => ConstValue::Tuple(values.iter().map(Self::to_const).collect()),
+            Self::Object(object) => ConstValue::Object(
+                object
+                    .iter()
+                    .map(|(key, value)| (key.clone(), value.to_const()))
+                    .collect(),
+            ),
+            Self::Option(value) => {
+                ConstValue::Option(value.as_deref().map(|value| Box::new(value.to_const())))
+            }
+        }
+    }
+}
@@ -272,6 +272,11 @@ pub(crate) enum ErrorKind {
     },
     UnsupportedPatternExpr,
     UnsupportedBinding,
+    UnsupportedPatternRange,
+    EmptyPatternRange,
+    PatternOrBindings {
+        name: Box<str>,
+    },
     DuplicateObjectKey {
         #[cfg(feature = "emit")]
         existing: Span,
@@ -279,6 +284,23 @@ pub(crate) enum ErrorKind {
         object: Span,
     },
     InstanceFunctionOutsideImpl,
+    TraitFunctionConflict {
+        name: Box<str>,
+    },
+    MissingTraitFunction {
+        name: Box<str>,
+        trait_item: ItemBuf,
+    },
+    NotTraitFunction {
+        name: Box<str>,
+        trait_item: ItemBuf,
+    },
+    TraitFunctionArguments {
+        name: Box<str>,
+        trait_item: ItemBuf,
+        expected: usize,
+        actual: usize,
+    },
     UnsupportedTupleIndex {
         number: ast::Number,
     },
@@ -351,6 +373,23 @@ pub(crate) enum ErrorKind {
         moved_at: Span,
     },
     UnsupportedGenerics,
+    TypeMismatch {
+        expected: Box<str>,
+        actual: Box<str>,
+    },
+    UnsupportedArgumentDefault,
+    ArgumentDefaultOrder,
+    NamedArgumentOrder,
+    UnsupportedNamedArguments,
+    DuplicateArgument {
+        name: Box<str>,
+    },
+    NoSuchArgument {
+        name: Box<str>,
+    },
+    MissingArgument {
+        name: Box<str>,
+    },
     NestedTest {
         #[cfg(feature = "emit")]
         nested_span: Span,
@@ -648,12 +687,53 @@ impl fmt::Display for ErrorKind {
             ErrorKind::UnsupportedBinding => {
                 write!(f, "Not a valid binding")?;
             }
+            ErrorKind::UnsupportedPatternRange => {
+                write!(
+                    f,
+                    "Range patterns only support integer, byte, and character literals of the same type"
+                )?;
+            }
+            ErrorKind::EmptyPatternRange => {
+                write!(f, "Range pattern is empty and will never match")?;
+            }
+            ErrorKind::PatternOrBindings { name } => {
+                write!(
+                    f,
+                    "Variable `{name}` is not bound in all alternatives of the pattern"
+                )?;
+            }
             ErrorKind::DuplicateObjectKey { .. } => {
                 write!(f, "Duplicate key in literal object")?;
             }
             ErrorKind::InstanceFunctionOutsideImpl => {
                 write!(f, "Instance function declared outside of `impl` block")?;
             }
+            ErrorKind::TraitFunctionConflict { name } => {
+                write!(f, "Function `{name}` is declared multiple times in trait")?;
+            }
+            ErrorKind::MissingTraitFunction { name, trait_item } => {
+                write!(
+                    f,
+                    "Missing function `{name}` required by trait `{trait_item}`"
+                )?;
+            }
+            ErrorKind::NotTraitFunction { name, trait_item } => {
+                write!(
+                    f,
+                    "Function `{name}` is not a member of trait `{trait_item}`"
+                )?;
+            }
+            ErrorKind::TraitFunctionArguments {
+                name,
+                trait_item,
+                expected,
+                actual,
+            } => {
+                write!(
+                    f,
+                    "Wrong number of arguments for `{name}` in trait `{trait_item}`, expected `{expected}` but got `{actual}`"
+                )?;
+            }
             ErrorKind::UnsupportedTupleIndex { number } => {
                 write!(f, "Unsupported tuple index `{number}`")?;
             }
@@ -794,6 +874,36 @@ impl fmt::Display for ErrorKind {
             ErrorKind::UnsupportedGenerics => {
                 write!(f, "Unsupported generic argument")?;
             }
+            ErrorKind::TypeMismatch { expected, actual } => {
+                write!(f, "Expected type `{expected}` but found `{actual}`")?;
+            }
+            ErrorKind::UnsupportedArgumentDefault => {
+                write!(f, "Default argument values are not supported here")?;
+            }
+            ErrorKind::ArgumentDefaultOrder => {
+                write!(
+                    f,
+                    "Arguments without a default value must come before arguments with one"
+                )?;
+            }
+            ErrorKind::NamedArgumentOrder => {
+                write!(f, "Positional arguments must come before named arguments")?;
+            }
+            ErrorKind::UnsupportedNamedArguments => {
+                write!(
+                    f,
+                    "Named arguments are not supported when calling this function"
+                )?;
+            }
+            ErrorKind::DuplicateArgument { name } => {
+                write!(f, "Argument `{name}` is passed more than once")?;
+            }
+            ErrorKind::NoSuchArgument { name } => {
+                write!(f, "Function has no argument named `{name}`")?;
+            }
+            ErrorKind::MissingArgument { name } => {
+                write!(f, "Missing value for argument `{name}`")?;
+            }
             ErrorKind::NestedTest { .. } => {
                 write!(f, "Attribute `#[test]` is not supported on nested items")?;
             }
@@ -2,6 +2,8 @@ use core::mem::{replace, take};
 
 use crate::no_std::prelude::*;
 
+use num::BigInt;
+
 use crate::ast::{self, Span, Spanned};
 use crate::compile::ir;
 use crate::compile::{self, ErrorKind, WithSpan};
@@ -209,6 +211,9 @@ fn lit(c: &mut Ctxt<'_, '_>, span: Span, hir: hir::Lit<'_>) -> compile::Result<i
         ),
         hir::Lit::Integer(n) => ir::Ir::new(span, ir::Value::Integer(n)),
         hir::Lit::Float(n) => ir::Ir::new(span, ir::Value::Float(n)),
+        hir::Lit::BigInt(bytes) => {
+            ir::Ir::new(span, ir::Value::BigInt(BigInt::from_signed_bytes_le(bytes)))
+        }
         hir::Lit::Byte(b) => ir::Ir::new(span, ir::Value::Byte(b)),
         hir::Lit::ByteStr(byte_str) => {
             let value =
@@ -4,6 +4,8 @@ use core::ops::{Add, Mul, Shl, Shr, Sub};
 use crate::no_std::collections::HashMap;
 use crate::no_std::prelude::*;
 
+use num::{BigInt, ToPrimitive, Zero};
+
 use crate::ast::{Span, Spanned};
 use crate::compile::ir;
 use crate::compile::{self, WithSpan};
@@ -131,11 +133,47 @@ fn eval_ir_binary(
                 return Ok(ir::Value::String(add_strings(span, &a, &b)?));
             }
         }
+        (ir::Value::BigInt(a), ir::Value::BigInt(b)) => {
+            return eval_bigint(ir, a, b);
+        }
+        (ir::Value::BigInt(a), ir::Value::Integer(b)) => {
+            return eval_bigint(ir, a, BigInt::from(b));
+        }
+        (ir::Value::Integer(a), ir::Value::BigInt(b)) => {
+            return eval_bigint(ir, BigInt::from(a), b);
+        }
         _ => (),
     }
 
     return Err(EvalOutcome::not_const(span));
 
+    fn eval_bigint(ir: &ir::IrBinary, a: BigInt, b: BigInt) -> Result<ir::Value, EvalOutcome> {
+        let shift = |b: BigInt| {
+            b.to_usize()
+                .ok_or_else(|| compile::Error::msg(&ir.rhs, "cannot be converted to shift operand"))
+        };
+
+        Ok(match ir.op {
+            ir::IrBinaryOp::Add => ir::Value::BigInt(a + b),
+            ir::IrBinaryOp::Sub => ir::Value::BigInt(a - b),
+            ir::IrBinaryOp::Mul => ir::Value::BigInt(a * b),
+            ir::IrBinaryOp::Div => {
+                if b.is_zero() {
+                    return Err(compile::Error::msg(ir, "division by zero").into());
+                }
+
+                ir::Value::BigInt(a / b)
+            }
+            ir::IrBinaryOp::Shl => ir::Value::BigInt(a << shift(b)?),
+            ir::IrBinaryOp::Shr => ir::Value::BigInt(a >> shift(b)?),
+            ir::IrBinaryOp::Lt => ir::Value::Bool(a < b),
+            ir::IrBinaryOp::Lte => ir::Value::Bool(a <= b),
+            ir::IrBinaryOp::Eq => ir::Value::Bool(a == b),
+            ir::IrBinaryOp::Gt => ir::Value::Bool(a > b),
+            ir::IrBinaryOp::Gte => ir::Value::Bool(a >= b),
+        })
+    }
+
     fn add_strings(
         span: Span,
         a: &Shared<String>,
@@ -341,6 +379,9 @@ fn eval_ir_template(
                     ir::Value::Integer(integer) => {
                         write!(buf, "{}", integer).unwrap();
                     }
+                    ir::Value::BigInt(integer) => {
+                        write!(buf, "{}", integer).unwrap();
+                    }
                     ir::Value::Float(float) => {
                         let mut buffer = ryu::Buffer::new();
                         buf.push_str(buffer.format(float));
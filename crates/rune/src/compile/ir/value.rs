@@ -1,6 +1,8 @@
 use crate::no_std::collections::HashMap;
 use crate::no_std::prelude::*;
 
+use num::BigInt;
+
 use crate::alloc::Error;
 use crate::ast::Spanned;
 use crate::compile::{self, WithSpan};
@@ -22,6 +24,8 @@ pub enum Value {
     Integer(i64),
     /// An float constant.
     Float(f64),
+    /// An arbitrary-precision integer constant.
+    BigInt(BigInt),
     /// A string constant designated by its slot.
     String(Shared<String>),
     /// An optional value.
@@ -65,6 +69,7 @@ impl Value {
             ConstValue::Bool(b) => Self::Bool(*b),
             ConstValue::Integer(n) => Self::Integer(*n),
             ConstValue::Float(n) => Self::Float(*n),
+            ConstValue::BigInt(n) => Self::BigInt(n.clone()),
             ConstValue::String(s) => Self::String(Shared::new(s.clone())?),
             ConstValue::Bytes(b) => Self::Bytes(Shared::new(b.clone())?),
             ConstValue::Option(option) => Self::Option(Shared::new(match option {
@@ -113,6 +118,7 @@ impl Value {
             Value::Bool(b) => ConstValue::Bool(b),
             Value::Integer(n) => ConstValue::Integer(n),
             Value::Float(f) => ConstValue::Float(f),
+            Value::BigInt(n) => ConstValue::BigInt(n),
             Value::String(s) => {
                 let s = s.take().with_span(spanned)?;
                 ConstValue::String(s)
@@ -169,6 +175,7 @@ impl Value {
             Self::Bytes(..) => TypeInfo::StaticType(rt::static_type::BYTES_TYPE),
             Self::Integer(..) => TypeInfo::StaticType(rt::static_type::INTEGER_TYPE),
             Self::Float(..) => TypeInfo::StaticType(rt::static_type::FLOAT_TYPE),
+            Self::BigInt(..) => TypeInfo::StaticType(rt::static_type::BIGINT_TYPE),
             Self::Option(..) => TypeInfo::StaticType(rt::static_type::OPTION_TYPE),
             Self::Vec(..) => TypeInfo::StaticType(rt::static_type::VEC_TYPE),
             Self::Tuple(..) => TypeInfo::StaticType(rt::static_type::TUPLE_TYPE),
@@ -126,6 +126,7 @@ impl Meta {
             Kind::Macro => None,
             Kind::AttributeMacro => None,
             Kind::Module => None,
+            Kind::Trait { .. } => None,
         }
     }
 }
@@ -225,6 +226,11 @@ pub enum Kind {
     Import(Import),
     /// A module.
     Module,
+    /// A trait declared in a script.
+    Trait {
+        /// Opaque identifier for the trait.
+        id: NonZeroId,
+    },
 }
 
 impl Kind {
@@ -286,6 +292,8 @@ pub struct FieldsNamed {
 pub struct FieldMeta {
     /// Position of the field in its containing type declaration.
     pub(crate) position: usize,
+    /// The type hash of the field if it has been annotated.
+    pub(crate) ty: Option<Hash>,
 }
 
 /// Item and the module that the item belongs to.
@@ -315,7 +323,6 @@ impl ItemMeta {
 #[derive(Debug, Clone)]
 pub struct Signature {
     /// An asynchronous function.
-    #[cfg(feature = "doc")]
     pub(crate) is_async: bool,
     /// Deprecation notice.
     #[cfg(feature = "doc")]
@@ -324,10 +331,8 @@ pub struct Signature {
     #[cfg(feature = "doc")]
     pub(crate) args: Option<usize>,
     /// Return type of the function.
-    #[cfg(feature = "doc")]
     pub(crate) return_type: Option<Hash>,
     /// Argument types to the function.
-    #[cfg(feature = "doc")]
     pub(crate) argument_types: Box<[Option<Hash>]>,
 }
 
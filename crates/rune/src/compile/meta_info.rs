@@ -87,6 +87,9 @@ impl fmt::Display for MetaInfo {
             MetaInfoKind::Module => {
                 write!(fmt, "module {name}")?;
             }
+            MetaInfoKind::Trait => {
+                write!(fmt, "trait {name}")?;
+            }
         }
 
         Ok(())
@@ -109,6 +112,7 @@ pub(crate) enum MetaInfoKind {
     ConstFn,
     Import,
     Module,
+    Trait,
 }
 
 impl MetaInfoKind {
@@ -133,6 +137,7 @@ impl MetaInfoKind {
             meta::Kind::ConstFn { .. } => MetaInfoKind::ConstFn,
             meta::Kind::Import { .. } => MetaInfoKind::Import,
             meta::Kind::Module { .. } => MetaInfoKind::Module,
+            meta::Kind::Trait { .. } => MetaInfoKind::Trait,
         }
     }
 }
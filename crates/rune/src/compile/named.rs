@@ -32,6 +32,12 @@ impl Named for i64 {
 
 impl InstallWith for i64 {}
 
+impl Named for num::BigInt {
+    const BASE_NAME: RawStr = RawStr::from_str("BigInt");
+}
+
+impl InstallWith for num::BigInt {}
+
 impl Named for f64 {
     const BASE_NAME: RawStr = RawStr::from_str("f64");
 }
@@ -46,6 +46,7 @@ impl Prelude {
         this.add_prelude("String", ["string", "String"]);
         this.add_prelude("stringify", ["stringify"]);
         this.add_prelude("Vec", ["vec", "Vec"]);
+        this.add_prelude("BigInt", ["bigint", "BigInt"]);
         this.add_prelude("Bytes", ["bytes", "Bytes"]);
 
         this
@@ -17,8 +17,8 @@ use crate::query::QueryInner;
 use crate::runtime::debug::{DebugArgs, DebugSignature};
 use crate::runtime::unit::UnitEncoder;
 use crate::runtime::{
-    Call, ConstValue, DebugInfo, DebugInst, Inst, Protocol, Rtti, StaticString, Unit, UnitFn,
-    VariantRtti,
+    Call, ConstValue, DebugInfo, DebugInst, DebugVariable, Inst, Protocol, Rtti, StaticString,
+    Unit, UnitFn, UnitFnParameters, VariantRtti,
 };
 use crate::{Context, Diagnostics, Hash, SourceId};
 
@@ -83,6 +83,8 @@ pub(crate) struct UnitBuilder {
     debug: Option<Box<DebugInfo>>,
     /// Constant values
     constants: hash::Map<ConstValue>,
+    /// Parameters of functions.
+    parameters: hash::Map<UnitFnParameters>,
     /// Hash to identifiers.
     hash_to_ident: HashMap<Hash, Box<str>>,
 }
@@ -111,6 +113,12 @@ impl UnitBuilder {
                         ErrorKind::FunctionConflictHash { hash: from },
                     ));
                 }
+
+                if let Some(parameters) = self.parameters.get(&to) {
+                    let parameters = parameters.clone();
+                    self.parameters.insert(from, parameters);
+                }
+
                 continue;
             }
 
@@ -143,6 +151,7 @@ impl UnitBuilder {
             self.variant_rtti,
             self.debug,
             self.constants,
+            self.parameters,
         ))
     }
 
@@ -539,6 +548,7 @@ impl UnitBuilder {
             meta::Kind::ConstFn { .. } => (),
             meta::Kind::Import { .. } => (),
             meta::Kind::Module { .. } => (),
+            meta::Kind::Trait { .. } => (),
         }
 
         Ok(())
@@ -576,6 +586,7 @@ impl UnitBuilder {
         item: &Item,
         instance: Option<(Hash, &str)>,
         args: usize,
+        parameters: UnitFnParameters,
         assembly: Assembly,
         call: Call,
         debug_args: Box<[Box<str>]>,
@@ -603,6 +614,7 @@ impl UnitBuilder {
             self.debug_info_mut()
                 .functions
                 .insert(instance_fn, signature.clone());
+            self.parameters.insert(instance_fn, parameters.clone());
         }
 
         let hash = Hash::type_hash(item);
@@ -622,6 +634,7 @@ impl UnitBuilder {
         );
 
         self.debug_info_mut().functions.insert(hash, signature);
+        self.parameters.insert(hash, parameters);
         self.functions_rev.insert(offset, hash);
         self.add_assembly(location, assembly, unit_storage)?;
         Ok(())
@@ -672,10 +685,14 @@ impl UnitBuilder {
             }
         }
 
+        // Instruction pointers corresponding to each assembly position.
+        let mut ips = Vec::with_capacity(assembly.instructions.len() + 1);
+
         for (pos, (inst, span)) in assembly.instructions.into_iter().enumerate() {
             let mut comment = String::new();
 
             let at = storage.offset();
+            ips.push(at);
 
             let mut labels = Vec::new();
 
@@ -852,6 +869,22 @@ impl UnitBuilder {
             );
         }
 
+        ips.push(storage.offset());
+
+        let debug = self.debug.get_or_insert_with(Default::default);
+
+        for var in assembly.variables {
+            let (Some(&start), Some(&end)) = (ips.get(var.start), ips.get(var.end)) else {
+                continue;
+            };
+
+            if start < end {
+                debug
+                    .variables
+                    .push(DebugVariable::new(var.name, var.offset, start, end));
+            }
+        }
+
         Ok(())
     }
 }
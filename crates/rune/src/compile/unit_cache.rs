@@ -0,0 +1,241 @@
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use crate::no_std::prelude::*;
+
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+
+use crate::ast::{Span, Spanned};
+use crate::compile::{self, Context, Item, ItemBuf, Options, SourceLoader};
+use crate::hash::ParametersBuilder;
+use crate::runtime::{Unit, UnitStorage};
+use crate::{Hash, Source, Sources};
+
+/// A cache of compiled units.
+///
+/// Entries are keyed by a hash of every source being built, the items
+/// installed in the [Context] and the compiler [Options]. Modules which are
+/// loaded while building are recorded in the entry, and are loaded again and
+/// compared when the entry is used so that changes to them are detected as
+/// well.
+///
+/// See [Build::with_cache][crate::Build::with_cache].
+pub trait UnitCache {
+    /// Load the entry with the given key, if it exists.
+    fn load(&mut self, key: Hash) -> io::Result<Option<Vec<u8>>>;
+
+    /// Store an entry under the given key, replacing any existing entry.
+    fn store(&mut self, key: Hash, entry: &[u8]) -> io::Result<()>;
+}
+
+/// A unit cache which stores every entry as a file in a directory.
+#[derive(Debug, Clone)]
+pub struct FileUnitCache {
+    directory: PathBuf,
+}
+
+impl FileUnitCache {
+    /// Construct a cache which stores entries in the given directory, which is
+    /// created if it doesn't exist.
+    pub fn new(directory: impl AsRef<Path>) -> Self {
+        Self {
+            directory: directory.as_ref().to_owned(),
+        }
+    }
+
+    fn path(&self, key: Hash) -> PathBuf {
+        self.directory.join(format!("{key}.rnc"))
+    }
+}
+
+impl UnitCache for FileUnitCache {
+    fn load(&mut self, key: Hash) -> io::Result<Option<Vec<u8>>> {
+        match fs::read(self.path(key)) {
+            Ok(entry) => Ok(Some(entry)),
+            Err(error) if error.kind() == io::ErrorKind::NotFound => Ok(None),
+            Err(error) => Err(error),
+        }
+    }
+
+    fn store(&mut self, key: Hash, entry: &[u8]) -> io::Result<()> {
+        fs::create_dir_all(&self.directory)?;
+
+        // NB: write to a temporary file first so that concurrent builds never
+        // observe a partially written entry.
+        let path = self.path(key);
+        let temporary = path.with_extension(format!("rnc.{}", std::process::id()));
+        fs::write(&temporary, entry)?;
+        fs::rename(&temporary, &path)
+    }
+}
+
+/// A module which was loaded while building a unit.
+#[derive(Serialize, Deserialize)]
+pub(crate) struct LoadedSource {
+    /// The root the module was loaded relative to.
+    root: PathBuf,
+    /// The item of the module.
+    item: ItemBuf,
+    /// The hash of the loaded source.
+    hash: Hash,
+}
+
+/// A cached unit.
+#[derive(Serialize, Deserialize)]
+struct Entry {
+    /// Modules loaded while building the unit, in the order they were loaded.
+    loaded: Vec<LoadedSource>,
+    /// The cached unit, encoded with [Unit::encode].
+    unit: Vec<u8>,
+}
+
+/// Calculate the key of the unit built from the given sources.
+pub(crate) fn key<S>(sources: &Sources, context: &Context, options: &Options) -> Hash {
+    let mut hasher = ParametersBuilder::new();
+    hasher.add(env!("CARGO_PKG_VERSION"));
+    hasher.add(core::any::type_name::<S>());
+    hasher.add(context.fingerprint());
+    hasher.add(options);
+
+    for source in sources.iter() {
+        hasher.add(source_hash(source));
+    }
+
+    hasher.finish()
+}
+
+/// Load a cached unit, adding any loaded modules to `sources`.
+///
+/// Returns `None` if there is no usable entry.
+pub(crate) fn load<S>(
+    cache: &mut dyn UnitCache,
+    key: Hash,
+    context: &Context,
+    sources: &mut Sources,
+    source_loader: &mut dyn SourceLoader,
+) -> Option<Unit<S>>
+where
+    S: UnitStorage + Serialize + DeserializeOwned,
+{
+    let entry = match cache.load(key) {
+        Ok(entry) => entry?,
+        Err(error) => {
+            tracing::warn!("failed to load cached unit {key}: {error}");
+            return None;
+        }
+    };
+
+    let entry = match bincode::deserialize::<Entry>(&entry) {
+        Ok(entry) => entry,
+        Err(error) => {
+            tracing::warn!("failed to deserialize cached unit {key}: {error}");
+            return None;
+        }
+    };
+
+    let mut loaded = Vec::with_capacity(entry.loaded.len());
+
+    for module in &entry.loaded {
+        let Ok(source) = source_loader.load(&module.root, &module.item, &Span::empty()) else {
+            tracing::trace!("module {} of cached unit {key} is missing", module.item);
+            return None;
+        };
+
+        if source_hash(&source) != module.hash {
+            tracing::trace!("module {} of cached unit {key} has changed", module.item);
+            return None;
+        }
+
+        loaded.push(source);
+    }
+
+    let unit = match Unit::decode(&entry.unit, &context.runtime()) {
+        Ok(unit) => unit,
+        Err(error) => {
+            tracing::warn!("failed to decode cached unit {key}: {error}");
+            return None;
+        }
+    };
+
+    for source in loaded {
+        sources.insert(source);
+    }
+
+    tracing::trace!("using cached unit {key}");
+    Some(unit)
+}
+
+/// Store a unit in the cache.
+pub(crate) fn store<S>(
+    cache: &mut dyn UnitCache,
+    key: Hash,
+    loaded: Vec<LoadedSource>,
+    unit: Unit<S>,
+) -> Unit<S>
+where
+    S: UnitStorage + Serialize + DeserializeOwned,
+{
+    let entry = match unit.encode() {
+        Ok(encoded) => Entry {
+            loaded,
+            unit: encoded,
+        },
+        Err(error) => {
+            tracing::warn!("failed to encode cached unit {key}: {error}");
+            return unit;
+        }
+    };
+
+    match bincode::serialize(&entry) {
+        Ok(bytes) => {
+            if let Err(error) = cache.store(key, &bytes) {
+                tracing::warn!("failed to store cached unit {key}: {error}");
+            }
+        }
+        Err(error) => {
+            tracing::warn!("failed to serialize cached unit {key}: {error}");
+        }
+    }
+
+    unit
+}
+
+/// A source loader which records every module it loads.
+pub(crate) struct RecordingSourceLoader<'a> {
+    inner: &'a mut dyn SourceLoader,
+    pub(crate) loaded: Vec<LoadedSource>,
+}
+
+impl<'a> RecordingSourceLoader<'a> {
+    pub(crate) fn new(inner: &'a mut dyn SourceLoader) -> Self {
+        Self {
+            inner,
+            loaded: Vec::new(),
+        }
+    }
+}
+
+impl SourceLoader for RecordingSourceLoader<'_> {
+    fn load(&mut self, root: &Path, item: &Item, span: &dyn Spanned) -> compile::Result<Source> {
+        let source = self.inner.load(root, item, span)?;
+
+        self.loaded.push(LoadedSource {
+            root: root.to_owned(),
+            item: item.to_owned(),
+            hash: source_hash(&source),
+        });
+
+        Ok(source)
+    }
+}
+
+/// Hash the name, path and contents of a source.
+fn source_hash(source: &Source) -> Hash {
+    let mut hasher = ParametersBuilder::new();
+    hasher.add(source.name());
+    hasher.add(source.path());
+    hasher.add(source.as_str());
+    hasher.finish()
+}
@@ -9,8 +9,8 @@ use crate::compile::{self, Assembly, ErrorKind, ItemId, ModId, Options, WithSpan
 use crate::hir;
 use crate::query::{ConstFn, Query, Used};
 use crate::runtime::{
-    ConstValue, Inst, InstAddress, InstAssignOp, InstOp, InstRange, InstTarget, InstValue,
-    InstVariant, Label, PanicReason, Protocol, TypeCheck,
+    static_type, ConstValue, Inst, InstAddress, InstAssignOp, InstOp, InstRange, InstTarget,
+    InstValue, InstVariant, Label, PanicReason, Protocol, TypeCheck,
 };
 use crate::{Hash, SourceId};
 
@@ -86,7 +86,7 @@ impl<'a, 'hir, 'arena> Ctxt<'a, 'hir, 'arena> {
         expected: ScopeGuard,
         needs: Needs,
     ) -> compile::Result<()> {
-        let scope = self.scopes.pop(expected, span)?;
+        let scope = self.scopes.pop(self.asm, expected, span)?;
 
         if needs.value() {
             self.locals_clean(scope.local, span);
@@ -227,7 +227,7 @@ pub(crate) fn fn_from_item_fn<'hir>(
                     return Err(compile::Error::new(*span, ErrorKind::UnsupportedSelf));
                 }
 
-                cx.scopes.define(hir::Name::SelfValue, span)?;
+                cx.scopes.define(cx.asm, hir::Name::SelfValue, span)?;
             }
             hir::FnArg::Pat(pat) => {
                 let offset = cx.scopes.alloc(pat)?;
@@ -259,7 +259,7 @@ pub(crate) fn fn_from_item_fn<'hir>(
         cx.asm.push(Inst::ReturnUnit, hir);
     }
 
-    cx.scopes.pop_last(hir)?;
+    cx.scopes.pop_last(cx.asm, hir)?;
     Ok(())
 }
 
@@ -270,11 +270,11 @@ pub(crate) fn async_block_secondary<'hir>(
     hir: &'hir hir::AsyncBlock<'hir>,
 ) -> compile::Result<()> {
     for name in hir.captures.iter().copied() {
-        cx.scopes.define(name, &hir.block)?;
+        cx.scopes.define(cx.asm, name, &hir.block)?;
     }
 
     return_(cx, &hir.block, &hir.block, block)?;
-    cx.scopes.pop_last(&hir.block)?;
+    cx.scopes.pop_last(cx.asm, &hir.block)?;
     Ok(())
 }
 
@@ -303,7 +303,7 @@ pub(crate) fn expr_closure_secondary<'hir>(
         cx.asm.push(Inst::PushTuple, span);
 
         for capture in hir.captures.iter().copied() {
-            cx.scopes.define(capture, span)?;
+            cx.scopes.define(cx.asm, capture, span)?;
         }
     }
 
@@ -312,7 +312,7 @@ pub(crate) fn expr_closure_secondary<'hir>(
     }
 
     return_(cx, span, &hir.body, expr)?;
-    cx.scopes.pop_last(span)?;
+    cx.scopes.pop_last(cx.asm, span)?;
     Ok(())
 }
 
@@ -406,7 +406,7 @@ fn pat<'hir>(
             }
             hir::PatPathKind::Ident(name) => {
                 load(cx, Needs::Value)?;
-                cx.scopes.define(hir::Name::Str(name), hir)?;
+                cx.scopes.define(cx.asm, hir::Name::Str(name), hir)?;
                 Ok(false)
             }
         },
@@ -419,6 +419,15 @@ fn pat<'hir>(
             pat_object(cx, hir, span, false_label, &load)?;
             Ok(true)
         }
+        hir::PatKind::Or(hir) => Ok(pat_or(cx, hir, span, false_label, load)?),
+        hir::PatKind::Range(range) => {
+            load(cx, Needs::Value)?;
+            let offset = cx.scopes.alloc(span)?;
+            pat_range(cx, *range, offset, span)?;
+            cx.asm
+                .pop_and_jump_if_not(cx.scopes.local(span)?, false_label, span);
+            Ok(true)
+        }
     }
 }
 
@@ -467,6 +476,177 @@ fn pat_lit_inst<'hir>(
     Ok(Some(inst))
 }
 
+/// Assemble a test of whether the value at the given offset is within a range,
+/// leaving the result as a boolean on the stack.
+///
+/// A range of a single value is tested like the corresponding literal.
+/// Otherwise the type of the value is checked first, so that values of other
+/// types don't match instead of causing a comparison error, followed by a
+/// comparison against each bound which isn't the limit of the type.
+fn pat_range(
+    cx: &mut Ctxt<'_, '_, '_>,
+    range: hir::PatRange,
+    offset: usize,
+    span: &dyn Spanned,
+) -> compile::Result<()> {
+    let single = match range {
+        hir::PatRange::Integer { start, end } if start == end => {
+            Some(Inst::EqInteger { integer: start })
+        }
+        hir::PatRange::Byte { start, end } if start == end => Some(Inst::EqByte { byte: start }),
+        hir::PatRange::Char { start, end } if start == end => Some(Inst::EqChar { char: start }),
+        _ => None,
+    };
+
+    if let Some(inst) = single {
+        cx.asm.push(Inst::Copy { offset }, span);
+        cx.asm.push(inst, span);
+        return Ok(());
+    }
+
+    let (hash, start, end) = match range {
+        hir::PatRange::Integer { start, end } => (
+            static_type::INTEGER_TYPE.hash,
+            (start != i64::MIN).then(|| Inst::integer(start)),
+            (end != i64::MAX).then(|| Inst::integer(end)),
+        ),
+        hir::PatRange::Byte { start, end } => (
+            static_type::BYTE_TYPE.hash,
+            (start != u8::MIN).then(|| Inst::byte(start)),
+            (end != u8::MAX).then(|| Inst::byte(end)),
+        ),
+        hir::PatRange::Char { start, end } => (
+            static_type::CHAR_TYPE.hash,
+            (start != '\0').then(|| Inst::char(start)),
+            (end != char::MAX).then(|| Inst::char(end)),
+        ),
+    };
+
+    let end_label = cx.asm.new_label("pat_range_end");
+
+    cx.asm.push(Inst::Copy { offset }, span);
+    cx.asm.push(Inst::MatchType { hash }, span);
+
+    let bounds = [(start, InstOp::Gte), (end, InstOp::Lte)];
+
+    for (bound, op) in bounds {
+        let Some(bound) = bound else {
+            continue;
+        };
+
+        cx.asm.jump_if_not_or_pop(&end_label, span);
+        cx.asm.push(Inst::Copy { offset }, span);
+        cx.asm.push(bound, span);
+        cx.asm.push(
+            Inst::Op {
+                op,
+                a: InstAddress::Top,
+                b: InstAddress::Top,
+            },
+            span,
+        );
+    }
+
+    cx.asm.label(&end_label)?;
+    Ok(())
+}
+
+/// Assemble an or-pattern.
+///
+/// Alternatives which are literals or ranges are tested directly against the
+/// matched value. Other alternatives are matched in a scope of their own, after
+/// which the variables they bind are moved into slots shared by all
+/// alternatives.
+#[instrument(span = span)]
+fn pat_or<'hir>(
+    cx: &mut Ctxt<'_, 'hir, '_>,
+    hir: &'hir hir::PatOr<'hir>,
+    span: &'hir dyn Spanned,
+    false_label: &Label,
+    load: &dyn Fn(&mut Ctxt<'_, 'hir, '_>, Needs) -> compile::Result<()>,
+) -> compile::Result<bool> {
+    load(cx, Needs::Value)?;
+    let offset = cx.scopes.alloc(span)?;
+
+    let mut slots = Vec::with_capacity(hir.names.len());
+
+    for name in hir.names {
+        cx.asm.push(Inst::unit(), span);
+        slots.push(cx.scopes.define(cx.asm, hir::Name::Str(name), span)?);
+    }
+
+    let ok_label = cx.asm.new_label("pat_or_ok");
+    let mut it = hir.alternatives.iter().peekable();
+
+    while let Some(alt) = it.next() {
+        let tested = match alt.kind {
+            hir::PatKind::Lit(hir) => match pat_lit_inst(cx, hir)? {
+                Some(inst) => {
+                    cx.asm.push(Inst::Copy { offset }, alt);
+                    cx.asm.push(inst, alt);
+                    true
+                }
+                None => false,
+            },
+            hir::PatKind::Range(range) => {
+                pat_range(cx, *range, offset, alt)?;
+                true
+            }
+            _ => false,
+        };
+
+        if tested {
+            if it.peek().is_some() {
+                cx.asm.jump_if(&ok_label, alt);
+            } else {
+                cx.asm
+                    .pop_and_jump_if_not(cx.scopes.local(alt)?, false_label, alt);
+            }
+
+            continue;
+        }
+
+        let alt_false = cx.asm.new_label("pat_or_false");
+        let guard = cx.scopes.child(alt)?;
+
+        let load = move |cx: &mut Ctxt<'_, 'hir, '_>, needs: Needs| {
+            if needs.value() {
+                cx.asm.push(Inst::Copy { offset }, alt);
+            }
+
+            Ok(())
+        };
+
+        let refutable = pat(cx, alt, &alt_false, &load)?;
+
+        for (name, slot) in hir.names.iter().zip(&slots) {
+            let var = cx.scopes.get(&mut cx.q, hir::Name::Str(name), alt)?;
+            cx.asm.push(Inst::Copy { offset: var.offset }, alt);
+            cx.asm.push(Inst::Replace { offset: *slot }, alt);
+        }
+
+        let layer = cx.scopes.pop(cx.asm, guard, alt)?;
+        cx.locals_pop(layer.local, alt);
+
+        // An irrefutable alternative makes the ones following it unreachable.
+        if !refutable {
+            cx.asm.label(&ok_label)?;
+            return Ok(false);
+        }
+
+        cx.asm.jump(&ok_label, alt);
+        cx.asm.label(&alt_false)?;
+
+        if it.peek().is_none() {
+            cx.locals_pop(cx.scopes.local(alt)?, alt);
+            cx.asm.jump(false_label, alt);
+        }
+    }
+
+    cx.asm.label(&ok_label)?;
+    Ok(true)
+}
+
 /// Assemble an [hir::Condition<'_>].
 #[instrument(span = condition)]
 fn condition<'hir>(
@@ -479,7 +659,7 @@ fn condition<'hir>(
             let guard = cx.scopes.child(e)?;
             expr(cx, e, Needs::Value)?.apply(cx)?;
             cx.asm.jump_if(then_label, e);
-            Ok(cx.scopes.pop(guard, e)?)
+            Ok(cx.scopes.pop(cx.asm, guard, e)?)
         }
         hir::Condition::ExprLet(expr_let) => {
             let span = expr_let;
@@ -500,7 +680,7 @@ fn condition<'hir>(
                 cx.asm.jump(then_label, span);
             };
 
-            Ok(cx.scopes.pop(expected, span)?)
+            Ok(cx.scopes.pop(cx.asm, expected, span)?)
         }
     }
 }
@@ -650,7 +830,7 @@ fn pat_object<'hir>(
             }
             hir::Binding::Ident(span, name) => {
                 cx.asm.push(Inst::ObjectIndexGetAt { offset, slot }, &span);
-                cx.scopes.define(hir::Name::Str(name), binding)?;
+                cx.scopes.define(cx.asm, hir::Name::Str(name), binding)?;
             }
         }
     }
@@ -704,7 +884,7 @@ fn block<'hir>(
         false
     };
 
-    let scope = cx.scopes.pop(scopes_count, hir)?;
+    let scope = cx.scopes.pop(cx.asm, scopes_count, hir)?;
 
     if needs.value() {
         if produced {
@@ -798,7 +978,7 @@ fn builtin_template<'hir>(
         cx.asm.push(Inst::Pop, span);
     }
 
-    let _ = cx.scopes.pop(expected, span)?;
+    let _ = cx.scopes.pop(cx.asm, expected, span)?;
     Ok(Asm::top(span))
 }
 
@@ -831,6 +1011,10 @@ fn const_<'hir>(
         ConstValue::Float(n) => {
             cx.asm.push(Inst::float(*n), span);
         }
+        ConstValue::BigInt(n) => {
+            let slot = cx.q.unit.new_static_bytes(span, &n.to_signed_bytes_le())?;
+            cx.asm.push(Inst::BigInt { slot }, span);
+        }
         ConstValue::Bool(b) => {
             cx.asm.push(Inst::bool(*b), span);
         }
@@ -1124,7 +1308,7 @@ fn expr_binary<'hir>(
         cx.asm.push(Inst::Pop, span);
     }
 
-    cx.scopes.pop(guard, span)?;
+    cx.scopes.pop(cx.asm, guard, span)?;
     return Ok(Asm::top(span));
 
     fn compile_conditional_binop<'hir>(
@@ -1356,6 +1540,17 @@ fn expr_call<'hir>(
 ) -> compile::Result<Asm<'hir>> {
     let args = hir.args.len();
 
+    // Slot of the static object keys naming the trailing arguments.
+    let named = match hir.named {
+        [] => None,
+        named => Some(cx.q.unit.new_static_object_keys_iter(span, named)?),
+    };
+
+    let call_fn = match named {
+        Some(slot) => Inst::CallFnNamed { args, slot },
+        None => Inst::CallFn { args },
+    };
+
     match hir.call {
         hir::Call::Var { name, .. } => {
             let var = cx.scopes.get(&mut cx.q, name, span)?;
@@ -1368,7 +1563,7 @@ fn expr_call<'hir>(
             var.copy(cx, span, &"call")?;
             cx.scopes.alloc(span)?;
 
-            cx.asm.push(Inst::CallFn { args }, span);
+            cx.asm.push(call_fn, span);
 
             cx.scopes.free(span, hir.args.len() + 1)?;
         }
@@ -1381,17 +1576,44 @@ fn expr_call<'hir>(
                 cx.scopes.alloc(span)?;
             }
 
-            cx.asm.push(Inst::CallAssociated { hash, args }, span);
+            match named {
+                Some(slot) => cx
+                    .asm
+                    .push(Inst::CallAssociatedNamed { hash, args, slot }, span),
+                None => cx.asm.push(Inst::CallAssociated { hash, args }, span),
+            }
+
             cx.scopes.free(span, hir.args.len() + 1)?;
         }
         hir::Call::Meta { hash } => {
+            let mut offsets = Vec::with_capacity(hir.args.len());
+
             for e in hir.args {
                 expr(cx, e, Needs::Value)?.apply(cx)?;
-                cx.scopes.alloc(span)?;
+                offsets.push(cx.scopes.alloc(span)?);
             }
 
-            cx.asm.push(Inst::Call { hash, args }, span);
-            cx.scopes.free(span, args)?;
+            if hir.order.is_empty() {
+                cx.asm.push(Inst::Call { hash, args }, span);
+                cx.scopes.free(span, args)?;
+            } else {
+                // Arguments which were evaluated out of order are copied into
+                // place before the call, and cleaned up after it.
+                for &index in hir.order {
+                    let Some(&offset) = offsets.get(index) else {
+                        return Err(compile::Error::msg(span, "Missing argument to reorder"));
+                    };
+
+                    cx.asm.push(Inst::Copy { offset }, span);
+                    cx.scopes.alloc(span)?;
+                }
+
+                let count = hir.order.len();
+                cx.asm.push(Inst::Call { hash, args: count }, span);
+                cx.scopes.free(span, count)?;
+                cx.locals_clean(args, span);
+                cx.scopes.free(span, args)?;
+            }
         }
         hir::Call::Expr { expr: e } => {
             for e in hir.args {
@@ -1402,7 +1624,7 @@ fn expr_call<'hir>(
             expr(cx, e, Needs::Value)?.apply(cx)?;
             cx.scopes.alloc(span)?;
 
-            cx.asm.push(Inst::CallFn { args }, span);
+            cx.asm.push(call_fn, span);
 
             cx.scopes.free(span, args + 1)?;
         }
@@ -1801,7 +2023,7 @@ fn expr_index<'hir>(
         cx.asm.push(Inst::Pop, span);
     }
 
-    cx.scopes.pop(guard, span)?;
+    cx.scopes.pop(cx.asm, guard, span)?;
     Ok(Asm::top(span))
 }
 
@@ -1886,14 +2108,14 @@ fn expr_match<'hir>(
 
             expr(cx, condition, Needs::Value)?.apply(cx)?;
             cx.clean_last_scope(span, guard, Needs::Value)?;
-            let scope = cx.scopes.pop(parent_guard, span)?;
+            let scope = cx.scopes.pop(cx.asm, parent_guard, span)?;
 
             cx.asm.pop_and_jump_if_not(scope.local, &match_false, span);
 
             cx.asm.jump(&branch_label, span);
             scope
         } else {
-            cx.scopes.pop(parent_guard, span)?
+            cx.scopes.pop(cx.asm, parent_guard, span)?
         };
 
         cx.asm.jump(&branch_label, span);
@@ -1979,7 +2201,7 @@ fn expr_object<'hir>(
         cx.asm.push(Inst::Pop, span);
     }
 
-    cx.scopes.pop(guard, span)?;
+    cx.scopes.pop(cx.asm, guard, span)?;
     Ok(Asm::top(span))
 }
 
@@ -2078,7 +2300,7 @@ fn expr_range<'hir>(
     }
 
     cx.scopes.free(span, count)?;
-    cx.scopes.pop(guard, span)?;
+    cx.scopes.pop(cx.asm, guard, span)?;
     Ok(Asm::top(span))
 }
 
@@ -2171,7 +2393,8 @@ fn expr_select<'hir>(
 
         match branch.pat.kind {
             hir::PatKind::Path(&hir::PatPathKind::Ident(name)) => {
-                cx.scopes.define(hir::Name::Str(name), &branch.pat)?;
+                cx.scopes
+                    .define(cx.asm, hir::Name::Str(name), &branch.pat)?;
             }
             hir::PatKind::Ignore => {
                 cx.asm.push(Inst::Pop, &branch.body);
@@ -2265,7 +2488,7 @@ fn expr_tuple<'hir>(
                 span,
             );
 
-            cx.scopes.pop(guard, span)?;
+            cx.scopes.pop(cx.asm, guard, span)?;
         }};
     }
 
@@ -2474,6 +2697,10 @@ fn lit<'hir>(
         hir::Lit::Float(float) => {
             cx.asm.push(Inst::float(float), span);
         }
+        hir::Lit::BigInt(bytes) => {
+            let slot = cx.q.unit.new_static_bytes(span, bytes)?;
+            cx.asm.push(Inst::BigInt { slot }, span);
+        }
         hir::Lit::Str(string) => {
             let slot = cx.q.unit.new_static_string(span, string)?;
             cx.asm.push(Inst::String { slot }, span);
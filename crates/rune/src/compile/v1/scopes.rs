@@ -5,7 +5,7 @@ use crate::no_std::prelude::*;
 
 use crate::ast::Spanned;
 use crate::compile::v1::Ctxt;
-use crate::compile::{self, Assembly, ErrorKind, WithSpan};
+use crate::compile::{self, Assembly, AssemblyVar, ErrorKind, WithSpan};
 use crate::hir;
 use crate::query::Query;
 use crate::runtime::Inst;
@@ -23,6 +23,8 @@ pub struct Var<'hir> {
     span: &'hir dyn Spanned,
     /// Variable has been taken at the given position.
     moved_at: Option<&'hir dyn Spanned>,
+    /// The assembly position at which the variable was declared.
+    start: usize,
 }
 
 impl<'hir> fmt::Debug for Var<'hir> {
@@ -32,6 +34,7 @@ impl<'hir> fmt::Debug for Var<'hir> {
             .field("name", &self.name)
             .field("span", &self.span.span())
             .field("moved_at", &self.moved_at.map(|s| s.span()))
+            .field("start", &self.start)
             .finish()
     }
 }
@@ -44,6 +47,17 @@ impl<'hir> fmt::Display for Var<'hir> {
 }
 
 impl<'hir> Var<'hir> {
+    /// Record that the variable is no longer live at the current position of
+    /// the given assembly.
+    fn retire(&self, asm: &mut Assembly) {
+        asm.variables.push(AssemblyVar {
+            name: self.name.to_string().into(),
+            offset: self.offset,
+            start: self.start,
+            end: asm.pos(),
+        });
+    }
+
     /// Copy the declared variable.
     pub(crate) fn copy(
         &self,
@@ -203,6 +217,7 @@ impl<'hir> Scopes<'hir> {
     #[tracing::instrument(skip_all, fields(variable, name))]
     pub(crate) fn define(
         &mut self,
+        asm: &mut Assembly,
         name: hir::Name<'hir>,
         span: &'hir dyn Spanned,
     ) -> compile::Result<usize> {
@@ -219,11 +234,16 @@ impl<'hir> Scopes<'hir> {
             name,
             span,
             moved_at: None,
+            start: asm.pos(),
         };
 
         layer.total += 1;
         layer.local += 1;
-        layer.variables.insert(name, local);
+
+        if let Some(shadowed) = layer.variables.insert(name, local) {
+            shadowed.retire(asm);
+        }
+
         Ok(offset)
     }
 
@@ -270,6 +290,7 @@ impl<'hir> Scopes<'hir> {
     #[tracing::instrument(skip_all, fields(expected))]
     pub(crate) fn pop(
         &mut self,
+        asm: &mut Assembly,
         expected: ScopeGuard,
         span: &dyn Spanned,
     ) -> compile::Result<Layer<'hir>> {
@@ -291,12 +312,21 @@ impl<'hir> Scopes<'hir> {
         };
 
         tracing::trace!(?layer, "pop");
+
+        for var in layer.variables.values() {
+            var.retire(asm);
+        }
+
         Ok(layer)
     }
 
     /// Pop the last of the scope.
-    pub(crate) fn pop_last(&mut self, span: &dyn Spanned) -> compile::Result<Layer<'hir>> {
-        self.pop(ScopeGuard(1), span)
+    pub(crate) fn pop_last(
+        &mut self,
+        asm: &mut Assembly,
+        span: &dyn Spanned,
+    ) -> compile::Result<Layer<'hir>> {
+        self.pop(asm, ScopeGuard(1), span)
     }
 
     /// Construct a new child scope and return its guard.
@@ -21,7 +21,7 @@ impl WarningDiagnostic {
     }
 
     /// The kind of the warning.
-    #[cfg(feature = "emit")]
+    #[cfg(any(feature = "emit", feature = "languageserver"))]
     pub(crate) fn kind(&self) -> &WarningDiagnosticKind {
         &self.kind
     }
@@ -11,5 +11,8 @@ mod templating;
 mod build;
 pub(crate) use self::build::build;
 
+mod declarations;
+pub(crate) use self::declarations::declarations;
+
 mod visitor;
 pub(crate) use self::visitor::{Visitor, VisitorData};
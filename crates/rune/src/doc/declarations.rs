@@ -0,0 +1,600 @@
+//! Machine-readable API declarations for a context.
+//!
+//! Declarations are rendered both as Rune-syntax stubs, which are meant to be
+//! read by script authors, and as JSON, which is meant to be loaded by tools
+//! such as editors and language servers.
+
+use core::fmt::Write;
+
+use crate::no_std::prelude::*;
+
+use anyhow::{Context as _, Result};
+use serde::Serialize;
+
+use crate::compile::Item;
+use crate::doc::context::{Assoc, AssocFnKind, Kind, Meta, Signature};
+use crate::doc::{Context, Visitor};
+use crate::runtime::ConstValue;
+use crate::Hash;
+
+/// The version of the JSON declarations format.
+pub(crate) const DECLARATIONS_VERSION: u32 = 1;
+
+/// Declarations for every module in a context.
+///
+/// When serialized this is the root of the JSON declarations, which has the
+/// following shape:
+///
+/// ```text
+/// {
+///   "version": 1,
+///   "modules": [Module]
+/// }
+///
+/// Module = {
+///   "item": "::std::i64", "docs": [String],
+///   "modules": [Module], "types": [Type], "functions": [Function],
+///   "macros": [Macro], "constants": [Constant]
+/// }
+///
+/// Type = {
+///   "item": String, "name": String, "kind": "type" | "struct" | "enum",
+///   "docs": [String], "variants": [{ "name": String, "docs": [String] }],
+///   "functions": [Function]
+/// }
+///
+/// Function = {
+///   "item": String?, "name": String, "kind": "function" | "associated",
+///   "associated": Associated?, "is_async": bool, "deprecated": String?,
+///   "args": [{ "name": String, "type": String? }]?, "return_type": String?,
+///   "docs": [String]
+/// }
+///
+/// Associated =
+///     { "type": "instance", "name": String }
+///   | { "type": "protocol", "protocol": String }
+///   | { "type": "field_fn", "protocol": String, "field": String }
+///   | { "type": "index_fn", "protocol": String, "index": usize }
+///
+/// Macro = { "item": String, "name": String, "docs": [String] }
+/// Constant = { "item": String, "name": String, "value": String?, "docs": [String] }
+/// ```
+///
+/// Missing `args` means that the function takes a variable number of
+/// arguments.
+#[derive(Serialize)]
+pub(crate) struct Declarations<'a> {
+    version: u32,
+    modules: Vec<Module<'a>>,
+}
+
+#[derive(Serialize)]
+struct Module<'a> {
+    item: String,
+    docs: &'a [String],
+    modules: Vec<Module<'a>>,
+    types: Vec<Type<'a>>,
+    functions: Vec<Function<'a>>,
+    macros: Vec<Macro<'a>>,
+    constants: Vec<Constant<'a>>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "snake_case")]
+enum TypeKind {
+    Type,
+    Struct,
+    Enum,
+}
+
+#[derive(Serialize)]
+struct Type<'a> {
+    item: String,
+    name: String,
+    kind: TypeKind,
+    docs: &'a [String],
+    variants: Vec<Variant<'a>>,
+    functions: Vec<Function<'a>>,
+}
+
+#[derive(Serialize)]
+struct Variant<'a> {
+    name: &'a str,
+    docs: &'a [String],
+}
+
+/// Corresponds to whether a function was registered as a plain or an
+/// associated function through `FunctionMetaKind`.
+#[derive(Serialize)]
+#[serde(rename_all = "snake_case")]
+enum FunctionKind {
+    Function,
+    Associated,
+}
+
+#[derive(Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+enum Associated<'a> {
+    Instance {
+        name: &'a str,
+    },
+    Protocol {
+        protocol: &'static str,
+    },
+    FieldFn {
+        protocol: &'static str,
+        field: &'a str,
+    },
+    IndexFn {
+        protocol: &'static str,
+        index: usize,
+    },
+}
+
+#[derive(Serialize)]
+struct Function<'a> {
+    item: Option<String>,
+    name: String,
+    kind: FunctionKind,
+    associated: Option<Associated<'a>>,
+    is_async: bool,
+    deprecated: Option<&'a str>,
+    args: Option<Vec<Argument>>,
+    return_type: Option<String>,
+    docs: &'a [String],
+}
+
+#[derive(Serialize)]
+struct Argument {
+    name: String,
+    #[serde(rename = "type")]
+    ty: Option<String>,
+}
+
+#[derive(Serialize)]
+struct Macro<'a> {
+    item: String,
+    name: String,
+    docs: &'a [String],
+}
+
+#[derive(Serialize)]
+struct Constant<'a> {
+    item: String,
+    name: String,
+    value: Option<String>,
+    docs: &'a [String],
+}
+
+/// Collect declarations for every module in the given context and visitors.
+pub(crate) fn declarations<'a>(
+    context: &'a crate::Context,
+    visitors: &'a [Visitor],
+) -> Result<Declarations<'a>> {
+    let cx = Context::new(context, visitors);
+
+    let mut modules = Vec::new();
+
+    for item in cx.iter_modules() {
+        let meta = cx
+            .meta(&item)
+            .into_iter()
+            .find(|m| matches!(&m.kind, Kind::Module))
+            .with_context(|| format!("Missing meta for {item}"))?;
+
+        modules.push(module(&cx, meta)?);
+    }
+
+    Ok(Declarations {
+        version: DECLARATIONS_VERSION,
+        modules,
+    })
+}
+
+impl Declarations<'_> {
+    /// Render declarations as JSON.
+    pub(crate) fn to_json(&self) -> Result<String> {
+        Ok(serde_json::to_string_pretty(self)?)
+    }
+
+    /// Render declarations as Rune-syntax stubs.
+    pub(crate) fn to_stubs(&self) -> Result<String> {
+        let mut out = String::new();
+
+        for (n, m) in self.modules.iter().enumerate() {
+            if n > 0 {
+                writeln!(out)?;
+            }
+
+            render_module(&mut out, 0, m)?;
+        }
+
+        Ok(out)
+    }
+}
+
+fn module<'a>(cx: &Context<'a>, meta: Meta<'a>) -> Result<Module<'a>> {
+    let meta_item = meta.item.context("Missing module item")?;
+
+    let mut out = Module {
+        item: meta_item.to_string(),
+        docs: meta.docs,
+        modules: Vec::new(),
+        types: Vec::new(),
+        functions: Vec::new(),
+        macros: Vec::new(),
+        constants: Vec::new(),
+    };
+
+    for (_, name) in cx.iter_components(meta_item) {
+        let item = meta_item.join([name]);
+
+        for m in cx.meta(&item) {
+            let kind = match m.kind {
+                Kind::Type => TypeKind::Type,
+                Kind::Struct => TypeKind::Struct,
+                Kind::Enum => TypeKind::Enum,
+                Kind::Function(f) => {
+                    if matches!(f.signature, Signature::Instance) {
+                        continue;
+                    }
+
+                    out.functions.push(Function {
+                        item: Some(item.to_string()),
+                        name: name.to_string(),
+                        kind: FunctionKind::Function,
+                        associated: None,
+                        is_async: f.is_async,
+                        deprecated: f.deprecated,
+                        args: arguments(cx, f.arg_names, f.args, f.signature, f.argument_types),
+                        return_type: f.return_type.and_then(|hash| type_name(cx, hash)),
+                        docs: m.docs,
+                    });
+
+                    continue;
+                }
+                Kind::Macro => {
+                    out.macros.push(Macro {
+                        item: item.to_string(),
+                        name: name.to_string(),
+                        docs: m.docs,
+                    });
+
+                    continue;
+                }
+                Kind::Const(value) => {
+                    out.constants.push(Constant {
+                        item: item.to_string(),
+                        name: name.to_string(),
+                        value: const_value(value),
+                        docs: m.docs,
+                    });
+
+                    continue;
+                }
+                Kind::Module => {
+                    let Some(child) = m.item else {
+                        continue;
+                    };
+
+                    // Skip over crate items, since they are added separately.
+                    if meta_item.is_empty() && child.as_crate().is_some() {
+                        continue;
+                    }
+
+                    out.modules.push(module(cx, m)?);
+                    continue;
+                }
+                _ => continue,
+            };
+
+            out.types.push(type_(cx, m, &item, kind)?);
+        }
+    }
+
+    Ok(out)
+}
+
+fn type_<'a>(cx: &Context<'a>, meta: Meta<'a>, item: &Item, kind: TypeKind) -> Result<Type<'a>> {
+    let mut variants = Vec::new();
+    let mut functions = Vec::new();
+
+    for assoc in cx.associated(meta.hash) {
+        let assoc = match assoc {
+            Assoc::Variant(variant) => {
+                variants.push(Variant {
+                    name: variant.name,
+                    docs: variant.docs,
+                });
+
+                continue;
+            }
+            Assoc::Fn(assoc) => assoc,
+        };
+
+        // Protocol functions don't record their number of arguments, but it
+        // can be derived from their argument types when they are known.
+        let protocol_args = (!assoc.argument_types.is_empty()).then_some(assoc.argument_types.len());
+
+        let (name, associated, args) = match assoc.kind {
+            AssocFnKind::Method(name, args, sig) => (
+                name.to_string(),
+                Associated::Instance { name },
+                arguments(cx, assoc.arg_names, args, sig, assoc.argument_types),
+            ),
+            AssocFnKind::Protocol(protocol) => (
+                protocol.name.to_lowercase(),
+                Associated::Protocol {
+                    protocol: protocol.name,
+                },
+                arguments(cx, assoc.arg_names, protocol_args, Signature::Instance, assoc.argument_types),
+            ),
+            AssocFnKind::FieldFn(protocol, field) => (
+                format!("{}_{field}", protocol.name.to_lowercase()),
+                Associated::FieldFn {
+                    protocol: protocol.name,
+                    field,
+                },
+                arguments(cx, assoc.arg_names, protocol_args, Signature::Instance, assoc.argument_types),
+            ),
+            AssocFnKind::IndexFn(protocol, index) => (
+                format!("{}_{index}", protocol.name.to_lowercase()),
+                Associated::IndexFn {
+                    protocol: protocol.name,
+                    index,
+                },
+                arguments(cx, assoc.arg_names, protocol_args, Signature::Instance, assoc.argument_types),
+            ),
+        };
+
+        let fn_item = match associated {
+            Associated::Instance { name } => Some(format!("{item}::{name}")),
+            _ => None,
+        };
+
+        functions.push(Function {
+            item: fn_item,
+            name,
+            kind: FunctionKind::Associated,
+            associated: Some(associated),
+            is_async: assoc.is_async,
+            deprecated: assoc.deprecated,
+            args,
+            return_type: assoc.return_type.and_then(|hash| type_name(cx, hash)),
+            docs: assoc.docs,
+        });
+    }
+
+    Ok(Type {
+        item: item.to_string(),
+        name: item.last().context("Missing type name")?.to_string(),
+        kind,
+        docs: meta.docs,
+        variants,
+        functions,
+    })
+}
+
+/// Collect the arguments of a function, returning `None` if it takes a
+/// variable number of arguments.
+///
+/// Arguments are named the same way as in the generated HTML documentation.
+fn arguments(
+    cx: &Context<'_>,
+    arg_names: Option<&[String]>,
+    args: Option<usize>,
+    sig: Signature,
+    argument_types: &[Option<Hash>],
+) -> Option<Vec<Argument>> {
+    let names = match arg_names {
+        Some(arg_names) => arg_names.to_vec(),
+        None => {
+            let count = args?;
+
+            (0..count)
+                .map(|n| match (sig, n) {
+                    (Signature::Instance, 0) => String::from("self"),
+                    (Signature::Instance, 1) | (Signature::Function, 0) => String::from("value"),
+                    (_, n) => format!("value{n}"),
+                })
+                .collect()
+        }
+    };
+
+    let mut types = argument_types.iter();
+
+    let args = names
+        .into_iter()
+        .map(|name| Argument {
+            name,
+            ty: types.next().copied().flatten().and_then(|hash| type_name(cx, hash)),
+        })
+        .collect();
+
+    Some(args)
+}
+
+/// Resolve the name of the type with the given hash.
+fn type_name(cx: &Context<'_>, hash: Hash) -> Option<String> {
+    let meta = cx
+        .meta_by_hash(hash)
+        .into_iter()
+        .find(|m| matches!(m.kind, Kind::Type | Kind::Struct | Kind::Enum))?;
+
+    Some(meta.item?.to_string())
+}
+
+/// Render a constant value if it's a simple literal.
+fn const_value(value: &ConstValue) -> Option<String> {
+    Some(match value {
+        ConstValue::EmptyTuple => String::from("()"),
+        ConstValue::Byte(b) => format!("b'\\x{b:02x}'"),
+        ConstValue::Char(c) => format!("{c:?}"),
+        ConstValue::Bool(b) => b.to_string(),
+        ConstValue::Integer(n) => n.to_string(),
+        ConstValue::Float(n) => format!("{n:?}"),
+        ConstValue::String(s) => format!("{s:?}"),
+        _ => return None,
+    })
+}
+
+fn render_docs(out: &mut String, indent: usize, docs: &[String]) -> Result<()> {
+    for line in docs {
+        write!(out, "{:indent$}///", "")?;
+
+        if !line.is_empty() && !line.starts_with(' ') {
+            out.push(' ');
+        }
+
+        writeln!(out, "{}", line.trim_end())?;
+    }
+
+    Ok(())
+}
+
+fn render_module(out: &mut String, indent: usize, m: &Module<'_>) -> Result<()> {
+    let name = m.item.rsplit("::").next().unwrap_or_default();
+
+    render_docs(out, indent, m.docs)?;
+    writeln!(out, "{:indent$}pub mod {name} {{", "")?;
+
+    let inner = indent + 4;
+    let mut first = true;
+
+    let mut separate = |out: &mut String| -> Result<()> {
+        if !first {
+            writeln!(out)?;
+        }
+
+        first = false;
+        Ok(())
+    };
+
+    for c in &m.constants {
+        separate(out)?;
+        render_docs(out, inner, c.docs)?;
+
+        match &c.value {
+            Some(value) => writeln!(out, "{:inner$}pub const {} = {value};", "", c.name)?,
+            None => writeln!(out, "{:inner$}pub const {};", "", c.name)?,
+        }
+    }
+
+    for mac in &m.macros {
+        separate(out)?;
+        render_docs(out, inner, mac.docs)?;
+        writeln!(out, "{:inner$}pub macro {};", "", mac.name)?;
+    }
+
+    for f in &m.functions {
+        separate(out)?;
+        render_function(out, inner, f)?;
+    }
+
+    for ty in &m.types {
+        separate(out)?;
+        render_type(out, inner, ty)?;
+    }
+
+    for child in &m.modules {
+        separate(out)?;
+        render_module(out, inner, child)?;
+    }
+
+    writeln!(out, "{:indent$}}}", "")?;
+    Ok(())
+}
+
+fn render_type(out: &mut String, indent: usize, ty: &Type<'_>) -> Result<()> {
+    render_docs(out, indent, ty.docs)?;
+
+    match ty.kind {
+        TypeKind::Type => writeln!(out, "{:indent$}pub type {};", "", ty.name)?,
+        TypeKind::Struct => writeln!(out, "{:indent$}pub struct {};", "", ty.name)?,
+        TypeKind::Enum => {
+            writeln!(out, "{:indent$}pub enum {} {{", "", ty.name)?;
+
+            for variant in &ty.variants {
+                render_docs(out, indent + 4, variant.docs)?;
+                writeln!(out, "{:1$}{2},", "", indent + 4, variant.name)?;
+            }
+
+            writeln!(out, "{:indent$}}}", "")?;
+        }
+    }
+
+    if ty.functions.is_empty() {
+        return Ok(());
+    }
+
+    writeln!(out)?;
+    writeln!(out, "{:indent$}impl {} {{", "", ty.name)?;
+
+    for (n, f) in ty.functions.iter().enumerate() {
+        if n > 0 {
+            writeln!(out)?;
+        }
+
+        render_function(out, indent + 4, f)?;
+    }
+
+    writeln!(out, "{:indent$}}}", "")?;
+    Ok(())
+}
+
+fn render_function(out: &mut String, indent: usize, f: &Function<'_>) -> Result<()> {
+    render_docs(out, indent, f.docs)?;
+
+    if let Some(deprecated) = f.deprecated {
+        writeln!(out, "{:indent$}#[deprecated = {deprecated:?}]", "")?;
+    }
+
+    match &f.associated {
+        Some(Associated::Protocol { protocol }) => {
+            writeln!(out, "{:indent$}#[protocol({protocol})]", "")?;
+        }
+        Some(Associated::FieldFn { protocol, field }) => {
+            writeln!(out, "{:indent$}#[protocol({protocol}, field = {field:?})]", "")?;
+        }
+        Some(Associated::IndexFn { protocol, index }) => {
+            writeln!(out, "{:indent$}#[protocol({protocol}, index = {index})]", "")?;
+        }
+        Some(Associated::Instance { .. }) | None => {}
+    }
+
+    write!(out, "{:indent$}pub ", "")?;
+
+    if f.is_async {
+        write!(out, "async ")?;
+    }
+
+    write!(out, "fn {}(", f.name)?;
+
+    match &f.args {
+        Some(args) => {
+            let mut it = args.iter().peekable();
+
+            while let Some(arg) = it.next() {
+                write!(out, "{}", arg.name)?;
+
+                if let (Some(ty), false) = (&arg.ty, arg.name == "self") {
+                    write!(out, ": {ty}")?;
+                }
+
+                if it.peek().is_some() {
+                    write!(out, ", ")?;
+                }
+            }
+        }
+        None => write!(out, "..")?,
+    }
+
+    write!(out, ")")?;
+
+    if let Some(return_type) = &f.return_type {
+        write!(out, " -> {return_type}")?;
+    }
+
+    writeln!(out, ";")?;
+    Ok(())
+}
@@ -130,6 +130,7 @@ impl<'a> Printer<'a> {
             ast::Item::Enum(item) => self.visit_enum(item, semi)?,
             ast::Item::Struct(item) => self.visit_struct(item, semi)?,
             ast::Item::Impl(item) => self.visit_impl(item, semi)?,
+            ast::Item::Trait(item) => self.visit_trait(item, semi)?,
             ast::Item::Mod(item) => self.visit_mod(item, semi)?,
             ast::Item::Const(item) => self.visit_const(item, semi)?,
             ast::Item::MacroCall(item) => self.visit_macro_call(item, semi)?,
@@ -221,6 +222,7 @@ impl<'a> Printer<'a> {
         let ast::ItemImpl {
             attributes,
             impl_,
+            trait_path,
             path,
             open,
             functions,
@@ -233,6 +235,13 @@ impl<'a> Printer<'a> {
         }
 
         self.writer.write_spanned_raw(impl_.span, false, true)?;
+
+        if let Some((trait_path, for_token)) = trait_path {
+            self.visit_path(trait_path)?;
+            self.writer.write_unspanned(" ")?;
+            self.writer.write_spanned_raw(for_token.span, false, true)?;
+        }
+
         self.visit_path(path)?;
 
         self.writer.write_unspanned(" ")?;
@@ -434,6 +443,7 @@ impl<'a> Printer<'a> {
             attributes,
             visibility,
             name,
+            ty,
         } = ast;
 
         for attribute in attributes {
@@ -444,6 +454,10 @@ impl<'a> Printer<'a> {
         self.emit_visibility(visibility)?;
         self.writer.write_spanned_raw(name.span, false, false)?;
 
+        if let Some((colon, ty)) = ty {
+            self.visit_type_annotation(colon, ty)?;
+        }
+
         Ok(())
     }
 
@@ -457,6 +471,7 @@ impl<'a> Printer<'a> {
             fn_token,
             name,
             args,
+            output,
             body,
         } = item;
 
@@ -479,6 +494,28 @@ impl<'a> Printer<'a> {
         self.writer.write_spanned_raw(fn_token.span, false, true)?;
         self.writer.write_spanned_raw(name.span, false, false)?;
 
+        self.visit_fn_args(args)?;
+
+        self.writer
+            .write_spanned_raw(args.close.span, false, true)?;
+
+        if let Some((arrow, ty)) = output {
+            self.writer.write_spanned_raw(arrow.span, false, true)?;
+            self.visit_type(ty)?;
+            self.writer.write_unspanned(" ")?;
+        }
+
+        self.visit_block(body)?;
+
+        if let Some(semi) = semi {
+            self.writer.write_spanned_raw(semi.span, false, false)?;
+        }
+
+        Ok(())
+    }
+
+    /// Visit the arguments of a function, except for the closing parenthesis.
+    fn visit_fn_args(&mut self, args: &ast::Parenthesized<ast::FnArg, T![,]>) -> Result<()> {
         self.writer
             .write_spanned_raw(args.open.span, false, false)?;
 
@@ -491,10 +528,7 @@ impl<'a> Printer<'a> {
         };
 
         for (arg, comma) in args {
-            match arg {
-                ast::FnArg::SelfValue(selfvalue) => self.visit_self_value(selfvalue)?,
-                ast::FnArg::Pat(pattern) => self.visit_pattern(pattern)?,
-            }
+            self.visit_fn_arg(arg)?;
 
             if let Some(comma) = comma {
                 self.writer
@@ -502,14 +536,102 @@ impl<'a> Printer<'a> {
             }
         }
 
-        if args.len() > 5 {
+        if multiline {
             self.writer.dedent();
             self.writer.newline()?;
         }
 
+        Ok(())
+    }
+
+    fn visit_fn_arg(&mut self, arg: &ast::FnArg) -> Result<()> {
+        match arg {
+            ast::FnArg::SelfValue(selfvalue) => self.visit_self_value(selfvalue)?,
+            ast::FnArg::Pat(pattern) => self.visit_pattern(pattern)?,
+            ast::FnArg::Typed(ast::PatType { pat, colon, ty }) => {
+                self.visit_pattern(pat)?;
+                self.visit_type_annotation(colon, ty)?;
+            }
+            ast::FnArg::Default(ast::FnArgDefault { pat, ty, eq, value }) => {
+                self.visit_pattern(pat)?;
+
+                if let Some((colon, ty)) = ty {
+                    self.visit_type_annotation(colon, ty)?;
+                }
+
+                self.writer.write_unspanned(" ")?;
+                self.writer.write_spanned_raw(eq.span, false, true)?;
+                self.visit_expr(value)?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Visit a type annotation `: <type>`.
+    fn visit_type_annotation(&mut self, colon: &T![:], ty: &ast::Type) -> Result<()> {
+        self.writer.write_spanned_raw(colon.span, false, true)?;
+        self.visit_type(ty)
+    }
+
+    fn visit_type(&mut self, ty: &ast::Type) -> Result<()> {
+        match ty {
+            ast::Type::Path(path) => self.visit_path(path)?,
+            ast::Type::Tuple(items) => {
+                self.writer
+                    .write_spanned_raw(items.open.span, false, false)?;
+
+                for (ty, comma) in items {
+                    self.visit_type(ty)?;
+
+                    if let Some(comma) = comma {
+                        self.writer.write_spanned_raw(comma.span, false, true)?;
+                    }
+                }
+
+                self.writer
+                    .write_spanned_raw(items.close.span, false, false)?;
+            }
+        }
+
+        Ok(())
+    }
+
+    fn visit_trait(&mut self, item: &ast::ItemTrait, semi: Option<ast::SemiColon>) -> Result<()> {
+        let ast::ItemTrait {
+            attributes,
+            visibility,
+            trait_token,
+            ident,
+            open,
+            items,
+            close,
+        } = item;
+
+        for attribute in attributes {
+            self.visit_attribute(attribute)?;
+            self.writer.newline()?;
+        }
+
+        self.emit_visibility(visibility)?;
         self.writer
-            .write_spanned_raw(args.close.span, false, true)?;
-        self.visit_block(body)?;
+            .write_spanned_raw(trait_token.span, false, true)?;
+        self.writer.write_spanned_raw(ident.span, false, true)?;
+        self.writer.write_spanned_raw(open.span, true, false)?;
+
+        self.writer.indent();
+
+        for item in items {
+            match item {
+                ast::TraitItem::Required(f) => self.visit_trait_fn(f)?,
+                ast::TraitItem::Provided(f) => self.visit_fn(f, None)?,
+            }
+
+            self.writer.newline()?;
+        }
+
+        self.writer.dedent();
+        self.writer.write_spanned_raw(close.span, false, false)?;
 
         if let Some(semi) = semi {
             self.writer.write_spanned_raw(semi.span, false, false)?;
@@ -518,6 +640,46 @@ impl<'a> Printer<'a> {
         Ok(())
     }
 
+    fn visit_trait_fn(&mut self, item: &ast::TraitFn) -> Result<()> {
+        let ast::TraitFn {
+            attributes,
+            async_token,
+            fn_token,
+            name,
+            args,
+            output,
+            semi,
+        } = item;
+
+        for attribute in attributes {
+            self.visit_attribute(attribute)?;
+            self.writer.newline()?;
+        }
+
+        if let Some(async_token) = async_token {
+            self.writer
+                .write_spanned_raw(async_token.span, false, true)?;
+        }
+
+        self.writer.write_spanned_raw(fn_token.span, false, true)?;
+        self.writer.write_spanned_raw(name.span, false, false)?;
+
+        self.visit_fn_args(args)?;
+
+        if let Some((arrow, ty)) = output {
+            self.writer
+                .write_spanned_raw(args.close.span, false, true)?;
+            self.writer.write_spanned_raw(arrow.span, false, true)?;
+            self.visit_type(ty)?;
+        } else {
+            self.writer
+                .write_spanned_raw(args.close.span, false, false)?;
+        }
+
+        self.writer.write_spanned_raw(semi.span, false, false)?;
+        Ok(())
+    }
+
     fn visit_use(&mut self, usage: &ast::ItemUse, semi: Option<ast::SemiColon>) -> Result<()> {
         let ast::ItemUse {
             attributes,
@@ -1123,7 +1285,12 @@ impl<'a> Printer<'a> {
 
         let count = args.parenthesized.len();
         for (idx, (arg, comma)) in args.parenthesized.iter().enumerate() {
-            self.visit_expr(arg)?;
+            if let Some((name, colon)) = &arg.name {
+                self.writer.write_spanned_raw(name.span, false, false)?;
+                self.writer.write_spanned_raw(colon.span, false, true)?;
+            }
+
+            self.visit_expr(&arg.expr)?;
             if idx != count - 1 {
                 if let Some(comma) = comma {
                     self.writer.write_spanned_raw(comma.span, false, true)?;
@@ -1263,10 +1430,8 @@ impl<'a> Printer<'a> {
             ast::ExprClosureArgs::List { args, open, close } => {
                 self.writer.write_spanned_raw(open.span, false, false)?;
                 for (arg, comma) in args {
-                    match arg {
-                        ast::FnArg::SelfValue(self_) => self.visit_self_value(self_)?,
-                        ast::FnArg::Pat(pat) => self.visit_pattern(pat)?,
-                    }
+                    self.visit_fn_arg(arg)?;
+
                     if let Some(comma) = comma {
                         self.writer.write_spanned_raw(comma.span, false, true)?;
                     }
@@ -1450,6 +1615,8 @@ impl<'a> Printer<'a> {
             ast::Pat::Object(ast) => self.visit_pat_object(ast)?,
             ast::Pat::Binding(binding) => self.visit_pat_binding(binding)?,
             ast::Pat::Rest(rest) => self.visit_pat_rest(rest)?,
+            ast::Pat::Or(or) => self.visit_pat_or(or)?,
+            ast::Pat::Range(range) => self.visit_pat_range(range)?,
         }
 
         Ok(())
@@ -1633,6 +1800,48 @@ impl<'a> Printer<'a> {
         Ok(())
     }
 
+    fn visit_pat_or(&mut self, ast: &ast::PatOr) -> Result<()> {
+        let ast::PatOr { first, rest } = ast;
+
+        self.visit_pattern(first)?;
+
+        for (pipe, pat) in rest {
+            self.writer.write_unspanned(" ")?;
+            self.writer.write_spanned_raw(pipe.span, false, true)?;
+            self.visit_pattern(pat)?;
+        }
+
+        Ok(())
+    }
+
+    fn visit_pat_range(&mut self, ast: &ast::PatRange) -> Result<()> {
+        let ast::PatRange {
+            attributes,
+            start,
+            limits,
+            end,
+        } = ast;
+
+        for attribute in attributes {
+            self.visit_attribute(attribute)?;
+        }
+
+        if let Some(start) = start {
+            self.visit_expr(start)?;
+        }
+
+        match limits {
+            ast::ExprRangeLimits::HalfOpen(_) => write!(self.writer, "..")?,
+            ast::ExprRangeLimits::Closed(_) => write!(self.writer, "..=")?,
+        }
+
+        if let Some(end) = end {
+            self.visit_expr(end)?;
+        }
+
+        Ok(())
+    }
+
     fn visit_pat_ignore(&mut self, ast: &ast::PatIgnore) -> Result<()> {
         let ast::PatIgnore {
             attributes,
@@ -1833,6 +2042,7 @@ impl<'a> Printer<'a> {
             let_token,
             mut_token,
             pat,
+            ty,
             eq,
             expr,
             semi,
@@ -1849,6 +2059,11 @@ impl<'a> Printer<'a> {
         }
 
         self.visit_pattern(pat)?;
+
+        if let Some((colon, ty)) = ty {
+            self.visit_type_annotation(colon, ty)?;
+        }
+
         self.writer.write_unspanned(" ")?;
         self.writer.write_spanned_raw(eq.span, false, true)?;
         self.visit_expr(expr)?;
@@ -110,6 +110,10 @@ pub(crate) enum PatKind<'hir> {
     Sequence(&'hir PatSequence<'hir>),
     /// An object pattern.
     Object(&'hir PatObject<'hir>),
+    /// An or-pattern.
+    Or(&'hir PatOr<'hir>),
+    /// A range pattern.
+    Range(&'hir PatRange),
 }
 
 #[derive(Debug, Clone, Copy)]
@@ -152,6 +156,24 @@ pub(crate) struct PatObject<'hir> {
     pub(crate) bindings: &'hir [Binding<'hir>],
 }
 
+/// An or-pattern.
+#[derive(Debug, Clone, Copy)]
+#[non_exhaustive]
+pub(crate) struct PatOr<'hir> {
+    /// The alternatives of the pattern.
+    pub(crate) alternatives: &'hir [Pat<'hir>],
+    /// The variables bound by every alternative.
+    pub(crate) names: &'hir [&'hir str],
+}
+
+/// A range pattern, where both bounds are inclusive.
+#[derive(Debug, Clone, Copy)]
+pub(crate) enum PatRange {
+    Integer { start: i64, end: i64 },
+    Byte { start: u8, end: u8 },
+    Char { start: char, end: char },
+}
+
 #[derive(Debug, Clone, Copy)]
 #[non_exhaustive]
 pub(crate) enum Binding<'hir> {
@@ -186,6 +208,37 @@ pub(crate) struct Expr<'hir> {
     pub(crate) span: Span,
     /// The kind of the expression.
     pub(crate) kind: ExprKind<'hir>,
+    /// The statically known type of the expression, if any.
+    pub(crate) ty: Option<Ty>,
+}
+
+/// A statically known type, as used when checking type annotations.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub(crate) struct Ty {
+    /// The type hash of the type.
+    pub(crate) hash: Hash,
+    /// Whether the type stems from an explicit annotation, as opposed to being
+    /// inferred from something like a literal or a native return type.
+    pub(crate) annotated: bool,
+}
+
+impl Ty {
+    /// A type which stems from an explicit annotation.
+    pub(crate) const fn annotated(hash: Hash) -> Self {
+        Self {
+            hash,
+            annotated: true,
+        }
+    }
+
+    /// A type which has been inferred.
+    pub(crate) const fn inferred(hash: Hash) -> Self {
+        Self {
+            hash,
+            annotated: false,
+        }
+    }
 }
 
 /// The kind of a number.
@@ -195,6 +248,8 @@ pub(crate) enum Lit<'hir> {
     Bool(bool),
     Integer(i64),
     Float(f64),
+    /// The signed little-endian bytes of an arbitrary-precision integer.
+    BigInt(&'hir [u8]),
     Byte(u8),
     Char(char),
     Str(&'hir str),
@@ -422,8 +477,14 @@ pub(crate) enum Call<'hir> {
 pub(crate) struct ExprCall<'hir> {
     /// The call being performed.
     pub(crate) call: Call<'hir>,
-    /// The arguments of the function call.
+    /// The arguments of the function call, in the order they are evaluated.
     pub(crate) args: &'hir [Expr<'hir>],
+    /// The names of the trailing arguments which are passed by name and bound
+    /// to parameters at runtime.
+    pub(crate) named: &'hir [&'hir str],
+    /// The index of the argument passed to each parameter, if the arguments
+    /// were bound to parameters out of order at compile time.
+    pub(crate) order: &'hir [usize],
 }
 
 /// A field access `<expr>.<field>`.
@@ -14,7 +14,7 @@ use crate::hir;
 use crate::indexing;
 use crate::parse::Resolve;
 use crate::query::{self, Build, BuildEntry, GenericsParameters, Named, Query};
-use crate::runtime::{Type, TypeCheck};
+use crate::runtime::{static_type, Call, Type, TypeCheck};
 use crate::SourceId;
 
 use rune_macros::instrument;
@@ -36,6 +36,8 @@ pub(crate) struct Ctxt<'hir, 'a, 'arena> {
     needs: Cell<Needs>,
     scopes: hir::Scopes<'hir>,
     const_eval: bool,
+    /// The annotated return type of the function being lowered, if any.
+    return_ty: Option<hir::Ty>,
 }
 
 impl<'hir, 'a, 'arena> Ctxt<'hir, 'a, 'arena> {
@@ -85,6 +87,7 @@ impl<'hir, 'a, 'arena> Ctxt<'hir, 'a, 'arena> {
             needs: Cell::new(Needs::default()),
             scopes: hir::Scopes::default(),
             const_eval,
+            return_ty: None,
         }
     }
 
@@ -144,13 +147,34 @@ pub(crate) fn empty_fn<'hir>(
 pub(crate) fn item_fn<'hir>(
     cx: &mut Ctxt<'hir, '_, '_>,
     ast: &ast::ItemFn,
+    call: Call,
 ) -> compile::Result<hir::ItemFn<'hir>> {
     alloc_with!(cx, ast);
 
+    // NB: the return type of generators and streams describe the value they
+    // complete with, which is not something we check.
+    let return_ty = match (&ast.output, call) {
+        (Some((_, ty)), Call::Immediate | Call::Async) => {
+            Some(hir::Ty::annotated(cx.q.convert_type(cx.source_id, ty)?))
+        }
+        _ => None,
+    };
+
+    let args = iter!(&ast.args, |(ast, _)| fn_arg(cx, ast)?);
+
+    let return_ty = core::mem::replace(&mut cx.return_ty, return_ty);
+    let body = block(cx, &ast.body)?;
+
+    if let (Some(expected), Some(hir::Stmt::Expr(tail))) = (cx.return_ty, body.statements.last()) {
+        check_ty(cx, tail, expected, tail.ty)?;
+    }
+
+    cx.return_ty = return_ty;
+
     Ok(hir::ItemFn {
         span: ast.span(),
-        args: iter!(&ast.args, |(ast, _)| fn_arg(cx, ast)?),
-        body: block(cx, &ast.body)?,
+        args,
+        body,
     })
 }
 
@@ -262,12 +286,15 @@ fn expr_call_closure<'hir>(
             tracing::trace!("queuing closure build entry");
 
             cx.scopes.push_captures();
+            let return_ty = cx.return_ty.take();
 
             for (arg, _) in ast.args.as_slice() {
                 fn_arg(cx, arg)?;
             }
 
             expr(cx, &ast.body)?;
+
+            cx.return_ty = return_ty;
             let layer = cx.scopes.pop().with_span(&ast.body)?;
 
             cx.q.set_used(&meta.item_meta);
@@ -399,6 +426,7 @@ pub(crate) fn expr_object<'hir>(
                 hir::Expr {
                     span: ast.span(),
                     kind: hir::ExprKind::Variable(name),
+                    ty: cx.scopes.ty_of(name),
                 }
             }
         };
@@ -464,6 +492,7 @@ pub(crate) fn expr_object<'hir>(
                     ..
                 } => {
                     check_object_fields(&st.fields, item)?;
+                    check_field_types(cx, assignments, &st.fields)?;
 
                     match constructor {
                         Some(_) => hir::ExprObjectKind::ExternalType {
@@ -478,6 +507,7 @@ pub(crate) fn expr_object<'hir>(
                     ..
                 } => {
                     check_object_fields(&st.fields, item)?;
+                    check_field_types(cx, assignments, &st.fields)?;
                     hir::ExprObjectKind::StructVariant { hash: meta.hash }
                 }
                 _ => {
@@ -508,13 +538,20 @@ pub(crate) fn expr<'hir>(
     alloc_with!(cx, ast);
 
     let in_path = cx.in_path.take();
+    let mut call_ty = None;
 
     let kind = match ast {
         ast::Expr::Path(ast) => expr_path(cx, ast, in_path)?,
-        ast::Expr::Assign(ast) => hir::ExprKind::Assign(alloc!(hir::ExprAssign {
-            lhs: expr(cx, &ast.lhs)?,
-            rhs: expr(cx, &ast.rhs)?,
-        })),
+        ast::Expr::Assign(ast) => {
+            let lhs = expr(cx, &ast.lhs)?;
+            let rhs = expr(cx, &ast.rhs)?;
+
+            if let Some(expected) = lhs.ty.filter(|ty| ty.annotated) {
+                check_ty(cx, &rhs, expected, rhs.ty)?;
+            }
+
+            hir::ExprKind::Assign(alloc!(hir::ExprAssign { lhs, rhs }))
+        }
         // TODO: lower all of these loop constructs to the same loop-like
         // representation. We only do different ones here right now since it's
         // easier when refactoring.
@@ -603,7 +640,11 @@ pub(crate) fn expr<'hir>(
                 }
             }),
         })),
-        ast::Expr::Call(ast) => hir::ExprKind::Call(alloc!(expr_call(cx, ast)?)),
+        ast::Expr::Call(ast) => {
+            let (call, ty) = expr_call(cx, ast)?;
+            call_ty = ty;
+            hir::ExprKind::Call(alloc!(call))
+        }
         ast::Expr::FieldAccess(ast) => {
             hir::ExprKind::FieldAccess(alloc!(expr_field_access(cx, ast)?))
         }
@@ -639,7 +680,15 @@ pub(crate) fn expr<'hir>(
         ast::Expr::Break(ast) => hir::ExprKind::Break(alloc!(expr_break(cx, ast)?)),
         ast::Expr::Continue(ast) => hir::ExprKind::Continue(alloc!(expr_continue(cx, ast)?)),
         ast::Expr::Yield(ast) => hir::ExprKind::Yield(option!(&ast.expr, |ast| expr(cx, ast)?)),
-        ast::Expr::Return(ast) => hir::ExprKind::Return(option!(&ast.expr, |ast| expr(cx, ast)?)),
+        ast::Expr::Return(ast) => {
+            let value = option!(&ast.expr, |ast| expr(cx, ast)?);
+
+            if let (Some(expected), Some(value)) = (cx.return_ty, value) {
+                check_ty(cx, value, expected, value.ty)?;
+            }
+
+            hir::ExprKind::Return(value)
+        }
         ast::Expr::Await(ast) => hir::ExprKind::Await(alloc!(expr(cx, &ast.expr)?)),
         ast::Expr::Try(ast) => hir::ExprKind::Try(alloc!(expr(cx, &ast.expr)?)),
         ast::Expr::Select(ast) => hir::ExprKind::Select(alloc!(hir::ExprSelect {
@@ -705,9 +754,31 @@ pub(crate) fn expr<'hir>(
         },
     };
 
+    let ty = match kind {
+        hir::ExprKind::Variable(name) => cx.scopes.ty_of(name),
+        hir::ExprKind::Lit(lit) => Some(hir::Ty::inferred(lit_ty(lit))),
+        hir::ExprKind::Group(expr) => expr.ty,
+        hir::ExprKind::Template(..) => Some(hir::Ty::inferred(static_type::STRING_TYPE.hash)),
+        hir::ExprKind::Vec(..) => Some(hir::Ty::inferred(static_type::VEC_TYPE.hash)),
+        hir::ExprKind::Tuple(..) => Some(hir::Ty::inferred(static_type::TUPLE_TYPE.hash)),
+        hir::ExprKind::Object(object) => match object.kind {
+            hir::ExprObjectKind::EmptyStruct { hash }
+            | hir::ExprObjectKind::Struct { hash }
+            | hir::ExprObjectKind::ExternalType { hash, .. } => Some(hir::Ty::inferred(hash)),
+            hir::ExprObjectKind::Anonymous => {
+                Some(hir::Ty::inferred(static_type::OBJECT_TYPE.hash))
+            }
+            hir::ExprObjectKind::StructVariant { .. } => None,
+        },
+        hir::ExprKind::FieldAccess(access) => field_ty(cx, ast, access)?,
+        hir::ExprKind::Call(..) => call_ty,
+        _ => None,
+    };
+
     Ok(hir::Expr {
         span: ast.span(),
         kind,
+        ty,
     })
 }
 
@@ -793,6 +864,9 @@ pub(crate) fn lit<'hir>(
 
                     Ok(hir::Lit::Byte(n))
                 }
+                (ast::NumberValue::Integer(int), Some(ast::NumberSuffix::BigInt(..))) => {
+                    Ok(hir::Lit::BigInt(alloc_bytes!(&int.to_signed_bytes_le())))
+                }
                 (ast::NumberValue::Integer(int), _) => {
                     let Some(n) = int.to_i64() else {
                         return Err(compile::Error::new(ast, ErrorKind::BadNumberOutOfBounds));
@@ -865,6 +939,10 @@ pub(crate) fn expr_unary<'hir>(
 
             Ok(hir::ExprKind::Lit(hir::Lit::Integer(n)))
         }
+        (ast::NumberValue::Integer(int), Some(ast::NumberSuffix::BigInt(..))) => {
+            let bytes = int.neg().to_signed_bytes_le();
+            Ok(hir::ExprKind::Lit(hir::Lit::BigInt(alloc_bytes!(&bytes))))
+        }
         _ => Err(compile::Error::new(ast, ErrorKind::BadNumberOutOfBounds)),
     }
 }
@@ -920,7 +998,9 @@ pub(crate) fn expr_block<'hir>(
                     tracing::trace!("queuing async block build entry");
 
                     cx.scopes.push_captures();
+                    let return_ty = cx.return_ty.take();
                     block(cx, &ast.block)?;
+                    cx.return_ty = return_ty;
                     let layer = cx.scopes.pop().with_span(&ast.block)?;
 
                     cx.q.insert_captures(meta.hash, layer.captures());
@@ -1047,6 +1127,22 @@ fn fn_arg<'hir>(
             hir::FnArg::SelfValue(ast.span())
         }
         ast::FnArg::Pat(ast) => hir::FnArg::Pat(alloc!(pat(cx, ast)?)),
+        ast::FnArg::Typed(ast) => {
+            let pat = pat(cx, &ast.pat)?;
+            let ty = cx.q.convert_type(cx.source_id, &ast.ty)?;
+            define_pat_ty(cx, &pat, hir::Ty::annotated(ty))?;
+            hir::FnArg::Pat(alloc!(pat))
+        }
+        ast::FnArg::Default(ast) => {
+            let pat = pat(cx, &ast.pat)?;
+
+            if let Some((_, ty)) = &ast.ty {
+                let ty = cx.q.convert_type(cx.source_id, ty)?;
+                define_pat_ty(cx, &pat, hir::Ty::annotated(ty))?;
+            }
+
+            hir::FnArg::Pat(alloc!(pat))
+        }
     })
 }
 
@@ -1055,8 +1151,22 @@ fn local<'hir>(cx: &mut Ctxt<'hir, '_, '_>, ast: &ast::Local) -> compile::Result
     // Note: expression needs to be assembled before pattern, otherwise the
     // expression will see declarations in the pattern.
     let expr = expr(cx, &ast.expr)?;
+
+    let ty = match &ast.ty {
+        Some((_, ty)) => {
+            let ty = hir::Ty::annotated(cx.q.convert_type(cx.source_id, ty)?);
+            check_ty(cx, &expr, ty, expr.ty)?;
+            Some(ty)
+        }
+        None => None,
+    };
+
     let pat = pat(cx, &ast.pat)?;
 
+    if let Some(ty) = ty {
+        define_pat_ty(cx, &pat, ty)?;
+    }
+
     Ok(hir::Local {
         span: ast.span(),
         pat,
@@ -1280,6 +1390,59 @@ fn pat<'hir>(cx: &mut Ctxt<'hir, '_, '_>, ast: &ast::Pat) -> compile::Result<hir
 
             hir::PatKind::Object(alloc!(hir::PatObject { kind, bindings }))
         }
+        ast::Pat::Or(ast) => {
+            let mut names = None::<Vec<&'hir str>>;
+
+            let alternatives = iter!(ast.alternatives(), ast.rest.len() + 1, |alt| {
+                // Each alternative binds its variables in a scope of its own,
+                // so that we can check that they all bind the same ones.
+                cx.scopes.push();
+                let pat = pat(cx, alt)?;
+                let layer = cx.scopes.pop().with_span(alt)?;
+
+                let mut bound = Vec::new();
+
+                for name in layer.into_drop_order().rev() {
+                    if let hir::Name::Str(name) = name {
+                        if !bound.contains(&name) {
+                            bound.push(name);
+                        }
+                    }
+                }
+
+                if let Some(names) = &names {
+                    let missing = names
+                        .iter()
+                        .find(|name| !bound.contains(name))
+                        .or_else(|| bound.iter().find(|name| !names.contains(name)));
+
+                    if let Some(name) = missing {
+                        return Err(compile::Error::new(
+                            alt,
+                            ErrorKind::PatternOrBindings {
+                                name: (*name).into(),
+                            },
+                        ));
+                    }
+                } else {
+                    names = Some(bound);
+                }
+
+                pat
+            });
+
+            let names = names.unwrap_or_default();
+
+            for name in &names {
+                cx.scopes.define(hir::Name::Str(name)).with_span(ast)?;
+            }
+
+            hir::PatKind::Or(alloc!(hir::PatOr {
+                alternatives,
+                names: iter!(names),
+            }))
+        }
+        ast::Pat::Range(ast) => hir::PatKind::Range(alloc!(pat_range(cx, ast)?)),
         _ => {
             return Err(compile::Error::new(ast, ErrorKind::UnsupportedPatternExpr));
         }
@@ -1403,6 +1566,8 @@ fn expr_path_meta<'hir>(
             } => Ok(hir::ExprKind::Call(alloc!(hir::ExprCall {
                 call: hir::Call::Meta { hash: meta.hash },
                 args: &[],
+                named: &[],
+                order: &[],
             }))),
             meta::Kind::Variant {
                 fields: meta::Fields::Unnamed(0),
@@ -1414,6 +1579,8 @@ fn expr_path_meta<'hir>(
             } => Ok(hir::ExprKind::Call(alloc!(hir::ExprCall {
                 call: hir::Call::Meta { hash: meta.hash },
                 args: &[],
+                named: &[],
+                order: &[],
             }))),
             meta::Kind::Struct {
                 fields: meta::Fields::Unnamed(..),
@@ -1458,6 +1625,75 @@ fn condition<'hir>(
     })
 }
 
+/// Lower a range pattern into its inclusive bounds.
+fn pat_range<'hir>(
+    cx: &mut Ctxt<'hir, '_, '_>,
+    ast: &ast::PatRange,
+) -> compile::Result<hir::PatRange> {
+    let mut bound = |ast: &Option<Box<ast::Expr>>| -> compile::Result<Option<hir::Lit<'hir>>> {
+        let Some(ast) = ast else {
+            return Ok(None);
+        };
+
+        let hir::ExprKind::Lit(lit) = expr(cx, ast)?.kind else {
+            return Err(compile::Error::new(ast, ErrorKind::UnsupportedPatternRange));
+        };
+
+        Ok(Some(lit))
+    };
+
+    let start = bound(&ast.start)?;
+    let end = bound(&ast.end)?;
+    let exclusive = matches!(ast.limits, ast::ExprRangeLimits::HalfOpen(..)) && end.is_some();
+
+    macro_rules! bounds {
+        ($variant:ident, $min:expr, $max:expr, $before:expr) => {{
+            let start = match start {
+                Some(hir::Lit::$variant(start)) => start,
+                None => $min,
+                Some(..) => {
+                    return Err(compile::Error::new(ast, ErrorKind::UnsupportedPatternRange));
+                }
+            };
+
+            let end = match end {
+                Some(hir::Lit::$variant(end)) if exclusive => match $before(end) {
+                    Some(end) => end,
+                    None => return Err(compile::Error::new(ast, ErrorKind::EmptyPatternRange)),
+                },
+                Some(hir::Lit::$variant(end)) => end,
+                None => $max,
+                Some(..) => {
+                    return Err(compile::Error::new(ast, ErrorKind::UnsupportedPatternRange));
+                }
+            };
+
+            if start > end {
+                return Err(compile::Error::new(ast, ErrorKind::EmptyPatternRange));
+            }
+
+            hir::PatRange::$variant { start, end }
+        }};
+    }
+
+    Ok(match start.or(end) {
+        Some(hir::Lit::Integer(..)) => {
+            bounds!(Integer, i64::MIN, i64::MAX, |n: i64| n.checked_sub(1))
+        }
+        Some(hir::Lit::Byte(..)) => bounds!(Byte, u8::MIN, u8::MAX, |n: u8| n.checked_sub(1)),
+        Some(hir::Lit::Char(..)) => bounds!(Char, '\0', char::MAX, char_before),
+        _ => return Err(compile::Error::new(ast, ErrorKind::UnsupportedPatternRange)),
+    })
+}
+
+/// The character immediately before the given one, skipping over surrogates.
+fn char_before(c: char) -> Option<char> {
+    match c {
+        '\u{e000}' => Some('\u{d7ff}'),
+        c => char::from_u32((c as u32).checked_sub(1)?),
+    }
+}
+
 /// Test if the given pattern is open or not.
 fn pat_items_count(items: &[(ast::Pat, Option<ast::Comma>)]) -> compile::Result<(bool, usize)> {
     let mut it = items.iter();
@@ -1605,7 +1841,7 @@ fn generics_parameters(
 fn expr_call<'hir>(
     cx: &mut Ctxt<'hir, '_, '_>,
     ast: &ast::ExprCall,
-) -> compile::Result<hir::ExprCall<'hir>> {
+) -> compile::Result<(hir::ExprCall<'hir>, Option<hir::Ty>)> {
     pub(crate) fn find_path(ast: &ast::Expr) -> Option<&ast::Path> {
         let mut current = ast;
 
@@ -1623,8 +1859,41 @@ fn expr_call<'hir>(
 
     alloc_with!(cx, ast);
 
+    // The names of the trailing arguments which are passed by name.
+    let mut names = Vec::new();
+
+    for (arg, _) in &ast.args {
+        match &arg.name {
+            Some((ident, _)) => {
+                let name = alloc_str!(ident.resolve(resolve_context!(cx.q))?);
+
+                if names.iter().any(|&(_, n)| n == name) {
+                    return Err(compile::Error::new(
+                        ident,
+                        ErrorKind::DuplicateArgument { name: name.into() },
+                    ));
+                }
+
+                names.push((ident, name));
+            }
+            None if !names.is_empty() => {
+                return Err(compile::Error::new(arg, ErrorKind::NamedArgumentOrder));
+            }
+            None => {}
+        }
+    }
+
     let expr = cx.in_path(true, |cx| expr(cx, &ast.expr))?;
 
+    // The argument types of the called function if it's statically known,
+    // whether it's declared in a script and how many leading arguments are
+    // passed implicitly.
+    let mut signature = None;
+    let mut ty = None;
+    // The parameters of the called function if it's declared in a script and
+    // statically known.
+    let mut fn_parameters = None;
+
     let call = 'ok: {
         match expr.kind {
             hir::ExprKind::Variable(name) => {
@@ -1641,6 +1910,19 @@ fn expr_call<'hir>(
                 let meta = cx.lookup_meta(path, named.item, parameters)?;
                 debug_assert_eq!(meta.item_meta.item, named.item);
 
+                if !meta.context {
+                    if let meta::Kind::Function { .. } = meta.kind {
+                        fn_parameters = cx.q.get_parameters(meta.hash);
+                    }
+                }
+
+                if let (Some(&(ident, _)), None) = (names.first(), &fn_parameters) {
+                    return Err(compile::Error::new(
+                        ident,
+                        ErrorKind::UnsupportedNamedArguments,
+                    ));
+                }
+
                 match &meta.kind {
                     meta::Kind::Struct {
                         fields: meta::Fields::Empty,
@@ -1687,7 +1969,10 @@ fn expr_call<'hir>(
                             );
                         }
                     }
-                    meta::Kind::Function { .. } => (),
+                    meta::Kind::Function { signature: s, .. } => {
+                        ty = signature_return_ty(s, !meta.context);
+                        signature = Some((s.argument_types.clone(), !meta.context, 0));
+                    }
                     meta::Kind::ConstFn { id, .. } => {
                         let id = *id;
                         let from = cx.q.item_for(ast.id).with_span(ast)?;
@@ -1707,6 +1992,10 @@ fn expr_call<'hir>(
                     }
                 };
 
+                if let meta::Kind::Struct { .. } = meta.kind {
+                    ty = Some(hir::Ty::inferred(meta.hash));
+                }
+
                 break 'ok hir::Call::Meta { hash: meta.hash };
             }
             hir::ExprKind::FieldAccess(&hir::ExprFieldAccess {
@@ -1717,6 +2006,16 @@ fn expr_call<'hir>(
                     hir::ExprField::Index(index) => Hash::index(index),
                     hir::ExprField::Ident(ident) => {
                         cx.q.unit.insert_debug_ident(ident);
+
+                        if let Some(target_ty) = target.ty {
+                            if let Some((s, script)) =
+                                associated_signature(cx, ast, target_ty.hash, ident)?
+                            {
+                                ty = signature_return_ty(&s, script);
+                                signature = Some((s.argument_types, script, 1));
+                            }
+                        }
+
                         Hash::ident(ident)
                     }
                     hir::ExprField::IdentGenerics(ident, hash) => {
@@ -1736,10 +2035,156 @@ fn expr_call<'hir>(
         break 'ok hir::Call::Expr { expr: alloc!(expr) };
     };
 
-    Ok(hir::ExprCall {
-        call,
-        args: iter!(&ast.args, |(ast, _)| self::expr(cx, ast)?),
-    })
+    let mut args = Vec::with_capacity(ast.args.len());
+
+    for (arg, _) in &ast.args {
+        args.push(self::expr(cx, &arg.expr)?);
+    }
+
+    let mut order = Vec::new();
+
+    let bound = match &fn_parameters {
+        Some(parameters) => bind_args(ast, parameters, &names)?,
+        None => None,
+    };
+
+    // Arguments bound at compile time are evaluated in the order they are
+    // passed, followed by any default values.
+    if let (Some(bound), Some(parameters)) = (bound, &fn_parameters) {
+        for (index, parameter) in bound.into_iter().zip(parameters.iter()) {
+            let index = match (index, parameter.default) {
+                (Some(index), _) => index,
+                (None, Some(hash)) => {
+                    args.push(hir::Expr {
+                        span: ast.span(),
+                        kind: hir::ExprKind::Const(hash),
+                        ty: None,
+                    });
+
+                    args.len() - 1
+                }
+                (None, None) => {
+                    return Err(compile::Error::msg(ast, "Missing default argument value"));
+                }
+            };
+
+            order.push(index);
+        }
+
+        if order.iter().copied().eq(0..args.len()) {
+            order.clear();
+        }
+
+        names.clear();
+    }
+
+    if let Some((argument_types, script, skip)) = signature {
+        let positional = args.len() - names.len();
+
+        let ordered = match order.as_slice() {
+            [] => args[..positional].iter().collect::<Vec<_>>(),
+            order => order.iter().map(|&index| &args[index]).collect(),
+        };
+
+        for (arg, expected) in ordered.into_iter().zip(argument_types.iter().skip(skip)) {
+            if let Some(hash) = *expected {
+                let expected = hir::Ty {
+                    hash,
+                    annotated: script,
+                };
+
+                check_ty(cx, arg, expected, arg.ty)?;
+            }
+        }
+    }
+
+    Ok((
+        hir::ExprCall {
+            call,
+            args: iter!(args),
+            named: iter!(names, |(_, name)| name),
+            order: iter!(order),
+        },
+        ty,
+    ))
+}
+
+/// Bind the arguments of a call to the parameters of a function declared in a
+/// script, returning the index of the argument passed to each parameter or
+/// `None` if its default value is used.
+///
+/// Calls which only pass arguments by position and don't rely on default
+/// values are left to be checked at runtime.
+fn bind_args(
+    ast: &ast::ExprCall,
+    parameters: &[query::QueryParameter],
+    names: &[(&ast::Ident, &str)],
+) -> compile::Result<Option<Vec<Option<usize>>>> {
+    let args = ast.args.len();
+    let positional = args - names.len();
+
+    if names.is_empty() {
+        match parameters.get(args..) {
+            Some(rest) if !rest.is_empty() && rest.iter().all(|p| p.default.is_some()) => {}
+            _ => return Ok(None),
+        }
+    }
+
+    if positional > parameters.len() {
+        return Err(compile::Error::new(
+            &ast.args,
+            ErrorKind::UnsupportedArgumentCount {
+                expected: parameters.len(),
+                actual: args,
+            },
+        ));
+    }
+
+    let mut bound = (0..parameters.len())
+        .map(|index| (index < positional).then_some(index))
+        .collect::<Vec<_>>();
+
+    for (n, &(ident, name)) in names.iter().enumerate() {
+        let Some(index) = parameters
+            .iter()
+            .position(|p| p.name.as_deref() == Some(name))
+        else {
+            return Err(compile::Error::new(
+                ident,
+                ErrorKind::NoSuchArgument { name: name.into() },
+            ));
+        };
+
+        if bound[index].is_some() {
+            return Err(compile::Error::new(
+                ident,
+                ErrorKind::DuplicateArgument { name: name.into() },
+            ));
+        }
+
+        bound[index] = Some(positional + n);
+    }
+
+    for (index, parameter) in bound.iter().zip(parameters) {
+        if index.is_some() || parameter.default.is_some() {
+            continue;
+        }
+
+        return Err(match &parameter.name {
+            Some(name) => {
+                compile::Error::new(ast, ErrorKind::MissingArgument { name: name.clone() })
+            }
+            None => compile::Error::new(
+                &ast.args,
+                ErrorKind::UnsupportedArgumentCount {
+                    expected: parameters.len(),
+                    actual: args,
+                },
+            ),
+        });
+    }
+
+    Ok(Some(bound))
 }
 
 #[instrument(span = ast)]
@@ -1793,3 +2238,176 @@ fn expr_field_access<'hir>(
         expr_field,
     })
 }
+
+/// Get the statically known type of a literal.
+fn lit_ty(lit: hir::Lit<'_>) -> Hash {
+    match lit {
+        hir::Lit::Bool(..) => static_type::BOOL_TYPE.hash,
+        hir::Lit::Integer(..) => static_type::INTEGER_TYPE.hash,
+        hir::Lit::Float(..) => static_type::FLOAT_TYPE.hash,
+        hir::Lit::BigInt(..) => static_type::BIGINT_TYPE.hash,
+        hir::Lit::Byte(..) => static_type::BYTE_TYPE.hash,
+        hir::Lit::Char(..) => static_type::CHAR_TYPE.hash,
+        hir::Lit::Str(..) => static_type::STRING_TYPE.hash,
+        hir::Lit::ByteStr(..) => static_type::BYTES_TYPE.hash,
+    }
+}
+
+/// Get the statically known type of a field access, which is only known for
+/// annotated fields on structs declared in scripts.
+fn field_ty(
+    cx: &mut Ctxt<'_, '_, '_>,
+    span: &dyn Spanned,
+    access: &hir::ExprFieldAccess<'_>,
+) -> compile::Result<Option<hir::Ty>> {
+    let (Some(target), hir::ExprField::Ident(ident)) = (access.expr.ty, access.expr_field) else {
+        return Ok(None);
+    };
+
+    let Some(item) = cx.q.lookup_type_item(target.hash) else {
+        return Ok(None);
+    };
+
+    let Some(meta) = cx.try_lookup_meta(span, item, &GenericsParameters::default())? else {
+        return Ok(None);
+    };
+
+    let meta::Kind::Struct {
+        fields: meta::Fields::Named(st),
+        ..
+    } = &meta.kind
+    else {
+        return Ok(None);
+    };
+
+    Ok(st
+        .fields
+        .get(ident)
+        .and_then(|field| field.ty)
+        .map(hir::Ty::annotated))
+}
+
+/// Look up the signature of an instance function called on a receiver of a
+/// statically known type, and whether it was declared in a script.
+fn associated_signature(
+    cx: &mut Ctxt<'_, '_, '_>,
+    span: &dyn Spanned,
+    ty: Hash,
+    name: &str,
+) -> compile::Result<Option<(meta::Signature, bool)>> {
+    let hash = Hash::associated_function(ty, name);
+
+    let signature =
+        cx.q.context
+            .lookup_meta_by_hash(hash)
+            .find_map(|meta| match &meta.kind {
+                meta::Kind::Function { signature, .. } => Some(signature.clone()),
+                _ => None,
+            });
+
+    if let Some(signature) = signature {
+        return Ok(Some((signature, false)));
+    }
+
+    let Some(item) = cx.q.lookup_type_item(ty) else {
+        return Ok(None);
+    };
+
+    let item = cx.q.pool.item(item).extended(name);
+    let item = cx.q.pool.alloc_item(item);
+
+    let Some(meta) = cx.try_lookup_meta(span, item, &GenericsParameters::default())? else {
+        return Ok(None);
+    };
+
+    let meta::Kind::Function {
+        signature,
+        associated: Some(..),
+        ..
+    } = meta.kind
+    else {
+        return Ok(None);
+    };
+
+    Ok(Some((signature, !meta.context)))
+}
+
+/// Get the type returned by calling a function with the given signature.
+fn signature_return_ty(signature: &meta::Signature, script: bool) -> Option<hir::Ty> {
+    let hash = if signature.is_async {
+        signature.return_type.map(|_| static_type::FUTURE_TYPE.hash)
+    } else {
+        signature.return_type
+    }?;
+
+    Some(hir::Ty {
+        hash,
+        annotated: script,
+    })
+}
+
+/// Associate an annotated type with a pattern if it binds a single variable.
+fn define_pat_ty<'hir>(
+    cx: &mut Ctxt<'hir, '_, '_>,
+    pat: &hir::Pat<'hir>,
+    ty: hir::Ty,
+) -> compile::Result<()> {
+    if let hir::PatKind::Path(&hir::PatPathKind::Ident(name)) = pat.kind {
+        cx.scopes
+            .define_ty(hir::Name::Str(name), ty)
+            .with_span(pat)?;
+    }
+
+    Ok(())
+}
+
+/// Check the types of the fields assigned in a struct literal.
+fn check_field_types(
+    cx: &mut Ctxt<'_, '_, '_>,
+    assignments: &[hir::FieldAssign<'_>],
+    fields: &HashMap<Box<str>, meta::FieldMeta>,
+) -> compile::Result<()> {
+    for assign in assignments {
+        if let Some(expected) = fields.get(assign.key.1).and_then(|field| field.ty) {
+            let expected = hir::Ty::annotated(expected);
+            check_ty(cx, &assign.assign, expected, assign.assign.ty)?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Check that an expression has the expected type.
+///
+/// Mismatches are only reported if one of the types stems from an annotation,
+/// so that code without annotations keeps working as before.
+fn check_ty(
+    cx: &mut Ctxt<'_, '_, '_>,
+    span: &dyn Spanned,
+    expected: hir::Ty,
+    actual: Option<hir::Ty>,
+) -> compile::Result<()> {
+    let Some(actual) = actual else {
+        return Ok(());
+    };
+
+    if expected.hash == actual.hash || !(expected.annotated || actual.annotated) {
+        return Ok(());
+    }
+
+    Err(compile::Error::new(
+        span,
+        ErrorKind::TypeMismatch {
+            expected: type_name(cx, expected.hash),
+            actual: type_name(cx, actual.hash),
+        },
+    ))
+}
+
+/// Get a human readable name of the given type.
+fn type_name(cx: &mut Ctxt<'_, '_, '_>, hash: Hash) -> Box<str> {
+    match cx.q.lookup_type_item(hash) {
+        Some(item) => cx.q.pool.item(item).to_string().into(),
+        None => hash.to_string().into(),
+    }
+}
@@ -4,7 +4,7 @@ use core::cell::RefCell;
 use core::fmt;
 use core::num::NonZeroUsize;
 
-use crate::no_std::collections::{BTreeSet, HashSet};
+use crate::no_std::collections::{BTreeSet, HashMap, HashSet};
 use crate::no_std::prelude::*;
 use crate::no_std::vec::Vec;
 
@@ -38,6 +38,8 @@ pub(crate) struct Layer<'hir> {
     variables: HashSet<hir::Name<'hir>>,
     /// Order of variable definitions.
     order: Vec<hir::Name<'hir>>,
+    /// Statically known types of variables in this layer.
+    types: HashMap<hir::Name<'hir>, hir::Ty>,
     /// Captures inside of this layer.
     captures: BTreeSet<hir::Name<'hir>>,
     /// An optional layer label.
@@ -51,7 +53,9 @@ impl<'hir> Layer<'hir> {
 
     /// Convert layer into variable drop order.
     #[inline(always)]
-    pub(crate) fn into_drop_order(self) -> impl ExactSizeIterator<Item = hir::Name<'hir>> {
+    pub(crate) fn into_drop_order(
+        self,
+    ) -> impl ExactSizeIterator<Item = hir::Name<'hir>> + DoubleEndedIterator {
         self.order.into_iter().rev()
     }
 
@@ -94,6 +98,7 @@ impl<'hir> Scopes<'hir> {
             parent: Some(NonZeroUsize::new(self.scope.0.wrapping_add(1)).expect("ran out of ids")),
             variables: HashSet::new(),
             order: Vec::new(),
+            types: HashMap::new(),
             kind,
             captures: BTreeSet::new(),
             label,
@@ -134,9 +139,42 @@ impl<'hir> Scopes<'hir> {
 
         layer.variables.insert(name);
         layer.order.push(name);
+        // A redefinition shadows any type the previous variable might have had.
+        layer.types.remove(&name);
         Ok(name)
     }
 
+    /// Associate a statically known type with a variable defined in the
+    /// current scope.
+    #[tracing::instrument(skip_all, fields(?self.scope, ?name))]
+    pub(crate) fn define_ty(
+        &mut self,
+        name: hir::Name<'hir>,
+        ty: hir::Ty,
+    ) -> Result<(), MissingScope> {
+        let Some(layer) = self.scopes.get_mut(self.scope.0) else {
+            return Err(MissingScope(self.scope.0));
+        };
+
+        layer.types.insert(name, ty);
+        Ok(())
+    }
+
+    /// Get the statically known type of the given variable, if any.
+    pub(crate) fn ty_of(&self, name: hir::Name<'hir>) -> Option<hir::Ty> {
+        let mut scope = self.scopes.get(self.scope.0);
+
+        while let Some(layer) = scope.take() {
+            if layer.variables.contains(&name) {
+                return layer.types.get(&name).copied();
+            }
+
+            scope = self.scopes.get(layer.parent()?);
+        }
+
+        None
+    }
+
     /// Try to lookup the given variable.
     #[tracing::instrument(skip_all, fields(?self.scope, ?name))]
     pub(crate) fn get(&mut self, name: hir::Name<'hir>) -> Option<(hir::Name<'hir>, Scope)> {
@@ -2,6 +2,7 @@ pub(crate) mod index;
 pub(crate) mod items;
 mod scopes;
 
+use crate::no_std::path::PathBuf;
 use crate::no_std::prelude::*;
 
 use crate as rune;
@@ -54,6 +55,8 @@ pub(crate) enum Indexed {
     Import(Import),
     /// An indexed module.
     Module,
+    /// A trait.
+    Trait(Trait),
 }
 
 /// The ast of a function.
@@ -109,6 +112,14 @@ pub(crate) struct Struct {
     pub(crate) ast: Box<ast::ItemStruct>,
 }
 
+#[derive(Debug, Clone)]
+pub(crate) struct Trait {
+    /// The root the trait was indexed from.
+    pub(crate) root: Option<PathBuf>,
+    /// The ast of the trait.
+    pub(crate) ast: Box<ast::ItemTrait>,
+}
+
 #[derive(Debug, Clone)]
 pub(crate) struct Variant {
     /// Id of of the enum type.
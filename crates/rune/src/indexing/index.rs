@@ -2,7 +2,7 @@ use core::num::NonZeroUsize;
 
 use core::mem::{replace, take};
 
-use crate::no_std::collections::{HashMap, VecDeque};
+use crate::no_std::collections::{HashMap, HashSet, VecDeque};
 use crate::no_std::path::PathBuf;
 use crate::no_std::prelude::*;
 
@@ -10,7 +10,9 @@ use crate::ast::spanned;
 use crate::ast::{self, OptionSpanned, Span, Spanned};
 use crate::compile::attrs;
 use crate::compile::meta;
-use crate::compile::{self, Doc, DynLocation, ErrorKind, Location, ModId, Visibility, WithSpan};
+use crate::compile::{
+    self, DeclarationKind, Doc, DynLocation, ErrorKind, Location, ModId, Visibility, WithSpan,
+};
 use crate::indexing::{self, Indexed, Items, Layer, Scopes};
 use crate::macros::MacroCompiler;
 use crate::parse::{NonZeroId, Parse, Parser, Resolve};
@@ -92,6 +94,22 @@ impl<'a, 'arena> Indexer<'a, 'arena> {
         self.macro_depth = self.macro_depth.wrapping_sub(1);
     }
 
+    /// Report a declaration to the compile visitor.
+    fn visit_declaration(&mut self, kind: DeclarationKind, span: Span, name_span: Span) {
+        let name = self
+            .q
+            .sources
+            .source(self.source_id, name_span)
+            .unwrap_or_default();
+
+        self.q.visitor.visit_declaration(
+            &DynLocation::new(self.source_id, &span),
+            kind,
+            name,
+            &name_span,
+        );
+    }
+
     /// Try to expand an internal macro.
     fn try_expand_internal_macro(
         &mut self,
@@ -729,12 +747,39 @@ pub(crate) fn item_fn_immediate(
 
     idx.scopes.push();
 
+    let mut has_default = false;
+
     for (arg, _) in &mut ast.args {
-        if let ast::FnArg::Pat(p) = arg {
-            pat(idx, p)?;
+        if has_default && !matches!(arg, ast::FnArg::Default(..)) {
+            return Err(compile::Error::new(&*arg, ErrorKind::ArgumentDefaultOrder));
+        }
+
+        match arg {
+            ast::FnArg::SelfValue(..) => {}
+            ast::FnArg::Pat(p) => {
+                pat(idx, p)?;
+            }
+            ast::FnArg::Typed(arg) => {
+                pat(idx, &mut arg.pat)?;
+                ty(idx, &mut arg.ty)?;
+            }
+            ast::FnArg::Default(arg) => {
+                pat(idx, &mut arg.pat)?;
+
+                if let Some((_, t)) = &mut arg.ty {
+                    ty(idx, t)?;
+                }
+
+                expr(idx, &mut arg.value)?;
+                has_default = true;
+            }
         }
     }
 
+    if let Some((_, output)) = &mut ast.output {
+        ty(idx, output)?;
+    }
+
     // Take and restore item nesting.
     let last = idx.nested_item.replace(ast.descriptive_span());
     block(idx, &mut ast.body)?;
@@ -858,6 +903,8 @@ pub(crate) fn item_fn_immediate(
 
 #[instrument(span = ast)]
 fn item_fn(idx: &mut Indexer<'_, '_>, ast: ast::ItemFn) -> compile::Result<()> {
+    idx.visit_declaration(DeclarationKind::Function, ast.span(), ast.name.span());
+
     if let Some(impl_item) = idx.item.impl_item {
         idx.q
             .inner
@@ -1008,6 +1055,10 @@ fn statements(idx: &mut Indexer<'_, '_>, ast: &mut Vec<ast::Stmt>) -> compile::R
 
 #[instrument(span = ast)]
 fn block(idx: &mut Indexer<'_, '_>, ast: &mut ast::Block) -> compile::Result<()> {
+    idx.q
+        .visitor
+        .visit_block(&DynLocation::new(idx.source_id, &ast));
+
     let guard = idx.items.push_id();
     let idx_item = idx.item.replace();
 
@@ -1042,6 +1093,11 @@ fn local(idx: &mut Indexer<'_, '_>, ast: &mut ast::Local) -> compile::Result<()>
     // declaration and use that instead of capturing from the outside.
     expr(idx, &mut ast.expr)?;
     pat(idx, &mut ast.pat)?;
+
+    if let Some((_, t)) = &mut ast.ty {
+        ty(idx, t)?;
+    }
+
     Ok(())
 }
 
@@ -1074,8 +1130,12 @@ fn pat(idx: &mut Indexer<'_, '_>, ast: &mut ast::Pat) -> compile::Result<()> {
         ast::Pat::Binding(pat) => {
             pat_binding(idx, pat)?;
         }
+        ast::Pat::Or(pat) => {
+            pat_or(idx, pat)?;
+        }
         ast::Pat::Ignore(..) => (),
         ast::Pat::Lit(..) => (),
+        ast::Pat::Range(..) => (),
         ast::Pat::Rest(..) => (),
     }
 
@@ -1132,6 +1192,15 @@ fn pat_binding(idx: &mut Indexer<'_, '_>, ast: &mut ast::PatBinding) -> compile:
     Ok(())
 }
 
+#[instrument(span = ast)]
+fn pat_or(idx: &mut Indexer<'_, '_>, ast: &mut ast::PatOr) -> compile::Result<()> {
+    for p in ast.alternatives_mut() {
+        pat(idx, p)?;
+    }
+
+    Ok(())
+}
+
 #[instrument(span = ast)]
 pub(crate) fn expr(idx: &mut Indexer<'_, '_>, ast: &mut ast::Expr) -> compile::Result<()> {
     match ast {
@@ -1341,6 +1410,8 @@ fn item_enum(idx: &mut Indexer<'_, '_>, mut ast: ast::ItemEnum) -> compile::Resu
         ));
     }
 
+    idx.visit_declaration(DeclarationKind::Enum, ast.span(), ast.name.span());
+
     let name = ast.name.resolve(resolve_context!(idx.q))?;
     let guard = idx.items.push_name(name.as_ref());
     let idx_item = idx.item.replace();
@@ -1368,6 +1439,12 @@ fn item_enum(idx: &mut Indexer<'_, '_>, mut ast: ast::ItemEnum) -> compile::Resu
             ));
         }
 
+        idx.visit_declaration(
+            DeclarationKind::Variant,
+            variant.span(),
+            variant.name.span(),
+        );
+
         let name = variant.name.resolve(resolve_context!(idx.q))?;
         let guard = idx.items.push_name(name.as_ref());
         let idx_item = idx.item.replace();
@@ -1408,6 +1485,12 @@ fn item_enum(idx: &mut Indexer<'_, '_>, mut ast: ast::ItemEnum) -> compile::Resu
             }
         }
 
+        for (field, _) in variant.body.fields_mut() {
+            if let Some((_, t)) = &mut field.ty {
+                ty(idx, t)?;
+            }
+        }
+
         idx.item = idx_item;
         idx.items.pop(guard).with_span(&variant)?;
         idx.q
@@ -1432,6 +1515,8 @@ fn item_struct(idx: &mut Indexer<'_, '_>, mut ast: ast::ItemStruct) -> compile::
         ));
     }
 
+    idx.visit_declaration(DeclarationKind::Struct, ast.span(), ast.ident.span());
+
     let ident = ast.ident.resolve(resolve_context!(idx.q))?;
     let guard = idx.items.push_name(ident);
     let idx_item = idx.item.replace();
@@ -1479,6 +1564,12 @@ fn item_struct(idx: &mut Indexer<'_, '_>, mut ast: ast::ItemStruct) -> compile::
         }
     }
 
+    for (field, _) in ast.body.fields_mut() {
+        if let Some((_, t)) = &mut field.ty {
+            ty(idx, t)?;
+        }
+    }
+
     idx.item = idx_item;
     idx.items.pop(guard).with_span(&ast)?;
     idx.q.index_struct(item_meta, Box::new(ast))?;
@@ -1494,13 +1585,24 @@ fn item_impl(idx: &mut Indexer<'_, '_>, mut ast: ast::ItemImpl) -> compile::Resu
         ));
     }
 
+    idx.visit_declaration(DeclarationKind::Impl, ast.span(), ast.path.span());
+
     path(idx, &mut ast.path)?;
 
+    let trait_path = match ast.trait_path {
+        Some((mut trait_path, _)) => {
+            path(idx, &mut trait_path)?;
+            Some(Box::new(trait_path))
+        }
+        None => None,
+    };
+
     let location = Location::new(idx.source_id, ast.path.span());
     let id = idx.q.gen.next();
 
     idx.q.inner.impl_item_queue.push_back(ItemImplEntry {
         path: Box::new(ast.path),
+        trait_path,
         location,
         id,
         root: idx.root.clone(),
@@ -1518,6 +1620,77 @@ fn item_impl(idx: &mut Indexer<'_, '_>, mut ast: ast::ItemImpl) -> compile::Resu
     Ok(())
 }
 
+#[instrument(span = ast)]
+fn item_trait(idx: &mut Indexer<'_, '_>, ast: ast::ItemTrait) -> compile::Result<()> {
+    let mut p = attrs::Parser::new(&ast.attributes);
+
+    let docs = Doc::collect_from(resolve_context!(idx.q), &mut p, &ast.attributes)?;
+
+    if let Some(first) = p.remaining(&ast.attributes).next() {
+        return Err(compile::Error::msg(
+            first,
+            "Attributes on traits are not supported",
+        ));
+    }
+
+    idx.visit_declaration(DeclarationKind::Trait, ast.span(), ast.ident.span());
+
+    // NB: the set of names borrows from the context, so it has to be dropped
+    // before the item is inserted.
+    {
+        let cx = resolve_context!(idx.q);
+        let mut names = HashSet::new();
+
+        for item in &ast.items {
+            let mut p = attrs::Parser::new(item_attributes(item));
+            Doc::collect_from(cx, &mut p, item_attributes(item))?;
+
+            if let Some(first) = p.remaining(item_attributes(item)).next() {
+                return Err(compile::Error::msg(
+                    first,
+                    "Attributes on trait functions are not supported",
+                ));
+            }
+
+            let name = item.name().resolve(cx)?;
+
+            if !names.insert(name) {
+                return Err(compile::Error::new(
+                    item.name(),
+                    ErrorKind::TraitFunctionConflict { name: name.into() },
+                ));
+            }
+        }
+    }
+
+    let ident = ast.ident.resolve(resolve_context!(idx.q))?;
+    let guard = idx.items.push_name(ident);
+    let idx_item = idx.item.replace();
+
+    let visibility = ast_to_visibility(&ast.visibility)?;
+    let item_meta = idx.q.insert_new_item(
+        &idx.items,
+        &DynLocation::new(idx.source_id, &ast),
+        idx.item.module,
+        visibility,
+        &docs,
+    )?;
+
+    idx.item = idx_item;
+    idx.items.pop(guard).with_span(&ast)?;
+    idx.q
+        .index_trait(item_meta, idx.root.clone(), Box::new(ast))?;
+    Ok(())
+}
+
+/// Get the attributes of a function in a trait.
+fn item_attributes(item: &ast::TraitItem) -> &[ast::Attribute] {
+    match item {
+        ast::TraitItem::Required(f) => &f.attributes,
+        ast::TraitItem::Provided(f) => &f.attributes,
+    }
+}
+
 #[instrument(span = ast)]
 fn item_mod(idx: &mut Indexer<'_, '_>, mut ast: ast::ItemMod) -> compile::Result<()> {
     let mut p = attrs::Parser::new(&ast.attributes);
@@ -1531,6 +1704,8 @@ fn item_mod(idx: &mut Indexer<'_, '_>, mut ast: ast::ItemMod) -> compile::Result
         ));
     }
 
+    idx.visit_declaration(DeclarationKind::Module, ast.span(), ast.name.span());
+
     let name_span = ast.name_span();
 
     match &mut ast.body {
@@ -1581,6 +1756,8 @@ fn item_const(idx: &mut Indexer<'_, '_>, mut ast: ast::ItemConst) -> compile::Re
         ));
     }
 
+    idx.visit_declaration(DeclarationKind::Const, ast.span(), ast.name.span());
+
     let name = ast.name.resolve(resolve_context!(idx.q))?;
     let guard = idx.items.push_name(name.as_ref());
     let idx_item = idx.item.replace();
@@ -1620,6 +1797,9 @@ fn item(idx: &mut Indexer<'_, '_>, ast: ast::Item) -> compile::Result<()> {
         ast::Item::Impl(item) => {
             item_impl(idx, item)?;
         }
+        ast::Item::Trait(item) => {
+            item_trait(idx, item)?;
+        }
         ast::Item::Mod(item) => {
             item_mod(idx, item)?;
         }
@@ -1694,6 +1874,22 @@ fn path(idx: &mut Indexer<'_, '_>, ast: &mut ast::Path) -> compile::Result<()> {
     Ok(())
 }
 
+#[instrument(span = ast)]
+fn ty(idx: &mut Indexer<'_, '_>, ast: &mut ast::Type) -> compile::Result<()> {
+    match ast {
+        ast::Type::Path(p) => {
+            path(idx, p)?;
+        }
+        ast::Type::Tuple(items) => {
+            for (item, _) in items {
+                ty(idx, item)?;
+            }
+        }
+    }
+
+    Ok(())
+}
+
 #[instrument(span = ast)]
 fn path_segment(idx: &mut Indexer<'_, '_>, ast: &mut ast::PathSegment) -> compile::Result<()> {
     if let ast::PathSegment::Generics(generics) = ast {
@@ -1751,6 +1947,16 @@ fn expr_closure(idx: &mut Indexer<'_, '_>, ast: &mut ast::ExprClosure) -> compil
             ast::FnArg::Pat(p) => {
                 pat(idx, p)?;
             }
+            ast::FnArg::Typed(arg) => {
+                pat(idx, &mut arg.pat)?;
+                ty(idx, &mut arg.ty)?;
+            }
+            ast::FnArg::Default(arg) => {
+                return Err(compile::Error::new(
+                    &*arg,
+                    ErrorKind::UnsupportedArgumentDefault,
+                ));
+            }
         }
     }
 
@@ -1886,8 +2092,8 @@ fn expr_select(idx: &mut Indexer<'_, '_>, ast: &mut ast::ExprSelect) -> compile:
 fn expr_call(idx: &mut Indexer<'_, '_>, ast: &mut ast::ExprCall) -> compile::Result<()> {
     ast.id.set(idx.items.id().with_span(&*ast)?);
 
-    for (e, _) in &mut ast.args {
-        expr(idx, e)?;
+    for (arg, _) in &mut ast.args {
+        expr(idx, &mut arg.expr)?;
     }
 
     expr(idx, &mut ast.expr)?;
@@ -2,11 +2,16 @@
 
 #![allow(clippy::too_many_arguments)]
 
+mod code_action;
 mod completion;
 mod connection;
 pub mod envelope;
 mod fs;
+mod hover;
+mod semantic_tokens;
+mod signature;
 mod state;
+mod symbols;
 mod url;
 
 use crate::no_std::prelude::*;
@@ -101,6 +106,18 @@ pub async fn run(context: Context, options: Options) -> Result<()> {
                     req(lsp::request::Shutdown, shutdown),
                     req(lsp::request::GotoDefinition, goto_definition),
                     req(lsp::request::Completion, completion),
+                    req(lsp::request::HoverRequest, hover),
+                    req(lsp::request::References, references),
+                    req(lsp::request::PrepareRenameRequest, prepare_rename),
+                    req(lsp::request::Rename, rename),
+                    req(lsp::request::DocumentSymbolRequest, document_symbol),
+                    req(lsp::request::WorkspaceSymbolRequest, workspace_symbol),
+                    req(lsp::request::FoldingRangeRequest, folding_range),
+                    req(lsp::request::SemanticTokensFullRequest, semantic_tokens_full),
+                    req(lsp::request::SemanticTokensRangeRequest, semantic_tokens_range),
+                    req(lsp::request::SignatureHelpRequest, signature_help),
+                    req(lsp::request::CodeActionRequest, code_action),
+                    req(lsp::request::InlayHintRequest, inlay_hint),
                     req(lsp::request::Formatting, formatting),
                     notif(lsp::notification::DidOpenTextDocument, did_open_text_document),
                     notif(lsp::notification::DidChangeTextDocument, did_change_text_document),
@@ -143,6 +160,45 @@ async fn initialize(
             }),
         }),
         document_formatting_provider: Some(lsp::OneOf::Left(true)),
+        hover_provider: Some(lsp::HoverProviderCapability::Simple(true)),
+        references_provider: Some(lsp::OneOf::Left(true)),
+        rename_provider: Some(lsp::OneOf::Right(lsp::RenameOptions {
+            prepare_provider: Some(true),
+            work_done_progress_options: lsp::WorkDoneProgressOptions {
+                work_done_progress: None,
+            },
+        })),
+        document_symbol_provider: Some(lsp::OneOf::Left(true)),
+        workspace_symbol_provider: Some(lsp::OneOf::Left(true)),
+        folding_range_provider: Some(lsp::FoldingRangeProviderCapability::Simple(true)),
+        signature_help_provider: Some(lsp::SignatureHelpOptions {
+            trigger_characters: Some(vec!["(".into(), ",".into()]),
+            retrigger_characters: None,
+            work_done_progress_options: lsp::WorkDoneProgressOptions {
+                work_done_progress: None,
+            },
+        }),
+        inlay_hint_provider: Some(lsp::OneOf::Left(true)),
+        code_action_provider: Some(lsp::CodeActionProviderCapability::Options(
+            lsp::CodeActionOptions {
+                code_action_kinds: Some(vec![lsp::CodeActionKind::QUICKFIX]),
+                work_done_progress_options: lsp::WorkDoneProgressOptions {
+                    work_done_progress: None,
+                },
+                resolve_provider: None,
+            },
+        )),
+        semantic_tokens_provider: Some(
+            lsp::SemanticTokensOptions {
+                work_done_progress_options: lsp::WorkDoneProgressOptions {
+                    work_done_progress: None,
+                },
+                legend: semantic_tokens::legend(),
+                range: Some(true),
+                full: Some(lsp::SemanticTokensFullOptions::Bool(true)),
+            }
+            .into(),
+        ),
         ..Default::default()
     };
 
@@ -219,6 +275,121 @@ async fn completion(
     Ok(results)
 }
 
+/// Handle hover request.
+async fn hover(state: &mut State<'_>, params: lsp::HoverParams) -> Result<Option<lsp::Hover>> {
+    Ok(state.hover(
+        &params.text_document_position_params.text_document.uri,
+        params.text_document_position_params.position,
+    ))
+}
+
+/// Handle references request.
+async fn references(
+    state: &mut State<'_>,
+    params: lsp::ReferenceParams,
+) -> Result<Option<Vec<lsp::Location>>> {
+    Ok(state.references(
+        &params.text_document_position.text_document.uri,
+        params.text_document_position.position,
+        params.context.include_declaration,
+    ))
+}
+
+/// Handle prepare rename request.
+async fn prepare_rename(
+    state: &mut State<'_>,
+    params: lsp::TextDocumentPositionParams,
+) -> Result<Option<lsp::PrepareRenameResponse>> {
+    Ok(state.prepare_rename(&params.text_document.uri, params.position))
+}
+
+/// Handle rename request.
+async fn rename(
+    state: &mut State<'_>,
+    params: lsp::RenameParams,
+) -> Result<Option<lsp::WorkspaceEdit>> {
+    Ok(state.rename(
+        &params.text_document_position.text_document.uri,
+        params.text_document_position.position,
+        &params.new_name,
+    ))
+}
+
+/// Handle document symbol request.
+async fn document_symbol(
+    state: &mut State<'_>,
+    params: lsp::DocumentSymbolParams,
+) -> Result<Option<lsp::DocumentSymbolResponse>> {
+    let symbols = state.document_symbols(&params.text_document.uri);
+    Ok(symbols.map(lsp::DocumentSymbolResponse::Nested))
+}
+
+/// Handle workspace symbol request.
+async fn workspace_symbol(
+    state: &mut State<'_>,
+    params: lsp::WorkspaceSymbolParams,
+) -> Result<Option<lsp::WorkspaceSymbolResponse>> {
+    let symbols = state.workspace_symbols(&params.query);
+    Ok(Some(lsp::WorkspaceSymbolResponse::Flat(symbols)))
+}
+
+/// Handle folding range request.
+async fn folding_range(
+    state: &mut State<'_>,
+    params: lsp::FoldingRangeParams,
+) -> Result<Option<Vec<lsp::FoldingRange>>> {
+    Ok(state.folding_ranges(&params.text_document.uri))
+}
+
+/// Handle semantic tokens request for a full document.
+async fn semantic_tokens_full(
+    state: &mut State<'_>,
+    params: lsp::SemanticTokensParams,
+) -> Result<Option<lsp::SemanticTokensResult>> {
+    let tokens = state.semantic_tokens(&params.text_document.uri, None);
+    Ok(tokens.map(lsp::SemanticTokensResult::Tokens))
+}
+
+/// Handle semantic tokens request for a range of a document.
+async fn semantic_tokens_range(
+    state: &mut State<'_>,
+    params: lsp::SemanticTokensRangeParams,
+) -> Result<Option<lsp::SemanticTokensRangeResult>> {
+    let tokens = state.semantic_tokens(&params.text_document.uri, Some(params.range));
+    Ok(tokens.map(lsp::SemanticTokensRangeResult::Tokens))
+}
+
+/// Handle code action request.
+async fn code_action(
+    state: &mut State<'_>,
+    params: lsp::CodeActionParams,
+) -> Result<Option<lsp::CodeActionResponse>> {
+    Ok(state.code_actions(
+        &params.text_document.uri,
+        params.range,
+        &params.context.diagnostics,
+    ))
+}
+
+/// Handle signature help request.
+async fn signature_help(
+    state: &mut State<'_>,
+    params: lsp::SignatureHelpParams,
+) -> Result<Option<lsp::SignatureHelp>> {
+    Ok(state.signature_help(
+        &params.text_document_position_params.text_document.uri,
+        params.text_document_position_params.position,
+    ))
+}
+
+/// Handle inlay hint request.
+async fn inlay_hint(
+    state: &mut State<'_>,
+    params: lsp::InlayHintParams,
+) -> Result<Option<Vec<lsp::InlayHint>>> {
+    Ok(state.inlay_hints(&params.text_document.uri, params.range))
+}
+
 /// Handle formatting request.
 async fn formatting(
     state: &mut State<'_>,
@@ -0,0 +1,434 @@
+use std::collections::BTreeSet;
+
+use crate::no_std::collections::HashMap;
+use crate::no_std::prelude::*;
+
+use crate::ast::{self, Span, Spanned};
+use crate::compile::{meta, ComponentRef, ErrorKind, Item};
+use crate::diagnostics::{Diagnostic, FatalDiagnosticKind, WarningDiagnosticKind};
+use crate::parse::Lexer;
+use crate::{Context, Diagnostics, SourceId, Sources};
+
+use super::state::is_identifier;
+
+/// A fix for a diagnostic which can be offered as a code action.
+pub(super) struct Fix {
+    /// The title of the fix.
+    pub(super) title: String,
+    /// The span of the diagnostic being fixed.
+    pub(super) span: Span,
+    /// Edits which make up the fix, replacing the given span with the given
+    /// text.
+    pub(super) edits: Vec<(Span, String)>,
+}
+
+/// Collect fixes for the given diagnostics, grouped by the source they apply
+/// to.
+pub(super) fn fixes(
+    context: &Context,
+    sources: &Sources,
+    diagnostics: &Diagnostics,
+) -> HashMap<SourceId, Vec<Fix>> {
+    let mut fixes = HashMap::<SourceId, Vec<Fix>>::new();
+
+    for diagnostic in diagnostics.diagnostics() {
+        let (source_id, found) = match diagnostic {
+            Diagnostic::Warning(warning) => {
+                let Some(source) = sources.get(warning.source_id()) else {
+                    continue;
+                };
+
+                let fix = for_warning(source.as_str(), warning.kind());
+                (warning.source_id(), fix.into_iter().collect::<Vec<_>>())
+            }
+            Diagnostic::Fatal(fatal) => {
+                let FatalDiagnosticKind::CompileError(error) = fatal.kind() else {
+                    continue;
+                };
+
+                let (ErrorKind::MissingItem { .. } | ErrorKind::MissingItemParameters { .. }) =
+                    error.kind()
+                else {
+                    continue;
+                };
+
+                let Some(source) = sources.get(fatal.source_id()) else {
+                    continue;
+                };
+
+                let found = add_use(context, source.as_str(), fatal.source_id(), error.span());
+                (fatal.source_id(), found)
+            }
+        };
+
+        fixes.entry(source_id).or_default().extend(found);
+    }
+
+    fixes
+}
+
+/// Construct a fix for the given warning.
+fn for_warning(text: &str, kind: &WarningDiagnosticKind) -> Option<Fix> {
+    let fix = match *kind {
+        WarningDiagnosticKind::UnnecessarySemiColon { span } => Fix {
+            title: String::from("Remove unnecessary semicolon"),
+            span,
+            edits: vec![(span, String::new())],
+        },
+        WarningDiagnosticKind::RemoveTupleCallParams { span, variant, .. } => Fix {
+            title: format!("Rewrite to `{}`", text.get(variant.range())?),
+            span,
+            edits: vec![(span, String::new())],
+        },
+        WarningDiagnosticKind::TemplateWithoutExpansions { span, .. } => Fix {
+            title: String::from("Convert to a string literal"),
+            span,
+            edits: vec![(span, template_to_string(text.get(span.range())?)?)],
+        },
+        WarningDiagnosticKind::NotUsed { span, .. } => {
+            let (name, offset) = function_name(text.get(span.range())?)?;
+
+            if name.starts_with('_') {
+                return None;
+            }
+
+            let at = Span::point(span.start.into_usize() + offset);
+
+            Fix {
+                title: format!("Rename to `_{name}`"),
+                span,
+                edits: vec![(at, String::from("_"))],
+            }
+        }
+        WarningDiagnosticKind::LetPatternMightPanic { span, .. } => {
+            let pattern = text.get(span.range())?.trim_end_matches(';').trim_end();
+
+            // Make sure to replace the semicolon terminating the let, in case
+            // it's not part of the span.
+            let replaced = match text.get(span.end.into_usize()..) {
+                Some(rest) if !pattern.ends_with(';') && rest.starts_with(';') => {
+                    Span::new(span.start, span.end.into_usize() + 1)
+                }
+                _ => span,
+            };
+
+            let indent = indentation(text, span.start.into_usize());
+
+            Fix {
+                title: String::from("Rewrite to `if let`"),
+                span,
+                edits: vec![(
+                    replaced,
+                    format!("if {pattern} {{\n{indent}    // ..\n{indent}}}"),
+                )],
+            }
+        }
+    };
+
+    Some(fix)
+}
+
+/// Construct fixes which import an item that is missing in the given span.
+fn add_use(context: &Context, text: &str, source_id: SourceId, span: Span) -> Vec<Fix> {
+    let Some(path) = text.get(span.range()) else {
+        return Vec::new();
+    };
+
+    let segments = path.split("::").map(str::trim).collect::<Vec<_>>();
+
+    let Some((&first, rest)) = segments.split_first() else {
+        return Vec::new();
+    };
+
+    if matches!(first, "crate" | "super" | "self") || !is_identifier(first) {
+        return Vec::new();
+    }
+
+    let mut candidates = BTreeSet::new();
+
+    for meta in context.iter_meta() {
+        let Some(item) = &meta.item else {
+            continue;
+        };
+
+        if item.last() != Some(ComponentRef::Str(first))
+            || item.parent().map_or(true, Item::is_empty)
+        {
+            continue;
+        }
+
+        let importable = match &meta.kind {
+            meta::Kind::Function { associated, .. } => associated.is_none(),
+            meta::Kind::Struct { .. }
+            | meta::Kind::Enum { .. }
+            | meta::Kind::Type { .. }
+            | meta::Kind::Module
+            | meta::Kind::Const => true,
+            _ => false,
+        };
+
+        if !importable {
+            continue;
+        }
+
+        // The rest of the path has to be available under the candidate.
+        let mut full = item.clone();
+
+        for segment in rest {
+            full.push(*segment);
+        }
+
+        if rest.is_empty() || context.contains_prefix(&full) {
+            candidates.insert(item.to_string());
+        }
+    }
+
+    let insert = use_insertion(text, source_id);
+
+    candidates
+        .into_iter()
+        .map(|item| {
+            let item = item.trim_start_matches("::");
+
+            let (at, edit) = match insert {
+                Insertion::After(at) => (at, format!("\nuse {item};")),
+                Insertion::Before(at) => (at, format!("use {item};\n\n")),
+            };
+
+            Fix {
+                title: format!("Import `{item}`"),
+                span,
+                edits: vec![(Span::point(at), edit)],
+            }
+        })
+        .collect()
+}
+
+/// Where to insert a new `use` declaration.
+#[derive(Clone, Copy)]
+enum Insertion {
+    /// After an existing `use` declaration ending at the given offset.
+    After(usize),
+    /// Before the first line of code at the given offset.
+    Before(usize),
+}
+
+/// Find where to insert a new `use` declaration, which is after the last
+/// top-level `use` or after any leading comments.
+fn use_insertion(text: &str, source_id: SourceId) -> Insertion {
+    let mut lexer = Lexer::new(text, source_id, true);
+    let mut depth = 0usize;
+    let mut in_use = false;
+    let mut last_use = None;
+    let mut leading = 0;
+    let mut in_leading = true;
+
+    while let Ok(Some(token)) = lexer.next() {
+        match token.kind {
+            ast::Kind::Whitespace => continue,
+            ast::Kind::Open(..) => depth += 1,
+            ast::Kind::Close(..) => depth = depth.saturating_sub(1),
+            K![use] if depth == 0 => in_use = true,
+            K![;] if depth == 0 && in_use => {
+                in_use = false;
+                last_use = Some(token.span.end.into_usize());
+            }
+            _ => {}
+        }
+
+        // Comments, including doc comments which are lexed into attributes,
+        // are kept before any imports.
+        if in_leading {
+            let token_text = text.get(token.span.range()).unwrap_or_default();
+
+            if ["//", "/*", "#!"].iter().any(|p| token_text.starts_with(p)) {
+                let end = token.span.end.into_usize();
+
+                // Line comments might include the line ending.
+                leading = if text[..end].ends_with('\n') {
+                    end
+                } else {
+                    match text[end..].find('\n') {
+                        Some(n) => end + n + 1,
+                        None => text.len(),
+                    }
+                };
+            } else if token.span.start.into_usize() >= leading {
+                in_leading = false;
+            }
+        }
+    }
+
+    match last_use {
+        Some(offset) => Insertion::After(offset),
+        None => Insertion::Before(leading),
+    }
+}
+
+/// Convert the source of a template without expansions into a string literal.
+fn template_to_string(template: &str) -> Option<String> {
+    let inner = template.strip_prefix('`')?.strip_suffix('`')?;
+
+    let mut string = String::from("\"");
+    let mut it = inner.chars();
+
+    while let Some(c) = it.next() {
+        match c {
+            '\\' => match it.next()? {
+                // Escapes which are only valid in templates.
+                c @ ('`' | '$') => string.push(c),
+                c => {
+                    string.push('\\');
+                    string.push(c);
+                }
+            },
+            '"' => string.push_str("\\\""),
+            c => string.push(c),
+        }
+    }
+
+    string.push('"');
+    Some(string)
+}
+
+/// Find the name of the function declared in the given source, and its offset.
+fn function_name(text: &str) -> Option<(&str, usize)> {
+    let mut lexer = Lexer::new(text, SourceId::empty(), true);
+    let mut is_fn = false;
+
+    while let Ok(Some(token)) = lexer.next() {
+        match token.kind {
+            K![fn] => is_fn = true,
+            ast::Kind::Ident(..) if is_fn => {
+                let name = text.get(token.span.range())?;
+                return Some((name, token.span.start.into_usize()));
+            }
+            ast::Kind::Whitespace => {}
+            _ if is_fn => return None,
+            _ => {}
+        }
+    }
+
+    None
+}
+
+/// The indentation of the line containing the given offset.
+fn indentation(text: &str, offset: usize) -> &str {
+    let start = text[..offset]
+        .rfind('\n')
+        .map(|n| n + 1)
+        .unwrap_or_default();
+    let line = &text[start..offset];
+    let end = line.len() - line.trim_start().len();
+    &line[..end]
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::no_std::prelude::*;
+
+    use crate::{Context, Diagnostics, Source, Sources};
+
+    use super::fixes;
+
+    /// Build the given source and apply every fix offered for it, returning
+    /// the title of each fix along with the fixed source.
+    fn apply(text: &str) -> Vec<(String, String)> {
+        let context = Context::with_default_modules().unwrap();
+        let mut sources = Sources::new();
+        let source_id = sources.insert(Source::memory(text));
+        let mut diagnostics = Diagnostics::new();
+
+        let _ = crate::prepare(&mut sources)
+            .with_context(&context)
+            .with_diagnostics(&mut diagnostics)
+            .build();
+
+        let mut fixes = fixes(&context, &sources, &diagnostics);
+
+        let mut output = fixes
+            .remove(&source_id)
+            .unwrap_or_default()
+            .into_iter()
+            .map(|fix| {
+                let mut edits = fix.edits;
+                edits.sort_by_key(|(span, _)| span.start);
+
+                let mut fixed = text.to_owned();
+
+                for (span, edit) in edits.into_iter().rev() {
+                    fixed.replace_range(span.range(), &edit);
+                }
+
+                (fix.title, fixed)
+            })
+            .collect::<Vec<_>>();
+
+        output.sort();
+        output
+    }
+
+    fn fix(title: &str, text: &str) -> (String, String) {
+        (title.to_owned(), text.to_owned())
+    }
+
+    #[test]
+    fn test_warning_fixes() {
+        let text = r#"fn unused() {};
+
+pub fn main() {
+    let [a] = [1];
+    `Hello "World"`
+}
+"#;
+
+        assert_eq!(
+            apply(text),
+            [
+                fix(
+                    "Convert to a string literal",
+                    "fn unused() {};\n\npub fn main() {\n    let [a] = [1];\n    \"Hello \\\"World\\\"\"\n}\n"
+                ),
+                fix(
+                    "Remove unnecessary semicolon",
+                    "fn unused() {}\n\npub fn main() {\n    let [a] = [1];\n    `Hello \"World\"`\n}\n"
+                ),
+                fix(
+                    "Rename to `_unused`",
+                    "fn _unused() {};\n\npub fn main() {\n    let [a] = [1];\n    `Hello \"World\"`\n}\n"
+                ),
+                fix(
+                    "Rewrite to `if let`",
+                    "fn unused() {};\n\npub fn main() {\n    if let [a] = [1] {\n        // ..\n    }\n    `Hello \"World\"`\n}\n"
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_remove_tuple_call_params() {
+        assert_eq!(
+            apply("pub fn main() { None() }"),
+            [fix("Rewrite to `None`", "pub fn main() { None }")]
+        );
+    }
+
+    #[test]
+    fn test_add_use() {
+        assert_eq!(
+            apply("// A comment.\npub fn main() { HashMap::new() }"),
+            [fix(
+                "Import `std::collections::HashMap`",
+                "// A comment.\nuse std::collections::HashMap;\n\npub fn main() { HashMap::new() }"
+            )]
+        );
+
+        assert_eq!(
+            apply("use std::iter;\n\npub fn main() { HashMap::new() }"),
+            [fix(
+                "Import `std::collections::HashMap`",
+                "use std::iter;\nuse std::collections::HashMap;\n\npub fn main() { HashMap::new() }"
+            )]
+        );
+    }
+}
@@ -0,0 +1,233 @@
+use core::fmt::Write;
+
+use crate::no_std::prelude::*;
+
+use crate::compile::meta;
+use crate::compile::Item;
+use crate::runtime::debug::DebugArgs;
+use crate::{Context, Hash, Unit};
+
+use super::state::{DefinitionKind, Source};
+
+/// Build hover contents for a local variable with the given name.
+pub(super) fn for_local(name: &str) -> String {
+    code_block(&format!("let {name}"))
+}
+
+/// Build hover contents for an item defined in the unit being edited.
+pub(super) fn for_unit(
+    source: &Source,
+    unit: Option<&Unit>,
+    kind: DefinitionKind,
+    item: &Item,
+    hash: Hash,
+) -> String {
+    let name = name_of(item);
+
+    let signature = match kind {
+        DefinitionKind::Function | DefinitionKind::AssociatedFunction => {
+            let args = unit
+                .and_then(|unit| unit.debug_info())
+                .and_then(|debug_info| debug_info.functions.get(&hash))
+                .map(|function| match &function.args {
+                    DebugArgs::EmptyArgs => String::new(),
+                    DebugArgs::TupleArgs(n) => (0..*n)
+                        .map(|n| format!("_{n}"))
+                        .collect::<Vec<_>>()
+                        .join(", "),
+                    DebugArgs::Named(names) => names.join(", "),
+                })
+                .unwrap_or_default();
+
+            format!("fn {name}({args})")
+        }
+        DefinitionKind::EmptyStruct | DefinitionKind::TupleStruct | DefinitionKind::Struct => {
+            format!("struct {name}")
+        }
+        DefinitionKind::Enum => format!("enum {name}"),
+        _ => name,
+    };
+
+    let docs = source
+        .get_docs_by_hash(hash)
+        .map(|data| data.docs.join("\n"))
+        .unwrap_or_default();
+
+    render(item.parent(), &signature, None, &docs)
+}
+
+/// Build hover contents for an item provided by a native module installed in
+/// the context.
+pub(super) fn for_context(context: &Context, item: &Item, hash: Hash) -> Option<String> {
+    let meta = context.lookup_meta_by_hash(hash).next()?;
+    let name = name_of(item);
+
+    let (signature, deprecated) = match &meta.kind {
+        meta::Kind::Function { signature, .. } => {
+            let args = match (meta.docs.args(), signature.args) {
+                (Some(args), _) => args.join(", "),
+                (None, Some(n)) => (0..n)
+                    .map(|n| format!("_{n}"))
+                    .collect::<Vec<_>>()
+                    .join(", "),
+                (None, None) => String::from(".."),
+            };
+
+            let mut string = String::new();
+
+            if signature.is_async {
+                string.push_str("async ");
+            }
+
+            write!(string, "fn {name}({args})").ok()?;
+
+            let return_type = signature
+                .return_type
+                .and_then(|hash| context.lookup_meta_by_hash(hash).next())
+                .and_then(|meta| meta.item.as_deref());
+
+            if let Some(return_type) = return_type {
+                write!(string, " -> {return_type}").ok()?;
+            }
+
+            (string, signature.deprecated.as_deref())
+        }
+        _ => (name, None),
+    };
+
+    let docs = meta.docs.lines().join("\n");
+    Some(render(item.parent(), &signature, deprecated, &docs))
+}
+
+/// Render the hover contents as Markdown, consisting of the module the item
+/// is defined in, its signature and documentation.
+fn render(module: Option<&Item>, signature: &str, deprecated: Option<&str>, docs: &str) -> String {
+    let mut sections = Vec::new();
+
+    if let Some(module) = module.filter(|module| !module.is_empty()) {
+        sections.push(code_block(&module.to_string()));
+    }
+
+    sections.push(code_block(signature));
+
+    if let Some(deprecated) = deprecated {
+        sections.push(format!("**Deprecated:** {deprecated}"));
+    }
+
+    if !docs.trim().is_empty() {
+        sections.push(String::from("---"));
+        sections.push(docs.to_owned());
+    }
+
+    sections.join("\n\n")
+}
+
+/// The last component of an item, used as its name.
+fn name_of(item: &Item) -> String {
+    match item.last() {
+        Some(last) => last.to_string(),
+        None => item.to_string(),
+    }
+}
+
+/// Wrap the given code in a Rune code block.
+fn code_block(code: &str) -> String {
+    format!("```rune\n{code}\n```")
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::no_std::prelude::*;
+
+    use lsp::Url;
+
+    use crate::ast::Span;
+    use crate::compile::ItemBuf;
+    use crate::{Context, Hash, Module};
+
+    use super::super::state::{DefinitionKind, Source};
+    use super::{for_context, for_local, for_unit, render};
+
+    #[test]
+    fn test_for_local() {
+        assert_eq!(for_local("value"), "```rune\nlet value\n```");
+    }
+
+    #[test]
+    fn test_render() {
+        let module = ItemBuf::with_item(["app", "math"]);
+
+        assert_eq!(
+            render(Some(&module), "fn add(a, b)", Some("Use `+`"), "Add numbers."),
+            "```rune\napp::math\n```\n\n```rune\nfn add(a, b)\n```\n\n**Deprecated:** Use `+`\n\n---\n\nAdd numbers."
+        );
+
+        // The root module and blank documentation are left out.
+        assert_eq!(
+            render(Some(&ItemBuf::new()), "fn main()", None, " \n"),
+            "```rune\nfn main()\n```"
+        );
+    }
+
+    #[test]
+    fn test_for_unit() {
+        let context = Context::default();
+        let url = Url::parse("file:///test.rn").unwrap();
+
+        let text = r#"
+        mod math {
+            /// Add two numbers.
+            pub fn add(a, b) { a + b }
+        }
+
+        pub fn main() {
+            math::add(1, 2)
+        }
+        "#;
+
+        let source = Source::build(&context, &url, text);
+
+        let offset = text.rfind("add").unwrap();
+        let (span, definition) = source.find_definition_at(Span::point(offset)).unwrap();
+        assert_eq!(&text[span.range()], "math::add");
+        assert!(matches!(definition.kind, DefinitionKind::Function));
+
+        let item = definition.item.as_ref().unwrap();
+
+        assert_eq!(
+            for_unit(
+                &source,
+                source.unit(),
+                definition.kind,
+                &item.item,
+                item.hash
+            ),
+            "```rune\nmath\n```\n\n```rune\nfn add(a, b)\n```\n\n---\n\n Add two numbers."
+        );
+    }
+
+    #[test]
+    fn test_for_context() {
+        let mut module = Module::with_crate("app");
+
+        module
+            .function(["add"], |a: i64, b: i64| a + b)
+            .unwrap()
+            .docs(["Add two numbers."])
+            .deprecated("Use `+` instead")
+            .return_type::<i64>();
+
+        let mut context = Context::with_default_modules().unwrap();
+        context.install(module).unwrap();
+
+        let item = ItemBuf::with_crate_item("app", ["add"]);
+        let hash = Hash::type_hash(&item);
+
+        assert_eq!(
+            for_context(&context, &item, hash).unwrap(),
+            "```rune\n::app\n```\n\n```rune\nfn add(_0, _1) -> ::std::i64\n```\n\n**Deprecated:** Use `+` instead\n\n---\n\nAdd two numbers."
+        );
+
+        assert!(for_context(&context, &item, Hash::type_hash(["missing"])).is_none());
+    }
+}
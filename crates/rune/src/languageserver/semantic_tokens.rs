@@ -0,0 +1,471 @@
+use std::collections::HashMap;
+
+use crate::no_std::prelude::*;
+
+use lsp::Url;
+
+use crate::ast::{self, Span};
+use crate::compile::{meta, DeclarationKind};
+use crate::parse::Lexer;
+use crate::{Context, Hash};
+
+use super::state::{DefinitionKind, DefinitionSource, Source};
+
+/// Token types in the order they are declared in the legend.
+const TOKEN_TYPES: &[lsp::SemanticTokenType] = &[
+    lsp::SemanticTokenType::NAMESPACE,
+    lsp::SemanticTokenType::TYPE,
+    lsp::SemanticTokenType::STRUCT,
+    lsp::SemanticTokenType::ENUM,
+    lsp::SemanticTokenType::ENUM_MEMBER,
+    lsp::SemanticTokenType::INTERFACE,
+    lsp::SemanticTokenType::FUNCTION,
+    lsp::SemanticTokenType::METHOD,
+    lsp::SemanticTokenType::MACRO,
+    lsp::SemanticTokenType::VARIABLE,
+    lsp::SemanticTokenType::PARAMETER,
+    lsp::SemanticTokenType::PROPERTY,
+    lsp::SemanticTokenType::KEYWORD,
+    lsp::SemanticTokenType::COMMENT,
+    lsp::SemanticTokenType::STRING,
+    lsp::SemanticTokenType::NUMBER,
+];
+
+/// Token modifiers in the order of their bits in the legend.
+const TOKEN_MODIFIERS: &[lsp::SemanticTokenModifier] = &[
+    lsp::SemanticTokenModifier::DECLARATION,
+    lsp::SemanticTokenModifier::READONLY,
+    lsp::SemanticTokenModifier::DEFAULT_LIBRARY,
+    lsp::SemanticTokenModifier::new("mutable"),
+];
+
+/// The type of a semantic token, as an index into [`TOKEN_TYPES`].
+#[derive(Debug, Clone, Copy)]
+#[repr(u32)]
+enum TokenType {
+    Namespace,
+    Type,
+    Struct,
+    Enum,
+    EnumMember,
+    Interface,
+    Function,
+    Method,
+    Macro,
+    Variable,
+    Parameter,
+    Property,
+    Keyword,
+    Comment,
+    String,
+    Number,
+}
+
+const DECLARATION: u32 = 1 << 0;
+const READONLY: u32 = 1 << 1;
+const DEFAULT_LIBRARY: u32 = 1 << 2;
+const MUTABLE: u32 = 1 << 3;
+
+/// The legend describing the semantic tokens produced by the server.
+pub(super) fn legend() -> lsp::SemanticTokensLegend {
+    lsp::SemanticTokensLegend {
+        token_types: TOKEN_TYPES.to_vec(),
+        token_modifiers: TOKEN_MODIFIERS.to_vec(),
+    }
+}
+
+/// What we know about a local variable.
+#[derive(Default, Clone, Copy)]
+struct LocalInfo {
+    /// The local is a function parameter.
+    parameter: bool,
+    /// The local is assigned to after being declared.
+    mutable: bool,
+}
+
+impl LocalInfo {
+    fn classify(self, modifiers: u32) -> (TokenType, u32) {
+        let ty = if self.parameter {
+            TokenType::Parameter
+        } else {
+            TokenType::Variable
+        };
+
+        let modifiers = if self.mutable {
+            modifiers | MUTABLE
+        } else {
+            modifiers
+        };
+
+        (ty, modifiers)
+    }
+}
+
+/// Compute encoded semantic tokens for the given source, optionally limited
+/// to the lines covered by the given range.
+pub(super) fn tokens(
+    context: &Context,
+    source: &Source,
+    url: &Url,
+    range: Option<lsp::Range>,
+) -> Option<Vec<lsp::SemanticToken>> {
+    let (source_id, build_source) = source.build_source(url)?;
+    let text = build_source.as_str();
+
+    let mut lexer = Lexer::new(text, source_id, true);
+    let mut tokens = Vec::new();
+
+    // NB: Lexing errors are ignored, since we still want to highlight
+    // everything up until the error.
+    while let Ok(Some(token)) = lexer.next() {
+        if !matches!(token.kind, ast::Kind::Whitespace) {
+            tokens.push(token);
+        }
+    }
+
+    let locals = locals(source, source_id, &tokens);
+
+    let mut classified = Vec::new();
+    let mut last_end = 0;
+
+    for (index, token) in tokens.iter().enumerate() {
+        // Tokens which are synthesized by the lexer, like doc comments
+        // expanded into attributes, share the span of the first token.
+        if token.span.start.into_usize() < last_end {
+            continue;
+        }
+
+        let class = match token.kind {
+            ast::Kind::Comment | ast::Kind::MultilineComment(..) | ast::Kind::Shebang(..) => {
+                Some((TokenType::Comment, 0))
+            }
+            ast::Kind::Str(..)
+            | ast::Kind::ByteStr(..)
+            | ast::Kind::Char(..)
+            | ast::Kind::Byte(..) => Some((TokenType::String, 0)),
+            ast::Kind::Number(..) => Some((TokenType::Number, 0)),
+            ast::Kind::Ident(..) => ident(context, source, &tokens, index, &locals),
+            kind if is_keyword(kind) => Some((TokenType::Keyword, 0)),
+            _ => None,
+        };
+
+        let class = match (class, text.get(token.span.range())) {
+            // Doc comments are lexed into attributes.
+            (_, Some(s)) if s.starts_with("//") || s.starts_with("/*") => {
+                Some((TokenType::Comment, 0))
+            }
+            (class, _) => class,
+        };
+
+        if let Some((ty, modifiers)) = class {
+            classified.push((token.span, ty, modifiers));
+            last_end = token.span.end.into_usize();
+        }
+    }
+
+    Some(encode(build_source, &classified, range))
+}
+
+/// Classify an identifier.
+fn ident(
+    context: &Context,
+    source: &Source,
+    tokens: &[ast::Token],
+    index: usize,
+    locals: &HashMap<Span, LocalInfo>,
+) -> Option<(TokenType, u32)> {
+    let span = tokens[index].span;
+    let next = tokens.get(index + 1).map(|t| t.kind);
+    let prev = index.checked_sub(1).map(|i| tokens[i].kind);
+
+    if let Some(K![!]) = next {
+        return Some((TokenType::Macro, 0));
+    }
+
+    if let Some((found, definition)) = source.find_definition_at(span) {
+        if let DefinitionSource::Location(location) = &definition.source {
+            let info = locals.get(&location.span).copied().unwrap_or_default();
+            return Some(info.classify(0));
+        }
+
+        let modifiers = match definition.source {
+            DefinitionSource::Context => DEFAULT_LIBRARY,
+            DefinitionSource::Source(..) => return Some((TokenType::Namespace, 0)),
+            _ => 0,
+        };
+
+        // The number of path segments following this one, which tells us
+        // which ancestor of the resolved item this segment refers to.
+        let following = tokens[index + 1..]
+            .iter()
+            .take_while(|t| {
+                t.span.end <= found.end && matches!(t.kind, ast::Kind::Ident(..) | K![::])
+            })
+            .filter(|t| matches!(t.kind, ast::Kind::Ident(..)))
+            .count();
+
+        if following == 0 {
+            return Some((definition_type(definition.kind), modifiers));
+        }
+
+        let mut item = &*definition.item.as_ref()?.item;
+
+        for _ in 0..following {
+            item = item.parent()?;
+        }
+
+        let hash = Hash::type_hash(item);
+
+        let ty = match source.item_kind(hash) {
+            Some(kind) => definition_type(kind),
+            None => match context.lookup_meta_by_hash(hash).next().map(|m| &m.kind) {
+                Some(meta::Kind::Enum { .. }) => TokenType::Enum,
+                Some(meta::Kind::Struct { .. }) => TokenType::Struct,
+                Some(meta::Kind::Type { .. }) => TokenType::Type,
+                Some(meta::Kind::Trait { .. }) => TokenType::Interface,
+                _ => TokenType::Namespace,
+            },
+        };
+
+        return Some((ty, modifiers));
+    }
+
+    if let Some(declaration) = source.declarations().iter().find(|d| d.name_span == span) {
+        let (ty, modifiers) = match declaration.kind {
+            DeclarationKind::Function => (TokenType::Function, 0),
+            DeclarationKind::Struct => (TokenType::Struct, 0),
+            DeclarationKind::Enum => (TokenType::Enum, 0),
+            DeclarationKind::Variant => (TokenType::EnumMember, 0),
+            DeclarationKind::Const => (TokenType::Variable, READONLY),
+            DeclarationKind::Impl => (TokenType::Type, 0),
+            DeclarationKind::Trait => (TokenType::Interface, 0),
+            DeclarationKind::Module => (TokenType::Namespace, 0),
+        };
+
+        return Some((ty, modifiers | DECLARATION));
+    }
+
+    if let Some(info) = locals.get(&span) {
+        return Some(info.classify(DECLARATION));
+    }
+
+    match (prev, next) {
+        (Some(K![.]), Some(K!['('])) => Some((TokenType::Method, 0)),
+        (Some(K![.]), _) => Some((TokenType::Property, 0)),
+        _ => None,
+    }
+}
+
+/// Collect information on every local variable declared in the source, keyed
+/// by the span of its declaration.
+fn locals(
+    source: &Source,
+    source_id: crate::SourceId,
+    tokens: &[ast::Token],
+) -> HashMap<Span, LocalInfo> {
+    let mut locals = HashMap::<Span, LocalInfo>::new();
+
+    for (span, definition) in source.definitions() {
+        let DefinitionSource::Location(location) = &definition.source else {
+            continue;
+        };
+
+        if location.source_id != source_id {
+            continue;
+        }
+
+        let info = locals.entry(location.span).or_insert_with(|| LocalInfo {
+            parameter: is_parameter(source, location.span),
+            mutable: false,
+        });
+
+        // A local is mutable if any of its uses is followed by an assignment.
+        let next = tokens.partition_point(|t| t.span.start < span.end);
+
+        if let Some(token) = tokens.get(next) {
+            if is_assignment(token.kind) {
+                info.mutable = true;
+            }
+        }
+    }
+
+    locals
+}
+
+/// Test if the local declared at the given span is a parameter, in that it's
+/// declared in between the name and the body of a function.
+fn is_parameter(source: &Source, span: Span) -> bool {
+    source.declarations().iter().any(|d| {
+        if !matches!(d.kind, DeclarationKind::Function) || span.start < d.name_span.end {
+            return false;
+        }
+
+        let body = source
+            .blocks()
+            .iter()
+            .filter(|b| b.start >= d.name_span.end && b.end <= d.span.end)
+            .map(|b| b.start)
+            .min();
+
+        body.is_some_and(|start| span.end <= start)
+    })
+}
+
+fn definition_type(kind: DefinitionKind) -> TokenType {
+    match kind {
+        DefinitionKind::EmptyStruct | DefinitionKind::TupleStruct | DefinitionKind::Struct => {
+            TokenType::Struct
+        }
+        DefinitionKind::UnitVariant
+        | DefinitionKind::TupleVariant
+        | DefinitionKind::StructVariant => TokenType::EnumMember,
+        DefinitionKind::Enum => TokenType::Enum,
+        DefinitionKind::Function | DefinitionKind::AssociatedFunction => TokenType::Function,
+        DefinitionKind::Local => TokenType::Variable,
+        DefinitionKind::Module => TokenType::Namespace,
+    }
+}
+
+/// Test if the given kind is a keyword.
+fn is_keyword(kind: ast::Kind) -> bool {
+    kind.as_literal_str()
+        .is_some_and(|s| s.chars().all(|c| c == '_' || c.is_ascii_alphabetic()))
+}
+
+/// Test if the given kind is an assignment operator.
+fn is_assignment(kind: ast::Kind) -> bool {
+    matches!(
+        kind,
+        K![=]
+            | K![+=]
+            | K![-=]
+            | K![*=]
+            | K![/=]
+            | K![%=]
+            | K![&=]
+            | K![^=]
+            | K![|=]
+            | K![<<=]
+            | K![>>=]
+    )
+}
+
+/// Encode classified spans into relative semantic tokens, splitting tokens
+/// which span multiple lines.
+fn encode(
+    source: &crate::Source,
+    classified: &[(Span, TokenType, u32)],
+    range: Option<lsp::Range>,
+) -> Vec<lsp::SemanticToken> {
+    let text = source.as_str();
+
+    let mut output = Vec::new();
+    let mut last_line = 0;
+    let mut last_start = 0;
+
+    for &(span, ty, modifiers) in classified {
+        let Some(token_text) = text.get(span.range()) else {
+            continue;
+        };
+
+        let mut offset = span.start.into_usize();
+
+        for part in token_text.split('\n') {
+            let (line, start) = source.pos_to_utf16cu_linecol(offset);
+            let (line, start) = (line as u32, start as u32);
+            let length = part.trim_end_matches('\r').encode_utf16().count() as u32;
+            offset += part.len() + 1;
+
+            if length == 0 {
+                continue;
+            }
+
+            if let Some(range) = range {
+                if line < range.start.line || line > range.end.line {
+                    continue;
+                }
+            }
+
+            let delta_start = if line == last_line {
+                start - last_start
+            } else {
+                start
+            };
+
+            output.push(lsp::SemanticToken {
+                delta_line: line - last_line,
+                delta_start,
+                length,
+                token_type: ty as u32,
+                token_modifiers_bitset: modifiers,
+            });
+
+            last_line = line;
+            last_start = start;
+        }
+    }
+
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::no_std::prelude::*;
+
+    use crate::ast::Span;
+
+    use super::{encode, TokenType, DECLARATION};
+
+    fn token(
+        delta_line: u32,
+        delta_start: u32,
+        length: u32,
+        ty: TokenType,
+        modifiers: u32,
+    ) -> lsp::SemanticToken {
+        lsp::SemanticToken {
+            delta_line,
+            delta_start,
+            length,
+            token_type: ty as u32,
+            token_modifiers_bitset: modifiers,
+        }
+    }
+
+    #[test]
+    fn test_encode() {
+        let text = "let s = \"é\"; s\r\n/* ü\n*/ s";
+        let source = crate::Source::new("test", text);
+
+        let classified = [
+            (Span::new(0, 3), TokenType::Keyword, 0),
+            (Span::new(4, 5), TokenType::Variable, DECLARATION),
+            (Span::new(8, 12), TokenType::String, 0),
+            (Span::new(14, 15), TokenType::Variable, 0),
+            (Span::new(17, 25), TokenType::Comment, 0),
+            (Span::new(26, 27), TokenType::Variable, 0),
+        ];
+
+        // Columns and lengths are in UTF-16 code units, and multi-line tokens
+        // are split into one token per line.
+        assert_eq!(
+            encode(&source, &classified, None),
+            [
+                token(0, 0, 3, TokenType::Keyword, 0),
+                token(0, 4, 1, TokenType::Variable, DECLARATION),
+                token(0, 4, 3, TokenType::String, 0),
+                token(0, 5, 1, TokenType::Variable, 0),
+                token(1, 0, 4, TokenType::Comment, 0),
+                token(1, 0, 2, TokenType::Comment, 0),
+                token(0, 3, 1, TokenType::Variable, 0),
+            ]
+        );
+
+        let range = lsp::Range::new(lsp::Position::new(1, 0), lsp::Position::new(1, 4));
+
+        assert_eq!(
+            encode(&source, &classified, Some(range)),
+            [token(1, 0, 4, TokenType::Comment, 0)]
+        );
+    }
+}
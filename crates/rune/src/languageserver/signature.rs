@@ -0,0 +1,615 @@
+use crate::no_std::prelude::*;
+
+use lsp::Url;
+
+use crate::ast::{self, Span};
+use crate::compile::context::ContextMeta;
+use crate::compile::{meta, ComponentRef, Item};
+use crate::parse::Lexer;
+use crate::runtime::debug::DebugArgs;
+use crate::{Context, Hash, SourceId, Unit};
+
+use super::state::{DefinitionSource, Source};
+
+/// The signature of a function which is being called.
+pub(super) struct FunctionSignature {
+    /// The name of the function.
+    pub(super) name: String,
+    /// The names of its parameters, including `self` for instance functions.
+    pub(super) parameters: Vec<String>,
+    /// The name of the type it returns, if known.
+    pub(super) return_type: Option<String>,
+    /// Documentation for the function.
+    pub(super) docs: Option<String>,
+}
+
+impl FunctionSignature {
+    /// Construct a signature from a native function in the context.
+    fn from_context(context: &Context, meta: &ContextMeta, signature: &meta::Signature) -> Self {
+        let name = match &meta.kind {
+            meta::Kind::Function {
+                associated: Some(meta::AssociatedKind::Instance(name)),
+                ..
+            } => name.to_string(),
+            _ => match meta.item.as_deref().and_then(Item::last) {
+                Some(last) => last.to_string(),
+                None => String::new(),
+            },
+        };
+
+        let parameters = match (meta.docs.args(), signature.args) {
+            (Some(args), _) => args.to_vec(),
+            (None, Some(n)) => (0..n).map(|n| format!("_{n}")).collect(),
+            (None, None) => Vec::new(),
+        };
+
+        let return_type = signature
+            .return_type
+            .and_then(|hash| context.lookup_meta_by_hash(hash).next())
+            .and_then(|meta| meta.item.as_deref()?.last())
+            .map(|last| last.to_string());
+
+        let docs = meta.docs.lines().join("\n");
+
+        Self {
+            name,
+            parameters,
+            return_type,
+            docs: (!docs.trim().is_empty()).then_some(docs),
+        }
+    }
+
+    /// Construct a signature from a function in the compiled unit.
+    fn from_unit(source: &Source, hash: Hash, path: &Item, args: &DebugArgs) -> Self {
+        let name = match path.last() {
+            Some(last) => last.to_string(),
+            None => String::new(),
+        };
+
+        let parameters = match args {
+            DebugArgs::EmptyArgs => Vec::new(),
+            DebugArgs::TupleArgs(n) => (0..*n).map(|n| format!("_{n}")).collect(),
+            DebugArgs::Named(names) => names.iter().map(|name| name.to_string()).collect(),
+        };
+
+        let docs = source
+            .get_docs_by_hash(hash)
+            .map(|data| data.docs.join("\n"))
+            .filter(|docs| !docs.trim().is_empty());
+
+        Self {
+            name,
+            parameters,
+            return_type: None,
+            docs,
+        }
+    }
+
+    /// Test if the first parameter is `self`.
+    pub(super) fn has_self(&self) -> bool {
+        self.parameters.first().is_some_and(|p| p == "self")
+    }
+
+    /// Build a label for the signature, returning the offsets of each
+    /// parameter in it.
+    pub(super) fn label(&self) -> (String, Vec<[u32; 2]>) {
+        let mut label = format!("fn {}(", self.name);
+        let mut offsets = Vec::new();
+
+        for (n, parameter) in self.parameters.iter().enumerate() {
+            if n > 0 {
+                label.push_str(", ");
+            }
+
+            let start = label.encode_utf16().count() as u32;
+            label.push_str(parameter);
+            let end = label.encode_utf16().count() as u32;
+            offsets.push([start, end]);
+        }
+
+        label.push(')');
+
+        if let Some(return_type) = &self.return_type {
+            label.push_str(" -> ");
+            label.push_str(return_type);
+        }
+
+        (label, offsets)
+    }
+}
+
+/// The callee of a call expression.
+pub(super) enum Callee<'a> {
+    /// A function called through a path, like `std::string::String::new`.
+    Path(Vec<&'a str>),
+    /// An instance function called with the given name.
+    Method(&'a str),
+}
+
+/// Lex the given text, stopping at the first error.
+pub(super) fn lex(text: &str, source_id: SourceId) -> Vec<ast::Token> {
+    let mut lexer = Lexer::new(text, source_id, true);
+    let mut tokens = Vec::new();
+
+    while let Ok(Some(token)) = lexer.next() {
+        if !matches!(
+            token.kind,
+            ast::Kind::Whitespace | ast::Kind::Comment | ast::Kind::MultilineComment(..)
+        ) {
+            tokens.push(token);
+        }
+    }
+
+    tokens
+}
+
+/// Find the callee of a call whose opening parenthesis is at the given token
+/// index, returning the index of the first token of the callee.
+pub(super) fn callee<'a>(
+    text: &'a str,
+    tokens: &[ast::Token],
+    open: usize,
+) -> Option<(usize, Callee<'a>)> {
+    let ident = |index: usize| match tokens.get(index)? {
+        token @ ast::Token {
+            kind: ast::Kind::Ident(..),
+            ..
+        } => text.get(token.span.range()),
+        _ => None,
+    };
+
+    let mut start = open.checked_sub(1)?;
+    let last = ident(start)?;
+
+    if start > 0 && matches!(tokens[start - 1].kind, K![.]) {
+        return Some((start, Callee::Method(last)));
+    }
+
+    let mut path = vec![last];
+
+    while start >= 2 && matches!(tokens[start - 1].kind, K![::]) {
+        let Some(segment) = ident(start - 2) else {
+            break;
+        };
+
+        path.push(segment);
+        start -= 2;
+    }
+
+    path.reverse();
+    Some((start, Callee::Path(path)))
+}
+
+/// Find the index of the closing parenthesis matching the opening one at the
+/// given index, along with the token ranges of each argument.
+pub(super) fn arguments(
+    tokens: &[ast::Token],
+    open: usize,
+) -> (Option<usize>, Vec<(usize, usize)>) {
+    let mut depth = 0usize;
+    let mut arguments = Vec::new();
+    let mut start = open + 1;
+
+    for (index, token) in tokens.iter().enumerate().skip(open + 1) {
+        match token.kind {
+            ast::Kind::Open(..) => depth += 1,
+            ast::Kind::Close(..) if depth > 0 => depth -= 1,
+            ast::Kind::Close(..) => {
+                if start < index {
+                    arguments.push((start, index));
+                }
+
+                return (Some(index), arguments);
+            }
+            K![,] if depth == 0 => {
+                arguments.push((start, index));
+                start = index + 1;
+            }
+            _ => {}
+        }
+    }
+
+    (None, arguments)
+}
+
+/// Find the innermost unclosed call before the end of the given tokens,
+/// returning the index of its opening parenthesis and the index of the active
+/// argument.
+pub(super) fn enclosing_call(tokens: &[ast::Token]) -> Option<(usize, u32)> {
+    let mut depth = 0usize;
+    let mut active = 0;
+
+    for (index, token) in tokens.iter().enumerate().rev() {
+        match token.kind {
+            ast::Kind::Close(..) => depth += 1,
+            ast::Kind::Open(ast::Delimiter::Parenthesis) if depth == 0 => {
+                return Some((index, active));
+            }
+            // The call can't extend beyond an unclosed block or index.
+            ast::Kind::Open(..) if depth == 0 => return None,
+            ast::Kind::Open(..) => depth -= 1,
+            K![,] if depth == 0 => active += 1,
+            K![;] if depth == 0 => return None,
+            _ => {}
+        }
+    }
+
+    None
+}
+
+/// Resolve the signatures a callee might refer to.
+///
+/// The definition index is used if the callee was resolved when the source
+/// was last built, otherwise we fall back to looking up functions by name.
+pub(super) fn resolve(
+    context: &Context,
+    source: &Source,
+    callee: &Callee<'_>,
+    span: Option<Span>,
+) -> Vec<FunctionSignature> {
+    if let Some((_, definition)) = span.and_then(|span| source.find_definition_at(span)) {
+        if let Some(item) = &definition.item {
+            let signature = match definition.source {
+                DefinitionSource::Context => {
+                    context.lookup_meta_by_hash(item.hash).find_map(|meta| {
+                        let signature = meta.kind.as_signature()?;
+                        Some(FunctionSignature::from_context(context, meta, signature))
+                    })
+                }
+                _ => source
+                    .unit()
+                    .and_then(Unit::debug_info)
+                    .and_then(|debug_info| debug_info.functions.get(&item.hash))
+                    .map(|f| FunctionSignature::from_unit(source, item.hash, &f.path, &f.args)),
+            };
+
+            if let Some(signature) = signature {
+                return vec![signature];
+            }
+        }
+    }
+
+    let mut signatures = Vec::new();
+
+    match callee {
+        Callee::Path(path) => {
+            if let Some(debug_info) = source.unit().and_then(Unit::debug_info) {
+                for (hash, f) in &debug_info.functions {
+                    if ends_with(&f.path, path) {
+                        signatures.push(FunctionSignature::from_unit(
+                            source, *hash, &f.path, &f.args,
+                        ));
+                    }
+                }
+            }
+
+            for (meta, signature) in context.iter_functions() {
+                let is_match = match &meta.kind {
+                    meta::Kind::Function {
+                        associated: Some(meta::AssociatedKind::Instance(..)),
+                        ..
+                    } => false,
+                    _ => meta
+                        .item
+                        .as_deref()
+                        .is_some_and(|item| ends_with(item, path)),
+                };
+
+                if is_match {
+                    signatures.push(FunctionSignature::from_context(context, meta, signature));
+                }
+            }
+        }
+        Callee::Method(name) => {
+            for (meta, signature) in context.iter_functions() {
+                if let meta::Kind::Function {
+                    associated: Some(meta::AssociatedKind::Instance(n)),
+                    ..
+                } = &meta.kind
+                {
+                    if n == name {
+                        signatures.push(FunctionSignature::from_context(context, meta, signature));
+                    }
+                }
+            }
+        }
+    }
+
+    signatures
+}
+
+/// Test if the item ends with the given path.
+fn ends_with(item: &Item, path: &[&str]) -> bool {
+    let components = item.iter().collect::<Vec<_>>();
+
+    if components.len() < path.len() {
+        return false;
+    }
+
+    components[components.len() - path.len()..]
+        .iter()
+        .zip(path)
+        .all(|(c, p)| matches!(c, ComponentRef::Str(s) if s == p))
+}
+
+/// Compute signature help for the call surrounding the given position.
+pub(super) fn signature_help(
+    context: &Context,
+    source: &Source,
+    url: &Url,
+    position: lsp::Position,
+) -> Option<lsp::SignatureHelp> {
+    let text = source.to_string();
+    let offset = source.lsp_position_to_byte(position)?;
+    let tokens = lex(text.get(..offset)?, SourceId::empty());
+
+    let (open, mut active) = enclosing_call(&tokens)?;
+    let (start, callee) = callee(&text, &tokens, open)?;
+
+    // Spans in the definition index can only be used if the source hasn't
+    // been modified since it was last built.
+    let span = match source.build_source(url) {
+        Some((_, built)) if built.as_str() == text => {
+            Some(tokens[start].span.join(tokens[open - 1].span))
+        }
+        _ => None,
+    };
+
+    let signatures = resolve(context, source, &callee, span);
+
+    let first = signatures.first()?;
+
+    if matches!(callee, Callee::Method(..)) && first.has_self() {
+        active += 1;
+    }
+
+    let signatures = signatures
+        .iter()
+        .map(|signature| {
+            let (label, offsets) = signature.label();
+
+            lsp::SignatureInformation {
+                label,
+                documentation: signature.docs.as_ref().map(|docs| {
+                    lsp::Documentation::MarkupContent(lsp::MarkupContent {
+                        kind: lsp::MarkupKind::Markdown,
+                        value: docs.clone(),
+                    })
+                }),
+                parameters: Some(
+                    offsets
+                        .into_iter()
+                        .map(|offsets| lsp::ParameterInformation {
+                            label: lsp::ParameterLabel::LabelOffsets(offsets),
+                            documentation: None,
+                        })
+                        .collect(),
+                ),
+                active_parameter: None,
+            }
+        })
+        .collect();
+
+    Some(lsp::SignatureHelp {
+        signatures,
+        active_signature: Some(0),
+        active_parameter: Some(active),
+    })
+}
+
+/// Compute inlay hints for calls in the given range, showing the names of
+/// parameters at call sites and the return types of functions bound to
+/// variables.
+pub(super) fn inlay_hints(
+    context: &Context,
+    source: &Source,
+    url: &Url,
+    range: lsp::Range,
+) -> Option<Vec<lsp::InlayHint>> {
+    let (source_id, built) = source.build_source(url)?;
+    let text = built.as_str();
+    let tokens = lex(text, source_id);
+
+    let mut hints = Vec::new();
+
+    let position = |offset: usize| {
+        let (line, character) = built.pos_to_utf16cu_linecol(offset);
+        lsp::Position::new(line as u32, character as u32)
+    };
+
+    for (open, token) in tokens.iter().enumerate() {
+        if !matches!(token.kind, K!['(']) {
+            continue;
+        }
+
+        let Some((start, callee)) = callee(text, &tokens, open) else {
+            continue;
+        };
+
+        // Skip function declarations.
+        if start > 0 && matches!(tokens[start - 1].kind, K![fn]) {
+            continue;
+        }
+
+        let line = position(tokens[start].span.start.into_usize()).line;
+
+        if line < range.start.line || line > range.end.line {
+            continue;
+        }
+
+        let span = tokens[start].span.join(tokens[open - 1].span);
+        let signatures = resolve(context, source, &callee, Some(span));
+
+        // Only provide hints if the callee is unambiguous.
+        let [signature] = &signatures[..] else {
+            continue;
+        };
+
+        let (close, arguments) = arguments(&tokens, open);
+
+        let skip = usize::from(matches!(callee, Callee::Method(..)) && signature.has_self());
+
+        for (&(from, to), parameter) in arguments.iter().zip(signature.parameters.iter().skip(skip))
+        {
+            if parameter == "self" || parameter.starts_with('_') {
+                continue;
+            }
+
+            // Arguments which are named like the parameter don't need a hint.
+            if to - from == 1 && text.get(tokens[from].span.range()) == Some(parameter.as_str()) {
+                continue;
+            }
+
+            hints.push(lsp::InlayHint {
+                position: position(tokens[from].span.start.into_usize()),
+                label: lsp::InlayHintLabel::String(format!("{parameter}:")),
+                kind: Some(lsp::InlayHintKind::PARAMETER),
+                text_edits: None,
+                tooltip: None,
+                padding_left: None,
+                padding_right: Some(true),
+                data: None,
+            });
+        }
+
+        // Hint the type of `let name = call(..);`.
+        let (Some(return_type), Some(close)) = (&signature.return_type, close) else {
+            continue;
+        };
+
+        if start < 3 || !matches!(tokens.get(close + 1).map(|t| t.kind), Some(K![;])) {
+            continue;
+        }
+
+        if let (K![let], ast::Kind::Ident(..), K![=]) = (
+            tokens[start - 3].kind,
+            tokens[start - 2].kind,
+            tokens[start - 1].kind,
+        ) {
+            hints.push(lsp::InlayHint {
+                position: position(tokens[start - 2].span.end.into_usize()),
+                label: lsp::InlayHintLabel::String(format!(": {return_type}")),
+                kind: Some(lsp::InlayHintKind::TYPE),
+                text_edits: None,
+                tooltip: None,
+                padding_left: None,
+                padding_right: None,
+                data: None,
+            });
+        }
+    }
+
+    Some(hints)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::no_std::prelude::*;
+
+    use lsp::Url;
+
+    use crate::{Context, SourceId};
+
+    use super::super::state::Source;
+    use super::{enclosing_call, inlay_hints, lex, signature_help, FunctionSignature};
+
+    const SOURCE: &str = r#"/// Add two numbers.
+fn add(a, b) { a + b }
+
+pub fn main() {
+    let b = 2;
+    let s = String::from("ä");
+    add(1, b) + add(add(3, 4), 5)
+}
+"#;
+
+    fn build() -> (Context, Url, Source) {
+        let context = Context::with_default_modules().unwrap();
+        let url = Url::parse("file:///test.rn").unwrap();
+        let source = Source::build(&context, &url, SOURCE);
+        (context, url, source)
+    }
+
+    #[test]
+    fn test_enclosing_call() {
+        let tokens = lex("foo(a, bar(b), ", SourceId::empty());
+        assert_eq!(enclosing_call(&tokens), Some((1, 2)));
+
+        let tokens = lex("foo(a, [1, ", SourceId::empty());
+        assert_eq!(enclosing_call(&tokens), None);
+
+        let tokens = lex("foo(a); bar", SourceId::empty());
+        assert_eq!(enclosing_call(&tokens), None);
+    }
+
+    #[test]
+    fn test_label() {
+        let signature = FunctionSignature {
+            name: String::from("add"),
+            parameters: vec![String::from("ä"), String::from("b")],
+            return_type: Some(String::from("i64")),
+            docs: None,
+        };
+
+        let (label, offsets) = signature.label();
+        assert_eq!(label, "fn add(ä, b) -> i64");
+        assert_eq!(offsets, [[7, 8], [10, 11]]);
+    }
+
+    #[test]
+    fn test_signature_help() {
+        let (context, url, source) = build();
+
+        // Inside of the nested call, at its second argument.
+        let help = signature_help(&context, &source, &url, lsp::Position::new(6, 27)).unwrap();
+
+        assert_eq!(help.active_parameter, Some(1));
+        assert_eq!(help.signatures.len(), 1);
+
+        let signature = &help.signatures[0];
+        assert_eq!(signature.label, "fn add(a, b)");
+        assert_eq!(
+            signature.documentation,
+            Some(lsp::Documentation::MarkupContent(lsp::MarkupContent {
+                kind: lsp::MarkupKind::Markdown,
+                value: String::from(" Add two numbers."),
+            }))
+        );
+
+        // Outside of any call.
+        assert!(signature_help(&context, &source, &url, lsp::Position::new(4, 10)).is_none());
+    }
+
+    #[test]
+    fn test_inlay_hints() {
+        let (context, url, source) = build();
+
+        let range = lsp::Range::new(lsp::Position::new(0, 0), lsp::Position::new(8, 0));
+
+        let hints = inlay_hints(&context, &source, &url, range)
+            .unwrap()
+            .into_iter()
+            .map(|hint| {
+                let lsp::InlayHintLabel::String(label) = hint.label else {
+                    panic!("expected string label");
+                };
+
+                (hint.position.line, hint.position.character, label)
+            })
+            .collect::<Vec<_>>();
+
+        assert_eq!(
+            hints,
+            [
+                (5, 25, String::from("value:")),
+                (5, 9, String::from(": String")),
+                // The second argument is named like the parameter.
+                (6, 8, String::from("a:")),
+                (6, 20, String::from("a:")),
+                (6, 31, String::from("b:")),
+                (6, 24, String::from("a:")),
+                (6, 27, String::from("b:")),
+            ]
+        );
+    }
+}
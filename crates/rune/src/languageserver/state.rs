@@ -1,4 +1,4 @@
-use std::collections::{BTreeMap, HashSet};
+use std::collections::{BTreeMap, BTreeSet, HashSet};
 use std::fmt;
 use std::path::{Path, PathBuf};
 use std::sync::Arc;
@@ -11,18 +11,21 @@ use lsp::Url;
 use ropey::Rope;
 use tokio::sync::Notify;
 
-use crate::ast::{Span, Spanned};
+use crate::ast::{self, Span, Spanned};
 use crate::compile::meta;
 use crate::compile::{
-    self, CompileVisitor, ComponentRef, Item, ItemBuf, LinkerError, Located, Location, MetaRef,
-    SourceMeta,
+    self, CompileVisitor, ComponentRef, DeclarationKind, Item, ItemBuf, LinkerError, Located,
+    Location, MetaError, MetaRef, SourceMeta,
 };
 use crate::diagnostics::{Diagnostic, FatalDiagnosticKind};
 use crate::doc::VisitorData;
+use crate::languageserver::code_action::{self, Fix};
 use crate::languageserver::connection::Output;
 use crate::languageserver::Language;
+use crate::languageserver::{semantic_tokens, signature, symbols};
+use crate::parse::Lexer;
 use crate::workspace::{self, WorkspaceError};
-use crate::{BuildError, Context, Options, SourceId, Unit};
+use crate::{BuildError, Context, Hash, Options, SourceId, Unit};
 
 #[derive(Default)]
 struct Reporter {
@@ -166,14 +169,17 @@ impl<'a> State<'a> {
     ) -> Option<lsp::Location> {
         let source = self.workspace.get(uri)?;
         let offset = source.lsp_position_to_offset(position);
-        let def = source.find_definition_at(Span::point(offset))?;
+        let (_, def) = source.find_definition_at(Span::point(offset))?;
 
         let url = match def.source.path() {
             Some(path) => crate::languageserver::url::from_file_path(path).ok()?,
             None => uri.clone(),
         };
 
-        let source = source.build_sources.as_ref()?.get(def.source.source_id())?;
+        let source = source
+            .build_sources
+            .as_ref()?
+            .get(def.source.source_id()?)?;
 
         let (l, c) = source.pos_to_utf16cu_linecol(def.source.span().start.into_usize());
         let start = lsp::Position {
@@ -195,6 +201,332 @@ impl<'a> State<'a> {
         Some(location)
     }
 
+    /// Describe the definition at the given uri and LSP position.
+    pub(super) fn hover(&self, uri: &Url, position: lsp::Position) -> Option<lsp::Hover> {
+        let source = self.workspace.get(uri)?;
+        let offset = source.lsp_position_to_offset(position);
+        let (span, def) = source.find_definition_at(Span::point(offset))?;
+
+        let value = match (def.kind, &def.item) {
+            (DefinitionKind::Local, _) => {
+                let build_sources = source.build_sources.as_ref()?;
+                let name = build_sources.source(def.source.source_id()?, def.source.span())?;
+                super::hover::for_local(name)
+            }
+            (_, Some(item)) if matches!(def.source, DefinitionSource::Context) => {
+                super::hover::for_context(&self.context, &item.item, item.hash)?
+            }
+            (kind, Some(item)) => {
+                super::hover::for_unit(source, source.unit.as_ref(), kind, &item.item, item.hash)
+            }
+            _ => return None,
+        };
+
+        Some(lsp::Hover {
+            contents: lsp::HoverContents::Markup(lsp::MarkupContent {
+                kind: lsp::MarkupKind::Markdown,
+                value,
+            }),
+            range: source.span_to_lsp_range(span),
+        })
+    }
+
+    /// Find all references to the symbol at the given uri and LSP position.
+    pub(super) fn references(
+        &self,
+        uri: &Url,
+        position: lsp::Position,
+        include_declaration: bool,
+    ) -> Option<Vec<lsp::Location>> {
+        let source = self.workspace.get(uri)?;
+        let references = source.references.as_ref()?;
+        let sources = source.build_sources.as_ref()?;
+        let offset = source.lsp_position_to_offset(position);
+        let (symbol, _) = source.find_symbol_at(uri, offset)?;
+
+        let mut locations = Vec::new();
+
+        for (source_id, span) in references.locations(sources, &symbol, include_declaration) {
+            let (Some(url), Some(source)) =
+                (references.urls.get(&source_id), sources.get(source_id))
+            else {
+                continue;
+            };
+
+            let Some(range) = span_to_lsp_range(source, span) else {
+                continue;
+            };
+
+            locations.push(lsp::Location::new(url.clone(), range));
+        }
+
+        Some(locations)
+    }
+
+    /// Test if the symbol at the given uri and LSP position can be renamed,
+    /// returning the range of the name being renamed.
+    pub(super) fn prepare_rename(
+        &self,
+        uri: &Url,
+        position: lsp::Position,
+    ) -> Option<lsp::PrepareRenameResponse> {
+        let source = self.workspace.get(uri)?;
+        let references = source.references.as_ref()?;
+        let offset = source.lsp_position_to_offset(position);
+        let (symbol, span) = source.find_symbol_at(uri, offset)?;
+
+        if !references.is_renameable(&symbol) {
+            return None;
+        }
+
+        let sources = source.build_sources.as_ref()?;
+        let source_id = references.source_id(uri)?;
+        let placeholder = sources.source(source_id, span)?.to_owned();
+        let range = span_to_lsp_range(sources.get(source_id)?, span)?;
+
+        Some(lsp::PrepareRenameResponse::RangeWithPlaceholder { range, placeholder })
+    }
+
+    /// Rename the symbol at the given uri and LSP position across all sources
+    /// it is used in.
+    pub(super) fn rename(
+        &self,
+        uri: &Url,
+        position: lsp::Position,
+        new_name: &str,
+    ) -> Option<lsp::WorkspaceEdit> {
+        if !is_identifier(new_name) {
+            tracing::warn!(new_name, "not a valid identifier");
+            return None;
+        }
+
+        let source = self.workspace.get(uri)?;
+        let references = source.references.as_ref()?;
+        let sources = source.build_sources.as_ref()?;
+        let offset = source.lsp_position_to_offset(position);
+        let (symbol, _) = source.find_symbol_at(uri, offset)?;
+
+        if !references.is_renameable(&symbol) {
+            return None;
+        }
+
+        let mut changes = BTreeMap::<Url, Vec<lsp::TextEdit>>::new();
+
+        for (source_id, span) in references.locations(sources, &symbol, true) {
+            let (Some(url), Some(source)) =
+                (references.urls.get(&source_id), sources.get(source_id))
+            else {
+                continue;
+            };
+
+            let Some(range) = span_to_lsp_range(source, span) else {
+                continue;
+            };
+
+            changes
+                .entry(url.clone())
+                .or_default()
+                .push(lsp::TextEdit::new(range, new_name.to_owned()));
+        }
+
+        Some(lsp::WorkspaceEdit {
+            changes: Some(changes.into_iter().collect()),
+            ..Default::default()
+        })
+    }
+
+    /// Get the outline of the symbols declared in the given uri.
+    #[allow(deprecated)]
+    pub(super) fn document_symbols(&self, uri: &Url) -> Option<Vec<lsp::DocumentSymbol>> {
+        let source = self.workspace.get(uri)?;
+
+        let mut symbols = Vec::new();
+
+        for declaration in &source.index.declarations {
+            let (Some(range), Some(selection_range)) = (
+                source.span_to_lsp_range(declaration.span),
+                source.span_to_lsp_range(declaration.name_span),
+            ) else {
+                continue;
+            };
+
+            let symbol = lsp::DocumentSymbol {
+                name: declaration.name.clone(),
+                detail: None,
+                kind: symbols::symbol_kind(declaration.kind),
+                tags: None,
+                deprecated: None,
+                range,
+                selection_range,
+                children: None,
+            };
+
+            symbols.push((declaration.span, symbol));
+        }
+
+        Some(symbols::nest(symbols))
+    }
+
+    /// Search for symbols matching the given query across all sources in the
+    /// workspace.
+    #[allow(deprecated)]
+    pub(super) fn workspace_symbols(&self, query: &str) -> Vec<lsp::SymbolInformation> {
+        let mut results = Vec::new();
+
+        for (url, source) in &self.workspace.sources {
+            let declarations = &source.index.declarations;
+
+            for declaration in declarations {
+                if !symbols::fuzzy_match(query, &declaration.name) {
+                    continue;
+                }
+
+                let Some(range) = source.span_to_lsp_range(declaration.name_span) else {
+                    continue;
+                };
+
+                let container_name = declarations
+                    .iter()
+                    .filter(|c| {
+                        c.span != declaration.span && symbols::contains(c.span, declaration.span)
+                    })
+                    .min_by_key(|c| c.span.end.into_usize() - c.span.start.into_usize())
+                    .map(|c| c.name.clone());
+
+                results.push(lsp::SymbolInformation {
+                    name: declaration.name.clone(),
+                    kind: symbols::symbol_kind(declaration.kind),
+                    tags: None,
+                    deprecated: None,
+                    location: lsp::Location::new(url.clone(), range),
+                    container_name,
+                });
+            }
+        }
+
+        results
+    }
+
+    /// Get folding ranges for blocks and declarations in the given uri.
+    pub(super) fn folding_ranges(&self, uri: &Url) -> Option<Vec<lsp::FoldingRange>> {
+        let source = self.workspace.get(uri)?;
+        let index = &source.index;
+
+        let declarations =
+            index
+                .declarations
+                .iter()
+                .filter_map(|declaration| match declaration.kind {
+                    DeclarationKind::Function | DeclarationKind::Const => None,
+                    _ => Some(declaration.span),
+                });
+
+        let ranges = index
+            .blocks
+            .iter()
+            .copied()
+            .chain(declarations)
+            .filter_map(|span| source.span_to_lsp_range(span));
+
+        Some(symbols::folding_ranges(ranges))
+    }
+
+    /// Compute semantic tokens for the given uri, optionally limited to the
+    /// given range.
+    pub(super) fn semantic_tokens(
+        &self,
+        uri: &Url,
+        range: Option<lsp::Range>,
+    ) -> Option<lsp::SemanticTokens> {
+        let source = self.workspace.get(uri)?;
+        let data = semantic_tokens::tokens(&self.context, source, uri, range)?;
+
+        Some(lsp::SemanticTokens {
+            result_id: None,
+            data,
+        })
+    }
+
+    /// Get code actions fixing diagnostics in the given uri and LSP range.
+    pub(super) fn code_actions(
+        &self,
+        uri: &Url,
+        range: lsp::Range,
+        diagnostics: &[lsp::Diagnostic],
+    ) -> Option<Vec<lsp::CodeActionOrCommand>> {
+        let source = self.workspace.get(uri)?;
+        let (_, build_source) = source.build_source(uri)?;
+
+        // Fixes refer to spans in the built source, which can't be applied if
+        // the source has since been modified.
+        if build_source.as_str() != source.to_string() {
+            return None;
+        }
+
+        let mut actions = Vec::new();
+
+        for fix in &source.fixes {
+            let Some(fix_range) = span_to_lsp_range(build_source, fix.span) else {
+                continue;
+            };
+
+            if fix_range.end < range.start || fix_range.start > range.end {
+                continue;
+            }
+
+            let edits = fix
+                .edits
+                .iter()
+                .map(|(span, text)| {
+                    Some(lsp::TextEdit {
+                        range: span_to_lsp_range(build_source, *span)?,
+                        new_text: text.clone(),
+                    })
+                })
+                .collect::<Option<Vec<_>>>();
+
+            // NB: a fix is only offered if all of its edits can be applied.
+            let Some(edits) = edits else {
+                continue;
+            };
+
+            let diagnostics = diagnostics
+                .iter()
+                .filter(|d| d.range == fix_range)
+                .cloned()
+                .collect::<Vec<_>>();
+
+            actions.push(lsp::CodeActionOrCommand::CodeAction(lsp::CodeAction {
+                title: fix.title.clone(),
+                kind: Some(lsp::CodeActionKind::QUICKFIX),
+                diagnostics: (!diagnostics.is_empty()).then_some(diagnostics),
+                edit: Some(lsp::WorkspaceEdit::new(
+                    [(uri.clone(), edits)].into_iter().collect(),
+                )),
+                ..Default::default()
+            }));
+        }
+
+        Some(actions)
+    }
+
+    /// Get help for the signature of the call at the given uri and LSP
+    /// position.
+    pub(super) fn signature_help(
+        &self,
+        uri: &Url,
+        position: lsp::Position,
+    ) -> Option<lsp::SignatureHelp> {
+        let source = self.workspace.get(uri)?;
+        signature::signature_help(&self.context, source, uri, position)
+    }
+
+    /// Get inlay hints for the given uri and LSP range.
+    pub(super) fn inlay_hints(&self, uri: &Url, range: lsp::Range) -> Option<Vec<lsp::InlayHint>> {
+        let source = self.workspace.get(uri)?;
+        signature::inlay_hints(&self.context, source, uri, range)
+    }
+
     /// Find definition at the given uri and LSP position.
     #[tracing::instrument(skip_all)]
     pub(super) fn complete(
@@ -358,21 +690,23 @@ impl<'a> State<'a> {
 
         for (diagnostics, mut build, source_visitor, doc_visitor, unit) in script_results {
             build.populate(&mut reporter);
+            let mut fixes = code_action::fixes(&self.context, &build.sources, &diagnostics);
             emit_scripts(diagnostics, &build, &mut reporter);
 
             let sources = Arc::new(build.sources);
             let doc_visitor = Arc::new(doc_visitor);
 
-            for (source_id, value) in source_visitor.into_indexes() {
-                let Some(url) = build.id_to_url.get(&source_id) else {
-                    continue;
-                };
+            let (mut indexes, mut references) = source_visitor.into_indexes();
+            references.urls = build.id_to_url.clone();
+            let references = Arc::new(references);
 
+            for (source_id, url) in &build.id_to_url {
                 let Some(source) = self.workspace.sources.get_mut(url) else {
                     continue;
                 };
 
-                source.index = value;
+                source.index = indexes.remove(source_id).unwrap_or_default();
+                source.references = Some(references.clone());
                 source.build_sources = Some(sources.clone());
 
                 if let Ok(unit) = unit.as_ref().map(|v| v.clone()) {
@@ -380,6 +714,7 @@ impl<'a> State<'a> {
                 }
 
                 source.docs = Some(doc_visitor.clone());
+                source.fixes = fixes.remove(source_id).unwrap_or_default();
             }
         }
 
@@ -582,10 +917,12 @@ impl Workspace {
         let source = Source {
             content: Rope::from(text),
             index: Default::default(),
+            references: None,
             build_sources: None,
             language,
             unit: None,
             docs: None,
+            fixes: Vec::new(),
         };
         self.sources.insert(url, source)
     }
@@ -614,6 +951,8 @@ pub(super) struct Source {
     content: Rope,
     /// Indexes used to answer queries.
     index: Index,
+    /// Reverse index of references, shared by all sources in the same build.
+    references: Option<Arc<References>>,
     /// Loaded Rune sources for this source file. Will be present after the
     /// source file has been built.
     build_sources: Option<Arc<crate::Sources>>,
@@ -623,21 +962,67 @@ pub(super) struct Source {
     unit: Option<Unit>,
     /// Comments captured
     docs: Option<Arc<crate::doc::Visitor>>,
+    /// Fixes for diagnostics emitted for the source.
+    fixes: Vec<Fix>,
 }
 
 impl Source {
-    /// Find the definition at the given span.
-    pub(super) fn find_definition_at(&self, span: Span) -> Option<&Definition> {
-        let (found_span, definition) = self.index.definitions.range(..=span).next_back()?;
+    /// Find the definition at the given span, returning the span it was
+    /// found at.
+    pub(super) fn find_definition_at(&self, span: Span) -> Option<(Span, &Definition)> {
+        // NB: Include definitions which start at the same position but end
+        // after the span being looked up.
+        let end = Span::new(span.start, u32::MAX);
+        let (found_span, definition) = self.index.definitions.range(..=end).next_back()?;
 
         if span.start >= found_span.start && span.end <= found_span.end {
             tracing::trace!("found {:?}", definition);
-            return Some(definition);
+            return Some((*found_span, definition));
         }
 
         None
     }
 
+    /// Find the symbol at the given offset, either at one of its uses or at
+    /// its declaration, returning the span of its name.
+    fn find_symbol_at(&self, url: &Url, offset: usize) -> Option<(Symbol, Span)> {
+        let references = self.references.as_ref()?;
+        let sources = self.build_sources.as_ref()?;
+        let point = Span::point(offset);
+
+        if let Some((span, definition)) = self.find_definition_at(point) {
+            let symbol = match (&definition.item, &definition.source) {
+                (Some(item), _) => Some(Symbol::Item(item.hash)),
+                (None, DefinitionSource::Location(location)) => {
+                    Some(Symbol::Local(location.source_id, location.span))
+                }
+                _ => None,
+            };
+
+            if let Some(symbol) = symbol {
+                let source_id = references.source_id(url)?;
+                let span = references.name_span(sources, &symbol, source_id, span, false)?;
+                return Some((symbol, span));
+            }
+        }
+
+        let source_id = references.source_id(url)?;
+
+        references.declarations().find_map(|(symbol, location)| {
+            if location.source_id != source_id {
+                return None;
+            }
+
+            let span = references.name_span(sources, &symbol, source_id, location.span, true)?;
+
+            if point.start >= span.start && point.end <= span.end {
+                Some((symbol, span))
+            } else {
+                None
+            }
+        })
+    }
+
     /// Modify the given lsp range in the file.
     pub(super) fn modify_lsp_range(&mut self, range: lsp::Range, content: &str) -> Result<()> {
         let start = rope_utf16_position(&self.content, range.start)?;
@@ -651,6 +1036,12 @@ impl Source {
         Ok(())
     }
 
+    /// Convert an lsp position into a byte offset in the current content.
+    pub(super) fn lsp_position_to_byte(&self, position: lsp::Position) -> Option<usize> {
+        let char = rope_utf16_position(&self.content, position).ok()?;
+        self.content.try_char_to_byte(char).ok()
+    }
+
     /// Offset in the rope to lsp position.
     fn lsp_position_to_offset(&self, position: lsp::Position) -> usize {
         let line = self.content.line_to_char(position.line as usize);
@@ -658,6 +1049,23 @@ impl Source {
             .utf16_cu_to_char(line + position.character as usize)
     }
 
+    /// Convert a byte span in the current content into an lsp range.
+    fn span_to_lsp_range(&self, span: Span) -> Option<lsp::Range> {
+        let start = self.byte_to_lsp_position(span.start.into_usize())?;
+        let end = self.byte_to_lsp_position(span.end.into_usize())?;
+        Some(lsp::Range::new(start, end))
+    }
+
+    /// Convert a byte offset in the current content into an lsp position.
+    fn byte_to_lsp_position(&self, offset: usize) -> Option<lsp::Position> {
+        let char = self.content.try_byte_to_char(offset).ok()?;
+        let line = self.content.try_char_to_line(char).ok()?;
+        let line_start = self.content.try_line_to_char(line).ok()?;
+        let character = self.content.try_char_to_utf16_cu(char).ok()?
+            - self.content.try_char_to_utf16_cu(line_start).ok()?;
+        Some(lsp::Position::new(line as u32, character as u32))
+    }
+
     /// Iterate over the text chunks in the source.
     pub(super) fn chunks(&self) -> impl Iterator<Item = &str> {
         self.content.chunks()
@@ -681,11 +1089,82 @@ impl Source {
         })
     }
 
+    /// Get the source id and the built source corresponding to this source.
+    pub(super) fn build_source(&self, url: &Url) -> Option<(SourceId, &crate::Source)> {
+        let source_id = self.references.as_ref()?.source_id(url)?;
+        let source = self.build_sources.as_ref()?.get(source_id)?;
+        Some((source_id, source))
+    }
+
+    /// Iterate over all definitions, keyed by the span they are used at.
+    pub(super) fn definitions(&self) -> impl Iterator<Item = (Span, &Definition)> {
+        self.index
+            .definitions
+            .iter()
+            .map(|(span, definition)| (*span, definition))
+    }
+
+    /// The unit the source was last built into.
+    pub(super) fn unit(&self) -> Option<&Unit> {
+        self.unit.as_ref()
+    }
+
+    /// Items declared in the source.
+    pub(super) fn declarations(&self) -> &[Declaration] {
+        &self.index.declarations
+    }
+
+    /// Spans of blocks in the source.
+    pub(super) fn blocks(&self) -> &[Span] {
+        &self.index.blocks
+    }
+
+    /// Get the kind of the item with the given hash, if it is known to the
+    /// build this source is part of.
+    pub(super) fn item_kind(&self, hash: Hash) -> Option<DefinitionKind> {
+        self.references.as_ref()?.item_kind(hash)
+    }
+
     pub(super) fn get_docs_by_hash(&self, hash: crate::Hash) -> Option<&VisitorData> {
         self.docs.as_ref().and_then(|docs| docs.get_by_hash(hash))
     }
 }
 
+#[cfg(test)]
+impl Source {
+    /// Build a standalone source with the given text the same way a rebuild
+    /// does, so that queries can be tested against it.
+    pub(super) fn build(context: &Context, url: &Url, text: &str) -> Self {
+        let mut sources = crate::Sources::new();
+        let source_id = sources.insert(crate::Source::new(url, text));
+
+        let mut diagnostics = crate::Diagnostics::new();
+        let mut source_visitor = Visitor::default();
+        let mut doc_visitor = crate::doc::Visitor::new(ItemBuf::new()).unwrap();
+
+        let unit = crate::prepare(&mut sources)
+            .with_context(context)
+            .with_diagnostics(&mut diagnostics)
+            .with_visitor(&mut doc_visitor)
+            .with_visitor(&mut source_visitor)
+            .build();
+
+        let (mut indexes, mut references) = source_visitor.into_indexes();
+        references.urls.insert(source_id, url.clone());
+
+        Self {
+            content: Rope::from(text),
+            index: indexes.remove(&source_id).unwrap_or_default(),
+            references: Some(Arc::new(references)),
+            build_sources: Some(Arc::new(sources)),
+            language: Language::Rune,
+            unit: unit.ok(),
+            docs: Some(Arc::new(doc_visitor)),
+            fixes: Vec::new(),
+        }
+    }
+}
+
 impl fmt::Display for Source {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         write!(f, "{}", self.content)
@@ -815,6 +1294,201 @@ where
 pub(super) struct Index {
     /// Spans mapping to their corresponding definitions.
     definitions: BTreeMap<Span, Definition>,
+    /// Items declared in the source.
+    declarations: Vec<Declaration>,
+    /// Spans of blocks in the source.
+    blocks: Vec<Span>,
+}
+
+/// An item declared in a source.
+pub(super) struct Declaration {
+    /// The kind of the declaration.
+    pub(super) kind: DeclarationKind,
+    /// The name of the declaration.
+    pub(super) name: String,
+    /// The span of the whole declaration.
+    pub(super) span: Span,
+    /// The span of the name of the declaration.
+    pub(super) name_span: Span,
+}
+
+/// A symbol which can be referenced from multiple locations.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub(super) enum Symbol {
+    /// An item with the given hash.
+    Item(Hash),
+    /// A local variable declared at the given source and span.
+    Local(SourceId, Span),
+}
+
+/// Where a single symbol is declared and used.
+#[derive(Default)]
+struct Usages {
+    /// The name of the symbol, which is used to narrow down a location to the
+    /// span of the name. Local variables don't need it since their spans are
+    /// exact.
+    name: Option<String>,
+    /// The kind of the symbol, if it is an item.
+    kind: Option<DefinitionKind>,
+    /// Where the symbol is declared, if it is declared in a source.
+    declaration: Option<Location>,
+    /// The sources and spans where the symbol is used.
+    uses: BTreeSet<(SourceId, Span)>,
+}
+
+/// A reverse index from symbols to the locations they are referenced from,
+/// shared by all sources in a single build.
+#[derive(Default)]
+pub(super) struct References {
+    /// Usages by symbol.
+    symbols: HashMap<Symbol, Usages>,
+    /// The urls of sources in the build.
+    urls: HashMap<SourceId, Url>,
+    /// The name spans of declarations, by the source and span of the whole
+    /// declaration.
+    names: HashMap<(SourceId, Span), Span>,
+}
+
+impl References {
+    /// Get the kind of the item with the given hash, if it is referenced or
+    /// declared in the build.
+    fn item_kind(&self, hash: Hash) -> Option<DefinitionKind> {
+        self.symbols.get(&Symbol::Item(hash))?.kind
+    }
+
+    /// Get the source id of the given url.
+    fn source_id(&self, url: &Url) -> Option<SourceId> {
+        self.urls
+            .iter()
+            .find(|(_, u)| *u == url)
+            .map(|(source_id, _)| *source_id)
+    }
+
+    /// Iterate over all symbols which are declared in a source.
+    fn declarations(&self) -> impl Iterator<Item = (Symbol, Location)> + '_ {
+        self.symbols.iter().filter_map(|(symbol, usages)| {
+            let location = match *symbol {
+                Symbol::Item(..) => usages.declaration?,
+                Symbol::Local(source_id, span) => Location::new(source_id, span),
+            };
+
+            Some((*symbol, location))
+        })
+    }
+
+    /// Test if the given symbol can be renamed, which is only the case if we
+    /// know where it is declared.
+    fn is_renameable(&self, symbol: &Symbol) -> bool {
+        match symbol {
+            Symbol::Item(..) => self
+                .symbols
+                .get(symbol)
+                .is_some_and(|usages| usages.declaration.is_some()),
+            Symbol::Local(..) => true,
+        }
+    }
+
+    /// Narrow down a span where the given symbol is referenced to the span of
+    /// its name.
+    ///
+    /// Declarations use the name span recorded while indexing. Paths are
+    /// matched by the last identifier token with the name, so that names in
+    /// comments and strings are never touched.
+    fn name_span(
+        &self,
+        sources: &crate::Sources,
+        symbol: &Symbol,
+        source_id: SourceId,
+        span: Span,
+        declaration: bool,
+    ) -> Option<Span> {
+        let Some(name) = self.symbols.get(symbol).and_then(|u| u.name.as_deref()) else {
+            return Some(span);
+        };
+
+        if declaration {
+            if let Some(name_span) = self.names.get(&(source_id, span)) {
+                return Some(*name_span);
+            }
+        }
+
+        let text = sources.source(source_id, span)?;
+        let offset = find_name(text, name, declaration)?;
+        let start = span.start.into_usize() + offset;
+        Some(Span::new(start, start + name.len()))
+    }
+
+    /// Collect the sources and name spans of every reference to the given
+    /// symbol.
+    fn locations(
+        &self,
+        sources: &crate::Sources,
+        symbol: &Symbol,
+        include_declaration: bool,
+    ) -> BTreeSet<(SourceId, Span)> {
+        let mut output = BTreeSet::new();
+
+        let Some(usages) = self.symbols.get(symbol) else {
+            return output;
+        };
+
+        if include_declaration {
+            let declaration = match symbol {
+                Symbol::Item(..) => usages.declaration.as_ref().and_then(|location| {
+                    let span =
+                        self.name_span(sources, symbol, location.source_id, location.span, true)?;
+                    Some((location.source_id, span))
+                }),
+                Symbol::Local(source_id, span) => Some((*source_id, *span)),
+            };
+
+            output.extend(declaration);
+        }
+
+        for &(source_id, span) in &usages.uses {
+            if let Some(span) = self.name_span(sources, symbol, source_id, span, false) {
+                output.insert((source_id, span));
+            }
+        }
+
+        output
+    }
+}
+
+/// Find the byte offset of the given name in the text as an identifier
+/// token, which skips over comments and strings. Either the first or the last
+/// occurence is returned.
+fn find_name(text: &str, name: &str, first: bool) -> Option<usize> {
+    let mut lexer = Lexer::new(text, SourceId::empty(), false);
+    let mut found = None;
+
+    while let Ok(Some(token)) = lexer.next() {
+        if !matches!(token.kind, ast::Kind::Ident(..)) || text.get(token.span.range()) != Some(name)
+        {
+            continue;
+        }
+
+        found = Some(token.span.start.into_usize());
+
+        if first {
+            break;
+        }
+    }
+
+    found
+}
+
+/// Test if the given string is a valid identifier which is not a keyword.
+pub(super) fn is_identifier(name: &str) -> bool {
+    let mut chars = name.chars();
+
+    let Some(c) = chars.next() else {
+        return false;
+    };
+
+    (c == '_' || c.is_alphabetic())
+        && chars.all(|c| c == '_' || c.is_alphanumeric())
+        && crate::ast::Kind::from_keyword(name).is_none()
 }
 
 /// A definition source.
@@ -826,22 +1500,25 @@ pub(super) enum DefinitionSource {
     Location(Location),
     /// A complete compile source.
     SourceMeta(SourceMeta),
+    /// A native item provided by the context, which has no source.
+    Context,
 }
 
 impl DefinitionSource {
     fn span(&self) -> Span {
         match self {
-            Self::Source(..) => Span::empty(),
+            Self::Source(..) | Self::Context => Span::empty(),
             Self::Location(location) => location.span,
             Self::SourceMeta(compile_source) => compile_source.location.span,
         }
     }
 
-    fn source_id(&self) -> SourceId {
+    fn source_id(&self) -> Option<SourceId> {
         match self {
-            Self::Source(source_id) => *source_id,
-            Self::Location(location) => location.source_id,
-            Self::SourceMeta(compile_source) => compile_source.location.source_id,
+            Self::Source(source_id) => Some(*source_id),
+            Self::Location(location) => Some(location.source_id),
+            Self::SourceMeta(compile_source) => Some(compile_source.location.source_id),
+            Self::Context => None,
         }
     }
 
@@ -859,6 +1536,17 @@ pub(super) struct Definition {
     pub(super) kind: DefinitionKind,
     /// The id of the source id the definition corresponds to.
     pub(super) source: DefinitionSource,
+    /// The item the definition refers to, if any.
+    pub(super) item: Option<DefinitionItem>,
+}
+
+/// The item a definition refers to.
+#[derive(Debug, Clone)]
+pub(super) struct DefinitionItem {
+    /// The item being referred to.
+    pub(super) item: ItemBuf,
+    /// The hash of the item.
+    pub(super) hash: Hash,
 }
 
 #[derive(Debug, Clone, Copy)]
@@ -890,65 +1578,116 @@ pub(super) enum DefinitionKind {
 #[derive(Default)]
 struct Visitor {
     indexes: HashMap<SourceId, Index>,
+    references: References,
 }
 
 impl Visitor {
-    /// Convert visitor back into an index.
-    pub(super) fn into_indexes(self) -> HashMap<SourceId, Index> {
-        self.indexes
+    /// Convert visitor back into indexes and the reverse index of references.
+    pub(super) fn into_indexes(self) -> (HashMap<SourceId, Index>, References) {
+        (self.indexes, self.references)
     }
 }
 
+/// Get the kind of definition corresponding to the given meta, if it is one
+/// we keep track of.
+fn definition_kind(kind: &meta::Kind) -> Option<DefinitionKind> {
+    let kind = match kind {
+        meta::Kind::Struct {
+            fields: meta::Fields::Empty,
+            ..
+        } => DefinitionKind::EmptyStruct,
+        meta::Kind::Struct {
+            fields: meta::Fields::Unnamed(..),
+            ..
+        } => DefinitionKind::TupleStruct,
+        meta::Kind::Struct {
+            fields: meta::Fields::Named(..),
+            ..
+        } => DefinitionKind::Struct,
+        meta::Kind::Variant {
+            fields: meta::Fields::Empty,
+            ..
+        } => DefinitionKind::UnitVariant,
+        meta::Kind::Variant {
+            fields: meta::Fields::Unnamed(..),
+            ..
+        } => DefinitionKind::TupleVariant,
+        meta::Kind::Variant {
+            fields: meta::Fields::Named(..),
+            ..
+        } => DefinitionKind::StructVariant,
+        meta::Kind::Enum { .. } => DefinitionKind::Enum,
+        meta::Kind::Function {
+            associated: None, ..
+        } => DefinitionKind::Function,
+        meta::Kind::Function {
+            associated: Some(..),
+            ..
+        } => DefinitionKind::AssociatedFunction,
+        _ => return None,
+    };
+
+    Some(kind)
+}
+
 impl CompileVisitor for Visitor {
+    fn register_meta(&mut self, meta: MetaRef<'_>) -> Result<(), MetaError> {
+        let Some(source) = meta.source else {
+            return Ok(());
+        };
+
+        let Some(kind) = definition_kind(meta.kind) else {
+            return Ok(());
+        };
+
+        let usages = self
+            .references
+            .symbols
+            .entry(Symbol::Item(meta.hash))
+            .or_default();
+
+        usages.name = meta.item.last().map(|name| name.to_string());
+        usages.kind = Some(kind);
+        usages.declaration = Some(source.location);
+        Ok(())
+    }
+
     fn visit_meta(&mut self, location: &dyn Located, meta: MetaRef<'_>) {
         let source = match meta.source {
-            Some(source) => source,
+            Some(source) => DefinitionSource::SourceMeta(source.clone()),
+            None if meta.context => DefinitionSource::Context,
             None => return,
         };
 
-        let kind = match &meta.kind {
-            meta::Kind::Struct {
-                fields: meta::Fields::Empty,
-                ..
-            } => DefinitionKind::EmptyStruct,
-            meta::Kind::Struct {
-                fields: meta::Fields::Unnamed(..),
-                ..
-            } => DefinitionKind::TupleStruct,
-            meta::Kind::Struct {
-                fields: meta::Fields::Named(..),
-                ..
-            } => DefinitionKind::Struct,
-            meta::Kind::Variant {
-                fields: meta::Fields::Empty,
-                ..
-            } => DefinitionKind::UnitVariant,
-            meta::Kind::Variant {
-                fields: meta::Fields::Unnamed(..),
-                ..
-            } => DefinitionKind::TupleVariant,
-            meta::Kind::Variant {
-                fields: meta::Fields::Named(..),
-                ..
-            } => DefinitionKind::StructVariant,
-            meta::Kind::Enum { .. } => DefinitionKind::Enum,
-            meta::Kind::Function {
-                associated: None, ..
-            } => DefinitionKind::Function,
-            meta::Kind::Function {
-                associated: Some(..),
-                ..
-            } => DefinitionKind::AssociatedFunction,
-            _ => return,
+        let Some(kind) = definition_kind(meta.kind) else {
+            return;
         };
 
+        let location = location.location();
+
+        let usages = self
+            .references
+            .symbols
+            .entry(Symbol::Item(meta.hash))
+            .or_default();
+
+        if usages.name.is_none() {
+            usages.name = meta.item.last().map(|name| name.to_string());
+        }
+
+        usages.kind = Some(kind);
+
+        usages.uses.insert((location.source_id, location.span));
+
         let definition = Definition {
             kind,
-            source: DefinitionSource::SourceMeta(source.clone()),
+            source,
+            item: Some(DefinitionItem {
+                item: meta.item.to_owned(),
+                hash: meta.hash,
+            }),
         };
 
-        let location = location.location();
-
         let index = self.indexes.entry(location.source_id).or_default();
 
         if let Some(d) = index.definitions.insert(location.span, definition) {
@@ -965,8 +1704,16 @@ impl CompileVisitor for Visitor {
         let definition = Definition {
             kind: DefinitionKind::Local,
             source: DefinitionSource::Location(Location::new(source_id, var_span.span())),
+            item: None,
         };
 
+        self.references
+            .symbols
+            .entry(Symbol::Local(source_id, var_span.span()))
+            .or_default()
+            .uses
+            .insert((source_id, span.span()));
+
         let index = self.indexes.entry(source_id).or_default();
 
         if let Some(d) = index.definitions.insert(span.span(), definition) {
@@ -980,6 +1727,7 @@ impl CompileVisitor for Visitor {
         let definition = Definition {
             kind: DefinitionKind::Module,
             source: DefinitionSource::Source(location.source_id),
+            item: None,
         };
 
         let index = self.indexes.entry(location.source_id).or_default();
@@ -988,6 +1736,35 @@ impl CompileVisitor for Visitor {
             tracing::warn!("replaced definition: {:?}", d.kind)
         }
     }
+
+    fn visit_declaration(
+        &mut self,
+        location: &dyn Located,
+        kind: DeclarationKind,
+        name: &str,
+        name_span: &dyn Spanned,
+    ) {
+        let location = location.location();
+
+        self.references
+            .names
+            .insert((location.source_id, location.span), name_span.span());
+
+        let index = self.indexes.entry(location.source_id).or_default();
+
+        index.declarations.push(Declaration {
+            kind,
+            name: name.to_owned(),
+            span: location.span,
+            name_span: name_span.span(),
+        });
+    }
+
+    fn visit_block(&mut self, location: &dyn Located) {
+        let location = location.location();
+        let index = self.indexes.entry(location.source_id).or_default();
+        index.blocks.push(location.span);
+    }
 }
 
 struct ScriptSourceLoader<'a> {
@@ -1091,3 +1868,113 @@ impl<'a> workspace::SourceLoader for WorkspaceSourceLoader<'a> {
         self.base.load(span, path)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::no_std::prelude::*;
+
+    use crate::ast::Span;
+    use crate::{Context, Hash, Source, Sources};
+
+    use super::{find_name, References, Symbol, Visitor};
+
+    /// Build the given source and collect its references.
+    fn references(source: &str) -> (Sources, References) {
+        let context = Context::default();
+        let mut sources = Sources::new();
+        sources.insert(Source::memory(source));
+
+        let mut visitor = Visitor::default();
+
+        crate::prepare(&mut sources)
+            .with_context(&context)
+            .with_visitor(&mut visitor)
+            .build()
+            .unwrap();
+
+        let (_, references) = visitor.into_indexes();
+        (sources, references)
+    }
+
+    /// Collect the spans of every reference to the item with the given name.
+    fn locations(source: &str, name: &str, include_declaration: bool) -> Vec<Span> {
+        let (sources, references) = references(source);
+        let symbol = Symbol::Item(Hash::type_hash([name]));
+
+        if include_declaration {
+            assert!(references.is_renameable(&symbol));
+        }
+
+        references
+            .locations(&sources, &symbol, include_declaration)
+            .into_iter()
+            .map(|(_, span)| span)
+            .collect()
+    }
+
+    /// The span of the name at the given offset.
+    fn at(offset: usize, name: &str) -> Span {
+        Span::new(offset, offset + name.len())
+    }
+
+    #[test]
+    fn test_find_name() {
+        assert_eq!(find_name("foo::bar::foo", "foo", true), Some(0));
+        assert_eq!(find_name("foo::bar::foo", "foo", false), Some(10));
+        assert_eq!(find_name("foobar(foo_bar)", "foo", true), None);
+        assert_eq!(find_name("\"foo\" // foo\nfoo", "foo", true), Some(13));
+        assert_eq!(
+            find_name("/* foo */ foo \"http://\"", "foo", false),
+            Some(10)
+        );
+    }
+
+    #[test]
+    fn test_references() {
+        let source = r#"
+        fn foo() { "foo" }
+
+        pub fn main() {
+            let url = "http://foo"; foo(); // foo
+            /* foo */ foo()
+        }
+        "#;
+
+        let declaration = source.find("foo()").unwrap();
+        let first = source.find("foo();").unwrap();
+        let second = source.rfind("foo()").unwrap();
+
+        assert_eq!(
+            locations(source, "foo", false),
+            [at(first, "foo"), at(second, "foo")]
+        );
+
+        assert_eq!(
+            locations(source, "foo", true),
+            [at(declaration, "foo"), at(first, "foo"), at(second, "foo")]
+        );
+    }
+
+    #[test]
+    fn test_rename_declaration() {
+        // The span of the declaration covers its doc comment, which mentions
+        // the name first.
+        let source = r#"
+        /// Construct a new Point.
+        struct Point { x, y }
+
+        pub fn main() {
+            let p = Point { x: 1, y: 2 };
+            p.x + p.y
+        }
+        "#;
+
+        let declaration = source.find("Point {").unwrap();
+        let usage = source.rfind("Point {").unwrap();
+
+        assert_eq!(
+            locations(source, "Point", true),
+            [at(declaration, "Point"), at(usage, "Point")]
+        );
+    }
+}
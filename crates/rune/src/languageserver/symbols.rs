@@ -0,0 +1,195 @@
+use std::collections::BTreeMap;
+
+use crate::no_std::prelude::*;
+
+use crate::ast::Span;
+use crate::compile::DeclarationKind;
+
+/// Convert the kind of a declaration into a symbol kind.
+pub(super) fn symbol_kind(kind: DeclarationKind) -> lsp::SymbolKind {
+    match kind {
+        DeclarationKind::Function => lsp::SymbolKind::FUNCTION,
+        DeclarationKind::Struct => lsp::SymbolKind::STRUCT,
+        DeclarationKind::Enum => lsp::SymbolKind::ENUM,
+        DeclarationKind::Variant => lsp::SymbolKind::ENUM_MEMBER,
+        DeclarationKind::Const => lsp::SymbolKind::CONSTANT,
+        DeclarationKind::Impl => lsp::SymbolKind::OBJECT,
+        DeclarationKind::Trait => lsp::SymbolKind::INTERFACE,
+        DeclarationKind::Module => lsp::SymbolKind::MODULE,
+    }
+}
+
+/// Test if the `outer` span fully contains the `inner` span.
+pub(super) fn contains(outer: Span, inner: Span) -> bool {
+    outer.start <= inner.start && inner.end <= outer.end
+}
+
+/// Nest symbols under the symbols whose span contains them.
+pub(super) fn nest(mut symbols: Vec<(Span, lsp::DocumentSymbol)>) -> Vec<lsp::DocumentSymbol> {
+    // Outer symbols sort before the symbols they contain.
+    symbols.sort_by(|(a, _), (b, _)| a.start.cmp(&b.start).then(b.end.cmp(&a.end)));
+
+    let mut roots = Vec::new();
+    let mut stack = Vec::<(Span, lsp::DocumentSymbol)>::new();
+
+    for (span, symbol) in symbols {
+        while let Some((parent, _)) = stack.last() {
+            if contains(*parent, span) {
+                break;
+            }
+
+            if let Some((_, done)) = stack.pop() {
+                push_child(&mut stack, &mut roots, done);
+            }
+        }
+
+        stack.push((span, symbol));
+    }
+
+    while let Some((_, done)) = stack.pop() {
+        push_child(&mut stack, &mut roots, done);
+    }
+
+    roots
+}
+
+fn push_child(
+    stack: &mut [(Span, lsp::DocumentSymbol)],
+    roots: &mut Vec<lsp::DocumentSymbol>,
+    symbol: lsp::DocumentSymbol,
+) {
+    match stack.last_mut() {
+        Some((_, parent)) => parent.children.get_or_insert_with(Vec::new).push(symbol),
+        None => roots.push(symbol),
+    }
+}
+
+/// Fuzzy match a symbol name against a query, where every character in the
+/// query has to appear in order in the name, ignoring case.
+pub(super) fn fuzzy_match(query: &str, name: &str) -> bool {
+    let mut name = name.chars().flat_map(char::to_lowercase);
+
+    query
+        .chars()
+        .flat_map(char::to_lowercase)
+        .all(|q| name.any(|c| c == q))
+}
+
+/// Collect folding ranges from the given ranges, skipping ranges which only
+/// cover a single line and keeping the largest range which starts on any
+/// given line.
+pub(super) fn folding_ranges<I>(ranges: I) -> Vec<lsp::FoldingRange>
+where
+    I: IntoIterator<Item = lsp::Range>,
+{
+    let mut by_line = BTreeMap::<u32, u32>::new();
+
+    for range in ranges {
+        if range.end.line <= range.start.line {
+            continue;
+        }
+
+        let end = by_line.entry(range.start.line).or_default();
+        *end = (*end).max(range.end.line);
+    }
+
+    by_line
+        .into_iter()
+        .map(|(start_line, end_line)| lsp::FoldingRange {
+            start_line,
+            start_character: None,
+            end_line,
+            end_character: None,
+            kind: Some(lsp::FoldingRangeKind::Region),
+            collapsed_text: None,
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::no_std::prelude::*;
+
+    use crate::ast::Span;
+
+    use super::{folding_ranges, fuzzy_match, nest};
+
+    fn symbol(name: &str) -> lsp::DocumentSymbol {
+        #[allow(deprecated)]
+        lsp::DocumentSymbol {
+            name: name.to_owned(),
+            detail: None,
+            kind: lsp::SymbolKind::FUNCTION,
+            tags: None,
+            deprecated: None,
+            range: lsp::Range::default(),
+            selection_range: lsp::Range::default(),
+            children: None,
+        }
+    }
+
+    /// Flatten nested symbols into their names, with children in brackets.
+    fn names(symbols: &[lsp::DocumentSymbol]) -> String {
+        let mut names = Vec::new();
+
+        for symbol in symbols {
+            match &symbol.children {
+                Some(children) => names.push(format!("{}[{}]", symbol.name, self::names(children))),
+                None => names.push(symbol.name.clone()),
+            }
+        }
+
+        names.join(", ")
+    }
+
+    fn range(start: u32, end: u32) -> lsp::Range {
+        lsp::Range::new(lsp::Position::new(start, 0), lsp::Position::new(end, 1))
+    }
+
+    #[test]
+    fn test_nest() {
+        let symbols = vec![
+            (Span::new(40, 50), symbol("c")),
+            (Span::new(10, 20), symbol("b")),
+            (Span::new(0, 30), symbol("a")),
+            (Span::new(12, 18), symbol("d")),
+            (Span::new(60, 70), symbol("e")),
+            // Symbols with the same start nest the shorter one.
+            (Span::new(60, 65), symbol("f")),
+        ];
+
+        assert_eq!(names(&nest(symbols)), "a[b[d]], c, e[f]");
+        assert!(nest(Vec::new()).is_empty());
+    }
+
+    #[test]
+    fn test_fuzzy_match() {
+        assert!(fuzzy_match("", "anything"));
+        assert!(fuzzy_match("fb", "foo_bar"));
+        assert!(fuzzy_match("FOOB", "foo_bar"));
+        assert!(fuzzy_match("hm", "HashMap"));
+        assert!(!fuzzy_match("bf", "foo_bar"));
+        assert!(!fuzzy_match("foo_barr", "foo_bar"));
+    }
+
+    #[test]
+    fn test_folding_ranges() {
+        let ranges = [
+            // Single line ranges can't be folded.
+            range(0, 0),
+            range(1, 4),
+            // The largest range starting on a line is kept.
+            range(2, 3),
+            range(2, 6),
+            range(2, 5),
+            range(8, 9),
+        ];
+
+        let folded = folding_ranges(ranges)
+            .into_iter()
+            .map(|range| (range.start_line, range.end_line))
+            .collect::<Vec<_>>();
+
+        assert_eq!(folded, [(1, 4), (2, 6), (8, 9)]);
+    }
+}
@@ -188,15 +188,12 @@ pub(crate) struct AssociatedKey {
 pub(crate) struct ModuleFunction {
     pub(crate) item: ItemBuf,
     pub(crate) handler: Arc<FunctionHandler>,
-    #[cfg(feature = "doc")]
     pub(crate) is_async: bool,
     #[cfg(feature = "doc")]
     pub(crate) deprecated: Option<Box<str>>,
     #[cfg(feature = "doc")]
     pub(crate) args: Option<usize>,
-    #[cfg(feature = "doc")]
     pub(crate) return_type: Option<FullTypeOf>,
-    #[cfg(feature = "doc")]
     pub(crate) argument_types: Box<[Option<FullTypeOf>]>,
     pub(crate) docs: Docs,
 }
@@ -207,15 +204,12 @@ pub(crate) struct ModuleAssociated {
     pub(crate) container_type_info: TypeInfo,
     pub(crate) name: AssociatedFunctionName,
     pub(crate) handler: Arc<FunctionHandler>,
-    #[cfg(feature = "doc")]
     pub(crate) is_async: bool,
     #[cfg(feature = "doc")]
     pub(crate) deprecated: Option<Box<str>>,
     #[cfg(feature = "doc")]
     pub(crate) args: Option<usize>,
-    #[cfg(feature = "doc")]
     pub(crate) return_type: Option<FullTypeOf>,
-    #[cfg(feature = "doc")]
     pub(crate) argument_types: Box<[Option<FullTypeOf>]>,
     pub(crate) docs: Docs,
 }
@@ -291,15 +285,12 @@ impl fmt::Debug for ItemMut<'_> {
 /// * [`Module::function_meta`].
 pub struct ItemFnMut<'a> {
     docs: &'a mut Docs,
-    #[cfg(feature = "doc")]
     is_async: &'a mut bool,
     #[cfg(feature = "doc")]
     deprecated: &'a mut Option<Box<str>>,
     #[cfg(feature = "doc")]
     args: &'a mut Option<usize>,
-    #[cfg(feature = "doc")]
     return_type: &'a mut Option<FullTypeOf>,
-    #[cfg(feature = "doc")]
     argument_types: &'a mut Box<[Option<FullTypeOf>]>,
 }
 
@@ -317,12 +308,8 @@ impl ItemFnMut<'_> {
     }
 
     /// Mark the given item as an async function.
-    pub fn is_async(self, #[cfg_attr(not(feature = "doc"), allow(unused))] is_async: bool) -> Self {
-        #[cfg(feature = "doc")]
-        {
-            *self.is_async = is_async;
-        }
-
+    pub fn is_async(self, is_async: bool) -> Self {
+        *self.is_async = is_async;
         self
     }
 
@@ -357,24 +344,13 @@ impl ItemFnMut<'_> {
     where
         T: MaybeTypeOf,
     {
-        #[cfg(feature = "doc")]
-        {
-            *self.return_type = T::maybe_type_of();
-        }
-
+        *self.return_type = T::maybe_type_of();
         self
     }
 
     /// Set argument types.
-    pub fn argument_types<const N: usize>(
-        self,
-        #[cfg_attr(not(feature = "doc"), allow(unused))] arguments: [Option<FullTypeOf>; N],
-    ) -> Self {
-        #[cfg(feature = "doc")]
-        {
-            *self.argument_types = Box::from(arguments.into_iter().collect::<Vec<_>>());
-        }
-
+    pub fn argument_types<const N: usize>(self, arguments: [Option<FullTypeOf>; N]) -> Self {
+        *self.argument_types = Box::from(arguments.into_iter().collect::<Vec<_>>());
         self
     }
 }
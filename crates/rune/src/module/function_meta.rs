@@ -51,15 +51,12 @@ pub type MacroMeta = fn() -> MacroMetaData;
 pub struct FunctionData {
     pub(crate) item: ItemBuf,
     pub(crate) handler: Arc<FunctionHandler>,
-    #[cfg(feature = "doc")]
     pub(crate) is_async: bool,
     #[cfg(feature = "doc")]
     pub(crate) deprecated: Option<Box<str>>,
     #[cfg(feature = "doc")]
     pub(crate) args: Option<usize>,
-    #[cfg(feature = "doc")]
     pub(crate) return_type: Option<FullTypeOf>,
-    #[cfg(feature = "doc")]
     pub(crate) argument_types: Box<[Option<FullTypeOf>]>,
 }
 
@@ -77,15 +74,12 @@ impl FunctionData {
         Self {
             item: ItemBuf::with_item(name),
             handler: Arc::new(move |stack, args| f.fn_call(stack, args)),
-            #[cfg(feature = "doc")]
             is_async: K::is_async(),
             #[cfg(feature = "doc")]
             deprecated: None,
             #[cfg(feature = "doc")]
             args: Some(F::args()),
-            #[cfg(feature = "doc")]
             return_type: F::Return::maybe_type_of(),
-            #[cfg(feature = "doc")]
             argument_types: A::into_box(),
         }
     }
@@ -213,15 +207,12 @@ pub struct AssociatedFunctionData {
     pub(crate) handler: Arc<FunctionHandler>,
     pub(crate) container: FullTypeOf,
     pub(crate) container_type_info: TypeInfo,
-    #[cfg(feature = "doc")]
     pub(crate) is_async: bool,
     #[cfg(feature = "doc")]
     pub(crate) deprecated: Option<Box<str>>,
     #[cfg(feature = "doc")]
     pub(crate) args: Option<usize>,
-    #[cfg(feature = "doc")]
     pub(crate) return_type: Option<FullTypeOf>,
-    #[cfg(feature = "doc")]
     pub(crate) argument_types: Box<[Option<FullTypeOf>]>,
 }
 
@@ -239,15 +230,12 @@ impl AssociatedFunctionData {
             handler: Arc::new(move |stack, args| f.fn_call(stack, args)),
             container: F::Instance::type_of(),
             container_type_info: F::Instance::type_info(),
-            #[cfg(feature = "doc")]
             is_async: K::is_async(),
             #[cfg(feature = "doc")]
             deprecated: None,
             #[cfg(feature = "doc")]
             args: Some(F::args()),
-            #[cfg(feature = "doc")]
             return_type: F::Return::maybe_type_of(),
-            #[cfg(feature = "doc")]
             argument_types: A::into_box(),
         }
     }
@@ -342,15 +330,12 @@ where
             handler: Arc::new(move |stack, args| self.f.fn_call(stack, args)),
             container: T::type_of(),
             container_type_info: T::type_info(),
-            #[cfg(feature = "doc")]
             is_async: K::is_async(),
             #[cfg(feature = "doc")]
             deprecated: None,
             #[cfg(feature = "doc")]
             args: Some(F::args()),
-            #[cfg(feature = "doc")]
             return_type: F::Return::maybe_type_of(),
-            #[cfg(feature = "doc")]
             argument_types: A::into_box(),
         })
     }
@@ -1220,15 +1220,12 @@ impl Module {
         self.functions.push(ModuleFunction {
             item,
             handler: Arc::new(move |stack, args| f(stack, args)),
-            #[cfg(feature = "doc")]
             is_async: false,
             #[cfg(feature = "doc")]
             deprecated: None,
             #[cfg(feature = "doc")]
             args: None,
-            #[cfg(feature = "doc")]
             return_type: None,
-            #[cfg(feature = "doc")]
             argument_types: Box::from([]),
             docs: Docs::EMPTY,
         });
@@ -1237,15 +1234,12 @@ impl Module {
 
         Ok(ItemFnMut {
             docs: &mut last.docs,
-            #[cfg(feature = "doc")]
             is_async: &mut last.is_async,
             #[cfg(feature = "doc")]
             deprecated: &mut last.deprecated,
             #[cfg(feature = "doc")]
             args: &mut last.args,
-            #[cfg(feature = "doc")]
             return_type: &mut last.return_type,
-            #[cfg(feature = "doc")]
             argument_types: &mut last.argument_types,
         })
     }
@@ -1267,15 +1261,12 @@ impl Module {
         self.functions.push(ModuleFunction {
             item: data.item,
             handler: data.handler,
-            #[cfg(feature = "doc")]
             is_async: data.is_async,
             #[cfg(feature = "doc")]
             deprecated: data.deprecated,
             #[cfg(feature = "doc")]
             args: data.args,
-            #[cfg(feature = "doc")]
             return_type: data.return_type,
-            #[cfg(feature = "doc")]
             argument_types: data.argument_types,
             docs,
         });
@@ -1284,15 +1275,12 @@ impl Module {
 
         Ok(ItemFnMut {
             docs: &mut last.docs,
-            #[cfg(feature = "doc")]
             is_async: &mut last.is_async,
             #[cfg(feature = "doc")]
             deprecated: &mut last.deprecated,
             #[cfg(feature = "doc")]
             args: &mut last.args,
-            #[cfg(feature = "doc")]
             return_type: &mut last.return_type,
-            #[cfg(feature = "doc")]
             argument_types: &mut last.argument_types,
         })
     }
@@ -1337,15 +1325,12 @@ impl Module {
             container_type_info: data.container_type_info,
             name: data.name,
             handler: data.handler,
-            #[cfg(feature = "doc")]
             is_async: data.is_async,
             #[cfg(feature = "doc")]
             deprecated: data.deprecated,
             #[cfg(feature = "doc")]
             args: data.args,
-            #[cfg(feature = "doc")]
             return_type: data.return_type,
-            #[cfg(feature = "doc")]
             argument_types: data.argument_types,
             docs,
         });
@@ -1354,15 +1339,12 @@ impl Module {
 
         Ok(ItemFnMut {
             docs: &mut last.docs,
-            #[cfg(feature = "doc")]
             is_async: &mut last.is_async,
             #[cfg(feature = "doc")]
             deprecated: &mut last.deprecated,
             #[cfg(feature = "doc")]
             args: &mut last.args,
-            #[cfg(feature = "doc")]
             return_type: &mut last.return_type,
-            #[cfg(feature = "doc")]
             argument_types: &mut last.argument_types,
         })
     }
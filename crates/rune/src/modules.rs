@@ -5,6 +5,7 @@
 //! [`Context::with_default_modules`][crate::Context::with_default_modules].
 
 pub mod any;
+pub mod bigint;
 pub mod bytes;
 #[cfg(feature = "capture-io")]
 pub mod capture_io;
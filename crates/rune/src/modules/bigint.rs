@@ -0,0 +1,276 @@
+//! The `std::bigint` module.
+
+use core::cmp::Ordering;
+
+use num::bigint::ParseBigIntError;
+use num::{BigInt, Signed, ToPrimitive};
+
+use crate as rune;
+use crate::alloc;
+use crate::alloc::string::TryToString;
+use crate::runtime::{bigint_limit, VmResult};
+use crate::{ContextError, Module};
+
+/// Construct the `std::bigint` module.
+pub fn module() -> Result<Module, ContextError> {
+    let mut module = Module::with_crate_item("std", ["bigint"]);
+
+    module.ty::<BigInt>()?;
+    module.ty::<ParseBigIntError>()?;
+
+    module.function_meta(parse)?;
+    module.function_meta(to_integer)?;
+    module.function_meta(to_float)?;
+
+    module.function_meta(abs)?;
+    module.function_meta(pow)?;
+    module.function_meta(bits)?;
+
+    module.function_meta(signum)?;
+    module.function_meta(is_positive)?;
+    module.function_meta(is_negative)?;
+
+    module.function_meta(partial_eq)?;
+    module.function_meta(eq)?;
+    module.function_meta(partial_cmp)?;
+    module.function_meta(cmp)?;
+    module.function_meta(to_string)?;
+    Ok(module)
+}
+
+crate::__internal_impl_any!(::std::bigint, ParseBigIntError);
+
+/// Parse a `BigInt` from its decimal representation.
+///
+/// # Examples
+///
+/// ```rune
+/// let n = BigInt::parse("18446744073709551616")?;
+/// assert_eq!(n, 18446744073709551616n);
+/// assert!(BigInt::parse("nope").is_err());
+/// ```
+#[rune::function(free, path = BigInt::parse)]
+fn parse(s: &str) -> Result<BigInt, ParseBigIntError> {
+    str::parse::<BigInt>(s)
+}
+
+/// Convert a `BigInt` into an `int`, returning `None` if it doesn't fit.
+///
+/// # Examples
+///
+/// ```rune
+/// assert_eq!(42n.to::<i64>(), Some(42));
+/// assert_eq!((1n << 64).to::<i64>(), None);
+/// ```
+#[rune::function(instance, path = to::<i64>)]
+#[inline]
+fn to_integer(this: &BigInt) -> Option<i64> {
+    this.to_i64()
+}
+
+/// Convert a `BigInt` into the nearest `float`.
+///
+/// # Examples
+///
+/// ```rune
+/// assert_eq!(42n.to::<f64>(), 42.0);
+/// ```
+#[rune::function(instance, path = to::<f64>)]
+#[inline]
+fn to_float(this: &BigInt) -> f64 {
+    this.to_f64().unwrap_or(f64::NAN)
+}
+
+/// Computes the absolute value of `self`.
+///
+/// # Examples
+///
+/// Basic usage:
+///
+/// ```rune
+/// assert_eq!(10n.abs(), 10n);
+/// assert_eq!((-10n).abs(), 10n);
+/// ```
+#[rune::function(instance)]
+#[inline]
+fn abs(this: &BigInt) -> BigInt {
+    this.abs()
+}
+
+/// Raises self to the power of `exp`, using exponentiation by squaring.
+///
+/// Unlike `int`, this never overflows, but errors if the result doesn't fit
+/// within the memory limit of the execution.
+///
+/// # Examples
+///
+/// Basic usage:
+///
+/// ```rune
+/// assert_eq!(2n.pow(5), 32n);
+/// assert_eq!(2n.pow(64), 18446744073709551616n);
+/// ```
+#[rune::function(instance)]
+#[inline]
+fn pow(this: &BigInt, exp: u32) -> VmResult<BigInt> {
+    vm_try!(bigint_limit(this.bits().saturating_mul(u64::from(exp))));
+    VmResult::Ok(this.pow(exp))
+}
+
+/// Returns the number of bits needed to represent the magnitude of `self`.
+///
+/// # Examples
+///
+/// Basic usage:
+///
+/// ```rune
+/// assert_eq!(0n.bits(), 0);
+/// assert_eq!(255n.bits(), 8);
+/// assert_eq!((-256n).bits(), 9);
+/// ```
+#[rune::function(instance)]
+#[inline]
+fn bits(this: &BigInt) -> u64 {
+    this.bits()
+}
+
+/// Returns a number representing sign of `self`.
+///
+/// - `0` if the number is zero
+/// - `1` if the number is positive
+/// - `-1` if the number is negative
+///
+/// # Examples
+///
+/// Basic usage:
+///
+/// ```rune
+/// assert_eq!(10n.signum(), 1n);
+/// assert_eq!(0n.signum(), 0n);
+/// assert_eq!((-10n).signum(), -1n);
+/// ```
+#[rune::function(instance)]
+#[inline]
+fn signum(this: &BigInt) -> BigInt {
+    this.signum()
+}
+
+/// Returns `true` if `self` is positive and `false` if the number is zero or
+/// negative.
+///
+/// # Examples
+///
+/// Basic usage:
+///
+/// ```rune
+/// assert!(10n.is_positive());
+/// assert!(!(-10n).is_positive());
+/// ```
+#[rune::function(instance)]
+#[inline]
+fn is_positive(this: &BigInt) -> bool {
+    Signed::is_positive(this)
+}
+
+/// Returns `true` if `self` is negative and `false` if the number is zero or
+/// positive.
+///
+/// # Examples
+///
+/// Basic usage:
+///
+/// ```rune
+/// assert!((-10n).is_negative());
+/// assert!(!10n.is_negative());
+/// ```
+#[rune::function(instance)]
+#[inline]
+fn is_negative(this: &BigInt) -> bool {
+    Signed::is_negative(this)
+}
+
+/// Test two big integers for partial equality.
+///
+/// # Examples
+///
+/// ```rune
+/// use std::ops::partial_eq;
+///
+/// assert_eq!(partial_eq(5n, 5n), true);
+/// assert_eq!(partial_eq(5n, 10n), false);
+/// assert_eq!(partial_eq(10n, 5n), false);
+/// ```
+#[rune::function(instance, protocol = PARTIAL_EQ)]
+#[inline]
+fn partial_eq(this: &BigInt, rhs: &BigInt) -> bool {
+    this.eq(rhs)
+}
+
+/// Test two big integers for total equality.
+///
+/// # Examples
+///
+/// ```rune
+/// use std::ops::eq;
+///
+/// assert_eq!(eq(5n, 5n), true);
+/// assert_eq!(eq(5n, 10n), false);
+/// assert_eq!(eq(10n, 5n), false);
+/// ```
+#[rune::function(instance, protocol = EQ)]
+#[inline]
+fn eq(this: &BigInt, rhs: &BigInt) -> bool {
+    this.eq(rhs)
+}
+
+/// Perform a partial ordered comparison between two big integers.
+///
+/// # Examples
+///
+/// ```rune
+/// use std::cmp::Ordering;
+/// use std::ops::partial_cmp;
+///
+/// assert_eq!(partial_cmp(5n, 10n), Some(Ordering::Less));
+/// assert_eq!(partial_cmp(10n, 5n), Some(Ordering::Greater));
+/// assert_eq!(partial_cmp(5n, 5n), Some(Ordering::Equal));
+/// ```
+#[rune::function(instance, protocol = PARTIAL_CMP)]
+#[inline]
+fn partial_cmp(this: &BigInt, rhs: &BigInt) -> Option<Ordering> {
+    this.partial_cmp(rhs)
+}
+
+/// Perform a total ordered comparison between two big integers.
+///
+/// # Examples
+///
+/// ```rune
+/// use std::cmp::Ordering;
+/// use std::ops::cmp;
+///
+/// assert_eq!(cmp(5n, 10n), Ordering::Less);
+/// assert_eq!(cmp(10n, 5n), Ordering::Greater);
+/// assert_eq!(cmp(5n, 5n), Ordering::Equal);
+/// ```
+#[rune::function(instance, protocol = CMP)]
+#[inline]
+fn cmp(this: &BigInt, rhs: &BigInt) -> Ordering {
+    this.cmp(rhs)
+}
+
+/// Returns the number as a string.
+///
+/// # Examples
+///
+/// Basic usage:
+///
+/// ```rune
+/// assert_eq!((-10n).to_string(), "-10");
+/// assert_eq!((1n << 64).to_string(), "18446744073709551616");
+/// ```
+#[rune::function(instance)]
+#[inline]
+fn to_string(this: &BigInt) -> VmResult<alloc::String> {
+    VmResult::Ok(vm_try!(this.try_to_string()))
+}
@@ -66,6 +66,7 @@ fn is_readable(value: Value) -> bool {
         Value::Char(_) => true,
         Value::Integer(_) => true,
         Value::Float(_) => true,
+        Value::BigInt(value) => value.is_readable(),
         Value::Type(_) => true,
         Value::Ordering(_) => true,
         Value::String(value) => value.is_readable(),
@@ -120,6 +121,7 @@ fn is_writable(value: Value) -> bool {
         Value::Char(_) => true,
         Value::Integer(_) => true,
         Value::Float(_) => true,
+        Value::BigInt(value) => value.is_writable(),
         Value::Type(_) => true,
         Value::Ordering(_) => true,
         Value::String(value) => value.is_writable(),
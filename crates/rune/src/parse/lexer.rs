@@ -224,7 +224,10 @@ impl<'a> Lexer<'a> {
                 }
                 c if c.is_alphanumeric() => {
                     if split.is_none()
-                        && matches!((c, base), ('u' | 'i', _) | ('f', ast::NumberBase::Decimal))
+                        && matches!(
+                            (c, base),
+                            ('u' | 'i' | 'n', _) | ('f', ast::NumberBase::Decimal)
+                        )
                     {
                         split = Some(self.iter.pos());
                     }
@@ -1109,6 +1112,20 @@ mod tests {
             },
             _,
         };
+
+        test_lexer! {
+            "0xffn",
+            ast::Token {
+                span: span!(0, 5),
+                kind: ast::Kind::Number(ast::NumberSource::Text(ast::NumberText {
+                    source_id: SourceId::EMPTY,
+                    is_fractional: false,
+                    base: ast::NumberBase::Hex,
+                    number: span!(2, 4),
+                    suffix: span!(4, 5),
+                })),
+            },
+        };
     }
 
     #[test]
@@ -144,6 +144,17 @@ pub(crate) struct BuildEntry {
     pub(crate) build: Build,
 }
 
+/// A trait which has been queried for.
+pub(crate) struct QueryTrait {
+    /// The item of the trait.
+    pub(crate) item_meta: ItemMeta,
+    /// The root the trait was indexed from. See [Indexer][crate::indexing::Indexer].
+    pub(crate) root: Option<PathBuf>,
+    /// The ast of the trait, whose default functions are indexed for every
+    /// type implementing it.
+    pub(crate) ast: Box<ast::ItemTrait>,
+}
+
 /// An implementation function.
 pub(crate) struct QueryImplFn {
     /// Ast for declaration.
@@ -153,6 +164,8 @@ pub(crate) struct QueryImplFn {
 pub(crate) struct ItemImplEntry {
     /// Non-expanded ast of the path.
     pub(crate) path: Box<ast::Path>,
+    /// Non-expanded ast of the path to the trait being implemented, if any.
+    pub(crate) trait_path: Option<Box<ast::Path>>,
     /// Location where the item impl is defined and is being expanded.
     pub(crate) location: Location,
     /// The item impl being expanded.
@@ -165,6 +178,15 @@ pub(crate) struct ItemImplEntry {
     pub(crate) macro_depth: usize,
 }
 
+/// A parameter of a function declared in a script.
+#[derive(Debug)]
+pub(crate) struct QueryParameter {
+    /// The name of the parameter, if it binds a plain identifier.
+    pub(crate) name: Option<Box<str>>,
+    /// The hash of the constant holding the default value of the parameter.
+    pub(crate) default: Option<Hash>,
+}
+
 /// Query information for a path.
 #[derive(Debug, Clone, Copy)]
 pub(crate) struct QueryPath {
@@ -4,6 +4,7 @@ use core::mem::take;
 
 use crate::no_std::borrow::Cow;
 use crate::no_std::collections::{hash_map, BTreeMap, HashMap, HashSet, VecDeque};
+use crate::no_std::path::PathBuf;
 use crate::no_std::prelude::*;
 use crate::no_std::rc::Rc;
 use crate::no_std::sync::Arc;
@@ -21,14 +22,12 @@ use crate::compile::{
 use crate::hir;
 use crate::indexing::{self, FunctionAst, Indexed, Items};
 use crate::macros::Storage;
-use crate::parse::{Id, NonZeroId, Opaque, Resolve, ResolveContext};
+use crate::parse::{Id, NonZeroId, Opaque, Resolve};
 use crate::query::{
     Build, BuildEntry, BuiltInMacro, ConstFn, GenericsParameters, ItemImplEntry, Named,
-    QueryImplFn, QueryPath, Used,
+    QueryImplFn, QueryParameter, QueryPath, QueryTrait, Used,
 };
-#[cfg(feature = "doc")]
-use crate::runtime::Call;
-use crate::runtime::ConstValue;
+use crate::runtime::{static_type, Call, ConstValue, UnitFnParameters};
 use crate::shared::{Consts, Gen};
 use crate::{ast, Options};
 use crate::{Context, Diagnostics, Hash, SourceId, Sources};
@@ -69,6 +68,8 @@ pub(crate) struct QueryInner<'arena> {
     indexed: BTreeMap<ItemId, Vec<indexing::Entry>>,
     /// Compiled constant functions.
     const_fns: HashMap<NonZeroId, Rc<ConstFn<'arena>>>,
+    /// Traits which have been queried for.
+    traits: HashMap<NonZeroId, Rc<QueryTrait>>,
     /// Indexed constant values.
     constants: HashMap<Hash, ConstValue>,
     /// Query paths.
@@ -89,6 +90,11 @@ pub(crate) struct QueryInner<'arena> {
     names: Names,
     /// Recorded captures.
     captures: HashMap<Hash, Vec<hir::OwnedName>>,
+    /// Items of types declared in scripts by their type hash, used when
+    /// checking type annotations.
+    types: HashMap<Hash, ItemId>,
+    /// Parameters of functions declared in scripts by their hash.
+    parameters: HashMap<Hash, Rc<[QueryParameter]>>,
 }
 
 impl QueryInner<'_> {
@@ -529,6 +535,10 @@ impl<'a, 'arena> Query<'a, 'arena> {
             hash_map::Entry::Vacant(e) => e.insert(meta),
         };
 
+        if let meta::Kind::Struct { .. } | meta::Kind::Enum { .. } = meta.kind {
+            self.inner.types.insert(meta.hash, meta.item_meta.item);
+        }
+
         Ok(&meta.item_meta)
     }
 
@@ -719,6 +729,35 @@ impl<'a, 'arena> Query<'a, 'arena> {
         Ok(())
     }
 
+    /// Add a new trait item that can be queried.
+    #[tracing::instrument(skip_all)]
+    pub(crate) fn index_trait(
+        &mut self,
+        item_meta: ItemMeta,
+        root: Option<PathBuf>,
+        ast: Box<ast::ItemTrait>,
+    ) -> compile::Result<()> {
+        tracing::trace!(item = ?self.pool.item(item_meta.item));
+
+        self.index(indexing::Entry {
+            item_meta,
+            indexed: Indexed::Trait(indexing::Trait { root, ast }),
+        })?;
+
+        Ok(())
+    }
+
+    /// Get the trait associated with the given identifier.
+    pub(crate) fn trait_for(&self, id: NonZeroId) -> compile::Result<Rc<QueryTrait>, MissingId> {
+        match self.inner.traits.get(&id) {
+            Some(query_trait) => Ok(query_trait.clone()),
+            None => Err(MissingId {
+                what: "trait",
+                id: Id::new(id),
+            }),
+        }
+    }
+
     /// Add a new enum item.
     #[tracing::instrument(skip_all)]
     pub(crate) fn index_enum(&mut self, item_meta: ItemMeta) -> compile::Result<()> {
@@ -889,6 +928,225 @@ impl<'a, 'arena> Query<'a, 'arena> {
         self.convert_path_with(path, false, Used::Used, Used::Used)
     }
 
+    /// Resolve the type referenced by a type annotation into its type hash.
+    pub(crate) fn convert_type(
+        &mut self,
+        source_id: SourceId,
+        ty: &ast::Type,
+    ) -> compile::Result<Hash> {
+        let path = match ty {
+            ast::Type::Path(path) => path,
+            ast::Type::Tuple(..) => return Ok(static_type::TUPLE_TYPE.hash),
+        };
+
+        let named = self.convert_path(path)?;
+
+        if let Some((span, _)) = named.parameters.iter().flatten().next() {
+            return Err(compile::Error::new(span, ErrorKind::UnsupportedGenerics));
+        }
+
+        let Some(meta) = self.try_lookup_meta(
+            &DynLocation::new(source_id, path),
+            named.item,
+            &GenericsParameters::default(),
+        )?
+        else {
+            return Err(compile::Error::new(
+                path,
+                ErrorKind::MissingItem {
+                    item: self.pool.item(named.item).to_owned(),
+                },
+            ));
+        };
+
+        match meta.kind {
+            meta::Kind::Type { .. } | meta::Kind::Struct { .. } | meta::Kind::Enum { .. } => {
+                Ok(meta.hash)
+            }
+            _ => Err(compile::Error::expected_meta(
+                path,
+                meta.info(self.pool),
+                "a type",
+            )),
+        }
+    }
+
+    /// Resolve the annotated argument and return types of a function.
+    ///
+    /// The return type of generators and streams is left unknown, since
+    /// calling them produces the generator or stream itself.
+    fn function_signature_types(
+        &mut self,
+        source_id: SourceId,
+        f: &indexing::Function,
+    ) -> compile::Result<(Box<[Option<Hash>]>, Option<Hash>)> {
+        let FunctionAst::Item(ast) = &f.ast else {
+            return Ok((Box::from([]), None));
+        };
+
+        let mut argument_types = Vec::with_capacity(ast.args.len());
+
+        for (arg, _) in &ast.args {
+            argument_types.push(match arg {
+                ast::FnArg::Typed(arg) => Some(self.convert_type(source_id, &arg.ty)?),
+                ast::FnArg::Default(ast::FnArgDefault {
+                    ty: Some((_, ty)), ..
+                }) => Some(self.convert_type(source_id, ty)?),
+                _ => None,
+            });
+        }
+
+        let return_type = match &ast.output {
+            Some((_, ty)) => Some(self.convert_type(source_id, ty)?),
+            None => None,
+        };
+
+        let return_type = match f.call {
+            Call::Generator | Call::Stream => None,
+            _ => return_type,
+        };
+
+        Ok((argument_types.into(), return_type))
+    }
+
+    /// Record the parameters of a function, evaluating any default values
+    /// into constants.
+    fn function_parameters(
+        &mut self,
+        item_meta: ItemMeta,
+        f: &indexing::Function,
+        used: Used,
+    ) -> compile::Result<()> {
+        let FunctionAst::Item(ast) = &f.ast else {
+            return Ok(());
+        };
+
+        let hash = self.pool.item_type_hash(item_meta.item);
+        let mut parameters = Vec::with_capacity(ast.args.len());
+
+        for (index, (arg, _)) in ast.args.iter().enumerate() {
+            let (pat, value) = match arg {
+                ast::FnArg::SelfValue(..) => {
+                    parameters.push(QueryParameter {
+                        name: None,
+                        default: None,
+                    });
+                    continue;
+                }
+                ast::FnArg::Pat(pat) => (pat, None),
+                ast::FnArg::Typed(arg) => (&arg.pat, None),
+                ast::FnArg::Default(arg) => (&arg.pat, Some(&arg.value)),
+            };
+
+            let name = self.pat_name(pat)?;
+
+            let default = match value {
+                Some(value) => {
+                    let ir = {
+                        let arena = crate::hir::Arena::new();
+                        let mut hir_ctx = crate::hir::lowering::Ctxt::with_const(
+                            &arena,
+                            self.borrow(),
+                            item_meta.location.source_id,
+                        );
+                        let hir = crate::hir::lowering::expr(&mut hir_ctx, value)?;
+
+                        let mut cx = ir::Ctxt {
+                            source_id: item_meta.location.source_id,
+                            q: self.borrow(),
+                        };
+                        ir::compiler::expr(&hir, &mut cx)?
+                    };
+
+                    let mut const_compiler = ir::Interpreter {
+                        budget: ir::Budget::new(1_000_000),
+                        scopes: Default::default(),
+                        module: item_meta.module,
+                        item: item_meta.item,
+                        q: self.borrow(),
+                    };
+
+                    // NB: the constant isn't cached by item, since every
+                    // default value belongs to the same function.
+                    let const_value = const_compiler.eval_value(&ir, used)?.into_const(&ir)?;
+                    let default = Hash::associated_function(hash, Hash::index(index));
+                    self.inner.constants.insert(default, const_value);
+                    Some(default)
+                }
+                None => None,
+            };
+
+            parameters.push(QueryParameter { name, default });
+        }
+
+        self.inner.parameters.insert(hash, parameters.into());
+        Ok(())
+    }
+
+    /// Get the name bound by a pattern if it's a plain identifier.
+    pub(crate) fn pat_name(&self, pat: &ast::Pat) -> compile::Result<Option<Box<str>>> {
+        let ast::Pat::Path(pat) = pat else {
+            return Ok(None);
+        };
+
+        let Some(ident) = pat.path.try_as_ident() else {
+            return Ok(None);
+        };
+
+        Ok(Some(ident.resolve(resolve_context!(self))?.into()))
+    }
+
+    /// Build the runtime parameters of the script function with the given
+    /// hash.
+    pub(crate) fn unit_fn_parameters(
+        &self,
+        span: &dyn Spanned,
+        hash: Hash,
+    ) -> compile::Result<UnitFnParameters> {
+        let Some(parameters) = self.get_parameters(hash) else {
+            return Ok(UnitFnParameters::default());
+        };
+
+        let mut names = Vec::with_capacity(parameters.len());
+        let mut defaults = Vec::new();
+
+        for parameter in parameters.iter() {
+            names.push(parameter.name.clone());
+
+            if let Some(hash) = parameter.default {
+                let Some(value) = self.get_const_value(hash) else {
+                    return Err(compile::Error::msg(span, "Missing default argument value"));
+                };
+
+                defaults.push(value.clone());
+            }
+        }
+
+        Ok(UnitFnParameters {
+            names: names.into(),
+            defaults: defaults.into(),
+        })
+    }
+
+    /// Look up the item of the type with the given type hash.
+    pub(crate) fn lookup_type_item(&mut self, hash: Hash) -> Option<ItemId> {
+        if let Some(item) = self.inner.types.get(&hash) {
+            return Some(*item);
+        }
+
+        let item = self
+            .context
+            .lookup_meta_by_hash(hash)
+            .find_map(|meta| match meta.kind {
+                meta::Kind::Type { .. } | meta::Kind::Struct { .. } | meta::Kind::Enum { .. } => {
+                    meta.item.as_deref()
+                }
+                _ => None,
+            })?;
+
+        Some(self.pool.alloc_item(item))
+    }
+
     /// Perform a path conversion with custom configuration.
     #[tracing::instrument(skip(self, path))]
     pub(crate) fn convert_path_with<'ast>(
@@ -1283,7 +1541,8 @@ impl<'a, 'arena> Query<'a, 'arena> {
     ) -> compile::Result<meta::Meta> {
         /// Convert AST fields into meta fields.
         fn convert_fields(
-            cx: ResolveContext<'_>,
+            q: &mut Query<'_, '_>,
+            source_id: SourceId,
             body: ast::Fields,
         ) -> compile::Result<meta::Fields> {
             Ok(match body {
@@ -1292,9 +1551,15 @@ impl<'a, 'arena> Query<'a, 'arena> {
                 ast::Fields::Named(st) => {
                     let mut fields = HashMap::with_capacity(st.len());
 
-                    for (position, (ast::Field { name, .. }, _)) in st.iter().enumerate() {
-                        let name = name.resolve(cx)?;
-                        fields.insert(name.into(), FieldMeta { position });
+                    for (position, (ast::Field { name, ty, .. }, _)) in st.iter().enumerate() {
+                        let name = Box::<str>::from(name.resolve(resolve_context!(q))?);
+
+                        let ty = match ty {
+                            Some((_, ty)) => Some(q.convert_type(source_id, ty)?),
+                            None => None,
+                        };
+
+                        fields.insert(name, FieldMeta { position, ty });
                     }
 
                     meta::Fields::Named(meta::FieldsNamed { fields })
@@ -1326,16 +1591,21 @@ impl<'a, 'arena> Query<'a, 'arena> {
                 meta::Kind::Variant {
                     enum_hash: enum_meta.hash,
                     index: variant.index,
-                    fields: convert_fields(resolve_context!(self), variant.ast.body)?,
+                    fields: convert_fields(self, item_meta.location.source_id, variant.ast.body)?,
                     constructor: None,
                 }
             }
             Indexed::Struct(st) => meta::Kind::Struct {
-                fields: convert_fields(resolve_context!(self), st.ast.body)?,
+                fields: convert_fields(self, item_meta.location.source_id, st.ast.body)?,
                 constructor: None,
                 parameters: Hash::EMPTY,
             },
             Indexed::Function(f) => {
+                let (argument_types, return_type) =
+                    self.function_signature_types(item_meta.location.source_id, &f)?;
+
+                self.function_parameters(item_meta, &f, used)?;
+
                 let kind = meta::Kind::Function {
                     associated: match (f.is_instance, &f.ast) {
                         (true, FunctionAst::Item(ast)) => {
@@ -1348,16 +1618,13 @@ impl<'a, 'arena> Query<'a, 'arena> {
                     is_test: f.is_test,
                     is_bench: f.is_bench,
                     signature: meta::Signature {
-                        #[cfg(feature = "doc")]
                         is_async: matches!(f.call, Call::Async | Call::Stream),
                         #[cfg(feature = "doc")]
                         deprecated: None,
                         #[cfg(feature = "doc")]
                         args: Some(f.ast.args()),
-                        #[cfg(feature = "doc")]
-                        return_type: None,
-                        #[cfg(feature = "doc")]
-                        argument_types: Box::from([]),
+                        return_type,
+                        argument_types,
                     },
                     parameters: Hash::EMPTY,
                     #[cfg(feature = "doc")]
@@ -1473,7 +1740,7 @@ impl<'a, 'arena> Query<'a, 'arena> {
                         self.borrow(),
                         item_meta.location.source_id,
                     );
-                    let hir = crate::hir::lowering::item_fn(&mut cx, &c.item_fn)?;
+                    let hir = crate::hir::lowering::item_fn(&mut cx, &c.item_fn, Call::Immediate)?;
 
                     let mut cx = ir::Ctxt {
                         source_id: item_meta.location.source_id,
@@ -1513,6 +1780,20 @@ impl<'a, 'arena> Query<'a, 'arena> {
                 meta::Kind::Import(import.entry)
             }
             Indexed::Module => meta::Kind::Module,
+            Indexed::Trait(t) => {
+                let id = self.gen.next();
+
+                self.inner.traits.insert(
+                    id,
+                    Rc::new(QueryTrait {
+                        item_meta,
+                        root: t.root,
+                        ast: t.ast,
+                    }),
+                );
+
+                meta::Kind::Trait { id }
+            }
         };
 
         let source = SourceMeta {
@@ -1642,7 +1923,12 @@ impl<'a, 'arena> Query<'a, 'arena> {
         used: Used,
     ) -> compile::Result<ItemId> {
         let mut base = self.pool.item(item).to_owned();
-        debug_assert!(base.starts_with(self.pool.module_item(module)));
+
+        // Default functions of traits are declared under the implementing
+        // type, but resolve names in the module of the trait.
+        if !base.starts_with(self.pool.module_item(module)) {
+            base = self.pool.module_item(module).to_owned();
+        }
 
         let local_str = local.resolve(resolve_context!(self))?.to_owned();
 
@@ -1781,4 +2067,9 @@ impl<'a, 'arena> Query<'a, 'arena> {
     pub(crate) fn get_captures(&self, hash: Hash) -> Option<&[hir::OwnedName]> {
         Some(self.inner.captures.get(&hash)?)
     }
+
+    /// Get the parameters of the script function with the given hash.
+    pub(crate) fn get_parameters(&self, hash: Hash) -> Option<Rc<[QueryParameter]>> {
+        self.inner.parameters.get(&hash).cloned()
+    }
 }
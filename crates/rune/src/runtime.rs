@@ -20,6 +20,9 @@ pub(crate) use self::awaited::Awaited;
 
 pub mod budget;
 
+mod bigint;
+pub use self::bigint::LimitedBigInt;
+
 mod bytes;
 pub use self::bytes::Bytes;
 
@@ -29,8 +32,16 @@ pub use self::call::Call;
 mod const_value;
 pub use self::const_value::ConstValue;
 
+#[cfg(feature = "std")]
+pub mod coverage;
+#[cfg(feature = "std")]
+pub use self::coverage::Coverage;
+
 pub mod debug;
-pub use self::debug::{DebugInfo, DebugInst};
+pub use self::debug::{DebugInfo, DebugInst, DebugVariable};
+
+pub mod debugger;
+pub use self::debugger::Debugger;
 
 mod env;
 
@@ -64,6 +75,10 @@ pub use self::inst::{
     PanicReason, TypeCheck,
 };
 
+mod interrupt;
+pub use self::interrupt::CancelToken;
+pub(crate) use self::interrupt::Interrupt;
+
 mod iterator;
 pub use self::iterator::{Iterator, IteratorTrait};
 
@@ -77,9 +92,17 @@ pub(crate) use self::label::Label;
 mod object;
 pub use self::object::Object;
 
+mod observer;
+pub(crate) use self::observer::Observer;
+
 mod panic;
 pub(crate) use self::panic::{BoxedPanic, Panic};
 
+#[cfg(feature = "std")]
+pub mod profiler;
+#[cfg(feature = "std")]
+pub use self::profiler::Profiler;
+
 mod protocol;
 pub use self::protocol::Protocol;
 
@@ -142,8 +165,8 @@ mod type_of;
 pub use self::type_of::{FullTypeOf, MaybeTypeOf, TypeOf};
 
 pub mod unit;
-pub(crate) use self::unit::UnitFn;
 pub use self::unit::{Unit, UnitStorage};
+pub(crate) use self::unit::{UnitFn, UnitFnParameters};
 
 mod value;
 pub use self::value::{EmptyStruct, Rtti, Struct, TupleStruct, Value, VariantRtti};
@@ -158,6 +181,7 @@ mod vec_tuple;
 pub use self::vec_tuple::VecTuple;
 
 mod vm;
+pub(crate) use self::vm::bigint_limit;
 pub use self::vm::{CallFrame, Vm};
 
 mod vm_call;
@@ -1,4 +1,4 @@
-use crate::runtime::{Future, Select, Shared, ToValue, Vm, VmResult};
+use crate::runtime::{Future, Interrupt, Select, Shared, ToValue, Vm, VmResult};
 
 /// A stored await task.
 #[derive(Debug)]
@@ -11,14 +11,19 @@ pub(crate) enum Awaited {
 
 impl Awaited {
     /// Wait for the given awaited into the specified virtual machine.
-    pub(crate) async fn into_vm(self, vm: &mut Vm) -> VmResult<()> {
+    ///
+    /// The given interrupt is checked every time the awaited future is polled.
+    pub(crate) async fn into_vm(self, vm: &mut Vm, interrupt: &Interrupt) -> VmResult<()> {
         match self {
             Self::Future(future) => {
-                let value = vm_try!(vm_try!(future.borrow_mut()).await.with_vm(vm));
+                let future = vm_try!(future.borrow_mut());
+                let value = vm_try!(interrupt.wrap(future).await.with_vm(vm));
+                let value = vm_try!(value.with_vm(vm));
                 vm_try!(vm.stack_mut().push(value));
             }
             Self::Select(select) => {
-                let (branch, value) = vm_try!(select.await.with_vm(vm));
+                let result = vm_try!(interrupt.wrap(select).await.with_vm(vm));
+                let (branch, value) = vm_try!(result.with_vm(vm));
                 vm_try!(vm.stack_mut().push(value));
                 vm_try!(vm.stack_mut().push(vm_try!(ToValue::to_value(branch))));
             }
@@ -0,0 +1,90 @@
+//! A big integer which is accounted for by the memory limit, corresponding to
+//! the [Value::BigInt] type.
+//!
+//! [Value::BigInt]: crate::Value::BigInt
+
+use core::fmt;
+use core::mem::take;
+use core::ops;
+
+use num::BigInt;
+
+use crate::alloc::{limit, Error};
+
+/// An arbitrary-precision integer stored in a [Value].
+///
+/// The digits of a big integer are allocated outside of [`rune::alloc`], so
+/// their estimated size is taken from the current memory limit when the value
+/// is constructed and given back to it when it's dropped.
+///
+/// [Value]: crate::Value
+/// [`rune::alloc`]: crate::alloc
+pub struct LimitedBigInt {
+    value: BigInt,
+    size: usize,
+}
+
+impl LimitedBigInt {
+    /// Construct a new big integer, taking its estimated size from the current
+    /// memory limit.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rune::alloc::limit;
+    /// use rune::runtime::LimitedBigInt;
+    ///
+    /// let f = limit::with(64, || {
+    ///     assert!(LimitedBigInt::new(num::BigInt::from(1u32) << 256u32).is_ok());
+    ///     assert!(LimitedBigInt::new(num::BigInt::from(1u32) << 1024u32).is_err());
+    /// });
+    ///
+    /// f.call();
+    /// ```
+    pub fn new(value: BigInt) -> Result<Self, Error> {
+        let size = Self::size_of(value.bits());
+        limit::try_take(size)?;
+        Ok(Self { value, size })
+    }
+
+    /// Convert into the underlying big integer, giving its estimated size back
+    /// to the current memory limit.
+    pub fn into_inner(mut self) -> BigInt {
+        take(&mut self.value)
+    }
+
+    /// The estimated size in bytes of a big integer with the given number of
+    /// bits.
+    pub(crate) fn size_of(bits: u64) -> usize {
+        usize::try_from(bits / 8 + 1).unwrap_or(usize::MAX)
+    }
+}
+
+impl ops::Deref for LimitedBigInt {
+    type Target = BigInt;
+
+    #[inline]
+    fn deref(&self) -> &Self::Target {
+        &self.value
+    }
+}
+
+impl Drop for LimitedBigInt {
+    fn drop(&mut self) {
+        limit::release(self.size);
+    }
+}
+
+impl fmt::Debug for LimitedBigInt {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.value.fmt(f)
+    }
+}
+
+impl fmt::Display for LimitedBigInt {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.value.fmt(f)
+    }
+}
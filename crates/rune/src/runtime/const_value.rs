@@ -1,3 +1,4 @@
+use num::BigInt;
 use serde::{Deserialize, Serialize};
 
 use crate::no_std::collections::HashMap;
@@ -24,6 +25,8 @@ pub enum ConstValue {
     Integer(i64),
     /// An float constant.
     Float(f64),
+    /// An arbitrary-precision integer constant.
+    BigInt(#[serde(with = "bigint")] BigInt),
     /// A string constant designated by its slot.
     String(std::String),
     /// A byte string.
@@ -51,6 +54,7 @@ impl ConstValue {
             Self::Bool(b) => Value::Bool(b),
             Self::Integer(n) => Value::Integer(n),
             Self::Float(n) => Value::Float(n),
+            Self::BigInt(n) => Value::try_from(n)?,
             Self::String(string) => {
                 let string = rune_alloc::String::try_from(string)?;
                 Value::String(Shared::new(string)?)
@@ -112,6 +116,7 @@ impl ConstValue {
             Self::Bytes(..) => TypeInfo::StaticType(crate::runtime::static_type::BYTES_TYPE),
             Self::Integer(..) => TypeInfo::StaticType(crate::runtime::static_type::INTEGER_TYPE),
             Self::Float(..) => TypeInfo::StaticType(crate::runtime::static_type::FLOAT_TYPE),
+            Self::BigInt(..) => TypeInfo::StaticType(crate::runtime::static_type::BIGINT_TYPE),
             Self::Vec(..) => TypeInfo::StaticType(crate::runtime::static_type::VEC_TYPE),
             Self::Tuple(..) => TypeInfo::StaticType(crate::runtime::static_type::TUPLE_TYPE),
             Self::Object(..) => TypeInfo::StaticType(crate::runtime::static_type::OBJECT_TYPE),
@@ -129,6 +134,7 @@ impl FromValue for ConstValue {
             Value::Bool(b) => Self::Bool(b),
             Value::Integer(n) => Self::Integer(n),
             Value::Float(f) => Self::Float(f),
+            Value::BigInt(n) => Self::BigInt(vm_try!(n.borrow_ref()).clone()),
             Value::String(s) => {
                 let s = vm_try!(s.take());
                 Self::String(std::String::from(s))
@@ -193,3 +199,26 @@ impl ToValue for ConstValue {
         VmResult::Ok(vm_try!(ConstValue::into_value(self)))
     }
 }
+
+/// Serialize big integers as their signed little-endian bytes.
+mod bigint {
+    use num::BigInt;
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+    use crate::no_std::std;
+
+    pub(super) fn serialize<S>(value: &BigInt, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        value.to_signed_bytes_le().serialize(serializer)
+    }
+
+    pub(super) fn deserialize<'de, D>(deserializer: D) -> Result<BigInt, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let bytes = std::Vec::<u8>::deserialize(deserializer)?;
+        Ok(BigInt::from_signed_bytes_le(&bytes))
+    }
+}
@@ -0,0 +1,543 @@
+//! Support for recording the code coverage of a virtual machine.
+//!
+//! [Coverage] records which instructions were executed and maps them back to
+//! the source lines they were compiled from through [DebugInfo]. For every
+//! conditional jump, like the ones used by `if` expressions, loops and `match`
+//! arms, it also records whether the jump was taken or not.
+//!
+//! Coverage is recorded by running an execution through
+//! [VmExecution::complete_with_coverage] or
+//! [VmExecution::async_complete_with_coverage], after which it can be written
+//! in the lcov format through [Coverage::write_lcov] or as an annotated HTML
+//! page through [Coverage::write_html].
+//!
+//! ```
+//! use rune::runtime::coverage::Coverage;
+//! use rune::{Context, Source, Sources, Vm};
+//! use std::sync::Arc;
+//!
+//! let context = Context::with_default_modules()?;
+//! let runtime = Arc::new(context.runtime());
+//!
+//! let mut sources = Sources::new();
+//!
+//! sources.insert(Source::new("script", r#"
+//! pub fn main(n) {
+//!     if n > 10 {
+//!         "big"
+//!     } else {
+//!         "small"
+//!     }
+//! }
+//! "#));
+//!
+//! let unit = Arc::new(rune::prepare(&mut sources).with_context(&context).build()?);
+//!
+//! let mut coverage = Coverage::new();
+//! coverage.insert(unit.clone(), &sources);
+//!
+//! let mut vm = Vm::new(runtime, unit);
+//! vm.execute(["main"], (1,))?.complete_with_coverage(&mut coverage).into_result()?;
+//!
+//! let summary = coverage.summary();
+//! assert!(summary.lines_hit < summary.lines_found);
+//! assert_eq!(summary.branches_found, 2);
+//! assert_eq!(summary.branches_hit, 1);
+//!
+//! let mut lcov = Vec::new();
+//! coverage.write_lcov(&mut lcov)?;
+//! let lcov = String::from_utf8(lcov)?;
+//! assert!(lcov.contains("DA:4,0"));
+//! assert!(lcov.contains("DA:6,1"));
+//! # Ok::<_, Box<dyn std::error::Error>>(())
+//! ```
+//!
+//! [DebugInfo]: crate::runtime::DebugInfo
+//! [VmExecution::complete_with_coverage]: crate::runtime::VmExecution::complete_with_coverage
+//! [VmExecution::async_complete_with_coverage]: crate::runtime::VmExecution::async_complete_with_coverage
+
+use core::fmt;
+
+use std::io::{self, Write};
+
+use crate::no_std::collections::{BTreeMap, HashMap};
+use crate::no_std::prelude::*;
+use crate::no_std::sync::Arc;
+
+use crate::runtime::{Inst, Observer, Unit, Vm};
+use crate::{Source, SourceId, Sources};
+
+/// A summary of the recorded coverage.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct CoverageSummary {
+    /// The number of lines which have instructions.
+    pub lines_found: usize,
+    /// The number of lines where instructions were executed.
+    pub lines_hit: usize,
+    /// The number of branches, where every conditional jump has two.
+    pub branches_found: usize,
+    /// The number of branches which were taken.
+    pub branches_hit: usize,
+}
+
+impl CoverageSummary {
+    /// The percentage of lines which were executed.
+    ///
+    /// If there are no lines, this is 100.
+    pub fn line_percent(&self) -> f64 {
+        percent(self.lines_hit, self.lines_found)
+    }
+
+    /// The percentage of branches which were taken.
+    ///
+    /// If there are no branches, this is 100.
+    pub fn branch_percent(&self) -> f64 {
+        percent(self.branches_hit, self.branches_found)
+    }
+
+    fn add(&mut self, other: CoverageSummary) {
+        self.lines_found += other.lines_found;
+        self.lines_hit += other.lines_hit;
+        self.branches_found += other.branches_found;
+        self.branches_hit += other.branches_hit;
+    }
+}
+
+impl fmt::Display for CoverageSummary {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}/{} lines ({:.1}%), {}/{} branches ({:.1}%)",
+            self.lines_hit,
+            self.lines_found,
+            self.line_percent(),
+            self.branches_hit,
+            self.branches_found,
+            self.branch_percent()
+        )
+    }
+}
+
+/// Information about the instruction which is about to be executed.
+struct Probe {
+    /// The index of the unit being executed.
+    unit: usize,
+    /// The instruction being executed.
+    ip: usize,
+}
+
+/// A conditional jump.
+struct Branch {
+    /// The instruction pointer of the instruction following the jump.
+    next: usize,
+    /// The number of times the jump fell through and was taken.
+    counts: [u64; 2],
+}
+
+/// Coverage recorded for a single unit.
+struct UnitCoverage {
+    /// The unit being covered.
+    unit: Arc<Unit>,
+    /// The file and zero-based line of each instruction.
+    locations: HashMap<usize, (usize, usize)>,
+    /// The number of times each instruction was executed.
+    hits: HashMap<usize, u64>,
+    /// The conditional jumps in the unit.
+    branches: HashMap<usize, Branch>,
+}
+
+/// The coverage of a single file, merged from every unit it is a part of.
+#[derive(Default)]
+struct FileReport {
+    /// The number of times each line was executed.
+    lines: BTreeMap<usize, u64>,
+    /// The branches on each line, ordered by their position.
+    branches: BTreeMap<usize, Vec<[u64; 2]>>,
+}
+
+impl FileReport {
+    fn summary(&self) -> CoverageSummary {
+        let mut summary = CoverageSummary {
+            lines_found: self.lines.len(),
+            lines_hit: self.lines.values().filter(|&&hits| hits > 0).count(),
+            ..CoverageSummary::default()
+        };
+
+        for counts in self.branches.values().flatten() {
+            summary.branches_found += counts.len();
+            summary.branches_hit += counts.iter().filter(|&&n| n > 0).count();
+        }
+
+        summary
+    }
+}
+
+/// Records which instructions and branches are executed.
+///
+/// See the [module level documentation][self] for more information.
+#[derive(Default)]
+pub struct Coverage {
+    /// The units being covered.
+    units: Vec<UnitCoverage>,
+    /// The files which are covered, deduplicated by name.
+    files: Vec<Source>,
+    /// The index of the unit which was most recently executed.
+    last: usize,
+    /// Probes of the instructions being executed, one for each nested
+    /// execution.
+    probes: Vec<Option<Probe>>,
+}
+
+impl Coverage {
+    /// Construct a new empty coverage recorder.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Record coverage for the given unit, which was compiled from the given
+    /// sources.
+    ///
+    /// Instructions executed in units which have not been inserted are
+    /// ignored. The unit must have been compiled with debug information,
+    /// otherwise instructions can't be associated with lines.
+    pub fn insert(&mut self, unit: Arc<Unit>, sources: &Sources) {
+        let mut files = HashMap::<SourceId, usize>::new();
+        let mut locations = HashMap::new();
+        let mut branches = HashMap::new();
+
+        if let Some(debug) = unit.debug_info() {
+            for (&ip, inst) in &debug.instructions {
+                let Some(source) = sources.get(inst.source_id) else {
+                    continue;
+                };
+
+                let file = *files.entry(inst.source_id).or_insert_with(|| {
+                    match self.files.iter().position(|f| f.name() == source.name()) {
+                        Some(file) => file,
+                        None => {
+                            self.files.push(source.clone());
+                            self.files.len() - 1
+                        }
+                    }
+                });
+
+                let (line, _) = source.pos_to_utf8_linecol(inst.span.start.into_usize());
+                locations.insert(ip, (file, line));
+
+                if let Ok(Some((inst, len))) = unit.instruction_at(ip) {
+                    if is_branch(&inst) {
+                        branches.insert(
+                            ip,
+                            Branch {
+                                next: ip + len,
+                                counts: [0, 0],
+                            },
+                        );
+                    }
+                }
+            }
+        }
+
+        self.units.push(UnitCoverage {
+            unit,
+            locations,
+            hits: HashMap::new(),
+            branches,
+        });
+    }
+
+    /// Summarize the recorded coverage.
+    pub fn summary(&self) -> CoverageSummary {
+        let mut summary = CoverageSummary::default();
+
+        for (_, report) in self.reports() {
+            summary.add(report.summary());
+        }
+
+        summary
+    }
+
+    /// Write the recorded coverage in the lcov tracefile format.
+    ///
+    /// Every file gets a record, where lines and branches are reported with
+    /// the number of times they were executed.
+    pub fn write_lcov<O>(&self, o: &mut O) -> io::Result<()>
+    where
+        O: ?Sized + Write,
+    {
+        for (source, report) in self.reports() {
+            writeln!(o, "TN:")?;
+
+            match source.path() {
+                Some(path) => writeln!(o, "SF:{}", path.display())?,
+                None => writeln!(o, "SF:{}", source.name())?,
+            }
+
+            for (&line, branches) in &report.branches {
+                let executed = report.lines.get(&line).copied().unwrap_or_default() > 0;
+
+                for (block, counts) in branches.iter().enumerate() {
+                    for (branch, &count) in counts.iter().enumerate() {
+                        if executed {
+                            writeln!(o, "BRDA:{},{block},{branch},{count}", line + 1)?;
+                        } else {
+                            writeln!(o, "BRDA:{},{block},{branch},-", line + 1)?;
+                        }
+                    }
+                }
+            }
+
+            let summary = report.summary();
+
+            writeln!(o, "BRF:{}", summary.branches_found)?;
+            writeln!(o, "BRH:{}", summary.branches_hit)?;
+
+            for (&line, &hits) in &report.lines {
+                writeln!(o, "DA:{},{hits}", line + 1)?;
+            }
+
+            writeln!(o, "LF:{}", summary.lines_found)?;
+            writeln!(o, "LH:{}", summary.lines_hit)?;
+            writeln!(o, "end_of_record")?;
+        }
+
+        Ok(())
+    }
+
+    /// Write the recorded coverage as a self-contained HTML page, where the
+    /// source of every file is annotated with how often each line was
+    /// executed and how many of its branches were taken.
+    pub fn write_html<O>(&self, o: &mut O) -> io::Result<()>
+    where
+        O: ?Sized + Write,
+    {
+        let reports = self.reports();
+
+        writeln!(o, "<!DOCTYPE html>")?;
+        writeln!(o, "<html>")?;
+        writeln!(o, "<head>")?;
+        writeln!(o, "<meta charset=\"utf-8\">")?;
+        writeln!(o, "<title>Coverage</title>")?;
+        writeln!(o, "<style>{HTML_STYLE}</style>")?;
+        writeln!(o, "</head>")?;
+        writeln!(o, "<body>")?;
+        writeln!(o, "<h1>Coverage</h1>")?;
+        writeln!(o, "<p>{}</p>", self.summary())?;
+        writeln!(o, "<table class=\"summary\">")?;
+        writeln!(o, "<tr><th>File</th><th>Lines</th><th>Branches</th></tr>")?;
+
+        for (n, (source, report)) in reports.iter().enumerate() {
+            let summary = report.summary();
+
+            writeln!(
+                o,
+                "<tr><td><a href=\"#file-{n}\">{}</a></td><td>{}/{} ({:.1}%)</td><td>{}/{} ({:.1}%)</td></tr>",
+                Escape(source.name()),
+                summary.lines_hit,
+                summary.lines_found,
+                summary.line_percent(),
+                summary.branches_hit,
+                summary.branches_found,
+                summary.branch_percent(),
+            )?;
+        }
+
+        writeln!(o, "</table>")?;
+
+        for (n, (source, report)) in reports.iter().enumerate() {
+            writeln!(o, "<h2 id=\"file-{n}\">{}</h2>", Escape(source.name()))?;
+            writeln!(o, "<table class=\"source\">")?;
+
+            for (line, text) in source.as_str().lines().enumerate() {
+                let hits = report.lines.get(&line).copied();
+
+                let class = match hits {
+                    Some(0) => "miss",
+                    Some(..) => "hit",
+                    None => "none",
+                };
+
+                write!(
+                    o,
+                    "<tr class=\"{class}\"><td class=\"line\">{}</td>",
+                    line + 1
+                )?;
+
+                match hits {
+                    Some(hits) => write!(o, "<td class=\"hits\">{hits}</td>")?,
+                    None => write!(o, "<td class=\"hits\"></td>")?,
+                }
+
+                match report.branches.get(&line) {
+                    Some(branches) => {
+                        let found = branches.len() * 2;
+                        let hit = branches.iter().flatten().filter(|&&n| n > 0).count();
+
+                        let class = if hit == found { "taken" } else { "partial" };
+                        write!(o, "<td class=\"branches {class}\">{hit}/{found}</td>")?;
+                    }
+                    None => write!(o, "<td class=\"branches\"></td>")?,
+                }
+
+                writeln!(
+                    o,
+                    "<td class=\"code\"><pre>{}</pre></td></tr>",
+                    Escape(text)
+                )?;
+            }
+
+            writeln!(o, "</table>")?;
+        }
+
+        writeln!(o, "</body>")?;
+        writeln!(o, "</html>")?;
+        Ok(())
+    }
+
+    /// Inspect the instruction which is about to be executed.
+    fn probe(&mut self, vm: &Vm) -> Option<Probe> {
+        let unit = match self.units.get(self.last) {
+            Some(covered) if Arc::ptr_eq(&covered.unit, vm.unit()) => self.last,
+            _ => {
+                let unit = self
+                    .units
+                    .iter()
+                    .position(|covered| Arc::ptr_eq(&covered.unit, vm.unit()))?;
+
+                self.last = unit;
+                unit
+            }
+        };
+
+        Some(Probe { unit, ip: vm.ip() })
+    }
+
+    /// Record the result of executing the probed instruction.
+    fn record(&mut self, probe: Probe, vm: &Vm) {
+        let covered = &mut self.units[probe.unit];
+        *covered.hits.entry(probe.ip).or_default() += 1;
+
+        if let Some(branch) = covered.branches.get_mut(&probe.ip) {
+            let taken = vm.ip() != branch.next;
+            branch.counts[usize::from(taken)] += 1;
+        }
+    }
+
+    /// Merge the recorded coverage of every unit into reports for each file.
+    fn reports(&self) -> Vec<(&Source, FileReport)> {
+        let mut reports = self
+            .files
+            .iter()
+            .map(|source| (source, FileReport::default()))
+            .collect::<Vec<_>>();
+
+        for covered in &self.units {
+            let mut branches = covered.branches.iter().collect::<Vec<_>>();
+            branches.sort_by_key(|&(&ip, _)| ip);
+
+            for (ip, &(file, line)) in &covered.locations {
+                let hits = covered.hits.get(ip).copied().unwrap_or_default();
+                let existing = reports[file].1.lines.entry(line).or_default();
+                *existing = (*existing).max(hits);
+            }
+
+            // NB: branches on the same line are told apart by their order,
+            // which is the same for every unit a file is a part of.
+            let mut seen = HashMap::<(usize, usize), usize>::new();
+
+            for (ip, branch) in branches {
+                let Some(&(file, line)) = covered.locations.get(ip) else {
+                    continue;
+                };
+
+                let index = seen.entry((file, line)).or_default();
+                let counts = reports[file].1.branches.entry(line).or_default();
+
+                match counts.get_mut(*index) {
+                    Some(existing) => {
+                        existing[0] += branch.counts[0];
+                        existing[1] += branch.counts[1];
+                    }
+                    None => counts.push(branch.counts),
+                }
+
+                *index += 1;
+            }
+        }
+
+        reports
+    }
+}
+
+impl fmt::Debug for Coverage {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Coverage")
+            .field("units", &self.units.len())
+            .field("files", &self.files.len())
+            .finish()
+    }
+}
+
+impl Observer for Coverage {
+    fn before(&mut self, vm: &Vm, _: bool) -> bool {
+        let probe = self.probe(vm);
+        self.probes.push(probe);
+        false
+    }
+
+    fn after(&mut self, vm: &Vm, _: bool) {
+        if let Some(Some(probe)) = self.probes.pop() {
+            self.record(probe, vm);
+        }
+    }
+}
+
+/// Test if the given instruction is a conditional jump.
+fn is_branch(inst: &Inst) -> bool {
+    matches!(
+        inst,
+        Inst::PopAndJumpIfNot { .. }
+            | Inst::JumpIf { .. }
+            | Inst::JumpIfOrPop { .. }
+            | Inst::JumpIfNotOrPop { .. }
+            | Inst::JumpIfBranch { .. }
+            | Inst::IterNext { .. }
+    )
+}
+
+fn percent(hit: usize, found: usize) -> f64 {
+    if found == 0 {
+        return 100.0;
+    }
+
+    hit as f64 * 100.0 / found as f64
+}
+
+/// Escape a string for inclusion in HTML.
+struct Escape<'a>(&'a str);
+
+impl fmt::Display for Escape<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for c in self.0.chars() {
+            match c {
+                '<' => f.write_str("&lt;")?,
+                '>' => f.write_str("&gt;")?,
+                '&' => f.write_str("&amp;")?,
+                '"' => f.write_str("&quot;")?,
+                c => fmt::Write::write_char(f, c)?,
+            }
+        }
+
+        Ok(())
+    }
+}
+
+const HTML_STYLE: &str = "body { font-family: sans-serif; } \
+table.source { border-collapse: collapse; font-family: monospace; } \
+table.source td { padding: 0 0.5em; vertical-align: top; } \
+table.source pre { margin: 0; } \
+td.line, td.hits, td.branches { text-align: right; color: #666; } \
+tr.hit td.code { background: #dfd; } \
+tr.miss td.code { background: #fdd; } \
+td.partial { background: #ffd; }";
@@ -25,6 +25,9 @@ pub struct DebugInfo {
     pub functions_rev: HashMap<usize, Hash>,
     /// Hash to identifier.
     pub hash_to_ident: HashMap<Hash, Box<str>>,
+    /// Named variables and the instructions over which they are live.
+    #[serde(default)]
+    pub variables: Vec<DebugVariable>,
 }
 
 impl DebugInfo {
@@ -44,6 +47,61 @@ impl DebugInfo {
     pub fn ident_for_hash(&self, hash: Hash) -> Option<&str> {
         Some(self.hash_to_ident.get(&hash)?)
     }
+
+    /// Iterate over the variables which are live at the given instruction
+    /// pointer.
+    ///
+    /// If a variable is shadowed, the innermost declaration is the one which
+    /// is returned.
+    pub fn variables_at(&self, ip: usize) -> impl Iterator<Item = &DebugVariable> + '_ {
+        let mut live = Vec::<&DebugVariable>::new();
+
+        for var in &self.variables {
+            if !var.is_live_at(ip) {
+                continue;
+            }
+
+            match live.iter_mut().find(|v| v.name == var.name) {
+                Some(existing) if existing.start < var.start => *existing = var,
+                Some(..) => {}
+                None => live.push(var),
+            }
+        }
+
+        live.sort_by_key(|v| v.offset);
+        live.into_iter()
+    }
+}
+
+/// Debug information about a named variable.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[non_exhaustive]
+pub struct DebugVariable {
+    /// The name of the variable.
+    pub name: Box<str>,
+    /// The offset of the variable from the bottom of the stack frame.
+    pub offset: usize,
+    /// The first instruction pointer at which the variable is live.
+    pub start: usize,
+    /// The instruction pointer at which the variable is no longer live.
+    pub end: usize,
+}
+
+impl DebugVariable {
+    /// Construct a new debug variable.
+    pub fn new(name: Box<str>, offset: usize, start: usize, end: usize) -> Self {
+        Self {
+            name,
+            offset,
+            start,
+            end,
+        }
+    }
+
+    /// Test if the variable is live at the given instruction pointer.
+    pub fn is_live_at(&self, ip: usize) -> bool {
+        self.start <= ip && ip < self.end
+    }
 }
 
 /// Debug information for every instruction.
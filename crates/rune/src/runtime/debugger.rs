@@ -0,0 +1,522 @@
+//! Support for debugging the execution of a virtual machine.
+//!
+//! The [Debugger] maps instructions in a [Unit] to the source lines they were
+//! compiled from, and keeps track of which lines have breakpoints. It is used
+//! to drive an execution through [VmExecution::debug] or
+//! [VmExecution::async_debug], which runs until a breakpoint is hit or a step
+//! completes.
+//!
+//! ```
+//! use rune::runtime::debugger::{DebugState, Debugger, Resume};
+//! use rune::{Context, Source, Sources, Vm};
+//! use std::sync::Arc;
+//!
+//! let context = Context::with_default_modules()?;
+//! let runtime = Arc::new(context.runtime());
+//!
+//! let mut sources = Sources::new();
+//!
+//! let source_id = sources.insert(Source::memory(r#"
+//! pub fn main() {
+//!     let a = 1;
+//!     let b = a + 2;
+//!     b * 3
+//! }
+//! "#));
+//!
+//! let unit = Arc::new(rune::prepare(&mut sources).with_context(&context).build()?);
+//!
+//! let mut debugger = Debugger::new(unit.clone(), &sources);
+//! debugger.set_breakpoints(source_id, [3]);
+//!
+//! let mut vm = Vm::new(runtime, unit);
+//! let mut execution = vm.execute(["main"], ())?;
+//!
+//! let state = execution.debug(&debugger, Resume::Continue).into_result()?;
+//! assert!(matches!(state, DebugState::Breakpoint));
+//!
+//! let frames = debugger.frames(execution.vm());
+//! let locals = debugger.locals(execution.vm(), &frames[0]);
+//! assert_eq!(locals.len(), 1);
+//! assert_eq!(locals[0].0, "a");
+//!
+//! let value = debugger.evaluate(&context, execution.vm(), &frames[0], "a * 10")?;
+//! assert_eq!(rune::from_value::<i64>(value)?, 10);
+//!
+//! let state = execution.debug(&debugger, Resume::Continue).into_result()?;
+//! let DebugState::Complete(value) = state else {
+//!     panic!("expected execution to complete");
+//! };
+//! assert_eq!(rune::from_value::<i64>(value)?, 9);
+//! # Ok::<_, rune::Error>(())
+//! ```
+//!
+//! [VmExecution::debug]: crate::runtime::VmExecution::debug
+//! [VmExecution::async_debug]: crate::runtime::VmExecution::async_debug
+
+use core::fmt;
+
+use crate::no_std::collections::{HashSet, VecDeque};
+use crate::no_std::prelude::*;
+use crate::no_std::sync::Arc;
+
+use crate::diagnostics::{Diagnostic, FatalDiagnostic};
+use crate::runtime::debug::DebugSignature;
+use crate::runtime::{Observer, Unit, Value, Vm, VmError};
+use crate::{Context, Diagnostics, Source, SourceId, Sources};
+
+/// The name of the function which is generated to evaluate expressions.
+const EVALUATE: &str = "__debugger_evaluate";
+
+/// The number of units compiled to evaluate expressions which are cached.
+const EVALUATED_CAPACITY: usize = 32;
+
+/// How an execution which is being debugged should be resumed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum Resume {
+    /// Run until a breakpoint is hit or the execution completes.
+    Continue,
+    /// Run until a new line is reached in the current function, without
+    /// stopping in any functions it calls.
+    StepOver,
+    /// Run until a new line is reached, including in any functions which are
+    /// called.
+    StepInto,
+    /// Run until the current function returns.
+    StepOut,
+}
+
+/// The state of an execution after it has been resumed through a debugger.
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum DebugState {
+    /// The execution stopped on a breakpoint.
+    Breakpoint,
+    /// The execution stopped because a step completed.
+    Step,
+    /// The execution completed with the given value.
+    Complete(Value),
+}
+
+/// A location in a source file.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub struct DebugLocation {
+    /// The source file the location belongs to.
+    pub source_id: SourceId,
+    /// The zero-based line in the source file.
+    pub line: usize,
+}
+
+/// A single call frame of an execution which is being debugged.
+#[derive(Debug, Clone, Copy)]
+#[non_exhaustive]
+pub struct DebugFrame {
+    /// The instruction pointer which is being executed in the frame.
+    pub ip: usize,
+    /// The bottom of the stack of the frame.
+    pub stack_bottom: usize,
+}
+
+/// Error raised when evaluating an expression in a paused frame.
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum EvaluateError {
+    /// The expression failed to compile.
+    Compile(FatalDiagnostic),
+    /// The expression errored when it was evaluated.
+    Vm(VmError),
+}
+
+impl fmt::Display for EvaluateError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            EvaluateError::Compile(error) => error.fmt(f),
+            EvaluateError::Vm(error) => error.fmt(f),
+        }
+    }
+}
+
+impl crate::no_std::error::Error for EvaluateError {}
+
+/// A debugger for a single unit.
+pub struct Debugger {
+    /// The unit being debugged.
+    unit: Arc<Unit>,
+    /// Copies of the sources the unit was compiled from.
+    sources: Vec<Source>,
+    /// Locations of each instruction, sorted by instruction pointer.
+    locations: Vec<(usize, DebugLocation)>,
+    /// Instructions at which execution can stop.
+    ///
+    /// These are the instructions whose span is contained in a single line,
+    /// which excludes things like the clean up performed at the end of a
+    /// block.
+    stops: HashSet<usize>,
+    /// Functions sorted by the instruction pointer they start at.
+    functions: Vec<(usize, DebugSignature)>,
+    /// Lines which have breakpoints.
+    breakpoints: HashSet<DebugLocation>,
+    /// Units compiled to evaluate expressions, by the source they were
+    /// compiled from, with the most recently used unit first.
+    evaluated: VecDeque<(String, Arc<Unit>)>,
+}
+
+impl Debugger {
+    /// Construct a new debugger for the given unit, which was compiled from
+    /// the given sources.
+    ///
+    /// The unit must have been compiled with debug information, otherwise no
+    /// instructions can be associated with lines.
+    pub fn new(unit: Arc<Unit>, sources: &Sources) -> Self {
+        let mut locations = Vec::new();
+        let mut stops = HashSet::new();
+        let mut functions = Vec::new();
+
+        if let Some(debug) = unit.debug_info() {
+            for (&ip, inst) in &debug.instructions {
+                let Some(source) = sources.get(inst.source_id) else {
+                    continue;
+                };
+
+                let (line, _) = source.pos_to_utf8_linecol(inst.span.start.into_usize());
+                let (end, _) = source.pos_to_utf8_linecol(inst.span.end.into_usize());
+
+                if line == end {
+                    stops.insert(ip);
+                }
+
+                locations.push((
+                    ip,
+                    DebugLocation {
+                        source_id: inst.source_id,
+                        line,
+                    },
+                ));
+            }
+
+            for (&ip, hash) in &debug.functions_rev {
+                if let Some(signature) = debug.functions.get(hash) {
+                    functions.push((ip, signature.clone()));
+                }
+            }
+        }
+
+        locations.sort_by_key(|&(ip, _)| ip);
+        functions.sort_by_key(|&(ip, _)| ip);
+
+        Self {
+            unit,
+            sources: sources.iter().cloned().collect(),
+            locations,
+            stops,
+            functions,
+            breakpoints: HashSet::new(),
+            evaluated: VecDeque::new(),
+        }
+    }
+
+    /// Replace the breakpoints in the given source with breakpoints on the
+    /// given zero-based lines.
+    ///
+    /// Lines which have no instructions are moved to the closest following
+    /// line that does. The returned vector contains the line each breakpoint
+    /// was placed on, or `None` if it couldn't be placed.
+    pub fn set_breakpoints<I>(&mut self, source_id: SourceId, lines: I) -> Vec<Option<usize>>
+    where
+        I: IntoIterator<Item = usize>,
+    {
+        self.breakpoints.retain(|l| l.source_id != source_id);
+
+        let mut available = self
+            .locations
+            .iter()
+            .filter(|(ip, l)| l.source_id == source_id && self.stops.contains(ip))
+            .map(|(_, l)| l.line)
+            .collect::<Vec<_>>();
+
+        available.sort_unstable();
+        available.dedup();
+
+        let mut placed = Vec::new();
+
+        for line in lines {
+            let index = match available.binary_search(&line) {
+                Ok(index) | Err(index) => index,
+            };
+
+            let Some(&line) = available.get(index) else {
+                placed.push(None);
+                continue;
+            };
+
+            self.breakpoints.insert(DebugLocation { source_id, line });
+            placed.push(Some(line));
+        }
+
+        placed
+    }
+
+    /// Remove all breakpoints.
+    pub fn clear_breakpoints(&mut self) {
+        self.breakpoints.clear();
+    }
+
+    /// Test if there is a breakpoint at the given location.
+    pub fn has_breakpoint(&self, location: DebugLocation) -> bool {
+        self.breakpoints.contains(&location)
+    }
+
+    /// Get the source location of the instruction at the given instruction
+    /// pointer.
+    pub fn location(&self, ip: usize) -> Option<DebugLocation> {
+        let index = self
+            .locations
+            .binary_search_by_key(&ip, |&(ip, _)| ip)
+            .ok()?;
+        Some(self.locations[index].1)
+    }
+
+    /// Get the signature of the function which contains the given instruction
+    /// pointer.
+    pub fn function_at(&self, ip: usize) -> Option<&DebugSignature> {
+        let index = self.functions.partition_point(|&(start, _)| start <= ip);
+        let (_, signature) = self.functions.get(index.checked_sub(1)?)?;
+        Some(signature)
+    }
+
+    /// Get the call frames of the given virtual machine, starting with the
+    /// innermost one.
+    pub fn frames(&self, vm: &Vm) -> Vec<DebugFrame> {
+        let mut frames = vec![DebugFrame {
+            ip: vm.ip(),
+            stack_bottom: vm.stack().stack_bottom(),
+        }];
+
+        for frame in vm.call_frames().iter().rev() {
+            // The stored instruction pointer is the one the frame returns to,
+            // so we report the instruction that performed the call.
+            frames.push(DebugFrame {
+                ip: self.call_site(frame.ip).unwrap_or(frame.ip),
+                stack_bottom: frame.stack_bottom,
+            });
+        }
+
+        frames
+    }
+
+    /// Get the instruction which performed the call that returns to the given
+    /// instruction pointer.
+    fn call_site(&self, ip: usize) -> Option<usize> {
+        let index = self.locations.partition_point(|&(at, _)| at < ip);
+        Some(self.locations.get(index.checked_sub(1)?)?.0)
+    }
+
+    /// Get the named local variables which are live in the given frame.
+    pub fn locals<'a>(&'a self, vm: &Vm, frame: &DebugFrame) -> Vec<(&'a str, Value)> {
+        let Some(debug) = self.unit.debug_info() else {
+            return Vec::new();
+        };
+
+        let mut locals = Vec::new();
+
+        for var in debug.variables_at(frame.ip) {
+            let Some(value) = frame
+                .stack_bottom
+                .checked_add(var.offset)
+                .and_then(|n| vm.stack().get(n))
+            else {
+                continue;
+            };
+
+            locals.push((var.name.as_ref(), value.clone()));
+        }
+
+        locals
+    }
+
+    /// Evaluate an expression in the given frame.
+    ///
+    /// The expression has access to the local variables of the frame and to
+    /// the items of the unit being debugged. It is compiled using the given
+    /// context, which should be the same one the unit was compiled with.
+    ///
+    /// The most recently compiled expressions are cached, so evaluating the
+    /// same expression with the same local variables again doesn't recompile
+    /// the sources of the unit.
+    pub fn evaluate(
+        &mut self,
+        context: &Context,
+        vm: &Vm,
+        frame: &DebugFrame,
+        expr: &str,
+    ) -> Result<Value, EvaluateError> {
+        let mut args = Vec::new();
+        let mut values = Vec::new();
+
+        for (name, value) in self.locals(vm, frame) {
+            if name != "self" {
+                args.push(name);
+                values.push(value);
+            }
+        }
+
+        let args = args.join(", ");
+
+        let source = format!("pub fn {EVALUATE}({args}) {{\n{expr}\n}}");
+
+        let unit = match self.evaluated.iter().position(|(s, _)| *s == source) {
+            Some(index) => {
+                self.evaluated.make_contiguous()[..=index].rotate_right(1);
+                self.evaluated[0].1.clone()
+            }
+            None => {
+                let unit = Arc::new(self.compile(context, &source)?);
+                self.evaluated.truncate(EVALUATED_CAPACITY - 1);
+                self.evaluated.push_front((source, unit.clone()));
+                unit
+            }
+        };
+
+        let mut eval = Vm::new(vm.context().clone(), unit);
+        let mut execution = eval
+            .execute([EVALUATE], values)
+            .map_err(EvaluateError::Vm)?;
+        execution
+            .complete()
+            .into_result()
+            .map_err(EvaluateError::Vm)
+    }
+
+    /// Compile the given source which evaluates an expression, together with
+    /// the sources of the unit being debugged.
+    fn compile(&self, context: &Context, source: &str) -> Result<Unit, EvaluateError> {
+        let mut sources = Sources::new();
+
+        for source in &self.sources {
+            sources.insert(source.clone());
+        }
+
+        sources.insert(Source::new("<evaluate>", source));
+
+        let mut diagnostics = Diagnostics::without_warnings();
+
+        let result = crate::prepare(&mut sources)
+            .with_context(context)
+            .with_diagnostics(&mut diagnostics)
+            .build();
+
+        let Ok(unit) = result else {
+            let error = diagnostics
+                .into_diagnostics()
+                .into_iter()
+                .find_map(|d| match d {
+                    Diagnostic::Fatal(fatal) => Some(fatal),
+                    _ => None,
+                });
+
+            return match error {
+                Some(error) => Err(EvaluateError::Compile(error)),
+                None => Err(EvaluateError::Vm(VmError::panic(
+                    "failed to compile expression",
+                ))),
+            };
+        };
+
+        Ok(unit)
+    }
+
+    /// Construct a cursor which keeps track of where a resumed execution
+    /// should stop.
+    pub(crate) fn cursor(&self, vm: &Vm, resume: Resume) -> DebugCursor<'_> {
+        let depth = vm.call_frames().len();
+        let location = self.location(vm.ip());
+
+        DebugCursor {
+            debugger: self,
+            resume,
+            start: (depth, location),
+            last: (depth, location),
+            state: None,
+        }
+    }
+}
+
+/// Keeps track of where an execution should stop.
+pub(crate) struct DebugCursor<'a> {
+    debugger: &'a Debugger,
+    resume: Resume,
+    /// The call depth and location the execution was resumed from.
+    start: (usize, Option<DebugLocation>),
+    /// The last call depth and location observed.
+    last: (usize, Option<DebugLocation>),
+    /// The state the execution stopped in.
+    state: Option<DebugState>,
+}
+
+impl DebugCursor<'_> {
+    /// Take the state the execution stopped in, if it was stopped.
+    pub(crate) fn take_state(&mut self) -> Option<DebugState> {
+        self.state.take()
+    }
+
+    /// Test if the execution should stop before executing the next
+    /// instruction of the given virtual machine.
+    fn stop(&mut self, vm: &Vm) -> Option<DebugState> {
+        let debugger = self.debugger;
+
+        if !vm.is_same_unit(&debugger.unit) {
+            return None;
+        }
+
+        let depth = vm.call_frames().len();
+
+        // Returning into a frame lands in the middle of the line which
+        // performed the call, so it doesn't count as entering a line.
+        if depth < self.last.0 {
+            let call_site = debugger.call_site(vm.ip());
+            self.last = (depth, call_site.and_then(|ip| debugger.location(ip)));
+        }
+
+        if !debugger.stops.contains(&vm.ip()) {
+            return None;
+        }
+
+        let location = debugger.location(vm.ip());
+        let (last_depth, last_location) = self.last;
+        self.last = (depth, location);
+
+        let entered = depth > last_depth || location != last_location;
+
+        if entered && location.is_some_and(|l| debugger.has_breakpoint(l)) {
+            return Some(DebugState::Breakpoint);
+        }
+
+        let (start_depth, start_location) = self.start;
+
+        let step = match self.resume {
+            Resume::Continue => false,
+            Resume::StepOver => {
+                depth < start_depth || depth == start_depth && location != start_location
+            }
+            Resume::StepInto => (depth, location) != self.start,
+            Resume::StepOut => depth < start_depth,
+        };
+
+        step.then_some(DebugState::Step)
+    }
+}
+
+impl Observer for DebugCursor<'_> {
+    fn before(&mut self, vm: &Vm, nested: bool) -> bool {
+        if nested {
+            return false;
+        }
+
+        self.state = self.stop(vm);
+        self.state.is_some()
+    }
+
+    fn after(&mut self, _: &Vm, _: bool) {}
+}
@@ -443,6 +443,37 @@ impl UnsafeToMut for Result<Value, Value> {
     }
 }
 
+impl FromValue for num::BigInt {
+    fn from_value(value: Value) -> VmResult<Self> {
+        match value {
+            Value::BigInt(bigint) => VmResult::Ok(vm_try!(bigint.borrow_ref()).clone()),
+            // NB: integers are promoted to big integers.
+            Value::Integer(integer) => VmResult::Ok(num::BigInt::from(integer)),
+            actual => VmResult::err(VmErrorKind::expected::<num::BigInt>(vm_try!(
+                actual.type_info()
+            ))),
+        }
+    }
+}
+
+impl FromValue for Ref<num::BigInt> {
+    fn from_value(value: Value) -> VmResult<Self> {
+        let bigint = vm_try!(vm_try!(value.into_bigint()).into_ref());
+        VmResult::Ok(Ref::map(bigint, |bigint| &**bigint))
+    }
+}
+
+impl UnsafeToRef for num::BigInt {
+    type Guard = RawRef;
+
+    unsafe fn unsafe_to_ref<'a>(value: Value) -> VmResult<(&'a Self, Self::Guard)> {
+        let bigint = vm_try!(value.into_bigint());
+        let bigint = vm_try!(bigint.into_ref());
+        let (bigint, guard) = Ref::into_raw(bigint);
+        VmResult::Ok((bigint.as_ref(), guard))
+    }
+}
+
 impl FromValue for u8 {
     #[inline]
     fn from_value(value: Value) -> VmResult<Self> {
@@ -192,6 +192,20 @@ impl Function {
         self.0.call_with_vm(vm, args)
     }
 
+    /// Call with the given virtual machine, where the trailing arguments are
+    /// passed by the given names.
+    pub(crate) fn call_with_vm_named<N>(
+        &self,
+        vm: &mut Vm,
+        args: usize,
+        names: &[N],
+    ) -> VmResult<Option<VmHalt>>
+    where
+        N: AsRef<str>,
+    {
+        self.0.call_with_vm_named(vm, args, names)
+    }
+
     /// Create a function pointer from a handler.
     pub(crate) fn from_handler(handler: Arc<FunctionHandler>, hash: Hash) -> Self {
         Self(FunctionImpl::from_handler(handler, hash))
@@ -583,7 +597,8 @@ where
                 None
             }
             Inner::FnOffset(fn_offset) => {
-                if let Some(vm_call) = vm_try!(fn_offset.call_with_vm(vm, args, ())) {
+                if let Some(vm_call) = vm_try!(fn_offset.call_with_vm::<&str, _>(vm, args, &[], ()))
+                {
                     return VmResult::Ok(Some(VmHalt::VmCall(vm_call)));
                 }
 
@@ -593,9 +608,12 @@ where
                 let environment = vm_try!(closure.environment.try_clone());
                 let environment = vm_try!(OwnedTuple::try_from(environment));
 
-                if let Some(vm_call) =
-                    vm_try!(closure.fn_offset.call_with_vm(vm, args, (environment,)))
-                {
+                if let Some(vm_call) = vm_try!(closure.fn_offset.call_with_vm::<&str, _>(
+                    vm,
+                    args,
+                    &[],
+                    (environment,)
+                )) {
                     return VmResult::Ok(Some(VmHalt::VmCall(vm_call)));
                 }
 
@@ -642,6 +660,35 @@ where
         VmResult::Ok(reason)
     }
 
+    /// Call with the given virtual machine, where the trailing arguments are
+    /// passed by the given names.
+    ///
+    /// Only functions declared in a unit support named arguments.
+    pub(crate) fn call_with_vm_named<N>(
+        &self,
+        vm: &mut Vm,
+        args: usize,
+        names: &[N],
+    ) -> VmResult<Option<VmHalt>>
+    where
+        N: AsRef<str>,
+    {
+        let vm_call = match &self.inner {
+            Inner::FnOffset(fn_offset) => vm_try!(fn_offset.call_with_vm(vm, args, names, ())),
+            Inner::FnClosureOffset(closure) => {
+                let environment = vm_try!(closure.environment.try_clone());
+                let environment = vm_try!(OwnedTuple::try_from(environment));
+                vm_try!(closure
+                    .fn_offset
+                    .call_with_vm(vm, args, names, (environment,)))
+            }
+            _ if names.is_empty() => return self.call_with_vm(vm, args),
+            _ => return VmResult::err(VmErrorKind::UnsupportedNamedArguments),
+        };
+
+        VmResult::Ok(vm_call.map(VmHalt::VmCall))
+    }
+
     /// Create a function pointer from a handler.
     pub(crate) fn from_handler(handler: Arc<FunctionHandler>, hash: Hash) -> Self {
         Self {
@@ -878,12 +925,14 @@ impl FnOffset {
         A: Args,
         E: Args,
     {
-        vm_try!(check_args(args.count(), self.args));
-
+        let count = args.count();
         let mut vm = Vm::new(self.context.clone(), self.unit.clone());
 
         vm.set_ip(self.offset);
         vm_try!(args.into_stack(vm.stack_mut()));
+        vm_try!(self
+            .unit
+            .bind_args::<&str>(self.hash, vm.stack_mut(), count, self.args, &[]));
         vm_try!(extra.into_stack(vm.stack_mut()));
 
         self.call.call_with_vm(vm)
@@ -894,19 +943,28 @@ impl FnOffset {
     /// This will cause a halt in case the vm being called into isn't the same
     /// as the context and unit of the function.
     #[tracing::instrument(skip_all, fields(args, extra = extra.count(), ?self.offset, ?self.call, ?self.args, ?self.hash))]
-    fn call_with_vm<E>(&self, vm: &mut Vm, args: usize, extra: E) -> VmResult<Option<VmCall>>
+    fn call_with_vm<N, E>(
+        &self,
+        vm: &mut Vm,
+        args: usize,
+        names: &[N],
+        extra: E,
+    ) -> VmResult<Option<VmCall>>
     where
+        N: AsRef<str>,
         E: Args,
     {
         tracing::trace!("calling");
 
-        vm_try!(check_args(args, self.args));
+        vm_try!(self
+            .unit
+            .bind_args(self.hash, vm.stack_mut(), args, self.args, names));
 
         let same_unit = matches!(self.call, Call::Immediate if vm.is_same_unit(&self.unit));
         let same_context =
             matches!(self.call, Call::Immediate if vm.is_same_context(&self.context));
 
-        vm_try!(vm.push_call_frame(self.offset, args, !same_context));
+        vm_try!(vm.push_call_frame(self.offset, self.args, !same_context));
         vm_try!(extra.into_stack(vm.stack_mut()));
 
         // Fast path, just allocate a call frame and keep running.
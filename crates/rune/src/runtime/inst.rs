@@ -160,6 +160,22 @@ pub enum Inst {
         /// The number of arguments expected on the stack for this call.
         args: usize,
     },
+    /// Perform a instance function call where the trailing arguments are
+    /// passed by name.
+    ///
+    /// The instance being called on should be on top of the stack, followed by
+    /// `args` number of arguments. The names of the trailing arguments are
+    /// stored in the static object keys slot `slot`.
+    #[musli(packed)]
+    CallAssociatedNamed {
+        /// The hash of the name of the function to call.
+        hash: Hash,
+        /// The number of arguments expected on the stack for this call.
+        args: usize,
+        /// The static object keys slot with the names of the trailing
+        /// arguments.
+        slot: usize,
+    },
     /// Lookup the specified instance function and put it on the stack.
     /// This might help in cases where a single instance function is called many
     /// times (like in a loop) since it avoids calculating its full hash on
@@ -193,6 +209,27 @@ pub enum Inst {
         /// The number of arguments expected on the stack for this call.
         args: usize,
     },
+    /// Perform a function call on a function pointer stored on the stack,
+    /// where the trailing arguments are passed by name.
+    ///
+    /// The names of the trailing arguments are stored in the static object
+    /// keys slot `slot`.
+    ///
+    /// # Operation
+    ///
+    /// ```text
+    /// <fn>
+    /// <args...>
+    /// => <ret>
+    /// ```
+    #[musli(packed)]
+    CallFnNamed {
+        /// The number of arguments expected on the stack for this call.
+        args: usize,
+        /// The static object keys slot with the names of the trailing
+        /// arguments.
+        slot: usize,
+    },
     /// Perform an index get operation. Pushing the result on the stack.
     ///
     /// # Operation
@@ -773,6 +810,19 @@ pub enum Inst {
         /// The static byte string slot to load the string from.
         slot: usize,
     },
+    /// Load an arbitrary-precision integer from a static byte string slot,
+    /// which holds its signed little-endian bytes.
+    ///
+    /// # Operation
+    ///
+    /// ```text
+    /// => <bigint>
+    /// ```
+    #[musli(packed)]
+    BigInt {
+        /// The static byte string slot to load the integer from.
+        slot: usize,
+    },
     /// Pop the given number of values from the stack, and concatenate a string
     /// from them.
     ///
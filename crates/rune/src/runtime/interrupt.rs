@@ -0,0 +1,315 @@
+#[cfg(feature = "std")]
+use core::cell::RefCell;
+use core::future::Future;
+use core::pin::Pin;
+use core::sync::atomic::{AtomicBool, Ordering};
+use core::task::{Context, Poll};
+
+#[cfg(feature = "std")]
+use std::time::Instant;
+
+use pin_project::pin_project;
+
+use crate::no_std::sync::Arc;
+
+use crate::runtime::{VmErrorKind, VmResult};
+
+/// The number of instructions which are executed in between checking whether a
+/// deadline has been reached.
+#[cfg(feature = "std")]
+const DEADLINE_INTERVAL: u32 = 1024;
+
+#[cfg(feature = "std")]
+std::thread_local!(static CURRENT: RefCell<Scope> = RefCell::new(Scope::default()));
+
+/// A token which can be used to cooperatively cancel an execution, possibly
+/// from another thread.
+///
+/// Cancellation is checked by the virtual machine at instruction boundaries and
+/// whenever it awaits a future or a select. Once cancelled, the execution
+/// errors with a [`VmError`] for which [`VmError::is_cancelled`] returns
+/// `true`.
+///
+/// [`VmError`]: crate::runtime::VmError
+/// [`VmError::is_cancelled`]: crate::runtime::VmError::is_cancelled
+///
+/// # Examples
+///
+/// ```
+/// use rune::runtime::CancelToken;
+/// use rune::Vm;
+/// use std::sync::Arc;
+///
+/// let mut sources = rune::sources! {
+///     entry => {
+///         pub fn main() {
+///             loop {}
+///         }
+///     }
+/// };
+///
+/// let unit = rune::prepare(&mut sources).build()?;
+/// let mut vm = Vm::without_runtime(Arc::new(unit));
+///
+/// let token = CancelToken::new();
+///
+/// let handle = std::thread::spawn({
+///     let token = token.clone();
+///     move || token.cancel()
+/// });
+///
+/// let mut execution = vm.execute(["main"], ())?.with_cancel_token(token);
+/// let error = execution.complete().into_result().unwrap_err();
+/// assert!(error.is_cancelled());
+/// handle.join().unwrap();
+/// # Ok::<_, rune::Error>(())
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct CancelToken {
+    cancelled: Arc<AtomicBool>,
+}
+
+impl CancelToken {
+    /// Construct a new cancellation token which is not cancelled.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Cancel any executions associated with this token.
+    pub fn cancel(&self) {
+        self.cancelled.store(true, Ordering::Release);
+    }
+
+    /// Test if the token has been cancelled.
+    pub fn is_cancelled(&self) -> bool {
+        self.cancelled.load(Ordering::Acquire)
+    }
+}
+
+impl From<Arc<AtomicBool>> for CancelToken {
+    /// Use an existing flag as a cancellation token, where storing `true`
+    /// indicates that executions should be cancelled.
+    #[inline]
+    fn from(cancelled: Arc<AtomicBool>) -> Self {
+        Self { cancelled }
+    }
+}
+
+/// Conditions under which an execution is interrupted.
+#[derive(Default)]
+pub(crate) struct Interrupt {
+    /// The token which is used for cancellation.
+    token: Option<CancelToken>,
+    /// The deadline after which execution is interrupted.
+    #[cfg(feature = "std")]
+    deadline: Option<Instant>,
+    /// Instructions executed since the deadline was last checked.
+    #[cfg(feature = "std")]
+    ticks: u32,
+}
+
+impl Interrupt {
+    /// Construct interrupt conditions which are inherited from the execution
+    /// which is currently running on this thread, if any.
+    ///
+    /// This ensures that executions started from native functions, like
+    /// closures called by an iterator, are interrupted together with the
+    /// execution that called them. In no-std environments nothing is
+    /// inherited.
+    pub(crate) fn inherited() -> Self {
+        #[cfg(feature = "std")]
+        {
+            CURRENT.with(|current| {
+                let current = current.borrow();
+
+                Self {
+                    token: current.token.clone(),
+                    deadline: current.deadline,
+                    ticks: 0,
+                }
+            })
+        }
+
+        #[cfg(not(feature = "std"))]
+        {
+            Self::default()
+        }
+    }
+
+    /// Capture the current interrupt conditions, so that they can be made
+    /// available to nested executions through [`Scope::with`].
+    pub(crate) fn scope(&self) -> Scope {
+        Scope {
+            token: self.token.clone(),
+            #[cfg(feature = "std")]
+            deadline: self.deadline,
+        }
+    }
+
+    /// Set the token used for cancellation.
+    pub(crate) fn set_token(&mut self, token: CancelToken) {
+        self.token = Some(token);
+    }
+
+    /// Set the deadline after which execution is interrupted.
+    #[cfg(feature = "std")]
+    pub(crate) fn set_deadline(&mut self, deadline: Instant) {
+        self.deadline = Some(deadline);
+        self.ticks = 0;
+    }
+
+    /// Check for interrupts at an instruction boundary.
+    ///
+    /// Since reading the current time is comparatively expensive, the deadline
+    /// is only checked once every [`DEADLINE_INTERVAL`] instructions.
+    #[inline]
+    pub(crate) fn tick(&mut self) -> Result<(), VmErrorKind> {
+        if let Some(token) = &self.token {
+            if token.is_cancelled() {
+                return Err(VmErrorKind::Cancelled);
+            }
+        }
+
+        #[cfg(feature = "std")]
+        if let Some(deadline) = self.deadline {
+            self.ticks += 1;
+
+            if self.ticks == DEADLINE_INTERVAL {
+                self.ticks = 0;
+
+                if Instant::now() >= deadline {
+                    return Err(VmErrorKind::DeadlineExceeded);
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Unconditionally check for interrupts.
+    pub(crate) fn check(&self) -> Result<(), VmErrorKind> {
+        if let Some(token) = &self.token {
+            if token.is_cancelled() {
+                return Err(VmErrorKind::Cancelled);
+            }
+        }
+
+        #[cfg(feature = "std")]
+        if let Some(deadline) = self.deadline {
+            if Instant::now() >= deadline {
+                return Err(VmErrorKind::DeadlineExceeded);
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Wrap a future so that interrupts are checked every time it is polled.
+    pub(crate) fn wrap<F>(&self, future: F) -> Interruptible<'_, F>
+    where
+        F: Future + Unpin,
+    {
+        Interruptible {
+            interrupt: self,
+            future,
+        }
+    }
+}
+
+/// Interrupt conditions which are inherited by nested executions.
+#[derive(Default, Clone)]
+pub(crate) struct Scope {
+    #[cfg_attr(not(feature = "std"), allow(dead_code))]
+    token: Option<CancelToken>,
+    #[cfg(feature = "std")]
+    deadline: Option<Instant>,
+}
+
+impl Scope {
+    /// Make the interrupt conditions current while the given function is
+    /// called or future is polled.
+    pub(crate) fn with<T>(self, value: T) -> Scoped<T> {
+        Scoped { scope: self, value }
+    }
+
+    /// Replace the current interrupt conditions of this thread, returning
+    /// the ones which were previously set.
+    #[cfg(feature = "std")]
+    fn replace(self) -> Self {
+        CURRENT.with(|current| current.replace(self))
+    }
+
+    #[cfg(not(feature = "std"))]
+    fn replace(self) -> Self {
+        self
+    }
+}
+
+/// Restores the previous interrupt conditions when dropped.
+struct ScopeGuard(Option<Scope>);
+
+impl Drop for ScopeGuard {
+    fn drop(&mut self) {
+        if let Some(scope) = self.0.take() {
+            let _ = scope.replace();
+        }
+    }
+}
+
+/// Something which is run with a set of interrupt conditions.
+#[pin_project]
+pub(crate) struct Scoped<T> {
+    scope: Scope,
+    #[pin]
+    value: T,
+}
+
+impl<T, O> Scoped<T>
+where
+    T: FnOnce() -> O,
+{
+    /// Call the wrapped function.
+    pub(crate) fn call(self) -> O {
+        let _guard = ScopeGuard(Some(self.scope.replace()));
+        (self.value)()
+    }
+}
+
+impl<T> Future for Scoped<T>
+where
+    T: Future,
+{
+    type Output = T::Output;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let this = self.project();
+        let _guard = ScopeGuard(Some(this.scope.clone().replace()));
+        this.value.poll(cx)
+    }
+}
+
+/// A future which checks for interrupts before it is polled.
+pub(crate) struct Interruptible<'a, F> {
+    interrupt: &'a Interrupt,
+    future: F,
+}
+
+impl<F> Future for Interruptible<'_, F>
+where
+    F: Future + Unpin,
+{
+    type Output = VmResult<F::Output>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let this = self.get_mut();
+
+        if let Err(error) = this.interrupt.check() {
+            return Poll::Ready(VmResult::err(error));
+        }
+
+        match Pin::new(&mut this.future).poll(cx) {
+            Poll::Ready(output) => Poll::Ready(VmResult::Ok(output)),
+            Poll::Pending => Poll::Pending,
+        }
+    }
+}
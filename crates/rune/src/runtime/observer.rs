@@ -0,0 +1,97 @@
+//! Observing the instructions executed by a virtual machine.
+//!
+//! An [Observer] is passed to [Vm::run] when the instructions executed by an
+//! execution should be inspected one at a time, like when it is being stepped
+//! through, debugged, profiled or covered. Observing an execution doesn't
+//! limit the budget of the virtual machine, so executions which are started
+//! from native functions, like closures called by an iterator, run to
+//! completion and are observed as nested executions.
+//!
+//! In no-std environments nested executions are not observed.
+//!
+//! [Vm::run]: crate::runtime::Vm::run
+
+#[cfg(feature = "std")]
+use core::cell::Cell;
+use core::marker::PhantomData;
+use core::ptr::NonNull;
+
+use crate::runtime::Vm;
+
+#[cfg(feature = "std")]
+std::thread_local!(static CURRENT: Cell<Option<NonNull<dyn Observer>>> = Cell::new(None));
+
+/// Observes the instructions executed by a virtual machine.
+pub(crate) trait Observer {
+    /// Called before the instruction at the instruction pointer of `vm` is
+    /// executed, where `nested` indicates that `vm` belongs to an execution
+    /// started by the one being observed.
+    ///
+    /// Returning `true` halts the observed virtual machine with
+    /// [`VmHalt::Limited`]. Nested executions can't be halted.
+    ///
+    /// [`VmHalt::Limited`]: crate::runtime::VmHalt::Limited
+    fn before(&mut self, vm: &Vm, nested: bool) -> bool;
+
+    /// Called after the instruction has been executed.
+    fn after(&mut self, vm: &Vm, nested: bool);
+}
+
+/// Get the observer of the execution which is currently running on this
+/// thread, if any.
+pub(crate) fn current() -> Option<NonNull<dyn Observer>> {
+    #[cfg(feature = "std")]
+    {
+        CURRENT.with(|current| current.get())
+    }
+
+    #[cfg(not(feature = "std"))]
+    {
+        None
+    }
+}
+
+/// Makes an observer available to nested executions while it is alive.
+pub(crate) struct Guard<'a> {
+    observer: NonNull<dyn Observer>,
+    #[cfg(feature = "std")]
+    old: Option<NonNull<dyn Observer>>,
+    _marker: PhantomData<&'a mut (dyn Observer + 'a)>,
+}
+
+impl<'a> Guard<'a> {
+    /// Install the given observer.
+    ///
+    /// The observer must only be accessed through [`Guard::get`] while the
+    /// guard is alive, and the guard must be dropped rather than leaked.
+    pub(crate) fn new(observer: &'a mut (dyn Observer + 'a)) -> Self {
+        let observer = NonNull::from(observer);
+
+        // SAFETY: The guard exclusively borrows the observer for `'a`, so the
+        // erased pointer can't outlive it while the guard is alive. The guard
+        // uninstalls the pointer when it's dropped, which we ensure since
+        // guards are only ever held on the stack of `Vm::run`.
+        let observer = unsafe {
+            core::mem::transmute::<NonNull<dyn Observer + 'a>, NonNull<dyn Observer>>(observer)
+        };
+
+        Self {
+            observer,
+            #[cfg(feature = "std")]
+            old: CURRENT.with(|current| current.replace(Some(observer))),
+            _marker: PhantomData,
+        }
+    }
+
+    /// Get the installed observer.
+    pub(crate) fn get(&self) -> NonNull<dyn Observer> {
+        self.observer
+    }
+}
+
+impl Drop for Guard<'_> {
+    fn drop(&mut self) {
+        #[cfg(feature = "std")]
+        CURRENT.with(|current| current.set(self.old));
+    }
+}
@@ -0,0 +1,613 @@
+//! Support for profiling the execution of a virtual machine.
+//!
+//! The [Profiler] attributes the number of instructions executed and the wall
+//! time spent to the script functions and source lines they belong to. Time
+//! spent in native functions is attributed to the native function, as called
+//! from the script function which called it.
+//!
+//! A profile is recorded by running an execution through
+//! [VmExecution::profile], [VmExecution::async_profile] or [Vm::profile].
+//! Afterwards it can be written as folded stacks compatible with flamegraph
+//! tools through [Profiler::write_folded], or summarized through
+//! [Profiler::write_report].
+//!
+//! ```
+//! use rune::runtime::profiler::{Metric, Profiler};
+//! use rune::{Context, Source, Sources, Vm};
+//! use std::sync::Arc;
+//!
+//! let context = Context::with_default_modules()?;
+//! let runtime = Arc::new(context.runtime());
+//!
+//! let mut sources = Sources::new();
+//!
+//! sources.insert(Source::memory(r#"
+//! fn square(n) {
+//!     n * n
+//! }
+//!
+//! pub fn main() {
+//!     let out = [];
+//!
+//!     for n in 0..10 {
+//!         out.push(square(n));
+//!     }
+//!
+//!     out
+//! }
+//! "#));
+//!
+//! let unit = Arc::new(rune::prepare(&mut sources).with_context(&context).build()?);
+//!
+//! let mut profiler = Profiler::new(unit.clone(), &sources).with_context(&context);
+//!
+//! let mut vm = Vm::new(runtime, unit);
+//! vm.profile(["main"], (), &mut profiler)?;
+//!
+//! let functions = profiler.functions();
+//! assert!(functions.iter().any(|f| f.name == "square"));
+//! assert!(functions.iter().any(|f| f.name == "std::vec::Vec::push"));
+//!
+//! let mut folded = Vec::new();
+//! profiler.write_folded(&mut folded, Metric::Instructions)?;
+//! let folded = String::from_utf8(folded)?;
+//! assert!(folded.lines().any(|line| line.starts_with("main;square ")));
+//! # Ok::<_, Box<dyn std::error::Error>>(())
+//! ```
+//!
+//! [VmExecution::profile]: crate::runtime::VmExecution::profile
+//! [VmExecution::async_profile]: crate::runtime::VmExecution::async_profile
+
+use core::fmt;
+use core::time::Duration;
+
+use std::io::{self, Write};
+use std::time::Instant;
+
+use crate::no_std::collections::HashMap;
+use crate::no_std::prelude::*;
+use crate::no_std::sync::Arc;
+
+use crate::runtime::{Inst, Observer, Protocol, Unit, Value, Vm};
+use crate::{Context, Hash, Sources};
+
+/// The name used for frames which can't be attributed to a function.
+const UNKNOWN: &str = "<unknown>";
+
+/// The measurement which folded stacks are weighted by.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum Metric {
+    /// Weigh stacks by the wall time spent in them, in nanoseconds.
+    Time,
+    /// Weigh stacks by the number of instructions executed in them.
+    Instructions,
+}
+
+/// Measurements collected for a stack, function or line.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct Sample {
+    /// The number of instructions executed.
+    pub instructions: u64,
+    /// The wall time spent.
+    pub time: Duration,
+}
+
+impl Sample {
+    fn add(&mut self, other: Sample) {
+        self.instructions += other.instructions;
+        self.time += other.time;
+    }
+
+    fn get(&self, metric: Metric) -> u128 {
+        match metric {
+            Metric::Time => self.time.as_nanos(),
+            Metric::Instructions => u128::from(self.instructions),
+        }
+    }
+}
+
+/// The profile of a single function or source line.
+#[derive(Debug, Clone, Copy)]
+#[non_exhaustive]
+pub struct ProfileEntry<'a> {
+    /// The name of the function or source line.
+    pub name: &'a str,
+    /// Measurements for the function or line itself.
+    pub exclusive: Sample,
+    /// Measurements including everything called by the function.
+    ///
+    /// For lines, this is the same as `exclusive`.
+    pub inclusive: Sample,
+}
+
+/// Information about the instruction which is about to be executed.
+struct Probe {
+    /// The number of call frames before the instruction was executed.
+    depth: usize,
+    /// The native function which might be called by the instruction.
+    native: Option<usize>,
+    /// The source line of the instruction.
+    line: Option<usize>,
+    /// The stack the instruction is executed in.
+    stack: Vec<usize>,
+    /// When the instruction started executing.
+    start: Instant,
+    /// The time spent in executions started by the instruction.
+    nested: Duration,
+}
+
+/// A profiler which attributes instructions and time to functions and lines.
+///
+/// See the [module level documentation][self] for more information.
+pub struct Profiler {
+    /// The unit being profiled.
+    unit: Arc<Unit>,
+    /// Interned frame names.
+    names: Vec<Box<str>>,
+    /// Lookup of interned frame names.
+    interned: HashMap<Box<str>, usize>,
+    /// The first instruction of each function, sorted by instruction pointer.
+    functions: Vec<(usize, usize)>,
+    /// The line each instruction belongs to.
+    lines: HashMap<usize, usize>,
+    /// The names of lines.
+    line_names: Vec<Box<str>>,
+    /// The names of native functions.
+    natives: HashMap<Hash, usize>,
+    /// Samples collected for each stack.
+    stacks: HashMap<Vec<usize>, Sample>,
+    /// Samples collected for each line.
+    line_samples: Vec<Sample>,
+    /// Probes of the instructions being executed, one for each nested
+    /// execution.
+    probes: Vec<Probe>,
+    /// Stacks which can be reused by probes.
+    spare: Vec<Vec<usize>>,
+}
+
+impl Profiler {
+    /// Construct a new profiler for the given unit, which was compiled from
+    /// the given sources.
+    ///
+    /// The unit must have been compiled with debug information, otherwise
+    /// instructions can't be attributed to functions or lines.
+    pub fn new(unit: Arc<Unit>, sources: &Sources) -> Self {
+        let mut this = Self {
+            unit,
+            names: Vec::new(),
+            interned: HashMap::new(),
+            functions: Vec::new(),
+            lines: HashMap::new(),
+            line_names: Vec::new(),
+            natives: HashMap::new(),
+            stacks: HashMap::new(),
+            line_samples: Vec::new(),
+            probes: Vec::new(),
+            spare: Vec::new(),
+        };
+
+        let unit = this.unit.clone();
+
+        if let Some(debug) = unit.debug_info() {
+            for (&ip, hash) in &debug.functions_rev {
+                if let Some(signature) = debug.functions.get(hash) {
+                    let name = this.intern(&signature.path.to_string());
+                    this.functions.push((ip, name));
+                }
+            }
+
+            let mut line_ids = HashMap::new();
+
+            for (&ip, inst) in &debug.instructions {
+                let Some(source) = sources.get(inst.source_id) else {
+                    continue;
+                };
+
+                let (line, _) = source.pos_to_utf8_linecol(inst.span.start.into_usize());
+
+                let id = *line_ids.entry((inst.source_id, line)).or_insert_with(|| {
+                    this.line_names
+                        .push(format!("{}:{}", source.name(), line + 1).into());
+                    this.line_names.len() - 1
+                });
+
+                this.lines.insert(ip, id);
+            }
+        }
+
+        this.functions.sort_by_key(|&(ip, _)| ip);
+        this.line_samples = vec![Sample::default(); this.line_names.len()];
+        this
+    }
+
+    /// Use the given context to name the native functions which are called.
+    ///
+    /// Native functions which can't be named are reported by their hash.
+    pub fn with_context(mut self, context: &Context) -> Self {
+        for (hash, item) in context.iter_function_names() {
+            let name = item.to_string();
+            let name = self.intern(name.trim_start_matches("::"));
+            self.natives.insert(hash, name);
+        }
+
+        self
+    }
+
+    /// Clear all collected measurements.
+    pub fn clear(&mut self) {
+        self.stacks.clear();
+
+        for sample in &mut self.line_samples {
+            *sample = Sample::default();
+        }
+    }
+
+    /// The total measurements collected.
+    pub fn total(&self) -> Sample {
+        let mut total = Sample::default();
+
+        for sample in self.stacks.values() {
+            total.add(*sample);
+        }
+
+        total
+    }
+
+    /// Get the profile of every function, sorted by the most exclusive time
+    /// spent first.
+    pub fn functions(&self) -> Vec<ProfileEntry<'_>> {
+        let mut entries = HashMap::<usize, (Sample, Sample)>::new();
+        let mut seen = Vec::new();
+
+        for (stack, sample) in &self.stacks {
+            seen.clear();
+
+            for &id in stack {
+                // NB: recursive functions only count once per stack.
+                if !seen.contains(&id) {
+                    seen.push(id);
+                    entries.entry(id).or_default().1.add(*sample);
+                }
+            }
+
+            if let Some(&leaf) = stack.last() {
+                entries.entry(leaf).or_default().0.add(*sample);
+            }
+        }
+
+        let mut entries = entries
+            .into_iter()
+            .map(|(id, (exclusive, inclusive))| ProfileEntry {
+                name: &self.names[id],
+                exclusive,
+                inclusive,
+            })
+            .collect::<Vec<_>>();
+
+        sort_entries(&mut entries);
+        entries
+    }
+
+    /// Get the profile of every line which was executed, sorted by the most
+    /// time spent first.
+    pub fn lines(&self) -> Vec<ProfileEntry<'_>> {
+        let mut entries = self
+            .line_samples
+            .iter()
+            .zip(&self.line_names)
+            .filter(|(sample, _)| sample.instructions > 0)
+            .map(|(&sample, name)| ProfileEntry {
+                name,
+                exclusive: sample,
+                inclusive: sample,
+            })
+            .collect::<Vec<_>>();
+
+        sort_entries(&mut entries);
+        entries
+    }
+
+    /// Write the collected stacks in the folded format, where each line is a
+    /// semicolon-separated stack followed by its weight.
+    ///
+    /// This is the format expected by tools such as `inferno` and
+    /// `flamegraph.pl`.
+    pub fn write_folded<O>(&self, o: &mut O, metric: Metric) -> io::Result<()>
+    where
+        O: ?Sized + Write,
+    {
+        let mut lines = Vec::with_capacity(self.stacks.len());
+
+        for (stack, sample) in &self.stacks {
+            let mut line = String::new();
+
+            for (n, &id) in stack.iter().enumerate() {
+                if n > 0 {
+                    line.push(';');
+                }
+
+                line.push_str(&self.names[id]);
+            }
+
+            lines.push((line, sample.get(metric)));
+        }
+
+        lines.sort();
+
+        for (line, weight) in lines {
+            if weight > 0 {
+                writeln!(o, "{line} {weight}")?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Write a summary of the `limit` most expensive functions and lines.
+    pub fn write_report<O>(&self, o: &mut O, limit: usize) -> io::Result<()>
+    where
+        O: ?Sized + Write,
+    {
+        let total = self.total();
+
+        writeln!(
+            o,
+            "# profile: {} instructions in {:?}",
+            total.instructions, total.time
+        )?;
+
+        writeln!(o, "# functions")?;
+        writeln!(
+            o,
+            "{:>12} {:>12} {:>14}  function",
+            "self", "total", "instructions"
+        )?;
+
+        for entry in self.functions().into_iter().take(limit) {
+            writeln!(
+                o,
+                "{:>12} {:>12} {:>14}  {}",
+                DisplayDuration(entry.exclusive.time),
+                DisplayDuration(entry.inclusive.time),
+                entry.exclusive.instructions,
+                entry.name
+            )?;
+        }
+
+        writeln!(o, "# lines")?;
+        writeln!(o, "{:>12} {:>14}  line", "self", "instructions")?;
+
+        for entry in self.lines().into_iter().take(limit) {
+            writeln!(
+                o,
+                "{:>12} {:>14}  {}",
+                DisplayDuration(entry.exclusive.time),
+                entry.exclusive.instructions,
+                entry.name
+            )?;
+        }
+
+        Ok(())
+    }
+
+    /// Inspect the instruction which is about to be executed, and build the
+    /// stack it is executed in.
+    fn probe(&mut self, vm: &Vm) -> Probe {
+        let depth = vm.call_frames().len();
+        let mut stack = self.spare.pop().unwrap_or_default();
+        stack.clear();
+
+        // NB: nested executions are started by a native function called by the
+        // instruction being executed in the parent execution, so they extend
+        // its stack.
+        if let Some(parent) = self.probes.last() {
+            stack.extend_from_slice(&parent.stack);
+            stack.extend(parent.native);
+        }
+
+        if !Arc::ptr_eq(vm.unit(), &self.unit) {
+            stack.push(self.intern(UNKNOWN));
+
+            return Probe {
+                depth,
+                native: None,
+                line: None,
+                stack,
+                start: Instant::now(),
+                nested: Duration::ZERO,
+            };
+        }
+
+        // NB: the instruction pointer of a call frame is the return address
+        // of the caller, so the call itself is the instruction before it.
+        for frame in vm.call_frames() {
+            stack.push(self.function_at(frame.ip.saturating_sub(1)));
+        }
+
+        stack.push(self.function_at(vm.ip()));
+
+        Probe {
+            depth,
+            native: self.native_call(vm),
+            line: self.lines.get(&vm.ip()).copied(),
+            stack,
+            start: Instant::now(),
+            nested: Duration::ZERO,
+        }
+    }
+
+    /// Record the result of executing the probed instruction.
+    fn record(&mut self, probe: Probe, vm: &Vm) {
+        let Probe {
+            depth,
+            native,
+            line,
+            mut stack,
+            start,
+            nested,
+        } = probe;
+
+        let elapsed = start.elapsed();
+
+        if let Some(parent) = self.probes.last_mut() {
+            parent.nested += elapsed;
+        }
+
+        // NB: if a call didn't push a call frame, it was handled by a native
+        // function.
+        if let Some(id) = native {
+            if vm.call_frames().len() == depth {
+                stack.push(id);
+            }
+        }
+
+        // NB: time spent in nested executions is attributed to them.
+        let sample = Sample {
+            instructions: 1,
+            time: elapsed.saturating_sub(nested),
+        };
+
+        match self.stacks.get_mut(stack.as_slice()) {
+            Some(existing) => existing.add(sample),
+            None => {
+                self.stacks.insert(stack.clone(), sample);
+            }
+        }
+
+        if let Some(line) = line {
+            self.line_samples[line].add(sample);
+        }
+
+        self.spare.push(stack);
+    }
+
+    /// Get the native function which might be called by the instruction
+    /// about to be executed.
+    fn native_call(&mut self, vm: &Vm) -> Option<usize> {
+        let (inst, _) = vm.unit().instruction_at(vm.ip()).ok()??;
+        let stack = vm.stack();
+
+        let hash = match inst {
+            Inst::Call { hash, .. } => hash,
+            Inst::CallAssociated { hash, args } | Inst::CallAssociatedNamed { hash, args, .. } => {
+                let instance = stack.at_offset_from_top(args + 1).ok()?;
+                return self.associated(instance, hash);
+            }
+            Inst::CallFn { .. } | Inst::CallFnNamed { .. } => {
+                match stack.at_offset_from_top(1).ok()? {
+                    Value::Function(function) => function.borrow_ref().ok()?.type_hash(),
+                    Value::Type(ty) => ty.into_hash(),
+                    _ => return None,
+                }
+            }
+            // NB: instance functions which are loaded are later called through
+            // `CallFn`, so this is where they can be named.
+            Inst::LoadInstanceFn { hash } => {
+                let instance = stack.at_offset_from_top(1).ok()?;
+                self.associated(instance, hash);
+                return None;
+            }
+            _ => return None,
+        };
+
+        if let Some(&id) = self.natives.get(&hash) {
+            return Some(id);
+        }
+
+        let id = self.intern(&format!("<native {hash}>"));
+        self.natives.insert(hash, id);
+        Some(id)
+    }
+
+    /// Get the function associated with the given instance.
+    fn associated(&mut self, instance: &Value, name: Hash) -> Option<usize> {
+        let type_hash = instance.type_hash().ok()?;
+        let hash = Hash::associated_function(type_hash, name);
+
+        if let Some(&id) = self.natives.get(&hash) {
+            return Some(id);
+        }
+
+        // NB: protocol functions are not named in the context, so they are
+        // named after the type they are associated with.
+        let name = match Protocol::from_hash(name) {
+            Some(protocol) => match instance.type_info().into_result() {
+                Ok(type_info) => format!("{type_info}::{}", protocol.name),
+                Err(..) => format!("<native {}>", protocol.name),
+            },
+            None => format!("<native {hash}>"),
+        };
+
+        let id = self.intern(&name);
+        self.natives.insert(hash, id);
+        Some(id)
+    }
+
+    /// Find the function which contains the given instruction pointer.
+    fn function_at(&mut self, ip: usize) -> usize {
+        let index = self.functions.partition_point(|&(start, _)| start <= ip);
+
+        match index.checked_sub(1).and_then(|i| self.functions.get(i)) {
+            Some(&(_, id)) => id,
+            None => self.intern(UNKNOWN),
+        }
+    }
+
+    /// Intern the given frame name.
+    fn intern(&mut self, name: &str) -> usize {
+        if let Some(&id) = self.interned.get(name) {
+            return id;
+        }
+
+        let id = self.names.len();
+        self.names.push(name.into());
+        self.interned.insert(name.into(), id);
+        id
+    }
+}
+
+impl fmt::Debug for Profiler {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Profiler")
+            .field("functions", &self.functions.len())
+            .field("lines", &self.line_names.len())
+            .field("stacks", &self.stacks.len())
+            .finish()
+    }
+}
+
+impl Observer for Profiler {
+    fn before(&mut self, vm: &Vm, _: bool) -> bool {
+        let probe = self.probe(vm);
+        self.probes.push(probe);
+        false
+    }
+
+    fn after(&mut self, vm: &Vm, _: bool) {
+        if let Some(probe) = self.probes.pop() {
+            self.record(probe, vm);
+        }
+    }
+}
+
+/// Sort entries by the most time spent first, breaking ties by name.
+fn sort_entries(entries: &mut [ProfileEntry<'_>]) {
+    entries.sort_by(|a, b| {
+        b.exclusive
+            .time
+            .cmp(&a.exclusive.time)
+            .then_with(|| b.exclusive.instructions.cmp(&a.exclusive.instructions))
+            .then_with(|| a.name.cmp(b.name))
+    });
+}
+
+/// Display a duration with padding support.
+struct DisplayDuration(Duration);
+
+impl fmt::Display for DisplayDuration {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.pad(&format!("{:.3?}", self.0))
+    }
+}
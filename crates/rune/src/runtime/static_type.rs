@@ -100,6 +100,17 @@ pub(crate) static FLOAT_TYPE: &StaticType = &StaticType {
 impl_static_type!(f32 => FLOAT_TYPE);
 impl_static_type!(f64 => FLOAT_TYPE);
 
+/// Hash for `::std::bigint::BigInt`.
+pub(crate) const BIGINT_TYPE_HASH: Hash = ::rune_macros::hash!(::std::bigint::BigInt);
+
+/// The specialized type information for an arbitrary-precision integer type.
+pub(crate) static BIGINT_TYPE: &StaticType = &StaticType {
+    name: RawStr::from_str("BigInt"),
+    hash: BIGINT_TYPE_HASH,
+};
+
+impl_static_type!(num::BigInt => BIGINT_TYPE);
+
 pub(crate) static STRING_TYPE: &StaticType = &StaticType {
     name: RawStr::from_str("String"),
     hash: ::rune_macros::hash!(::std::string::String),
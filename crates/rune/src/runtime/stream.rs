@@ -1,9 +1,19 @@
 use core::fmt;
+use core::pin::Pin;
+
+use crate::no_std::prelude::*;
+
+use futures_util::stream::StreamExt;
 
 use crate as rune;
-use crate::runtime::{GeneratorState, Shared, Value, Vm, VmErrorKind, VmExecution, VmResult};
+use crate::runtime::{
+    GeneratorState, Shared, ToValue, Value, Vm, VmErrorKind, VmExecution, VmResult,
+};
 use crate::Any;
 
+/// dyn stream alias.
+type DynStream = dyn futures_core::Stream<Item = VmResult<Value>> + 'static;
+
 /// A stream with a stored virtual machine.
 #[derive(Any)]
 #[rune(builtin, static_type = STREAM_TYPE, from_value = Value::into_stream, from_value_params = [Vm])]
@@ -11,7 +21,18 @@ pub struct Stream<T>
 where
     T: AsRef<Vm> + AsMut<Vm>,
 {
-    execution: Option<VmExecution<T>>,
+    inner: Option<Inner<T>>,
+}
+
+/// What produces the values of a stream.
+enum Inner<T>
+where
+    T: AsRef<Vm> + AsMut<Vm>,
+{
+    /// An async generator executing in a virtual machine.
+    Execution(VmExecution<T>),
+    /// A native stream.
+    Native(Pin<Box<DynStream>>),
 }
 
 impl<T> Stream<T>
@@ -21,14 +42,30 @@ where
     /// Construct a stream from a virtual machine.
     pub(crate) fn new(vm: T) -> Self {
         Self {
-            execution: Some(VmExecution::new(vm)),
+            inner: Some(Inner::Execution(VmExecution::new(vm))),
         }
     }
 
     /// Construct a generator from a complete execution.
     pub(crate) fn from_execution(execution: VmExecution<T>) -> Self {
         Self {
-            execution: Some(execution),
+            inner: Some(Inner::Execution(execution)),
+        }
+    }
+
+    /// Construct a stream from a native stream of values.
+    ///
+    /// Since a native stream can't receive values, any value it's resumed
+    /// with is ignored.
+    pub fn from_stream<S, O>(stream: S) -> Self
+    where
+        S: 'static + futures_core::Stream<Item = VmResult<O>>,
+        O: ToValue,
+    {
+        let stream = stream.map(|value| vm_try!(value).to_value());
+
+        Self {
+            inner: Some(Inner::Native(Box::pin(stream))),
         }
     }
 
@@ -46,19 +83,24 @@ where
 
     /// Get the next value produced by this stream.
     pub async fn resume(&mut self, value: Value) -> VmResult<GeneratorState> {
-        let execution = vm_try!(self
-            .execution
-            .as_mut()
-            .ok_or(VmErrorKind::GeneratorComplete));
-
-        let state = if execution.is_resumed() {
-            vm_try!(execution.async_resume_with(value).await)
-        } else {
-            vm_try!(execution.async_resume().await)
+        let inner = vm_try!(self.inner.as_mut().ok_or(VmErrorKind::GeneratorComplete));
+
+        let state = match inner {
+            Inner::Execution(execution) => {
+                if execution.is_resumed() {
+                    vm_try!(execution.async_resume_with(value).await)
+                } else {
+                    vm_try!(execution.async_resume().await)
+                }
+            }
+            Inner::Native(stream) => match stream.next().await {
+                Some(value) => GeneratorState::Yielded(vm_try!(value)),
+                None => GeneratorState::Complete(Value::EmptyTuple),
+            },
         };
 
         if state.is_complete() {
-            self.execution = None;
+            self.inner = None;
         }
 
         VmResult::Ok(state)
@@ -75,9 +117,12 @@ where
 impl Stream<&mut Vm> {
     /// Convert the current stream into one which owns its virtual machine.
     pub fn into_owned(self) -> Stream<Vm> {
-        Stream {
-            execution: self.execution.map(|e| e.into_owned()),
-        }
+        let inner = self.inner.map(|inner| match inner {
+            Inner::Execution(execution) => Inner::Execution(execution.into_owned()),
+            Inner::Native(stream) => Inner::Native(stream),
+        });
+
+        Stream { inner }
     }
 }
 
@@ -87,7 +132,7 @@ where
 {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         f.debug_struct("Stream")
-            .field("completed", &self.execution.is_none())
+            .field("completed", &self.inner.is_none())
             .finish()
     }
 }
@@ -5,7 +5,10 @@
 
 #[cfg(feature = "byte-code")]
 mod byte_code;
+#[cfg(feature = "unit-format")]
+mod format;
 mod storage;
+mod verify;
 
 use core::fmt;
 
@@ -17,7 +20,8 @@ use serde::{Deserialize, Serialize};
 
 use crate::hash;
 use crate::runtime::{
-    Call, ConstValue, DebugInfo, Inst, Rtti, StaticString, VariantRtti, VmError, VmErrorKind,
+    Call, ConstValue, DebugInfo, Inst, Rtti, Stack, StaticString, Value, VariantRtti, VmError,
+    VmErrorKind,
 };
 use crate::Hash;
 
@@ -27,6 +31,9 @@ pub use self::storage::{
 
 #[cfg(feature = "byte-code")]
 pub use self::byte_code::ByteCodeUnit;
+#[cfg(feature = "unit-format")]
+pub use self::format::{UnitFormatError, UnitHeader, FORMAT_VERSION};
+pub use self::verify::VerifyError;
 
 /// Default storage implementation to use.
 #[cfg(not(rune_byte_code))]
@@ -40,7 +47,6 @@ pub type DefaultStorage = ByteCodeUnit;
 #[serde(bound = "S: Serialize + DeserializeOwned")]
 pub struct Unit<S = DefaultStorage> {
     /// The information needed to execute the program.
-    #[serde(flatten)]
     logic: Logic<S>,
     /// Debug info if available for unit.
     debug: Option<Box<DebugInfo>>,
@@ -71,6 +77,9 @@ pub struct Logic<S = DefaultStorage> {
     variant_rtti: hash::Map<Arc<VariantRtti>>,
     /// Named constants
     constants: hash::Map<ConstValue>,
+    /// Parameters of functions, used to bind named arguments and to fill in
+    /// default values.
+    parameters: hash::Map<UnitFnParameters>,
 }
 
 impl<S> Unit<S> {
@@ -94,6 +103,7 @@ impl<S> Unit<S> {
         variant_rtti: hash::Map<Arc<VariantRtti>>,
         debug: Option<Box<DebugInfo>>,
         constants: hash::Map<ConstValue>,
+        parameters: hash::Map<UnitFnParameters>,
     ) -> Self {
         Self {
             logic: Logic {
@@ -105,6 +115,7 @@ impl<S> Unit<S> {
                 rtti,
                 variant_rtti,
                 constants,
+                parameters,
             },
             debug,
         }
@@ -203,6 +214,42 @@ impl<S> Unit<S> {
     pub(crate) fn constant(&self, hash: Hash) -> Option<&ConstValue> {
         self.logic.constants.get(&hash)
     }
+
+    /// Lookup the parameters of a function in the unit.
+    pub(crate) fn parameters(&self, hash: Hash) -> Option<&UnitFnParameters> {
+        self.logic.parameters.get(&hash)
+    }
+
+    /// Bind the `args` arguments on top of the stack to the parameters of the
+    /// function with the given hash, where the trailing arguments are passed
+    /// by the given names.
+    ///
+    /// Once bound, the stack holds the `expected` arguments of the function in
+    /// the order in which they are declared.
+    pub(crate) fn bind_args<N>(
+        &self,
+        hash: Hash,
+        stack: &mut Stack,
+        args: usize,
+        expected: usize,
+        names: &[N],
+    ) -> Result<(), VmErrorKind>
+    where
+        N: AsRef<str>,
+    {
+        if names.is_empty() && args == expected {
+            return Ok(());
+        }
+
+        match self.parameters(hash) {
+            Some(parameters) => parameters.bind(stack, args, names),
+            None if names.is_empty() => Err(VmErrorKind::BadArgumentCount {
+                actual: args,
+                expected,
+            }),
+            None => Err(VmErrorKind::UnsupportedNamedArguments),
+        }
+    }
 }
 
 impl<S> Unit<S>
@@ -268,6 +315,92 @@ pub(crate) enum UnitFn {
     },
 }
 
+/// The parameters of a function.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[non_exhaustive]
+pub(crate) struct UnitFnParameters {
+    /// The names of the parameters, if they bind a plain identifier.
+    pub(crate) names: Box<[Option<Box<str>>]>,
+    /// The default values of the trailing parameters.
+    pub(crate) defaults: Box<[ConstValue]>,
+}
+
+impl UnitFnParameters {
+    /// Bind arguments to parameters, see [`Unit::bind_args`].
+    fn bind<N>(&self, stack: &mut Stack, args: usize, names: &[N]) -> Result<(), VmErrorKind>
+    where
+        N: AsRef<str>,
+    {
+        let expected = self.names.len();
+
+        let positional = match args.checked_sub(names.len()) {
+            Some(positional) if positional <= expected => positional,
+            _ => {
+                return Err(VmErrorKind::BadArgumentCount {
+                    actual: args,
+                    expected,
+                })
+            }
+        };
+
+        let named = stack.pop_sequence(names.len())??;
+        let mut slots = (positional..expected)
+            .map(|_| None)
+            .collect::<Vec<Option<Value>>>();
+
+        for (name, value) in names.iter().zip(named) {
+            let name = name.as_ref();
+
+            let Some(index) = self.names.iter().position(|n| n.as_deref() == Some(name)) else {
+                return Err(VmErrorKind::NoSuchArgument { name: name.into() });
+            };
+
+            let slot = index
+                .checked_sub(positional)
+                .and_then(|index| slots.get_mut(index))
+                .filter(|slot| slot.is_none());
+
+            let Some(slot) = slot else {
+                return Err(VmErrorKind::DuplicateArgument { name: name.into() });
+            };
+
+            *slot = Some(value);
+        }
+
+        let first_default = expected - self.defaults.len();
+
+        for (index, slot) in (positional..).zip(slots) {
+            let value = match slot {
+                Some(value) => value,
+                None => {
+                    let default = index
+                        .checked_sub(first_default)
+                        .and_then(|index| self.defaults.get(index));
+
+                    match (default, &self.names[index]) {
+                        (Some(default), _) => default.clone().into_value()?,
+                        (None, Some(name)) => {
+                            return Err(VmErrorKind::MissingArgument {
+                                name: name.as_ref().into(),
+                            });
+                        }
+                        (None, None) => {
+                            return Err(VmErrorKind::BadArgumentCount {
+                                actual: args,
+                                expected,
+                            });
+                        }
+                    }
+                }
+            };
+
+            stack.push(value)?;
+        }
+
+        Ok(())
+    }
+}
+
 impl fmt::Display for UnitFn {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
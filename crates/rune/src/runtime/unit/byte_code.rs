@@ -73,6 +73,8 @@ impl UnitEncoder for ByteCodeUnit {
 impl UnitStorage for ByteCodeUnit {
     type Iter<'this> = ByteCodeUnitIter<'this>;
 
+    const STORAGE: u32 = 1;
+
     #[inline]
     fn end(&self) -> usize {
         self.bytes.len()
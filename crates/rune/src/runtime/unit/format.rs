@@ -0,0 +1,248 @@
+use core::fmt;
+
+use crate::no_std::error;
+use crate::no_std::prelude::*;
+
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+
+use crate::runtime::unit::{Unit, UnitStorage, VerifyError};
+use crate::runtime::RuntimeContext;
+use crate::Hash;
+
+/// The version of the binary unit format produced by [Unit::encode].
+///
+/// This is increased whenever the encoding of a unit changes, and units with
+/// a different version are rejected by [Unit::decode].
+pub const FORMAT_VERSION: u32 = 5;
+
+/// Magic bytes at the start of every encoded unit.
+const MAGIC: [u8; 4] = *b"RUNU";
+
+/// The size of the header in bytes.
+const HEADER_SIZE: usize = 20;
+
+/// The header of a unit encoded with [Unit::encode].
+///
+/// The header is laid out as follows, with all integers in little-endian
+/// byte order:
+///
+/// | Offset | Size | Content                                              |
+/// |--------|------|------------------------------------------------------|
+/// | 0      | 4    | The magic bytes `RUNU`.                              |
+/// | 4      | 4    | The format version, see [FORMAT_VERSION].            |
+/// | 8      | 4    | The instruction storage, `0` for [ArrayUnit] and `1` for `ByteCodeUnit`. |
+/// | 12     | 8    | The hash of the unit's requirements, see [Unit::requirements]. |
+/// | 20     | ..   | The unit, serialized with [bincode].                 |
+///
+/// [ArrayUnit]: crate::runtime::unit::ArrayUnit
+/// [bincode]: https://docs.rs/bincode
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct UnitHeader {
+    /// The format version of the unit.
+    pub version: u32,
+    /// The instruction storage used by the unit.
+    pub storage: u32,
+    /// The hash of the native functions and types which the unit requires.
+    pub requirements: Hash,
+}
+
+impl UnitHeader {
+    /// Read the header of an encoded unit.
+    ///
+    /// This can be used to check whether a unit is compatible with a context
+    /// without decoding it.
+    pub fn read(bytes: &[u8]) -> Result<Self, UnitFormatError> {
+        let Some(header) = bytes.get(..HEADER_SIZE) else {
+            return Err(UnitFormatError::new(UnitFormatErrorKind::Truncated));
+        };
+
+        let (magic, rest) = header.split_at(4);
+
+        if magic != MAGIC {
+            return Err(UnitFormatError::new(UnitFormatErrorKind::BadMagic));
+        }
+
+        let (version, rest) = rest.split_at(4);
+        let (storage, requirements) = rest.split_at(4);
+
+        Ok(Self {
+            version: u32::from_le_bytes(version.try_into().unwrap_or_default()),
+            storage: u32::from_le_bytes(storage.try_into().unwrap_or_default()),
+            requirements: Hash::new(u64::from_le_bytes(
+                requirements.try_into().unwrap_or_default(),
+            )),
+        })
+    }
+
+    fn write(&self, output: &mut Vec<u8>) {
+        output.extend_from_slice(&MAGIC);
+        output.extend_from_slice(&self.version.to_le_bytes());
+        output.extend_from_slice(&self.storage.to_le_bytes());
+        output.extend_from_slice(&self.requirements.into_inner().to_le_bytes());
+    }
+}
+
+/// Error raised when encoding or decoding a unit fails.
+#[derive(Debug)]
+pub struct UnitFormatError {
+    kind: UnitFormatErrorKind,
+}
+
+impl UnitFormatError {
+    fn new(kind: UnitFormatErrorKind) -> Self {
+        Self { kind }
+    }
+}
+
+impl fmt::Display for UnitFormatError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.kind {
+            UnitFormatErrorKind::Truncated => write!(f, "Unit is missing a header"),
+            UnitFormatErrorKind::BadMagic => write!(f, "Not an encoded unit"),
+            UnitFormatErrorKind::UnsupportedVersion { version } => {
+                write!(
+                    f,
+                    "Unit has format version {version}, but only version {FORMAT_VERSION} is supported"
+                )
+            }
+            UnitFormatErrorKind::StorageMismatch { expected, actual } => {
+                write!(
+                    f,
+                    "Unit uses instruction storage {actual}, but storage {expected} was expected"
+                )
+            }
+            UnitFormatErrorKind::RequirementsMismatch { expected, actual } => {
+                write!(
+                    f,
+                    "Unit requirements {actual} do not match {expected} in its header"
+                )
+            }
+            UnitFormatErrorKind::Bincode { error } => error.fmt(f),
+            UnitFormatErrorKind::Verify { error } => error.fmt(f),
+        }
+    }
+}
+
+impl error::Error for UnitFormatError {
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        match &self.kind {
+            UnitFormatErrorKind::Bincode { error } => Some(error),
+            UnitFormatErrorKind::Verify { error } => Some(error),
+            _ => None,
+        }
+    }
+}
+
+impl From<VerifyError> for UnitFormatError {
+    fn from(error: VerifyError) -> Self {
+        Self::new(UnitFormatErrorKind::Verify { error })
+    }
+}
+
+impl From<bincode::Error> for UnitFormatError {
+    fn from(error: bincode::Error) -> Self {
+        Self::new(UnitFormatErrorKind::Bincode { error })
+    }
+}
+
+#[derive(Debug)]
+enum UnitFormatErrorKind {
+    Truncated,
+    BadMagic,
+    UnsupportedVersion { version: u32 },
+    StorageMismatch { expected: u32, actual: u32 },
+    RequirementsMismatch { expected: Hash, actual: Hash },
+    Bincode { error: bincode::Error },
+    Verify { error: VerifyError },
+}
+
+impl<S> Unit<S>
+where
+    S: UnitStorage + Serialize + DeserializeOwned,
+{
+    /// Encode the unit using the versioned binary unit format, which is
+    /// described in [UnitHeader].
+    ///
+    /// The encoded unit can be loaded again with [Unit::decode].
+    pub fn encode(&self) -> Result<Vec<u8>, UnitFormatError> {
+        let header = UnitHeader {
+            version: FORMAT_VERSION,
+            storage: S::STORAGE,
+            requirements: self.requirements()?,
+        };
+
+        let mut output = Vec::new();
+        header.write(&mut output);
+        bincode::serialize_into(&mut output, self)?;
+        Ok(output)
+    }
+
+    /// Decode a unit which was encoded with [Unit::encode], and
+    /// [verify][Unit::verify] it against the context it will run in.
+    ///
+    /// This fails if the unit was encoded with a different format version or
+    /// instruction storage, if it has been modified after it was encoded, or
+    /// if it's not compatible with `context`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rune::runtime::unit::UnitHeader;
+    /// use rune::{Context, Source, Sources, Unit, Vm};
+    /// use std::sync::Arc;
+    ///
+    /// let context = Context::with_default_modules()?;
+    ///
+    /// let mut sources = Sources::new();
+    /// sources.insert(Source::new("entry", "pub fn main() { 42 }"));
+    ///
+    /// let unit = rune::prepare(&mut sources).with_context(&context).build()?;
+    /// let bytes = unit.encode()?;
+    ///
+    /// let header = UnitHeader::read(&bytes)?;
+    /// assert_eq!(header.requirements, unit.requirements()?);
+    ///
+    /// let runtime = Arc::new(context.runtime());
+    /// let unit = Unit::decode(&bytes, &runtime)?;
+    ///
+    /// let mut vm = Vm::new(runtime, Arc::new(unit));
+    /// let output: i64 = rune::from_value(vm.call(["main"], ())?)?;
+    /// assert_eq!(output, 42);
+    /// # Ok::<_, Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn decode(bytes: &[u8], context: &RuntimeContext) -> Result<Self, UnitFormatError> {
+        let header = UnitHeader::read(bytes)?;
+
+        if header.version != FORMAT_VERSION {
+            return Err(UnitFormatError::new(
+                UnitFormatErrorKind::UnsupportedVersion {
+                    version: header.version,
+                },
+            ));
+        }
+
+        if header.storage != S::STORAGE {
+            return Err(UnitFormatError::new(UnitFormatErrorKind::StorageMismatch {
+                expected: S::STORAGE,
+                actual: header.storage,
+            }));
+        }
+
+        let unit: Self = bincode::deserialize(&bytes[HEADER_SIZE..])?;
+        let requirements = unit.requirements()?;
+
+        if requirements != header.requirements {
+            return Err(UnitFormatError::new(
+                UnitFormatErrorKind::RequirementsMismatch {
+                    expected: header.requirements,
+                    actual: requirements,
+                },
+            ));
+        }
+
+        unit.verify(context)?;
+        Ok(unit)
+    }
+}
@@ -51,6 +51,10 @@ pub trait UnitStorage: self::sealed::Sealed + fmt::Debug + Default + Clone {
     where
         Self: 'this;
 
+    /// Identifier of the storage in the header of an encoded unit.
+    #[doc(hidden)]
+    const STORAGE: u32;
+
     /// Size of unit storage. This can be seen as the instruction pointer which
     /// is just beyond the last instruction.
     fn end(&self) -> usize;
@@ -103,6 +107,8 @@ impl UnitEncoder for ArrayUnit {
 impl UnitStorage for ArrayUnit {
     type Iter<'this> = iter::Enumerate<iter::Copied<slice::Iter<'this, Inst>>>;
 
+    const STORAGE: u32 = 0;
+
     #[inline]
     fn end(&self) -> usize {
         self.instructions.len()
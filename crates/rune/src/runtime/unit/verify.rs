@@ -0,0 +1,582 @@
+use core::fmt;
+
+use crate::no_std::collections::BTreeSet;
+use crate::no_std::error;
+use crate::no_std::prelude::*;
+
+use crate::hash::ParametersBuilder;
+use crate::runtime::unit::{Unit, UnitFn, UnitStorage};
+use crate::runtime::{Inst, InstAddress, InstTarget, RuntimeContext};
+use crate::Hash;
+
+/// The maximum number of values a single instruction pushes onto the stack,
+/// with the exception of [Inst::PushTuple] which pushes a dynamic number of
+/// values.
+const MAX_PUSH: usize = 2;
+
+/// Error raised when a [Unit] fails verification.
+#[derive(Debug)]
+pub struct VerifyError {
+    kind: VerifyErrorKind,
+}
+
+impl VerifyError {
+    fn new(kind: VerifyErrorKind) -> Self {
+        Self { kind }
+    }
+}
+
+impl fmt::Display for VerifyError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.kind {
+            VerifyErrorKind::BadInstruction { ip } => {
+                write!(f, "Bad instruction at {ip}")
+            }
+            VerifyErrorKind::BadJump { ip, jump } => {
+                write!(f, "Bad jump {jump} at {ip}")
+            }
+            VerifyErrorKind::BadOffset { ip, offset } => {
+                write!(
+                    f,
+                    "Call at {ip} to offset {offset} which is not an instruction"
+                )
+            }
+            VerifyErrorKind::BadFunctionOffset { hash, offset } => {
+                write!(
+                    f,
+                    "Function {hash} is at offset {offset} which is not an instruction"
+                )
+            }
+            VerifyErrorKind::BadStackOffset { ip, offset, limit } => {
+                write!(
+                    f,
+                    "Stack offset {offset} at {ip} is out of bounds, the function uses at most {limit} stack slots"
+                )
+            }
+            VerifyErrorKind::MissingStaticString { ip, slot } => {
+                write!(f, "Missing static string for slot {slot} at {ip}")
+            }
+            VerifyErrorKind::MissingStaticBytes { ip, slot } => {
+                write!(f, "Missing static byte string for slot {slot} at {ip}")
+            }
+            VerifyErrorKind::MissingStaticObjectKeys { ip, slot } => {
+                write!(f, "Missing static object keys for slot {slot} at {ip}")
+            }
+            VerifyErrorKind::MissingFunction { ip, hash } => {
+                write!(f, "Missing function {hash} called at {ip}")
+            }
+            VerifyErrorKind::MissingRtti { hash } => {
+                write!(f, "Missing runtime information for type {hash}")
+            }
+            VerifyErrorKind::MissingVariantRtti { hash } => {
+                write!(f, "Missing runtime information for variant {hash}")
+            }
+        }
+    }
+}
+
+impl error::Error for VerifyError {}
+
+#[derive(Debug)]
+enum VerifyErrorKind {
+    BadInstruction {
+        ip: usize,
+    },
+    BadJump {
+        ip: usize,
+        jump: usize,
+    },
+    BadOffset {
+        ip: usize,
+        offset: usize,
+    },
+    BadFunctionOffset {
+        hash: Hash,
+        offset: usize,
+    },
+    BadStackOffset {
+        ip: usize,
+        offset: usize,
+        limit: usize,
+    },
+    MissingStaticString {
+        ip: usize,
+        slot: usize,
+    },
+    MissingStaticBytes {
+        ip: usize,
+        slot: usize,
+    },
+    MissingStaticObjectKeys {
+        ip: usize,
+        slot: usize,
+    },
+    MissingFunction {
+        ip: usize,
+        hash: Hash,
+    },
+    MissingRtti {
+        hash: Hash,
+    },
+    MissingVariantRtti {
+        hash: Hash,
+    },
+}
+
+/// Native functions and types which a unit expects to be provided by the
+/// context it runs in.
+struct Requirements {
+    functions: BTreeSet<Hash>,
+    types: BTreeSet<Hash>,
+}
+
+impl<S> Unit<S>
+where
+    S: UnitStorage,
+{
+    /// Verify that the unit is well-formed and can run with the given
+    /// context.
+    ///
+    /// This checks that:
+    /// * Every instruction can be decoded.
+    /// * Jumps and calls land on an instruction.
+    /// * Stack offsets are within the number of stack slots the function
+    ///   they're in can use.
+    /// * Static strings, byte strings and object keys referenced by
+    ///   instructions exist.
+    /// * Functions which are called are either defined in the unit or
+    ///   provided by `context`.
+    /// * Type information used to construct values exists.
+    ///
+    /// Units produced by the compiler always pass verification, but units
+    /// which are loaded from elsewhere should be verified before they are
+    /// handed to a [Vm][crate::Vm].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rune::{Context, Source, Sources};
+    ///
+    /// let context = Context::with_default_modules()?;
+    ///
+    /// let mut sources = Sources::new();
+    /// sources.insert(Source::new("entry", "pub fn main() { std::i64::max(1, 2) }"));
+    ///
+    /// let unit = rune::prepare(&mut sources).with_context(&context).build()?;
+    /// assert!(unit.verify(&context.runtime()).is_ok());
+    ///
+    /// // The unit calls `std::i64::max`, which an empty context lacks.
+    /// let empty = Context::new().runtime();
+    /// assert!(unit.verify(&empty).is_err());
+    /// # Ok::<_, Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn verify(&self, context: &RuntimeContext) -> Result<(), VerifyError> {
+        let instructions = self.decode_instructions()?;
+        let starts = instructions
+            .iter()
+            .map(|&(ip, _)| ip)
+            .collect::<BTreeSet<_>>();
+
+        // Sorted function entrypoints, used to associate each instruction
+        // with the function it belongs to.
+        let mut functions = Vec::new();
+
+        for (&hash, f) in &self.logic.functions {
+            match *f {
+                UnitFn::Offset { offset, args, .. } => {
+                    if !starts.contains(&offset) {
+                        return Err(VerifyError::new(VerifyErrorKind::BadFunctionOffset {
+                            hash,
+                            offset,
+                        }));
+                    }
+
+                    functions.push((offset, args));
+                }
+                UnitFn::EmptyStruct { hash } | UnitFn::TupleStruct { hash, .. } => {
+                    self.verify_rtti(hash)?;
+                }
+                UnitFn::UnitVariant { hash } | UnitFn::TupleVariant { hash, .. } => {
+                    self.verify_variant_rtti(hash)?;
+                }
+            }
+        }
+
+        functions.sort_unstable();
+        functions.dedup_by_key(|&mut (offset, _)| offset);
+
+        let limits = stack_limits(&instructions, &functions);
+
+        for (index, &(ip, inst)) in instructions.iter().enumerate() {
+            let cx = Verifier {
+                unit: self,
+                context,
+                starts: &starts,
+                ip,
+                limit: limits[index],
+            };
+
+            cx.verify(inst)?;
+        }
+
+        Ok(())
+    }
+
+    /// Calculate a hash of the native functions and types which the unit
+    /// requires from the context it runs in.
+    ///
+    /// This only depends on what the unit requires, so two units which
+    /// require the same functions and types have the same hash.
+    pub fn requirements(&self) -> Result<Hash, VerifyError> {
+        let requirements = self.collect_requirements()?;
+
+        let mut hasher = ParametersBuilder::new();
+        hasher.add(requirements.functions.len());
+
+        for hash in requirements.functions {
+            hasher.add(hash);
+        }
+
+        hasher.add(requirements.types.len());
+
+        for hash in requirements.types {
+            hasher.add(hash);
+        }
+
+        Ok(hasher.finish())
+    }
+
+    fn collect_requirements(&self) -> Result<Requirements, VerifyError> {
+        let mut requirements = Requirements {
+            functions: BTreeSet::new(),
+            types: BTreeSet::new(),
+        };
+
+        for (_, inst) in self.decode_instructions()? {
+            match inst {
+                Inst::Call { hash, .. } | Inst::LoadFn { hash }
+                    if self.function(hash).is_none() =>
+                {
+                    requirements.functions.insert(hash);
+                }
+                Inst::MatchType { hash } if self.lookup_rtti(hash).is_none() => {
+                    requirements.types.insert(hash);
+                }
+                Inst::MatchVariant {
+                    variant_hash,
+                    enum_hash,
+                    ..
+                } if self.lookup_variant_rtti(variant_hash).is_none() => {
+                    requirements.types.insert(variant_hash);
+                    requirements.types.insert(enum_hash);
+                }
+                _ => {}
+            }
+        }
+
+        Ok(requirements)
+    }
+
+    /// Decode every instruction in the unit.
+    fn decode_instructions(&self) -> Result<Vec<(usize, Inst)>, VerifyError> {
+        let storage = self.instructions();
+        let end = storage.end();
+
+        let mut instructions = Vec::new();
+        let mut ip = 0;
+
+        while ip < end {
+            let Ok(Some((inst, len))) = storage.get(ip) else {
+                return Err(VerifyError::new(VerifyErrorKind::BadInstruction { ip }));
+            };
+
+            if len == 0 {
+                return Err(VerifyError::new(VerifyErrorKind::BadInstruction { ip }));
+            }
+
+            instructions.push((ip, inst));
+            ip = ip.wrapping_add(len);
+        }
+
+        Ok(instructions)
+    }
+
+    fn verify_rtti(&self, hash: Hash) -> Result<(), VerifyError> {
+        if self.lookup_rtti(hash).is_none() {
+            return Err(VerifyError::new(VerifyErrorKind::MissingRtti { hash }));
+        }
+
+        Ok(())
+    }
+
+    fn verify_variant_rtti(&self, hash: Hash) -> Result<(), VerifyError> {
+        if self.lookup_variant_rtti(hash).is_none() {
+            return Err(VerifyError::new(VerifyErrorKind::MissingVariantRtti {
+                hash,
+            }));
+        }
+
+        Ok(())
+    }
+}
+
+/// Calculate an upper bound on the number of stack slots which can be
+/// addressed by each instruction.
+///
+/// The bound for a function is its number of arguments plus the most values
+/// its instructions can push. Since the stack is balanced across loops this
+/// is never exceeded by a well-formed function. Instructions outside of any
+/// function, and functions which unpack tuples onto the stack, are
+/// unbounded.
+fn stack_limits(
+    instructions: &[(usize, Inst)],
+    functions: &[(usize, usize)],
+) -> Vec<Option<usize>> {
+    let mut limits = vec![None; instructions.len()];
+    let mut index = 0;
+
+    for (n, &(offset, args)) in functions.iter().enumerate() {
+        let end = functions.get(n + 1).map(|&(offset, _)| offset);
+
+        while instructions.get(index).is_some_and(|&(ip, _)| ip < offset) {
+            index += 1;
+        }
+
+        let start = index;
+        let mut limit = Some(args);
+
+        while let Some(&(ip, inst)) = instructions.get(index) {
+            if end.is_some_and(|end| ip >= end) {
+                break;
+            }
+
+            limit = match inst {
+                Inst::PushTuple => None,
+                _ => limit.and_then(|limit| limit.checked_add(MAX_PUSH)),
+            };
+
+            index += 1;
+        }
+
+        for slot in &mut limits[start..index] {
+            *slot = limit;
+        }
+    }
+
+    limits
+}
+
+struct Verifier<'a, S> {
+    unit: &'a Unit<S>,
+    context: &'a RuntimeContext,
+    starts: &'a BTreeSet<usize>,
+    ip: usize,
+    limit: Option<usize>,
+}
+
+impl<S> Verifier<'_, S>
+where
+    S: UnitStorage,
+{
+    fn verify(&self, inst: Inst) -> Result<(), VerifyError> {
+        match inst {
+            Inst::Jump { jump }
+            | Inst::JumpIf { jump }
+            | Inst::JumpIfOrPop { jump }
+            | Inst::JumpIfNotOrPop { jump }
+            | Inst::JumpIfBranch { jump, .. }
+            | Inst::PopAndJumpIfNot { jump, .. } => {
+                self.jump(jump)?;
+            }
+            Inst::IterNext { offset, jump } => {
+                self.offset(offset)?;
+                self.jump(jump)?;
+            }
+            Inst::CallOffset { offset, .. } => {
+                self.call_offset(offset)?;
+            }
+            Inst::Call { hash, .. } | Inst::LoadFn { hash } => {
+                self.function(hash)?;
+            }
+            Inst::Closure { hash, .. } => {
+                self.closure(hash)?;
+            }
+            Inst::Copy { offset }
+            | Inst::Move { offset }
+            | Inst::Drop { offset }
+            | Inst::Replace { offset } => {
+                self.offset(offset)?;
+            }
+            Inst::Swap { a, b } => {
+                self.offset(a)?;
+                self.offset(b)?;
+            }
+            Inst::TupleIndexGetAt { offset, .. } => {
+                self.offset(offset)?;
+            }
+            Inst::ObjectIndexGetAt { offset, slot } => {
+                self.offset(offset)?;
+                self.string(slot)?;
+            }
+            Inst::ObjectIndexGet { slot }
+            | Inst::ObjectIndexSet { slot }
+            | Inst::String { slot }
+            | Inst::EqString { slot } => {
+                self.string(slot)?;
+            }
+            Inst::Bytes { slot } | Inst::BigInt { slot } | Inst::EqBytes { slot } => {
+                self.bytes(slot)?;
+            }
+            Inst::Object { slot }
+            | Inst::MatchObject { slot, .. }
+            | Inst::CallFnNamed { slot, .. }
+            | Inst::CallAssociatedNamed { slot, .. } => {
+                self.object_keys(slot)?;
+            }
+            Inst::EmptyStruct { hash } => {
+                self.unit.verify_rtti(hash)?;
+            }
+            Inst::Struct { hash, slot } => {
+                self.object_keys(slot)?;
+                self.unit.verify_rtti(hash)?;
+            }
+            Inst::UnitVariant { hash } => {
+                self.unit.verify_variant_rtti(hash)?;
+            }
+            Inst::StructVariant { hash, slot } => {
+                self.object_keys(slot)?;
+                self.unit.verify_variant_rtti(hash)?;
+            }
+            Inst::IndexGet { target, index } => {
+                self.address(target)?;
+                self.address(index)?;
+            }
+            Inst::Tuple1 { args } => self.addresses(&args)?,
+            Inst::Tuple2 { args } => self.addresses(&args)?,
+            Inst::Tuple3 { args } => self.addresses(&args)?,
+            Inst::Tuple4 { args } => self.addresses(&args)?,
+            Inst::Return { address, .. } | Inst::Try { address, .. } => {
+                self.address(address)?;
+            }
+            Inst::Op { a, b, .. } => {
+                self.address(a)?;
+                self.address(b)?;
+            }
+            Inst::Assign { target, .. } => match target {
+                InstTarget::Offset(offset) => self.offset(offset)?,
+                InstTarget::Field(slot) => self.string(slot)?,
+                InstTarget::TupleField(..) => {}
+            },
+            _ => {}
+        }
+
+        Ok(())
+    }
+
+    fn jump(&self, jump: usize) -> Result<(), VerifyError> {
+        match self.unit.translate(jump) {
+            Ok(target) if self.starts.contains(&target) => Ok(()),
+            _ => Err(VerifyError::new(VerifyErrorKind::BadJump {
+                ip: self.ip,
+                jump,
+            })),
+        }
+    }
+
+    fn call_offset(&self, offset: usize) -> Result<(), VerifyError> {
+        if !self.starts.contains(&offset) {
+            return Err(VerifyError::new(VerifyErrorKind::BadOffset {
+                ip: self.ip,
+                offset,
+            }));
+        }
+
+        Ok(())
+    }
+
+    fn function(&self, hash: Hash) -> Result<(), VerifyError> {
+        if self.unit.function(hash).is_none() && self.context.function(hash).is_none() {
+            return Err(VerifyError::new(VerifyErrorKind::MissingFunction {
+                ip: self.ip,
+                hash,
+            }));
+        }
+
+        Ok(())
+    }
+
+    fn closure(&self, hash: Hash) -> Result<(), VerifyError> {
+        if !matches!(self.unit.function(hash), Some(UnitFn::Offset { .. })) {
+            return Err(VerifyError::new(VerifyErrorKind::MissingFunction {
+                ip: self.ip,
+                hash,
+            }));
+        }
+
+        Ok(())
+    }
+
+    fn offset(&self, offset: usize) -> Result<(), VerifyError> {
+        let Some(limit) = self.limit else {
+            return Ok(());
+        };
+
+        if offset >= limit {
+            return Err(VerifyError::new(VerifyErrorKind::BadStackOffset {
+                ip: self.ip,
+                offset,
+                limit,
+            }));
+        }
+
+        Ok(())
+    }
+
+    fn address(&self, address: InstAddress) -> Result<(), VerifyError> {
+        match address {
+            InstAddress::Top => Ok(()),
+            InstAddress::Offset(offset) => self.offset(offset),
+        }
+    }
+
+    fn addresses(&self, addresses: &[InstAddress]) -> Result<(), VerifyError> {
+        for &address in addresses {
+            self.address(address)?;
+        }
+
+        Ok(())
+    }
+
+    fn string(&self, slot: usize) -> Result<(), VerifyError> {
+        if self.unit.lookup_string(slot).is_err() {
+            return Err(VerifyError::new(VerifyErrorKind::MissingStaticString {
+                ip: self.ip,
+                slot,
+            }));
+        }
+
+        Ok(())
+    }
+
+    fn bytes(&self, slot: usize) -> Result<(), VerifyError> {
+        if self.unit.lookup_bytes(slot).is_err() {
+            return Err(VerifyError::new(VerifyErrorKind::MissingStaticBytes {
+                ip: self.ip,
+                slot,
+            }));
+        }
+
+        Ok(())
+    }
+
+    fn object_keys(&self, slot: usize) -> Result<(), VerifyError> {
+        if self.unit.lookup_object_keys(slot).is_none() {
+            return Err(VerifyError::new(VerifyErrorKind::MissingStaticObjectKeys {
+                ip: self.ip,
+                slot,
+            }));
+        }
+
+        Ok(())
+    }
+}
@@ -14,16 +14,17 @@ use crate::compile::ItemBuf;
 use crate::runtime::vm::CallResult;
 use crate::runtime::{
     AccessKind, AnyObj, Bytes, ConstValue, ControlFlow, EnvProtocolCaller, Format, Formatter,
-    FromValue, FullTypeOf, Function, Future, Generator, GeneratorState, Iterator, MaybeTypeOf, Mut,
-    Object, OwnedTuple, Protocol, ProtocolCaller, Range, RangeFrom, RangeFull, RangeInclusive,
-    RangeTo, RangeToInclusive, RawMut, RawRef, Ref, Shared, Stream, ToValue, Type, TypeInfo,
-    Variant, Vec, Vm, VmError, VmErrorKind, VmIntegerRepr, VmResult,
+    FromValue, FullTypeOf, Function, Future, Generator, GeneratorState, Iterator, LimitedBigInt,
+    MaybeTypeOf, Mut, Object, OwnedTuple, Protocol, ProtocolCaller, Range, RangeFrom, RangeFull,
+    RangeInclusive, RangeTo, RangeToInclusive, RawMut, RawRef, Ref, Shared, Stream, ToValue, Type,
+    TypeInfo, Variant, Vec, Vm, VmError, VmErrorKind, VmIntegerRepr, VmResult,
 };
 #[cfg(feature = "alloc")]
 use crate::runtime::{Hasher, Tuple};
 use crate::{Any, Hash};
 
 use ::serde::{Deserialize, Serialize};
+use num::BigInt;
 
 // Small helper function to build errors.
 fn err<T, E>(error: E) -> VmResult<T>
@@ -250,6 +251,8 @@ pub enum Value {
     Integer(i64),
     /// A float.
     Float(f64),
+    /// An arbitrary-precision integer.
+    BigInt(Shared<LimitedBigInt>),
     /// A type hash. Describes a type in the virtual machine.
     Type(Type),
     /// Ordering.
@@ -352,6 +355,9 @@ impl Value {
                 let mut buffer = ryu::Buffer::new();
                 vm_try!(f.push_str(buffer.format(*float)));
             }
+            Value::BigInt(value) => {
+                vm_write!(f, "{}", *vm_try!(value.borrow_ref()));
+            }
             Value::Bool(bool) => {
                 return VmResult::Ok(vm_write!(f, "{}", bool));
             }
@@ -409,6 +415,9 @@ impl Value {
             Value::Float(value) => {
                 vm_write!(f, "{:?}", value);
             }
+            Value::BigInt(value) => {
+                vm_write!(f, "{:?}", value);
+            }
             Value::Type(value) => {
                 vm_write!(f, "{:?}", value);
             }
@@ -641,6 +650,7 @@ impl Value {
             Self::Char(value) => Self::Char(value),
             Self::Integer(value) => Self::Integer(value),
             Self::Float(value) => Self::Float(value),
+            Self::BigInt(value) => Self::BigInt(vm_try!(Shared::new(vm_try!(value.take())))),
             Self::Type(value) => Self::Type(value),
             Self::Ordering(value) => Self::Ordering(value),
             Self::String(value) => Self::String(vm_try!(Shared::new(vm_try!(value.take())))),
@@ -911,6 +921,15 @@ impl Value {
         }
     }
 
+    /// Try to coerce value into an arbitrary-precision integer.
+    #[inline]
+    pub fn into_bigint(self) -> VmResult<Shared<LimitedBigInt>> {
+        match self {
+            Self::BigInt(bigint) => VmResult::Ok(bigint),
+            actual => err(VmErrorKind::expected::<BigInt>(vm_try!(actual.type_info()))),
+        }
+    }
+
     /// Try to coerce value into bytes.
     #[inline]
     pub fn into_bytes(self) -> VmResult<Shared<Bytes>> {
@@ -1124,6 +1143,7 @@ impl Value {
             Self::Char(..) => crate::runtime::static_type::CHAR_TYPE.hash,
             Self::Integer(..) => crate::runtime::static_type::INTEGER_TYPE.hash,
             Self::Float(..) => crate::runtime::static_type::FLOAT_TYPE.hash,
+            Self::BigInt(..) => crate::runtime::static_type::BIGINT_TYPE.hash,
             Self::Type(..) => crate::runtime::static_type::TYPE.hash,
             Self::Ordering(..) => crate::runtime::static_type::ORDERING_TYPE.hash,
             Self::String(..) => crate::runtime::static_type::STRING_TYPE.hash,
@@ -1164,6 +1184,7 @@ impl Value {
             Self::Char(..) => TypeInfo::StaticType(crate::runtime::static_type::CHAR_TYPE),
             Self::Integer(..) => TypeInfo::StaticType(crate::runtime::static_type::INTEGER_TYPE),
             Self::Float(..) => TypeInfo::StaticType(crate::runtime::static_type::FLOAT_TYPE),
+            Self::BigInt(..) => TypeInfo::StaticType(crate::runtime::static_type::BIGINT_TYPE),
             Self::Type(..) => TypeInfo::StaticType(crate::runtime::static_type::TYPE),
             Self::Ordering(..) => TypeInfo::StaticType(crate::runtime::static_type::ORDERING_TYPE),
             Self::String(..) => TypeInfo::StaticType(crate::runtime::static_type::STRING_TYPE),
@@ -1238,6 +1259,10 @@ impl Value {
             (Self::Char(a), Self::Char(b)) => return VmResult::Ok(a == b),
             (Self::Integer(a), Self::Integer(b)) => return VmResult::Ok(a == b),
             (Self::Float(a), Self::Float(b)) => return VmResult::Ok(a == b),
+            (Self::BigInt(..), Self::BigInt(..) | Self::Integer(..))
+            | (Self::Integer(..), Self::BigInt(..)) => {
+                return VmResult::Ok(vm_try!(Value::bigint_cmp(a, b)).is_eq());
+            }
             (Self::Type(a), Self::Type(b)) => return VmResult::Ok(a == b),
             (Self::Bytes(a), Self::Bytes(b)) => {
                 let a = vm_try!(a.borrow_ref());
@@ -1384,6 +1409,18 @@ impl Value {
                 hasher.write_u8(*value);
                 return VmResult::Ok(());
             }
+            // NB: big integers which fit in an `i64` hash like integers, since
+            // they compare equal to them.
+            Value::BigInt(value) => {
+                let value = vm_try!(value.borrow_ref());
+
+                match num::ToPrimitive::to_i64(&**value) {
+                    Some(value) => hasher.write_i64(value),
+                    None => hasher.write(&value.to_signed_bytes_le()),
+                }
+
+                return VmResult::Ok(());
+            }
             // Care must be taken whan hashing floats, to ensure that `hash(v1)
             // === hash(v2)` if `eq(v1) === eq(v2)`. Hopefully we accomplish
             // this by rejecting NaNs and rectifying subnormal values of zero.
@@ -1459,6 +1496,10 @@ impl Value {
                 return VmResult::err(VmErrorKind::IllegalFloatComparison { lhs: *a, rhs: *b });
             }
             (Self::Integer(a), Self::Integer(b)) => return VmResult::Ok(a == b),
+            (Self::BigInt(..), Self::BigInt(..) | Self::Integer(..))
+            | (Self::Integer(..), Self::BigInt(..)) => {
+                return VmResult::Ok(vm_try!(Value::bigint_cmp(self, b)).is_eq());
+            }
             (Self::Type(a), Self::Type(b)) => return VmResult::Ok(a == b),
             (Self::Bytes(a), Self::Bytes(b)) => {
                 let a = vm_try!(a.borrow_ref());
@@ -1611,6 +1652,10 @@ impl Value {
             (Self::Char(a), Self::Char(b)) => return VmResult::Ok(a.partial_cmp(b)),
             (Self::Float(a), Self::Float(b)) => return VmResult::Ok(a.partial_cmp(b)),
             (Self::Integer(a), Self::Integer(b)) => return VmResult::Ok(a.partial_cmp(b)),
+            (Self::BigInt(..), Self::BigInt(..) | Self::Integer(..))
+            | (Self::Integer(..), Self::BigInt(..)) => {
+                return VmResult::Ok(Some(vm_try!(Value::bigint_cmp(a, b))));
+            }
             (Self::Type(a), Self::Type(b)) => return VmResult::Ok(a.partial_cmp(b)),
             (Self::Bytes(a), Self::Bytes(b)) => {
                 let a = vm_try!(a.borrow_ref());
@@ -1771,6 +1816,8 @@ impl Value {
                 return VmResult::err(VmErrorKind::IllegalFloatComparison { lhs: *a, rhs: *b });
             }
             (Self::Integer(a), Self::Integer(b)) => return VmResult::Ok(a.cmp(b)),
+            (Self::BigInt(..), Self::BigInt(..) | Self::Integer(..))
+            | (Self::Integer(..), Self::BigInt(..)) => return Value::bigint_cmp(a, b),
             (Self::Type(a), Self::Type(b)) => return VmResult::Ok(a.cmp(b)),
             (Self::Bytes(a), Self::Bytes(b)) => {
                 let a = vm_try!(a.borrow_ref());
@@ -1924,6 +1971,27 @@ impl Value {
             }),
         }
     }
+
+    /// Compare two values where one is a big integer, and the other is either
+    /// a big integer or an integer which is promoted to one.
+    fn bigint_cmp(a: &Value, b: &Value) -> VmResult<Ordering> {
+        VmResult::Ok(match (a, b) {
+            (Self::BigInt(a), Self::BigInt(b)) => {
+                let a = vm_try!(a.borrow_ref());
+                let b = vm_try!(b.borrow_ref());
+                a.cmp(&b)
+            }
+            (Self::BigInt(a), Self::Integer(b)) => vm_try!(a.borrow_ref()).cmp(&BigInt::from(*b)),
+            (Self::Integer(a), Self::BigInt(b)) => BigInt::from(*a).cmp(&*vm_try!(b.borrow_ref())),
+            (a, b) => {
+                return err(VmErrorKind::UnsupportedBinaryOperation {
+                    op: "cmp",
+                    lhs: vm_try!(a.type_info()),
+                    rhs: vm_try!(b.type_info()),
+                });
+            }
+        })
+    }
 }
 
 impl fmt::Debug for Value {
@@ -1944,6 +2012,9 @@ impl fmt::Debug for Value {
             Value::Float(value) => {
                 write!(f, "{:?}", value)?;
             }
+            Value::BigInt(value) => {
+                write!(f, "{:?}", value)?;
+            }
             Value::Type(value) => {
                 write!(f, "{:?}", value)?;
             }
@@ -2113,6 +2184,26 @@ impl_from! {
     Result => Shared<Result<Value, Value>>,
 }
 
+impl_from!(BigInt => Shared<LimitedBigInt>);
+
+impl TryFrom<BigInt> for Value {
+    type Error = rune_alloc::Error;
+
+    /// Convert a big integer into a value, taking its estimated size from the
+    /// current memory limit.
+    #[inline]
+    fn try_from(value: BigInt) -> Result<Self, rune_alloc::Error> {
+        Ok(Self::BigInt(Shared::new(LimitedBigInt::new(value)?)?))
+    }
+}
+
+impl ToValue for BigInt {
+    #[inline]
+    fn to_value(self) -> VmResult<Value> {
+        VmResult::Ok(vm_try!(Value::try_from(self)))
+    }
+}
+
 impl_from_wrapper! {
     Format => Shared<Format>,
     Iterator => Shared<Iterator>,
@@ -4,6 +4,7 @@ use crate::alloc::{self, Global, TryToOwned};
 use crate::no_std::std;
 use crate::runtime::{Bytes, Object, Shared, Vec};
 
+use num::{BigInt, ToPrimitive};
 use serde::de::{self, Deserialize as _, Error};
 use serde::ser::{self, SerializeMap as _, SerializeSeq as _};
 
@@ -31,6 +32,23 @@ impl ser::Serialize for Value {
             Value::Byte(c) => serializer.serialize_u8(*c),
             Value::Integer(integer) => serializer.serialize_i64(*integer),
             Value::Float(float) => serializer.serialize_f64(*float),
+            Value::BigInt(bigint) => {
+                let bigint = bigint.borrow_ref().map_err(ser::Error::custom)?;
+
+                if let Some(value) = bigint.to_i64() {
+                    serializer.serialize_i64(value)
+                } else if let Some(value) = bigint.to_u64() {
+                    serializer.serialize_u64(value)
+                } else if let Some(value) = bigint.to_i128() {
+                    serializer.serialize_i128(value)
+                } else if let Some(value) = bigint.to_u128() {
+                    serializer.serialize_u128(value)
+                } else {
+                    Err(ser::Error::custom(
+                        "cannot serialize big integers larger than 128 bits",
+                    ))
+                }
+            }
             Value::Type(..) => Err(ser::Error::custom("cannot serialize types")),
             Value::Ordering(..) => Err(ser::Error::custom("cannot serialize orderings")),
             Value::String(string) => {
@@ -193,7 +211,7 @@ impl<'de> de::Visitor<'de> for VmVisitor {
     where
         E: de::Error,
     {
-        Ok(Value::Integer(v as i64))
+        integer(v)
     }
 
     #[inline]
@@ -225,7 +243,7 @@ impl<'de> de::Visitor<'de> for VmVisitor {
     where
         E: de::Error,
     {
-        Ok(Value::Integer(v as i64))
+        integer(v)
     }
 
     #[inline]
@@ -233,7 +251,7 @@ impl<'de> de::Visitor<'de> for VmVisitor {
     where
         E: de::Error,
     {
-        Ok(Value::Integer(v as i64))
+        integer(v)
     }
 
     #[inline]
@@ -322,3 +340,17 @@ impl<'de> de::Visitor<'de> for VmVisitor {
         ))
     }
 }
+
+/// Construct an integer value, which is promoted to a big integer if it doesn't
+/// fit in an `i64`.
+fn integer<T, E>(value: T) -> Result<Value, E>
+where
+    T: Copy + TryInto<i64>,
+    BigInt: From<T>,
+    E: de::Error,
+{
+    match value.try_into() {
+        Ok(value) => Ok(Value::Integer(value)),
+        Err(..) => Value::try_from(BigInt::from(value)).map_err(E::custom),
+    }
+}
@@ -1,8 +1,12 @@
 use core::cmp::Ordering;
 use core::mem::{replace, swap};
 use core::ops;
+use core::ptr::NonNull;
 use core::slice;
 
+use num::bigint::ToBigInt;
+use num::{BigInt, ToPrimitive, Zero};
+
 use crate::alloc::{Error, IteratorExt, String, TryClone, TryToOwned};
 use crate::hash::{Hash, IntoHash, ToTypeHash};
 use crate::modules::{option, result};
@@ -12,15 +16,18 @@ use crate::no_std::sync::Arc;
 use crate::no_std::vec;
 use crate::runtime::budget;
 use crate::runtime::future::SelectFuture;
+use crate::runtime::observer::{self, Observer};
 use crate::runtime::unit::{UnitFn, UnitStorage};
+#[cfg(feature = "std")]
+use crate::runtime::Profiler;
 use crate::runtime::{
     self, Args, Awaited, BorrowMut, Bytes, Call, ControlFlow, EmptyStruct, Format, FormatSpec,
     Formatter, FromValue, Function, Future, Generator, GuardedArgs, Inst, InstAddress,
-    InstAssignOp, InstOp, InstRange, InstTarget, InstValue, InstVariant, Object, OwnedTuple, Panic,
-    Protocol, Range, RangeFrom, RangeFull, RangeInclusive, RangeTo, RangeToInclusive,
-    RuntimeContext, Select, Shared, Stack, Stream, Struct, Type, TypeCheck, TypeOf, Unit, Value,
-    Variant, VariantData, Vec, VmError, VmErrorKind, VmExecution, VmHalt, VmIntegerRepr, VmResult,
-    VmSendExecution,
+    InstAssignOp, InstOp, InstRange, InstTarget, InstValue, InstVariant, Interrupt, LimitedBigInt,
+    Object, OwnedTuple, Panic, Protocol, Range, RangeFrom, RangeFull, RangeInclusive, RangeTo,
+    RangeToInclusive, RuntimeContext, Select, Shared, Stack, Stream, Struct, ToValue, Type,
+    TypeCheck, TypeOf, Unit, Value, Variant, VariantData, Vec, VmError, VmErrorKind, VmExecution,
+    VmHalt, VmIntegerRepr, VmResult, VmSendExecution,
 };
 
 /// Small helper function to build errors.
@@ -347,8 +354,10 @@ impl Vm {
         N: ToTypeHash,
         A: Args,
     {
-        self.set_entrypoint(name, args.count())?;
+        let count = args.count();
+        let entry = self.set_entrypoint(name)?;
         args.into_stack(&mut self.stack).into_result()?;
+        self.bind_entrypoint::<&str>(entry, count, &[])?;
         Result::Ok(VmExecution::new(self))
     }
 
@@ -367,8 +376,10 @@ impl Vm {
         // being sent along with the virtual machine.
         self.stack.clear();
 
-        self.set_entrypoint(name, args.count())?;
+        let count = args.count();
+        let entry = self.set_entrypoint(name)?;
         args.into_stack(&mut self.stack).into_result()?;
+        self.bind_entrypoint::<&str>(entry, count, &[])?;
         Result::Ok(VmSendExecution(VmExecution::new(self)))
     }
 
@@ -391,7 +402,54 @@ impl Vm {
         N: ToTypeHash,
         A: GuardedArgs,
     {
-        self.set_entrypoint(name, args.count())?;
+        self.call_named(name, args, None::<(&str, Value)>)
+    }
+
+    /// Call the given function immediately, where the arguments in `named`
+    /// are passed by name after the positional `args`.
+    ///
+    /// Arguments which are not passed use the default value declared for them
+    /// in the function.
+    ///
+    /// ```
+    /// use rune::Vm;
+    /// use std::sync::Arc;
+    ///
+    /// let mut sources = rune::sources! {
+    ///     entry => {
+    ///         pub fn connect(host, port = 8080, secure = false) {
+    ///             (host, port, secure)
+    ///         }
+    ///     }
+    /// };
+    ///
+    /// let unit = rune::prepare(&mut sources).build()?;
+    /// let mut vm = Vm::without_runtime(Arc::new(unit));
+    ///
+    /// let output = vm.call_named(["connect"], ("localhost",), [("secure", true)])?;
+    /// let output: (String, i64, bool) = rune::from_value(output)?;
+    /// assert_eq!(output, (String::from("localhost"), 8080, true));
+    /// # Ok::<_, rune::Error>(())
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// See [`Vm::call`].
+    pub fn call_named<A, N, I, K, V>(
+        &mut self,
+        name: N,
+        args: A,
+        named: I,
+    ) -> Result<Value, VmError>
+    where
+        N: ToTypeHash,
+        A: GuardedArgs,
+        I: IntoIterator<Item = (K, V)>,
+        K: AsRef<str>,
+        V: ToValue,
+    {
+        let count = args.count();
+        let entry = self.set_entrypoint(name)?;
 
         // Safety: We hold onto the guard until the vm has completed and
         // `VmExecution` will clear the stack before this function returns.
@@ -402,6 +460,15 @@ impl Vm {
             // Clearing the stack here on panics has safety implications - see
             // above.
             let vm = ClearStack(self);
+
+            let mut names = vec::Vec::new();
+
+            for (name, value) in named {
+                vm.0.stack.push(value.to_value().into_result()?)?;
+                names.push(name);
+            }
+
+            vm.0.bind_entrypoint(entry, count + names.len(), &names)?;
             VmExecution::new(&mut *vm.0).complete().into_result()?
         };
 
@@ -432,7 +499,8 @@ impl Vm {
         N: ToTypeHash,
         A: GuardedArgs,
     {
-        self.set_entrypoint(name, args.count())?;
+        let count = args.count();
+        let entry = self.set_entrypoint(name)?;
 
         // Safety: We hold onto the guard until the vm has completed and
         // `VmExecution` will clear the stack before this function returns.
@@ -443,6 +511,7 @@ impl Vm {
             // Clearing the stack here on panics has safety implications - see
             // above.
             let vm = ClearStack(self);
+            vm.0.bind_entrypoint::<&str>(entry, count, &[])?;
             VmExecution::new(&mut *vm.0)
                 .async_complete()
                 .await
@@ -456,9 +525,27 @@ impl Vm {
         Result::Ok(value)
     }
 
+    /// Call the given function while recording a profile of its execution in
+    /// the given profiler.
+    ///
+    /// See [`Profiler`] for more information.
+    #[cfg(feature = "std")]
+    pub fn profile<A, N>(
+        &mut self,
+        name: N,
+        args: A,
+        profiler: &mut Profiler,
+    ) -> Result<Value, VmError>
+    where
+        N: ToTypeHash,
+        A: Args,
+    {
+        self.execute(name, args)?.profile(profiler).into_result()
+    }
+
     /// Update the instruction pointer to match the function matching the given
-    /// name and check that the number of argument matches.
-    fn set_entrypoint<N>(&mut self, name: N, count: usize) -> Result<(), VmErrorKind>
+    /// name, returning its hash and the number of arguments it takes.
+    fn set_entrypoint<N>(&mut self, name: N) -> Result<(Hash, usize), VmErrorKind>
     where
         N: ToTypeHash,
     {
@@ -472,17 +559,14 @@ impl Vm {
             }
         })?;
 
-        let offset = match info {
+        let (offset, expected) = match info {
             // NB: we ignore the calling convention.
             // everything is just async when called externally.
             UnitFn::Offset {
                 offset,
                 args: expected,
                 ..
-            } => {
-                check_args(count, expected)?;
-                offset
-            }
+            } => (offset, expected),
             _ => {
                 return Err(VmErrorKind::MissingFunction { hash });
             }
@@ -491,7 +575,22 @@ impl Vm {
         self.ip = offset;
         self.stack.clear();
         self.call_frames.clear();
-        Ok(())
+        Ok((hash, expected))
+    }
+
+    /// Bind the `count` arguments pushed on the stack to the parameters of the
+    /// entrypoint, where the trailing arguments are passed by the given names.
+    fn bind_entrypoint<N>(
+        &mut self,
+        (hash, expected): (Hash, usize),
+        count: usize,
+        names: &[N],
+    ) -> Result<(), VmErrorKind>
+    where
+        N: AsRef<str>,
+    {
+        self.unit
+            .bind_args(hash, &mut self.stack, count, expected, names)
     }
 
     /// Helper function to call an instance function.
@@ -1099,6 +1198,15 @@ impl Vm {
                     runtime::static_type::FLOAT_TYPE_HASH => Value::Float($value as f64),
                     runtime::static_type::BYTE_TYPE_HASH => Value::Byte($value as u8),
                     runtime::static_type::INTEGER_TYPE_HASH => Value::Integer($value as i64),
+                    runtime::static_type::BIGINT_TYPE_HASH => {
+                        let Some(value) = $value.to_bigint() else {
+                            return err(VmErrorKind::IllegalFloatOperation {
+                                value: $value as f64,
+                            });
+                        };
+
+                        vm_try!(Value::try_from(value))
+                    }
                     ty => {
                         return err(VmErrorKind::UnsupportedAs {
                             value: <$from as TypeOf>::type_info(),
@@ -1113,6 +1221,42 @@ impl Vm {
             Value::Integer(a) => convert!(i64, a, ty),
             Value::Float(a) => convert!(f64, a, ty),
             Value::Byte(a) => convert!(u8, a, ty),
+            Value::BigInt(a) => {
+                let a = vm_try!(a.borrow_ref());
+
+                match ty.into_hash() {
+                    runtime::static_type::FLOAT_TYPE_HASH => {
+                        Value::Float(a.to_f64().unwrap_or(f64::NAN))
+                    }
+                    runtime::static_type::BYTE_TYPE_HASH => match a.to_u8() {
+                        Some(a) => Value::Byte(a),
+                        None => {
+                            return err(VmErrorKind::ValueToIntegerCoercionError {
+                                from: VmIntegerRepr::from(a.clone()),
+                                to: "u8",
+                            });
+                        }
+                    },
+                    runtime::static_type::INTEGER_TYPE_HASH => match a.to_i64() {
+                        Some(a) => Value::Integer(a),
+                        None => {
+                            return err(VmErrorKind::ValueToIntegerCoercionError {
+                                from: VmIntegerRepr::from(a.clone()),
+                                to: "i64",
+                            });
+                        }
+                    },
+                    runtime::static_type::BIGINT_TYPE_HASH => {
+                        vm_try!(Value::try_from(a.clone()))
+                    }
+                    ty => {
+                        return err(VmErrorKind::UnsupportedAs {
+                            value: <BigInt as TypeOf>::type_info(),
+                            type_hash: ty,
+                        });
+                    }
+                }
+            }
             value => {
                 return err(VmErrorKind::UnsupportedAs {
                     value: vm_try!(value.type_info()),
@@ -1230,6 +1374,7 @@ impl Vm {
         error: fn() -> VmErrorKind,
         integer_op: fn(i64, i64) -> Option<i64>,
         float_op: fn(f64, f64) -> f64,
+        bigint_op: fn(&BigInt, &BigInt) -> Option<BigInt>,
     ) -> VmResult<()> {
         let lhs;
         let mut guard;
@@ -1246,7 +1391,16 @@ impl Vm {
                     *lhs = out;
                     return VmResult::Ok(());
                 }
-                (lhs, rhs) => TargetFallback::Value(lhs.clone(), rhs),
+                (lhs, rhs) => {
+                    if let Some((a, b)) = vm_try!(bigint_operands(lhs, &rhs)) {
+                        vm_try!(bigint_limit(product_bits(&a, &b)));
+                        let out = vm_try!(bigint_op(&a, &b).ok_or_else(error));
+                        *lhs = vm_try!(Value::try_from(out));
+                        return VmResult::Ok(());
+                    }
+
+                    TargetFallback::Value(lhs.clone(), rhs)
+                }
             },
             TargetValue::Fallback(fallback) => fallback,
         };
@@ -1311,6 +1465,7 @@ impl Vm {
         error: fn() -> VmErrorKind,
         integer_op: fn(i64, i64) -> Option<i64>,
         float_op: fn(f64, f64) -> f64,
+        bigint_op: fn(&BigInt, &BigInt) -> Option<BigInt>,
         lhs: InstAddress,
         rhs: InstAddress,
     ) -> VmResult<()> {
@@ -1331,37 +1486,13 @@ impl Vm {
             (lhs, rhs) => (lhs, rhs),
         };
 
-        if let CallResult::Unsupported(lhs) = vm_try!(self.call_instance_fn(lhs, protocol, (&rhs,)))
-        {
-            return err(VmErrorKind::UnsupportedBinaryOperation {
-                op: protocol.name,
-                lhs: vm_try!(lhs.type_info()),
-                rhs: vm_try!(rhs.type_info()),
-            });
+        if let Some((a, b)) = vm_try!(bigint_operands(&lhs, &rhs)) {
+            vm_try!(bigint_limit(product_bits(&a, &b)));
+            let out = vm_try!(bigint_op(&a, &b).ok_or_else(error));
+            vm_try!(self.stack.push(vm_try!(Value::try_from(out))));
+            return VmResult::Ok(());
         }
 
-        VmResult::Ok(())
-    }
-
-    /// Internal impl of a numeric operation.
-    fn internal_infallible_bitwise(
-        &mut self,
-        protocol: Protocol,
-        integer_op: fn(i64, i64) -> i64,
-        lhs: InstAddress,
-        rhs: InstAddress,
-    ) -> VmResult<()> {
-        let rhs = vm_try!(self.stack.address(rhs));
-        let lhs = vm_try!(self.stack.address(lhs));
-
-        let (lhs, rhs) = match (lhs, rhs) {
-            (Value::Integer(lhs), Value::Integer(rhs)) => {
-                vm_try!(self.stack.push(Value::from(integer_op(lhs, rhs))));
-                return VmResult::Ok(());
-            }
-            (lhs, rhs) => (lhs, rhs),
-        };
-
         if let CallResult::Unsupported(lhs) = vm_try!(self.call_instance_fn(lhs, protocol, (&rhs,)))
         {
             return err(VmErrorKind::UnsupportedBinaryOperation {
@@ -1380,6 +1511,7 @@ impl Vm {
         protocol: Protocol,
         integer_op: fn(i64, i64) -> i64,
         bool_op: fn(bool, bool) -> bool,
+        bigint_op: fn(&BigInt, &BigInt) -> BigInt,
         lhs: InstAddress,
         rhs: InstAddress,
     ) -> VmResult<()> {
@@ -1398,6 +1530,12 @@ impl Vm {
             (lhs, rhs) => (lhs, rhs),
         };
 
+        if let Some((a, b)) = vm_try!(bigint_operands(&lhs, &rhs)) {
+            vm_try!(bigint_limit(product_bits(&a, &b)));
+            vm_try!(self.stack.push(vm_try!(Value::try_from(bigint_op(&a, &b)))));
+            return VmResult::Ok(());
+        }
+
         if let CallResult::Unsupported(lhs) = vm_try!(self.call_instance_fn(lhs, protocol, (&rhs,)))
         {
             return err(VmErrorKind::UnsupportedBinaryOperation {
@@ -1415,6 +1553,7 @@ impl Vm {
         target: InstTarget,
         protocol: Protocol,
         integer_op: fn(&mut i64, i64),
+        bigint_op: fn(&BigInt, &BigInt) -> BigInt,
     ) -> VmResult<()> {
         let lhs;
         let mut guard;
@@ -1425,7 +1564,15 @@ impl Vm {
                     integer_op(lhs, rhs);
                     return VmResult::Ok(());
                 }
-                (lhs, rhs) => TargetFallback::Value(lhs.clone(), rhs),
+                (lhs, rhs) => {
+                    if let Some((a, b)) = vm_try!(bigint_operands(lhs, &rhs)) {
+                        vm_try!(bigint_limit(product_bits(&a, &b)));
+                        *lhs = vm_try!(Value::try_from(bigint_op(&a, &b)));
+                        return VmResult::Ok(());
+                    }
+
+                    TargetFallback::Value(lhs.clone(), rhs)
+                }
             },
             TargetValue::Fallback(fallback) => fallback,
         };
@@ -1438,6 +1585,8 @@ impl Vm {
         protocol: Protocol,
         error: fn() -> VmErrorKind,
         integer_op: fn(i64, i64) -> Option<i64>,
+        bigint_op: fn(&BigInt, &BigInt) -> Option<BigInt>,
+        bigint_bits: fn(&BigInt, &BigInt) -> u64,
         lhs: InstAddress,
         rhs: InstAddress,
     ) -> VmResult<()> {
@@ -1453,6 +1602,13 @@ impl Vm {
             (lhs, rhs) => (lhs, rhs),
         };
 
+        if let Some((a, b)) = vm_try!(bigint_operands(&lhs, &rhs)) {
+            vm_try!(bigint_limit(bigint_bits(&a, &b)));
+            let out = vm_try!(bigint_op(&a, &b).ok_or_else(error));
+            vm_try!(self.stack.push(vm_try!(Value::try_from(out))));
+            return VmResult::Ok(());
+        }
+
         if let CallResult::Unsupported(lhs) = vm_try!(self.call_instance_fn(lhs, protocol, (&rhs,)))
         {
             return err(VmErrorKind::UnsupportedBinaryOperation {
@@ -1471,6 +1627,8 @@ impl Vm {
         protocol: Protocol,
         error: fn() -> VmErrorKind,
         integer_op: fn(i64, i64) -> Option<i64>,
+        bigint_op: fn(&BigInt, &BigInt) -> Option<BigInt>,
+        bigint_bits: fn(&BigInt, &BigInt) -> u64,
     ) -> VmResult<()> {
         let lhs;
         let mut guard;
@@ -1482,7 +1640,16 @@ impl Vm {
                     *lhs = out;
                     return VmResult::Ok(());
                 }
-                (lhs, rhs) => TargetFallback::Value(lhs.clone(), rhs),
+                (lhs, rhs) => {
+                    if let Some((a, b)) = vm_try!(bigint_operands(lhs, &rhs)) {
+                        vm_try!(bigint_limit(bigint_bits(&a, &b)));
+                        let out = vm_try!(bigint_op(&a, &b).ok_or_else(error));
+                        *lhs = vm_try!(Value::try_from(out));
+                        return VmResult::Ok(());
+                    }
+
+                    TargetFallback::Value(lhs.clone(), rhs)
+                }
             },
             TargetValue::Fallback(fallback) => fallback,
         };
@@ -1713,6 +1880,7 @@ impl Vm {
         let value = match value {
             Value::Bool(value) => Value::from(!value),
             Value::Integer(value) => Value::from(!value),
+            Value::BigInt(value) => vm_try!(Value::try_from(!&**vm_try!(value.borrow_ref()))),
             other => {
                 let operand = vm_try!(other.type_info());
                 return err(VmErrorKind::UnsupportedUnaryOperation { op: "!", operand });
@@ -1730,6 +1898,7 @@ impl Vm {
         let value = match value {
             Value::Float(value) => Value::from(-value),
             Value::Integer(value) => Value::from(-value),
+            Value::BigInt(value) => vm_try!(Value::try_from(-&**vm_try!(value.borrow_ref()))),
             other => {
                 let operand = vm_try!(other.type_info());
                 return err(VmErrorKind::UnsupportedUnaryOperation { op: "-", operand });
@@ -1749,6 +1918,7 @@ impl Vm {
                     || VmErrorKind::Overflow,
                     i64::checked_add,
                     ops::Add::add,
+                    |a, b| Some(a + b),
                     lhs,
                     rhs,
                 ));
@@ -1759,6 +1929,7 @@ impl Vm {
                     || VmErrorKind::Underflow,
                     i64::checked_sub,
                     ops::Sub::sub,
+                    |a, b| Some(a - b),
                     lhs,
                     rhs,
                 ));
@@ -1769,6 +1940,7 @@ impl Vm {
                     || VmErrorKind::Overflow,
                     i64::checked_mul,
                     ops::Mul::mul,
+                    |a, b| Some(a * b),
                     lhs,
                     rhs,
                 ));
@@ -1779,6 +1951,7 @@ impl Vm {
                     || VmErrorKind::DivideByZero,
                     i64::checked_div,
                     ops::Div::div,
+                    |a, b| (!b.is_zero()).then(|| a / b),
                     lhs,
                     rhs,
                 ));
@@ -1789,6 +1962,7 @@ impl Vm {
                     || VmErrorKind::DivideByZero,
                     i64::checked_rem,
                     ops::Rem::rem,
+                    |a, b| (!b.is_zero()).then(|| a % b),
                     lhs,
                     rhs,
                 ));
@@ -1799,6 +1973,7 @@ impl Vm {
                     Protocol::BIT_AND,
                     i64::bitand,
                     bool::bitand,
+                    |a, b| a.bitand(b),
                     lhs,
                     rhs,
                 ));
@@ -1809,6 +1984,7 @@ impl Vm {
                     Protocol::BIT_XOR,
                     i64::bitxor,
                     bool::bitxor,
+                    |a, b| a.bitxor(b),
                     lhs,
                     rhs,
                 ));
@@ -1819,6 +1995,7 @@ impl Vm {
                     Protocol::BIT_OR,
                     i64::bitor,
                     bool::bitor,
+                    |a, b| a.bitor(b),
                     lhs,
                     rhs,
                 ));
@@ -1828,12 +2005,22 @@ impl Vm {
                     Protocol::SHL,
                     || VmErrorKind::Overflow,
                     |a, b| a.checked_shl(u32::try_from(b).ok()?),
+                    |a, b| Some(a << b.to_u32()?),
+                    shl_bits,
                     lhs,
                     rhs,
                 ));
             }
             InstOp::Shr => {
-                vm_try!(self.internal_infallible_bitwise(Protocol::SHR, ops::Shr::shr, lhs, rhs));
+                vm_try!(self.internal_bitwise(
+                    Protocol::SHR,
+                    || VmErrorKind::Overflow,
+                    |a, b| Some(a >> b),
+                    |a, b| Some(a >> b.to_u32()?),
+                    |a, _| a.bits(),
+                    lhs,
+                    rhs,
+                ));
             }
             InstOp::Gt => {
                 vm_try!(self.internal_boolean_ops(
@@ -1916,6 +2103,7 @@ impl Vm {
                     || VmErrorKind::Overflow,
                     i64::checked_add,
                     ops::Add::add,
+                    |a, b| Some(a + b),
                 ));
             }
             InstAssignOp::Sub => {
@@ -1925,6 +2113,7 @@ impl Vm {
                     || VmErrorKind::Underflow,
                     i64::checked_sub,
                     ops::Sub::sub,
+                    |a, b| Some(a - b),
                 ));
             }
             InstAssignOp::Mul => {
@@ -1934,6 +2123,7 @@ impl Vm {
                     || VmErrorKind::Overflow,
                     i64::checked_mul,
                     ops::Mul::mul,
+                    |a, b| Some(a * b),
                 ));
             }
             InstAssignOp::Div => {
@@ -1943,6 +2133,7 @@ impl Vm {
                     || VmErrorKind::DivideByZero,
                     i64::checked_div,
                     ops::Div::div,
+                    |a, b| (!b.is_zero()).then(|| a / b),
                 ));
             }
             InstAssignOp::Rem => {
@@ -1952,6 +2143,7 @@ impl Vm {
                     || VmErrorKind::DivideByZero,
                     i64::checked_rem,
                     ops::Rem::rem,
+                    |a, b| (!b.is_zero()).then(|| a % b),
                 ));
             }
             InstAssignOp::BitAnd => {
@@ -1959,6 +2151,7 @@ impl Vm {
                     target,
                     Protocol::BIT_AND_ASSIGN,
                     ops::BitAndAssign::bitand_assign,
+                    |a, b| ops::BitAnd::bitand(a, b),
                 ));
             }
             InstAssignOp::BitXor => {
@@ -1966,6 +2159,7 @@ impl Vm {
                     target,
                     Protocol::BIT_XOR_ASSIGN,
                     ops::BitXorAssign::bitxor_assign,
+                    |a, b| ops::BitXor::bitxor(a, b),
                 ));
             }
             InstAssignOp::BitOr => {
@@ -1973,6 +2167,7 @@ impl Vm {
                     target,
                     Protocol::BIT_OR_ASSIGN,
                     ops::BitOrAssign::bitor_assign,
+                    |a, b| ops::BitOr::bitor(a, b),
                 ));
             }
             InstAssignOp::Shl => {
@@ -1981,13 +2176,18 @@ impl Vm {
                     Protocol::SHL_ASSIGN,
                     || VmErrorKind::Overflow,
                     |a, b| a.checked_shl(u32::try_from(b).ok()?),
+                    |a, b| Some(a << b.to_u32()?),
+                    shl_bits,
                 ));
             }
             InstAssignOp::Shr => {
-                vm_try!(self.internal_infallible_bitwise_assign(
+                vm_try!(self.internal_bitwise_assign(
                     target,
                     Protocol::SHR_ASSIGN,
-                    ops::ShrAssign::shr_assign,
+                    || VmErrorKind::Overflow,
+                    |a, b| Some(a >> b),
+                    |a, b| Some(a >> b.to_u32()?),
+                    |a, _| a.bits(),
                 ));
             }
         }
@@ -2491,6 +2691,14 @@ impl Vm {
         VmResult::Ok(())
     }
 
+    #[cfg_attr(feature = "bench", inline(never))]
+    fn op_bigint(&mut self, slot: usize) -> VmResult<()> {
+        let bytes = vm_try!(self.unit.lookup_bytes(slot));
+        let value = vm_try!(Value::try_from(BigInt::from_signed_bytes_le(bytes)));
+        vm_try!(self.stack.push(value));
+        VmResult::Ok(())
+    }
+
     /// Optimize operation to perform string concatenation.
     #[cfg_attr(feature = "bench", inline(never))]
     fn op_string_concat(&mut self, len: usize, size_hint: usize) -> VmResult<()> {
@@ -2853,8 +3061,14 @@ impl Vm {
                     call,
                     args: expected,
                 } => {
-                    vm_try!(check_args(args, expected));
-                    vm_try!(self.call_offset_fn(offset, call, args));
+                    vm_try!(self.unit.bind_args::<&str>(
+                        hash,
+                        &mut self.stack,
+                        args,
+                        expected,
+                        &[]
+                    ));
+                    vm_try!(self.call_offset_fn(offset, call, expected));
                 }
                 UnitFn::EmptyStruct { hash } => {
                     vm_try!(check_args(args, 0));
@@ -2931,6 +3145,26 @@ impl Vm {
 
     #[cfg_attr(feature = "bench", inline(never))]
     fn op_call_associated(&mut self, hash: Hash, args: usize) -> VmResult<()> {
+        self.call_associated::<&str>(hash, args, &[])
+    }
+
+    #[cfg_attr(feature = "bench", inline(never))]
+    fn op_call_associated_named(&mut self, hash: Hash, args: usize, slot: usize) -> VmResult<()> {
+        let unit = self.unit.clone();
+
+        let names = vm_try!(unit
+            .lookup_object_keys(slot)
+            .ok_or(VmErrorKind::MissingStaticObjectKeys { slot }));
+
+        self.call_associated(hash, args, names)
+    }
+
+    /// Call an instance function, where the trailing arguments are passed by
+    /// the given names.
+    fn call_associated<N>(&mut self, hash: Hash, args: usize, names: &[N]) -> VmResult<()>
+    where
+        N: AsRef<str>,
+    {
         // NB: +1 to include the instance itself.
         let args = args + 1;
         let instance = vm_try!(self.stack.at_offset_from_top(args));
@@ -2943,12 +3177,18 @@ impl Vm {
             args: expected,
         }) = self.unit.function(hash)
         {
-            vm_try!(check_args(args, expected));
-            vm_try!(self.call_offset_fn(offset, call, args));
+            vm_try!(self
+                .unit
+                .bind_args(hash, &mut self.stack, args, expected, names));
+            vm_try!(self.call_offset_fn(offset, call, expected));
             return VmResult::Ok(());
         }
 
         if let Some(handler) = self.context.function(hash) {
+            if !names.is_empty() {
+                return err(VmErrorKind::UnsupportedNamedArguments);
+            }
+
             vm_try!(handler(&mut self.stack, args));
             return VmResult::Ok(());
         }
@@ -2980,6 +3220,28 @@ impl Vm {
         VmResult::Ok(None)
     }
 
+    #[cfg_attr(feature = "bench", inline(never))]
+    fn op_call_fn_named(&mut self, args: usize, slot: usize) -> VmResult<Option<VmHalt>> {
+        let function = vm_try!(self.stack.pop());
+        let unit = self.unit.clone();
+
+        let names = vm_try!(unit
+            .lookup_object_keys(slot)
+            .ok_or(VmErrorKind::MissingStaticObjectKeys { slot }));
+
+        match function {
+            Value::Function(function) => {
+                let function = vm_try!(function.into_ref());
+                function.call_with_vm_named(self, args, names)
+            }
+            Value::Type(..) => err(VmErrorKind::UnsupportedNamedArguments),
+            actual => {
+                let actual = vm_try!(actual.type_info());
+                err(VmErrorKind::UnsupportedCallFn { actual })
+            }
+        }
+    }
+
     #[cfg_attr(feature = "bench", inline(never))]
     fn op_iter_next(&mut self, offset: usize, jump: usize) -> VmResult<()> {
         let value = vm_try!(self.stack.at_offset_mut(offset));
@@ -3047,276 +3309,349 @@ impl Vm {
     }
 
     /// Evaluate a single instruction.
-    pub(crate) fn run(&mut self) -> VmResult<VmHalt> {
+    ///
+    /// The given interrupt is checked at every instruction boundary. If an
+    /// observer is specified, it observes every instruction executed by this
+    /// virtual machine and by any executions which are started from it.
+    pub(crate) fn run(
+        &mut self,
+        interrupt: &mut Interrupt,
+        observer: Option<&mut dyn Observer>,
+    ) -> VmResult<VmHalt> {
         // NB: set up environment so that native function can access context and
         // unit.
         let _guard = crate::runtime::env::Guard::new(&self.context, &self.unit);
 
+        if let Some(observer) = observer {
+            let observer = observer::Guard::new(observer);
+            return self.run_observed(interrupt, observer.get(), false);
+        }
+
+        if let Some(observer) = observer::current() {
+            return self.run_observed(interrupt, observer, true);
+        }
+
         loop {
             if !budget::take() {
                 return VmResult::Ok(VmHalt::Limited);
             }
 
-            let Some((inst, inst_len)) = vm_try!(self.unit.instruction_at(self.ip)) else {
-                return VmResult::err(VmErrorKind::IpOutOfBounds {
-                    ip: self.ip,
-                    length: self.unit.instructions().end(),
-                });
-            };
+            vm_try!(interrupt.tick());
 
-            tracing::trace!(ip = ?self.ip, ?inst);
+            if let Some(halt) = vm_try!(self.run_instruction()) {
+                return VmResult::Ok(halt);
+            }
+        }
+    }
 
-            self.ip = self.ip.wrapping_add(inst_len);
-            self.last_ip_len = inst_len as u8;
+    /// Evaluate instructions while they are being observed.
+    fn run_observed(
+        &mut self,
+        interrupt: &mut Interrupt,
+        observer: NonNull<dyn Observer>,
+        nested: bool,
+    ) -> VmResult<VmHalt> {
+        loop {
+            if !budget::take() {
+                return VmResult::Ok(VmHalt::Limited);
+            }
 
-            match inst {
-                Inst::Not => {
-                    vm_try!(self.op_not());
-                }
-                Inst::Neg => {
-                    vm_try!(self.op_neg());
-                }
-                Inst::Closure { hash, count } => {
-                    vm_try!(self.op_closure(hash, count));
-                }
-                Inst::Call { hash, args } => {
-                    vm_try!(self.op_call(hash, args));
-                }
-                Inst::CallOffset { offset, call, args } => {
-                    vm_try!(self.op_call_offset(offset, call, args));
-                }
-                Inst::CallAssociated { hash, args } => {
-                    vm_try!(self.op_call_associated(hash, args));
-                }
-                Inst::CallFn { args } => {
-                    if let Some(reason) = vm_try!(self.op_call_fn(args)) {
-                        return VmResult::Ok(reason);
-                    }
-                }
-                Inst::LoadInstanceFn { hash } => {
-                    vm_try!(self.op_load_instance_fn(hash));
-                }
-                Inst::IndexGet { target, index } => {
-                    vm_try!(self.op_index_get(target, index));
-                }
-                Inst::TupleIndexGet { index } => {
-                    vm_try!(self.op_tuple_index_get(index));
-                }
-                Inst::TupleIndexSet { index } => {
-                    vm_try!(self.op_tuple_index_set(index));
-                }
-                Inst::TupleIndexGetAt { offset, index } => {
-                    vm_try!(self.op_tuple_index_get_at(offset, index));
-                }
-                Inst::ObjectIndexGet { slot } => {
-                    vm_try!(self.op_object_index_get(slot));
-                }
-                Inst::ObjectIndexSet { slot } => {
-                    vm_try!(self.op_object_index_set(slot));
-                }
-                Inst::ObjectIndexGetAt { offset, slot } => {
-                    vm_try!(self.op_object_index_get_at(offset, slot));
-                }
-                Inst::IndexSet => {
-                    vm_try!(self.op_index_set());
-                }
-                Inst::Return { address, clean } => {
-                    if vm_try!(self.op_return(address, clean)) {
-                        return VmResult::Ok(VmHalt::Exited);
-                    }
-                }
-                Inst::ReturnUnit => {
-                    if vm_try!(self.op_return_unit()) {
-                        return VmResult::Ok(VmHalt::Exited);
-                    }
-                }
-                Inst::Await => {
-                    let future = vm_try!(self.op_await());
-                    return VmResult::Ok(VmHalt::Awaited(Awaited::Future(future)));
-                }
-                Inst::Select { len } => {
-                    if let Some(select) = vm_try!(self.op_select(len)) {
-                        return VmResult::Ok(VmHalt::Awaited(Awaited::Select(select)));
-                    }
-                }
-                Inst::LoadFn { hash } => {
-                    vm_try!(self.op_load_fn(hash));
-                }
-                Inst::Push { value } => {
-                    vm_try!(self.op_push(value));
-                }
-                Inst::Pop => {
-                    vm_try!(self.op_pop());
-                }
-                Inst::PopN { count } => {
-                    vm_try!(self.op_popn(count));
-                }
-                Inst::PopAndJumpIfNot { count, jump } => {
-                    vm_try!(self.op_pop_and_jump_if_not(count, jump));
-                }
-                Inst::Clean { count } => {
-                    vm_try!(self.op_clean(count));
-                }
-                Inst::Copy { offset } => {
-                    vm_try!(self.op_copy(offset));
-                }
-                Inst::Move { offset } => {
-                    vm_try!(self.op_move(offset));
-                }
-                Inst::Drop { offset } => {
-                    vm_try!(self.op_drop(offset));
-                }
-                Inst::Swap { a, b } => {
-                    vm_try!(self.op_swap(a, b));
-                }
-                Inst::Replace { offset } => {
-                    vm_try!(self.op_replace(offset));
-                }
-                Inst::Jump { jump } => {
-                    vm_try!(self.op_jump(jump));
-                }
-                Inst::JumpIf { jump } => {
-                    vm_try!(self.op_jump_if(jump));
-                }
-                Inst::JumpIfOrPop { jump } => {
-                    vm_try!(self.op_jump_if_or_pop(jump));
-                }
-                Inst::JumpIfNotOrPop { jump } => {
-                    vm_try!(self.op_jump_if_not_or_pop(jump));
-                }
-                Inst::JumpIfBranch { branch, jump } => {
-                    vm_try!(self.op_jump_if_branch(branch, jump));
-                }
-                Inst::Vec { count } => {
-                    vm_try!(self.op_vec(count));
-                }
-                Inst::Tuple { count } => {
-                    vm_try!(self.op_tuple(count));
-                }
-                Inst::Tuple1 { args } => {
-                    vm_try!(self.op_tuple_n(&args[..]));
-                }
-                Inst::Tuple2 { args } => {
-                    vm_try!(self.op_tuple_n(&args[..]));
-                }
-                Inst::Tuple3 { args } => {
-                    vm_try!(self.op_tuple_n(&args[..]));
-                }
-                Inst::Tuple4 { args } => {
-                    vm_try!(self.op_tuple_n(&args[..]));
-                }
-                Inst::PushTuple => {
-                    vm_try!(self.op_push_tuple());
-                }
-                Inst::Object { slot } => {
-                    vm_try!(self.op_object(slot));
-                }
-                Inst::Range { range } => {
-                    vm_try!(self.op_range(range));
-                }
-                Inst::EmptyStruct { hash } => {
-                    vm_try!(self.op_empty_struct(hash));
-                }
-                Inst::Struct { hash, slot } => {
-                    vm_try!(self.op_struct(hash, slot));
-                }
-                Inst::UnitVariant { hash } => {
-                    vm_try!(self.op_unit_variant(hash));
-                }
-                Inst::StructVariant { hash, slot } => {
-                    vm_try!(self.op_object_variant(hash, slot));
-                }
-                Inst::String { slot } => {
-                    vm_try!(self.op_string(slot));
-                }
-                Inst::Bytes { slot } => {
-                    vm_try!(self.op_bytes(slot));
-                }
-                Inst::StringConcat { len, size_hint } => {
-                    vm_try!(self.op_string_concat(len, size_hint));
-                }
-                Inst::Format { spec } => {
-                    vm_try!(self.op_format(spec));
-                }
-                Inst::IsUnit => {
-                    vm_try!(self.op_is_unit());
-                }
-                Inst::Try {
-                    address,
-                    clean,
-                    preserve,
-                } => {
-                    if vm_try!(self.op_try(address, clean, preserve)) {
-                        return VmResult::Ok(VmHalt::Exited);
-                    }
-                }
-                Inst::EqByte { byte } => {
-                    vm_try!(self.op_eq_byte(byte));
-                }
-                Inst::EqChar { char: character } => {
-                    vm_try!(self.op_eq_character(character));
-                }
-                Inst::EqInteger { integer } => {
-                    vm_try!(self.op_eq_integer(integer));
-                }
-                Inst::EqBool { boolean } => {
-                    vm_try!(self.op_eq_bool(boolean));
-                }
-                Inst::EqString { slot } => {
-                    vm_try!(self.op_eq_string(slot));
-                }
-                Inst::EqBytes { slot } => {
-                    vm_try!(self.op_eq_bytes(slot));
-                }
-                Inst::MatchSequence {
-                    type_check,
-                    len,
-                    exact,
-                } => {
-                    vm_try!(self.op_match_sequence(type_check, len, exact));
-                }
-                Inst::MatchType { hash } => {
-                    vm_try!(self.op_match_type(hash));
-                }
-                Inst::MatchVariant {
-                    enum_hash,
-                    variant_hash,
-                    index,
-                } => {
-                    vm_try!(self.op_match_variant(enum_hash, variant_hash, index));
-                }
-                Inst::MatchBuiltIn { type_check } => {
-                    vm_try!(self.op_match_builtin(type_check));
-                }
-                Inst::MatchObject { slot, exact } => {
-                    vm_try!(self.op_match_object(slot, exact));
-                }
-                Inst::Yield => {
-                    return VmResult::Ok(VmHalt::Yielded);
-                }
-                Inst::YieldUnit => {
-                    vm_try!(self.stack.push(Value::EmptyTuple));
-                    return VmResult::Ok(VmHalt::Yielded);
+            vm_try!(interrupt.tick());
+
+            // SAFETY: the observer is installed through a guard which outlives
+            // the outermost observed execution, and it is only accessed for
+            // the duration of each call.
+            if unsafe { (*observer.as_ptr()).before(self, nested) } && !nested {
+                return VmResult::Ok(VmHalt::Limited);
+            }
+
+            let result = self.run_instruction();
+
+            // SAFETY: see above.
+            unsafe { (*observer.as_ptr()).after(self, nested) };
+
+            if let Some(halt) = vm_try!(result) {
+                return VmResult::Ok(halt);
+            }
+        }
+    }
+
+    /// Evaluate the instruction at the current instruction pointer, returning
+    /// the reason the virtual machine halted, if it did.
+    #[inline(always)]
+    fn run_instruction(&mut self) -> VmResult<Option<VmHalt>> {
+        let Some((inst, inst_len)) = vm_try!(self.unit.instruction_at(self.ip)) else {
+            return VmResult::err(VmErrorKind::IpOutOfBounds {
+                ip: self.ip,
+                length: self.unit.instructions().end(),
+            });
+        };
+
+        tracing::trace!(ip = ?self.ip, ?inst);
+
+        self.ip = self.ip.wrapping_add(inst_len);
+        self.last_ip_len = inst_len as u8;
+
+        match inst {
+            Inst::Not => {
+                vm_try!(self.op_not());
+            }
+            Inst::Neg => {
+                vm_try!(self.op_neg());
+            }
+            Inst::Closure { hash, count } => {
+                vm_try!(self.op_closure(hash, count));
+            }
+            Inst::Call { hash, args } => {
+                vm_try!(self.op_call(hash, args));
+            }
+            Inst::CallOffset { offset, call, args } => {
+                vm_try!(self.op_call_offset(offset, call, args));
+            }
+            Inst::CallAssociated { hash, args } => {
+                vm_try!(self.op_call_associated(hash, args));
+            }
+            Inst::CallAssociatedNamed { hash, args, slot } => {
+                vm_try!(self.op_call_associated_named(hash, args, slot));
+            }
+            Inst::CallFn { args } => {
+                if let Some(reason) = vm_try!(self.op_call_fn(args)) {
+                    return VmResult::Ok(Some(reason));
                 }
-                Inst::Variant { variant } => {
-                    vm_try!(self.op_variant(variant));
+            }
+            Inst::CallFnNamed { args, slot } => {
+                if let Some(reason) = vm_try!(self.op_call_fn_named(args, slot)) {
+                    return VmResult::Ok(Some(reason));
                 }
-                Inst::Op { op, a, b } => {
-                    vm_try!(self.op_op(op, a, b));
+            }
+            Inst::LoadInstanceFn { hash } => {
+                vm_try!(self.op_load_instance_fn(hash));
+            }
+            Inst::IndexGet { target, index } => {
+                vm_try!(self.op_index_get(target, index));
+            }
+            Inst::TupleIndexGet { index } => {
+                vm_try!(self.op_tuple_index_get(index));
+            }
+            Inst::TupleIndexSet { index } => {
+                vm_try!(self.op_tuple_index_set(index));
+            }
+            Inst::TupleIndexGetAt { offset, index } => {
+                vm_try!(self.op_tuple_index_get_at(offset, index));
+            }
+            Inst::ObjectIndexGet { slot } => {
+                vm_try!(self.op_object_index_get(slot));
+            }
+            Inst::ObjectIndexSet { slot } => {
+                vm_try!(self.op_object_index_set(slot));
+            }
+            Inst::ObjectIndexGetAt { offset, slot } => {
+                vm_try!(self.op_object_index_get_at(offset, slot));
+            }
+            Inst::IndexSet => {
+                vm_try!(self.op_index_set());
+            }
+            Inst::Return { address, clean } => {
+                if vm_try!(self.op_return(address, clean)) {
+                    return VmResult::Ok(Some(VmHalt::Exited));
                 }
-                Inst::Assign { target, op } => {
-                    vm_try!(self.op_assign(target, op));
+            }
+            Inst::ReturnUnit => {
+                if vm_try!(self.op_return_unit()) {
+                    return VmResult::Ok(Some(VmHalt::Exited));
                 }
-                Inst::IterNext { offset, jump } => {
-                    vm_try!(self.op_iter_next(offset, jump));
+            }
+            Inst::Await => {
+                let future = vm_try!(self.op_await());
+                return VmResult::Ok(Some(VmHalt::Awaited(Awaited::Future(future))));
+            }
+            Inst::Select { len } => {
+                if let Some(select) = vm_try!(self.op_select(len)) {
+                    return VmResult::Ok(Some(VmHalt::Awaited(Awaited::Select(select))));
                 }
-                Inst::Panic { reason } => {
-                    return err(VmErrorKind::Panic {
-                        reason: Panic::from(reason),
-                    });
+            }
+            Inst::LoadFn { hash } => {
+                vm_try!(self.op_load_fn(hash));
+            }
+            Inst::Push { value } => {
+                vm_try!(self.op_push(value));
+            }
+            Inst::Pop => {
+                vm_try!(self.op_pop());
+            }
+            Inst::PopN { count } => {
+                vm_try!(self.op_popn(count));
+            }
+            Inst::PopAndJumpIfNot { count, jump } => {
+                vm_try!(self.op_pop_and_jump_if_not(count, jump));
+            }
+            Inst::Clean { count } => {
+                vm_try!(self.op_clean(count));
+            }
+            Inst::Copy { offset } => {
+                vm_try!(self.op_copy(offset));
+            }
+            Inst::Move { offset } => {
+                vm_try!(self.op_move(offset));
+            }
+            Inst::Drop { offset } => {
+                vm_try!(self.op_drop(offset));
+            }
+            Inst::Swap { a, b } => {
+                vm_try!(self.op_swap(a, b));
+            }
+            Inst::Replace { offset } => {
+                vm_try!(self.op_replace(offset));
+            }
+            Inst::Jump { jump } => {
+                vm_try!(self.op_jump(jump));
+            }
+            Inst::JumpIf { jump } => {
+                vm_try!(self.op_jump_if(jump));
+            }
+            Inst::JumpIfOrPop { jump } => {
+                vm_try!(self.op_jump_if_or_pop(jump));
+            }
+            Inst::JumpIfNotOrPop { jump } => {
+                vm_try!(self.op_jump_if_not_or_pop(jump));
+            }
+            Inst::JumpIfBranch { branch, jump } => {
+                vm_try!(self.op_jump_if_branch(branch, jump));
+            }
+            Inst::Vec { count } => {
+                vm_try!(self.op_vec(count));
+            }
+            Inst::Tuple { count } => {
+                vm_try!(self.op_tuple(count));
+            }
+            Inst::Tuple1 { args } => {
+                vm_try!(self.op_tuple_n(&args[..]));
+            }
+            Inst::Tuple2 { args } => {
+                vm_try!(self.op_tuple_n(&args[..]));
+            }
+            Inst::Tuple3 { args } => {
+                vm_try!(self.op_tuple_n(&args[..]));
+            }
+            Inst::Tuple4 { args } => {
+                vm_try!(self.op_tuple_n(&args[..]));
+            }
+            Inst::PushTuple => {
+                vm_try!(self.op_push_tuple());
+            }
+            Inst::Object { slot } => {
+                vm_try!(self.op_object(slot));
+            }
+            Inst::Range { range } => {
+                vm_try!(self.op_range(range));
+            }
+            Inst::EmptyStruct { hash } => {
+                vm_try!(self.op_empty_struct(hash));
+            }
+            Inst::Struct { hash, slot } => {
+                vm_try!(self.op_struct(hash, slot));
+            }
+            Inst::UnitVariant { hash } => {
+                vm_try!(self.op_unit_variant(hash));
+            }
+            Inst::StructVariant { hash, slot } => {
+                vm_try!(self.op_object_variant(hash, slot));
+            }
+            Inst::String { slot } => {
+                vm_try!(self.op_string(slot));
+            }
+            Inst::Bytes { slot } => {
+                vm_try!(self.op_bytes(slot));
+            }
+            Inst::BigInt { slot } => {
+                vm_try!(self.op_bigint(slot));
+            }
+            Inst::StringConcat { len, size_hint } => {
+                vm_try!(self.op_string_concat(len, size_hint));
+            }
+            Inst::Format { spec } => {
+                vm_try!(self.op_format(spec));
+            }
+            Inst::IsUnit => {
+                vm_try!(self.op_is_unit());
+            }
+            Inst::Try {
+                address,
+                clean,
+                preserve,
+            } => {
+                if vm_try!(self.op_try(address, clean, preserve)) {
+                    return VmResult::Ok(Some(VmHalt::Exited));
                 }
             }
+            Inst::EqByte { byte } => {
+                vm_try!(self.op_eq_byte(byte));
+            }
+            Inst::EqChar { char: character } => {
+                vm_try!(self.op_eq_character(character));
+            }
+            Inst::EqInteger { integer } => {
+                vm_try!(self.op_eq_integer(integer));
+            }
+            Inst::EqBool { boolean } => {
+                vm_try!(self.op_eq_bool(boolean));
+            }
+            Inst::EqString { slot } => {
+                vm_try!(self.op_eq_string(slot));
+            }
+            Inst::EqBytes { slot } => {
+                vm_try!(self.op_eq_bytes(slot));
+            }
+            Inst::MatchSequence {
+                type_check,
+                len,
+                exact,
+            } => {
+                vm_try!(self.op_match_sequence(type_check, len, exact));
+            }
+            Inst::MatchType { hash } => {
+                vm_try!(self.op_match_type(hash));
+            }
+            Inst::MatchVariant {
+                enum_hash,
+                variant_hash,
+                index,
+            } => {
+                vm_try!(self.op_match_variant(enum_hash, variant_hash, index));
+            }
+            Inst::MatchBuiltIn { type_check } => {
+                vm_try!(self.op_match_builtin(type_check));
+            }
+            Inst::MatchObject { slot, exact } => {
+                vm_try!(self.op_match_object(slot, exact));
+            }
+            Inst::Yield => {
+                return VmResult::Ok(Some(VmHalt::Yielded));
+            }
+            Inst::YieldUnit => {
+                vm_try!(self.stack.push(Value::EmptyTuple));
+                return VmResult::Ok(Some(VmHalt::Yielded));
+            }
+            Inst::Variant { variant } => {
+                vm_try!(self.op_variant(variant));
+            }
+            Inst::Op { op, a, b } => {
+                vm_try!(self.op_op(op, a, b));
+            }
+            Inst::Assign { target, op } => {
+                vm_try!(self.op_assign(target, op));
+            }
+            Inst::IterNext { offset, jump } => {
+                vm_try!(self.op_iter_next(offset, jump));
+            }
+            Inst::Panic { reason } => {
+                return err(VmErrorKind::Panic {
+                    reason: Panic::from(reason),
+                });
+            }
         }
+
+        VmResult::Ok(None)
     }
 }
 
@@ -3386,3 +3721,45 @@ fn check_args(args: usize, expected: usize) -> Result<(), VmErrorKind> {
 
     Result::Ok(())
 }
+
+/// Check that a big integer of at most the given number of bits can be
+/// allocated within the memory limit.
+///
+/// The result is only charged against the memory limit once it's stored in a
+/// [Value], so this prevents a single operation from allocating past it first.
+pub(crate) fn bigint_limit(bits: u64) -> VmResult<()> {
+    vm_try!(crate::alloc::limit::check(LimitedBigInt::size_of(bits)));
+    VmResult::Ok(())
+}
+
+/// The number of bits needed by the result of an arithmetic or bitwise
+/// operation on big integers, which is bounded by that of their product.
+fn product_bits(a: &BigInt, b: &BigInt) -> u64 {
+    a.bits().saturating_add(b.bits()).saturating_add(1)
+}
+
+/// The number of bits needed by the result of shifting a big integer to the
+/// left.
+fn shl_bits(a: &BigInt, b: &BigInt) -> u64 {
+    a.bits().saturating_add(b.to_u64().unwrap_or_default())
+}
+
+/// Promote the operands of a binary operation to big integers if either of
+/// them is one, so that integers and big integers can be mixed freely.
+fn bigint_operands(lhs: &Value, rhs: &Value) -> VmResult<Option<(BigInt, BigInt)>> {
+    let operands = match (lhs, rhs) {
+        (Value::BigInt(lhs), Value::BigInt(rhs)) => (
+            vm_try!(lhs.borrow_ref()).clone(),
+            vm_try!(rhs.borrow_ref()).clone(),
+        ),
+        (Value::BigInt(lhs), Value::Integer(rhs)) => {
+            (vm_try!(lhs.borrow_ref()).clone(), BigInt::from(*rhs))
+        }
+        (Value::Integer(lhs), Value::BigInt(rhs)) => {
+            (BigInt::from(*lhs), vm_try!(rhs.borrow_ref()).clone())
+        }
+        _ => return VmResult::Ok(None),
+    };
+
+    VmResult::Ok(Some(operands))
+}
@@ -180,6 +180,38 @@ impl VmError {
         self.inner.stacktrace.first()
     }
 
+    /// Test if the error was caused by the execution exceeding its memory
+    /// limit.
+    ///
+    /// See [`VmExecution::with_memory_limit`].
+    ///
+    /// [`VmExecution::with_memory_limit`]: crate::runtime::VmExecution::with_memory_limit
+    pub fn is_memory_limit_exceeded(&self) -> bool {
+        matches!(
+            self.inner.error.kind,
+            VmErrorKind::MemoryLimitExceeded { .. }
+        )
+    }
+
+    /// Test if the error was caused by the execution being cancelled through
+    /// a [`CancelToken`].
+    ///
+    /// [`CancelToken`]: crate::runtime::CancelToken
+    pub fn is_cancelled(&self) -> bool {
+        matches!(self.inner.error.kind, VmErrorKind::Cancelled)
+    }
+
+    /// Test if the error was caused by the execution running past its
+    /// deadline.
+    ///
+    /// See [`VmExecution::with_deadline`].
+    ///
+    /// [`VmExecution::with_deadline`]: crate::runtime::VmExecution::with_deadline
+    #[cfg(feature = "std")]
+    pub fn is_deadline_exceeded(&self) -> bool {
+        matches!(self.inner.error.kind, VmErrorKind::DeadlineExceeded)
+    }
+
     #[cfg(test)]
     pub(crate) fn into_kind(self) -> VmErrorKind {
         self.inner.error.kind
@@ -517,6 +549,16 @@ pub(crate) enum VmErrorKind {
     BadArgument {
         arg: usize,
     },
+    MissingArgument {
+        name: String,
+    },
+    NoSuchArgument {
+        name: String,
+    },
+    DuplicateArgument {
+        name: String,
+    },
+    UnsupportedNamedArguments,
     UnsupportedIndexSet {
         target: TypeInfo,
         index: TypeInfo,
@@ -653,6 +695,12 @@ pub(crate) enum VmErrorKind {
     AllocError {
         error: AllocError,
     },
+    MemoryLimitExceeded {
+        error: AllocError,
+    },
+    Cancelled,
+    #[cfg(feature = "std")]
+    DeadlineExceeded,
 }
 
 impl fmt::Display for VmErrorKind {
@@ -720,6 +768,21 @@ impl fmt::Display for VmErrorKind {
                 "Wrong number of arguments `{actual}`, expected `{expected}`",
             ),
             VmErrorKind::BadArgument { arg } => write!(f, "Bad argument #{arg}"),
+            VmErrorKind::MissingArgument { name } => {
+                write!(f, "Missing value for argument `{name}`")
+            }
+            VmErrorKind::NoSuchArgument { name } => {
+                write!(f, "Function has no argument named `{name}`")
+            }
+            VmErrorKind::DuplicateArgument { name } => {
+                write!(f, "Argument `{name}` is passed more than once")
+            }
+            VmErrorKind::UnsupportedNamedArguments => {
+                write!(
+                    f,
+                    "Named arguments are not supported when calling this function"
+                )
+            }
             VmErrorKind::UnsupportedIndexSet {
                 target,
                 index,
@@ -862,6 +925,10 @@ impl fmt::Display for VmErrorKind {
                 )
             }
             VmErrorKind::AllocError { error } => error.fmt(f),
+            VmErrorKind::MemoryLimitExceeded { error } => error.fmt(f),
+            VmErrorKind::Cancelled => write!(f, "Execution was cancelled"),
+            #[cfg(feature = "std")]
+            VmErrorKind::DeadlineExceeded => write!(f, "Execution deadline exceeded"),
         }
     }
 }
@@ -904,14 +971,23 @@ impl From<BadJump> for VmErrorKind {
 impl From<Error> for VmErrorKind {
     #[inline]
     fn from(error: Error) -> Self {
-        VmErrorKind::TryReserveError { error }
+        match error {
+            Error::AllocError { error } if error.is_limit_exceeded() => {
+                VmErrorKind::MemoryLimitExceeded { error }
+            }
+            error => VmErrorKind::TryReserveError { error },
+        }
     }
 }
 
 impl From<AllocError> for VmErrorKind {
     #[inline]
     fn from(error: AllocError) -> Self {
-        VmErrorKind::AllocError { error }
+        if error.is_limit_exceeded() {
+            VmErrorKind::MemoryLimitExceeded { error }
+        } else {
+            VmErrorKind::AllocError { error }
+        }
     }
 }
 
@@ -5,10 +5,15 @@ use core::mem::{replace, take};
 use crate::no_std::prelude::*;
 use crate::no_std::sync::Arc;
 
-use crate::runtime::budget;
+use crate::alloc::limit;
+#[cfg(feature = "std")]
+use crate::runtime::coverage::Coverage;
+use crate::runtime::debugger::{DebugState, Debugger, Resume};
+#[cfg(feature = "std")]
+use crate::runtime::profiler::Profiler;
 use crate::runtime::{
-    Generator, GeneratorState, RuntimeContext, Stream, Unit, Value, Vm, VmErrorKind, VmHalt,
-    VmHaltInfo, VmResult,
+    CancelToken, Generator, GeneratorState, Interrupt, Observer, RuntimeContext, Stream, Unit,
+    Value, Vm, VmErrorKind, VmHalt, VmHaltInfo, VmResult,
 };
 use crate::shared::AssertSend;
 
@@ -54,6 +59,11 @@ where
     state: ExecutionState,
     /// Indicates the current stack of suspended contexts.
     states: Vec<VmExecutionState>,
+    /// The memory limit of the execution, or `None` if it allocates against the
+    /// limit of its caller.
+    memory: Option<MemoryLimit>,
+    /// Conditions under which the execution is interrupted.
+    interrupt: Interrupt,
 }
 
 impl<T> VmExecution<T>
@@ -66,9 +76,131 @@ where
             head,
             state: ExecutionState::Initial,
             states: vec![],
+            memory: None,
+            interrupt: Interrupt::inherited(),
         }
     }
 
+    /// Limit the amount of memory in bytes which the execution is permitted
+    /// to allocate.
+    ///
+    /// The limit is enforced through the fallible allocation APIs in
+    /// [`rune::alloc`], and memory which is freed during execution is returned
+    /// to the limit, but never past it. If the limit is exceeded, the execution
+    /// errors with a [`VmError`] for which
+    /// [`VmError::is_memory_limit_exceeded`] returns `true`.
+    ///
+    /// [`rune::alloc`]: crate::alloc
+    /// [`VmError`]: crate::runtime::VmError
+    /// [`VmError::is_memory_limit_exceeded`]: crate::runtime::VmError::is_memory_limit_exceeded
+    ///
+    /// ```
+    /// use rune::{Context, Vm};
+    /// use std::sync::Arc;
+    ///
+    /// let context = Context::with_default_modules()?;
+    /// let runtime = Arc::new(context.runtime());
+    ///
+    /// let mut sources = rune::sources! {
+    ///     entry => {
+    ///         pub fn main(n) {
+    ///             let values = [];
+    ///
+    ///             for n in 0..n {
+    ///                 values.push(n);
+    ///             }
+    ///
+    ///             values.len()
+    ///         }
+    ///     }
+    /// };
+    ///
+    /// let unit = rune::prepare(&mut sources).with_context(&context).build()?;
+    /// let mut vm = Vm::new(runtime, Arc::new(unit));
+    ///
+    /// let mut execution = vm.execute(["main"], (10,))?.with_memory_limit(4096);
+    /// let value: i64 = rune::from_value(execution.complete().into_result()?)?;
+    /// assert_eq!(value, 10);
+    ///
+    /// let mut execution = vm.execute(["main"], (10000,))?.with_memory_limit(4096);
+    /// let error = execution.complete().into_result().unwrap_err();
+    /// assert!(error.is_memory_limit_exceeded());
+    /// # Ok::<_, rune::Error>(())
+    /// ```
+    pub fn with_memory_limit(mut self, memory: usize) -> Self {
+        self.memory = Some(MemoryLimit {
+            limit: memory,
+            remaining: memory,
+        });
+        self
+    }
+
+    /// Associate a [`CancelToken`] with the execution.
+    ///
+    /// The token is checked at every instruction boundary and whenever the
+    /// execution awaits a future or a select. Once it is cancelled the
+    /// execution errors with a [`VmError`] for which [`VmError::is_cancelled`]
+    /// returns `true`. The stack and call frames of the virtual machine are
+    /// left intact so that they can be inspected.
+    ///
+    /// [`VmError`]: crate::runtime::VmError
+    /// [`VmError::is_cancelled`]: crate::runtime::VmError::is_cancelled
+    pub fn with_cancel_token(mut self, token: CancelToken) -> Self {
+        self.interrupt.set_token(token);
+        self
+    }
+
+    /// Set a wall-clock deadline for the execution.
+    ///
+    /// The deadline is checked periodically while instructions are being
+    /// executed and whenever the execution awaits a future or a select. Once
+    /// it has passed the execution errors with a [`VmError`] for which
+    /// [`VmError::is_deadline_exceeded`] returns `true`. The stack and call
+    /// frames of the virtual machine are left intact so that they can be
+    /// inspected.
+    ///
+    /// Note that a future which never wakes up cannot be interrupted, since
+    /// the deadline is only checked when it is polled.
+    ///
+    /// [`VmError`]: crate::runtime::VmError
+    /// [`VmError::is_deadline_exceeded`]: crate::runtime::VmError::is_deadline_exceeded
+    ///
+    /// ```
+    /// use rune::Vm;
+    /// use std::sync::Arc;
+    /// use std::time::{Duration, Instant};
+    ///
+    /// let mut sources = rune::sources! {
+    ///     entry => {
+    ///         fn spin() {
+    ///             loop {}
+    ///         }
+    ///
+    ///         pub fn main() {
+    ///             spin()
+    ///         }
+    ///     }
+    /// };
+    ///
+    /// let unit = rune::prepare(&mut sources).build()?;
+    /// let mut vm = Vm::without_runtime(Arc::new(unit));
+    ///
+    /// let deadline = Instant::now() + Duration::from_millis(10);
+    /// let mut execution = vm.execute(["main"], ())?.with_deadline(deadline);
+    ///
+    /// let error = execution.complete().into_result().unwrap_err();
+    /// assert!(error.is_deadline_exceeded());
+    ///
+    /// // We were interrupted while inside of `spin`.
+    /// assert_eq!(execution.vm().call_frames().len(), 1);
+    /// # Ok::<_, rune::Error>(())
+    /// ```
+    #[cfg(feature = "std")]
+    pub fn with_deadline(mut self, deadline: std::time::Instant) -> Self {
+        self.interrupt.set_deadline(deadline);
+        self
+    }
+
     /// Test if the current execution state is resumed.
     pub(crate) fn is_resumed(&self) -> bool {
         matches!(self.state, ExecutionState::Resumed)
@@ -207,13 +339,22 @@ where
     }
 
     async fn inner_async_resume(&mut self) -> VmResult<GeneratorState> {
+        let mut memory = self.memory;
+        let scope = self.interrupt.scope();
+        let future = async_limited(&mut memory, self.inner_async_resume_limited());
+        let result = scope.with(future).await;
+        self.memory = memory;
+        result
+    }
+
+    async fn inner_async_resume_limited(&mut self) -> VmResult<GeneratorState> {
         loop {
             let vm = self.head.as_mut();
 
-            match vm_try!(vm.run().with_vm(vm)) {
+            match vm_try!(vm.run(&mut self.interrupt, None).with_vm(vm)) {
                 VmHalt::Exited => (),
                 VmHalt::Awaited(awaited) => {
-                    vm_try!(awaited.into_vm(vm).await);
+                    vm_try!(awaited.into_vm(vm, &self.interrupt).await);
                     continue;
                 }
                 VmHalt::VmCall(vm_call) => {
@@ -273,11 +414,21 @@ where
     }
 
     fn inner_resume(&mut self) -> VmResult<GeneratorState> {
+        let mut memory = self.memory;
+        let scope = self.interrupt.scope();
+        let result = scope
+            .with(|| limited(&mut memory, || self.inner_resume_limited()))
+            .call();
+        self.memory = memory;
+        result
+    }
+
+    fn inner_resume_limited(&mut self) -> VmResult<GeneratorState> {
         loop {
             let len = self.states.len();
             let vm = self.head.as_mut();
 
-            match vm_try!(vm.run().with_vm(vm)) {
+            match vm_try!(vm.run(&mut self.interrupt, None).with_vm(vm)) {
                 VmHalt::Exited => (),
                 VmHalt::VmCall(vm_call) => {
                     vm_try!(vm_call.into_execution(self));
@@ -308,10 +459,30 @@ where
     ///
     /// If any async instructions are encountered, this will error.
     pub fn step(&mut self) -> VmResult<Option<Value>> {
+        self.run_observed(&mut Step::default())
+    }
+
+    /// Step the single execution for one step with support for async
+    /// instructions.
+    pub async fn async_step(&mut self) -> VmResult<Option<Value>> {
+        self.async_run_observed(&mut Step::default()).await
+    }
+
+    /// Run the execution until the given observer halts it, or the virtual
+    /// machine halts for some other reason.
+    fn run_observed(&mut self, observer: &mut dyn Observer) -> VmResult<Option<Value>> {
         let len = self.states.len();
         let vm = self.head.as_mut();
+        let scope = self.interrupt.scope();
+
+        let memory = &mut self.memory;
+        let interrupt = &mut self.interrupt;
 
-        match vm_try!(budget::with(1, || vm.run().with_vm(vm)).call()) {
+        let result = scope
+            .with(|| limited(memory, || vm.run(interrupt, Some(observer)).with_vm(vm)))
+            .call();
+
+        match vm_try!(result) {
             VmHalt::Exited => (),
             VmHalt::VmCall(vm_call) => {
                 vm_try!(vm_call.into_execution(self));
@@ -334,15 +505,26 @@ where
         VmResult::Ok(None)
     }
 
-    /// Step the single execution for one step with support for async
-    /// instructions.
-    pub async fn async_step(&mut self) -> VmResult<Option<Value>> {
+    /// Run the execution with support for async instructions until the given
+    /// observer halts it, or the virtual machine halts for some other reason.
+    async fn async_run_observed(&mut self, observer: &mut dyn Observer) -> VmResult<Option<Value>> {
         let vm = self.head.as_mut();
+        let scope = self.interrupt.scope();
+
+        let memory = &mut self.memory;
+        let interrupt = &mut self.interrupt;
 
-        match vm_try!(budget::with(1, || vm.run().with_vm(vm)).call()) {
+        let result = scope
+            .with(|| limited(memory, || vm.run(interrupt, Some(observer)).with_vm(vm)))
+            .call();
+
+        match vm_try!(result) {
             VmHalt::Exited => (),
             VmHalt::Awaited(awaited) => {
-                vm_try!(awaited.into_vm(vm).await);
+                let vm = self.head.as_mut();
+                let scope = self.interrupt.scope();
+                let future = async_limited(&mut self.memory, awaited.into_vm(vm, &self.interrupt));
+                vm_try!(scope.with(future).await);
                 return VmResult::Ok(None);
             }
             VmHalt::VmCall(vm_call) => {
@@ -366,6 +548,115 @@ where
         VmResult::Ok(None)
     }
 
+    /// Resume the execution under the control of the given debugger without
+    /// support for async instructions.
+    ///
+    /// This runs the execution until a breakpoint is reached, the step
+    /// described by `resume` completes, or the execution completes.
+    ///
+    /// Executions which are started by native functions, like closures called
+    /// by an iterator, run to completion without stopping since they can't be
+    /// suspended.
+    ///
+    /// If any async instructions are encountered, this will error.
+    pub fn debug(&mut self, debugger: &Debugger, resume: Resume) -> VmResult<DebugState> {
+        let mut cursor = debugger.cursor(self.head.as_ref(), resume);
+
+        loop {
+            if let Some(value) = vm_try!(self.run_observed(&mut cursor)) {
+                return VmResult::Ok(DebugState::Complete(value));
+            }
+
+            if let Some(state) = cursor.take_state() {
+                return VmResult::Ok(state);
+            }
+        }
+    }
+
+    /// Resume the execution under the control of the given debugger with
+    /// support for async instructions.
+    ///
+    /// See [`VmExecution::debug`].
+    pub async fn async_debug(
+        &mut self,
+        debugger: &Debugger,
+        resume: Resume,
+    ) -> VmResult<DebugState> {
+        let mut cursor = debugger.cursor(self.head.as_ref(), resume);
+
+        loop {
+            if let Some(value) = vm_try!(self.async_run_observed(&mut cursor).await) {
+                return VmResult::Ok(DebugState::Complete(value));
+            }
+
+            if let Some(state) = cursor.take_state() {
+                return VmResult::Ok(state);
+            }
+        }
+    }
+
+    /// Complete the execution while recording which instructions and
+    /// branches are executed in the given coverage.
+    ///
+    /// This includes executions which are started by native functions, like
+    /// closures called by an iterator.
+    ///
+    /// If any async instructions are encountered, this will error.
+    #[cfg(feature = "std")]
+    pub fn complete_with_coverage(&mut self, coverage: &mut Coverage) -> VmResult<Value> {
+        loop {
+            if let Some(value) = vm_try!(self.run_observed(coverage)) {
+                return VmResult::Ok(value);
+            }
+        }
+    }
+
+    /// Complete the execution with support for async instructions while
+    /// recording which instructions and branches are executed in the given
+    /// coverage.
+    ///
+    /// See [`VmExecution::complete_with_coverage`].
+    #[cfg(feature = "std")]
+    pub async fn async_complete_with_coverage(
+        &mut self,
+        coverage: &mut Coverage,
+    ) -> VmResult<Value> {
+        loop {
+            if let Some(value) = vm_try!(self.async_run_observed(coverage).await) {
+                return VmResult::Ok(value);
+            }
+        }
+    }
+
+    /// Complete the execution while recording a profile of it in the given
+    /// profiler.
+    ///
+    /// This includes executions which are started by native functions, like
+    /// closures called by an iterator.
+    ///
+    /// If any async instructions are encountered, this will error.
+    #[cfg(feature = "std")]
+    pub fn profile(&mut self, profiler: &mut Profiler) -> VmResult<Value> {
+        loop {
+            if let Some(value) = vm_try!(self.run_observed(profiler)) {
+                return VmResult::Ok(value);
+            }
+        }
+    }
+
+    /// Complete the execution with support for async instructions while
+    /// recording a profile of it in the given profiler.
+    ///
+    /// See [`VmExecution::profile`].
+    #[cfg(feature = "std")]
+    pub async fn async_profile(&mut self, profiler: &mut Profiler) -> VmResult<Value> {
+        loop {
+            if let Some(value) = vm_try!(self.async_run_observed(profiler).await) {
+                return VmResult::Ok(value);
+            }
+        }
+    }
+
     /// End execution and perform debug checks.
     pub(crate) fn end(&mut self) -> VmResult<Value> {
         let vm = self.head.as_mut();
@@ -415,10 +706,77 @@ impl VmExecution<&mut Vm> {
             head,
             states: self.states,
             state: self.state,
+            memory: self.memory,
+            interrupt: self.interrupt,
         }
     }
 }
 
+/// The memory limit of an execution.
+#[derive(Debug, Clone, Copy)]
+struct MemoryLimit {
+    /// The configured limit in bytes.
+    limit: usize,
+    /// The memory in bytes which remains to be allocated within the limit.
+    remaining: usize,
+}
+
+/// Call the given function with the memory limit of an execution, and update
+/// the limit with the memory which remains once it returns.
+///
+/// Executions without a limit of their own, like those started from native
+/// functions, allocate against the limit which is in effect when they run.
+fn limited<O>(memory: &mut Option<MemoryLimit>, f: impl FnOnce() -> O) -> O {
+    let Some(m) = memory else {
+        return f();
+    };
+
+    let (output, remaining) = limit::with(m.limit, || {
+        let output = f();
+        (output, limit::get())
+    })
+    .with_remaining(m.remaining)
+    .call();
+
+    m.remaining = remaining;
+    output
+}
+
+/// The asynchronous counterpart of [`limited`].
+async fn async_limited<F>(memory: &mut Option<MemoryLimit>, future: F) -> F::Output
+where
+    F: Future,
+{
+    let Some(m) = memory else {
+        return future.await;
+    };
+
+    let (output, remaining) = limit::with(m.limit, async {
+        let output = future.await;
+        (output, limit::get())
+    })
+    .with_remaining(m.remaining)
+    .await;
+
+    m.remaining = remaining;
+    output
+}
+
+/// Halts the observed virtual machine after it has executed a single
+/// instruction.
+#[derive(Default)]
+struct Step {
+    stepped: bool,
+}
+
+impl Observer for Step {
+    fn before(&mut self, _: &Vm, nested: bool) -> bool {
+        !nested && replace(&mut self.stepped, true)
+    }
+
+    fn after(&mut self, _: &Vm, _: bool) {}
+}
+
 /// A wrapper that makes [`VmExecution`] [`Send`].
 ///
 /// This is accomplished by preventing any [`Value`] from escaping the [`Vm`].
@@ -434,6 +792,29 @@ pub struct VmSendExecution(pub(crate) VmExecution<Vm>);
 unsafe impl Send for VmSendExecution {}
 
 impl VmSendExecution {
+    /// Limit the amount of memory in bytes which the execution is permitted
+    /// to allocate.
+    ///
+    /// See [`VmExecution::with_memory_limit`].
+    pub fn with_memory_limit(self, memory: usize) -> Self {
+        Self(self.0.with_memory_limit(memory))
+    }
+
+    /// Associate a [`CancelToken`] with the execution.
+    ///
+    /// See [`VmExecution::with_cancel_token`].
+    pub fn with_cancel_token(self, token: CancelToken) -> Self {
+        Self(self.0.with_cancel_token(token))
+    }
+
+    /// Set a wall-clock deadline for the execution.
+    ///
+    /// See [`VmExecution::with_deadline`].
+    #[cfg(feature = "std")]
+    pub fn with_deadline(self, deadline: std::time::Instant) -> Self {
+        Self(self.0.with_deadline(deadline))
+    }
+
     /// Complete the current execution with support for async instructions.
     ///
     /// This requires that the result of the Vm is converted into a
@@ -108,7 +108,6 @@ impl Sources {
     }
 
     /// Iterate over all registered sources.
-    #[cfg(feature = "cli")]
     pub(crate) fn iter(&self) -> impl Iterator<Item = &Source> {
         self.sources.iter()
     }
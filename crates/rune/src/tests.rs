@@ -353,6 +353,7 @@ macro_rules! prelude {
 }
 
 mod attribute;
+mod bigint;
 mod binary;
 mod bug_326;
 mod bug_344;
@@ -375,11 +376,14 @@ mod compiler_patterns;
 mod compiler_use;
 mod compiler_visibility;
 mod compiler_warnings;
+mod context_signature;
 mod continue_;
 mod core_macros;
 mod custom_macros;
 mod derive_from_to_value;
 mod destructuring;
+#[cfg(feature = "doc")]
+mod doc_declarations;
 mod esoteric_impls;
 mod external_constructor;
 mod external_generic;
@@ -395,6 +399,7 @@ mod iter;
 mod iterator;
 mod macros;
 mod moved;
+mod named_arguments;
 mod option;
 mod patterns;
 mod quote;
@@ -403,10 +408,13 @@ mod reference_error;
 mod rename_type;
 mod result;
 mod stmt_reordering;
+mod traits;
 mod tuple;
+mod type_annotations;
 mod type_name_native;
 mod type_name_rune;
 mod unit_constants;
+mod unit_verify;
 mod variants;
 mod vm_arithmetic;
 mod vm_assign_exprs;
@@ -414,18 +422,23 @@ mod vm_async_block;
 mod vm_blocks;
 mod vm_closures;
 mod vm_const_exprs;
+mod vm_coverage;
+mod vm_debugger;
 mod vm_early_termination;
 mod vm_function;
 mod vm_function_pointers;
 mod vm_general;
 mod vm_generators;
+mod vm_interrupt;
 mod vm_is;
 mod vm_lazy_and_or;
 mod vm_literals;
 mod vm_match;
+mod vm_memory_limit;
 mod vm_not_used;
 mod vm_option;
 mod vm_pat;
+mod vm_profiler;
 mod vm_result;
 mod vm_streams;
 mod vm_test_from_value_derive;
@@ -437,4 +450,6 @@ mod vm_try;
 mod vm_tuples;
 mod vm_typed_tuple;
 mod vm_types;
+#[cfg(feature = "cache")]
+mod vm_unit_cache;
 mod wildcard_imports;
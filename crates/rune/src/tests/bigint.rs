@@ -0,0 +1,325 @@
+prelude!();
+
+use num::BigInt;
+
+use crate::no_std::sync::Arc;
+use crate::to_value;
+
+fn big(s: &str) -> BigInt {
+    s.parse().unwrap()
+}
+
+#[test]
+fn bigint_literals() {
+    let out: BigInt = rune_s!("pub fn main() { 340282366920938463463374607431768211456n }");
+    assert_eq!(out, big("340282366920938463463374607431768211456"));
+
+    let out: BigInt = rune_s!("pub fn main() { -0xffffffffffffffffffn }");
+    assert_eq!(out, big("-4722366482869645213695"));
+
+    let out: BigInt = rune! {
+        const LIMIT = 1n << 100;
+
+        pub fn main() {
+            LIMIT
+        }
+    };
+    assert_eq!(out, BigInt::from(1) << 100);
+
+    let out: bool = rune!(pub fn main() { 42n is BigInt });
+    assert!(out);
+}
+
+#[test]
+fn bigint_arithmetic() {
+    let out: BigInt = rune_s!("pub fn main() { 18446744073709551615n + 1 }");
+    assert_eq!(out, big("18446744073709551616"));
+
+    let out: BigInt = rune! {
+        pub fn main() {
+            2 * 3n - 1
+        }
+    };
+    assert_eq!(out, BigInt::from(5));
+
+    let out: (BigInt, BigInt) = rune! {
+        pub fn main() {
+            (-7n / 2, -7n % 2)
+        }
+    };
+    assert_eq!(out, (BigInt::from(-3), BigInt::from(-1)));
+
+    let out: (BigInt, BigInt, BigInt) = rune! {
+        pub fn main() {
+            (6n & 3, 6n | 3, 6n ^ 3)
+        }
+    };
+    assert_eq!(out, (BigInt::from(2), BigInt::from(7), BigInt::from(5)));
+
+    let out: (BigInt, BigInt) = rune! {
+        pub fn main() {
+            ((1n << 80) >> 78, !0n)
+        }
+    };
+    assert_eq!(out, (BigInt::from(4), BigInt::from(-1)));
+
+    let out: BigInt = rune! {
+        pub fn main() {
+            let a = i64::MAX;
+            let b = a;
+            b += 1n;
+            b *= 2;
+            b
+        }
+    };
+    assert_eq!(out, (BigInt::from(i64::MAX) + 1) * 2);
+
+    let out: (BigInt, BigInt) = rune! {
+        pub fn main() {
+            let a = 10n;
+            let b = a;
+            b -= 1;
+            (a, b)
+        }
+    };
+    assert_eq!(out, (BigInt::from(10), BigInt::from(9)));
+
+    let out: BigInt = rune! {
+        pub fn main() {
+            let o = #{ total: 0 };
+
+            for n in [i64::MAX, i64::MAX, i64::MAX] {
+                o.total += n as BigInt;
+            }
+
+            o.total
+        }
+    };
+    assert_eq!(out, BigInt::from(i64::MAX) * 3);
+}
+
+#[test]
+fn bigint_overflow() {
+    assert_vm_error!(
+        "pub fn main() { i64::MAX + 1 }",
+        VmErrorKind::Overflow => {}
+    );
+
+    assert_vm_error!(
+        "pub fn main() { 1n / 0 }",
+        VmErrorKind::DivideByZero => {}
+    );
+
+    assert_vm_error!(
+        "pub fn main() { 1n % 0n }",
+        VmErrorKind::DivideByZero => {}
+    );
+
+    assert_vm_error!(
+        "pub fn main() { 1n << -1 }",
+        VmErrorKind::Overflow => {}
+    );
+
+    assert_vm_error!(
+        "pub fn main() { 18446744073709551615n as i64 }",
+        VmErrorKind::ValueToIntegerCoercionError { to: "i64", .. } => {}
+    );
+
+    assert_vm_error!(
+        "pub fn main() { 256n as u8 }",
+        VmErrorKind::ValueToIntegerCoercionError { to: "u8", .. } => {}
+    );
+
+    assert_vm_error!(
+        "pub fn main() { (0.0 / 0.0) as BigInt }",
+        VmErrorKind::IllegalFloatOperation { .. } => {}
+    );
+}
+
+#[test]
+fn bigint_comparisons() {
+    let out: (bool, bool, bool, bool) = rune! {
+        pub fn main() {
+            (5n == 5, 5 == 5n, 4 < 5n, 6n >= 7)
+        }
+    };
+    assert_eq!(out, (true, true, true, false));
+
+    let out: bool = rune_s!("pub fn main() { 18446744073709551616n > i64::MAX }");
+    assert!(out);
+
+    let out: Vec<BigInt> = rune! {
+        pub fn main() {
+            let values = [3n, -1n, 2n];
+            values.sort();
+            values
+        }
+    };
+    assert_eq!(out, [BigInt::from(-1), BigInt::from(2), BigInt::from(3)]);
+
+    let out: bool = rune! {
+        pub fn main() {
+            match 42n {
+                n if n == 42 => true,
+                _ => false,
+            }
+        }
+    };
+    assert!(out);
+}
+
+#[test]
+fn bigint_conversions() {
+    let out: (i64, u8, f64) = rune! {
+        pub fn main() {
+            (42n as i64, 42n as u8, 42n as f64)
+        }
+    };
+    assert_eq!(out, (42, 42, 42.0));
+
+    let out: (BigInt, BigInt, BigInt) = rune! {
+        pub fn main() {
+            (42 as BigInt, b'a' as BigInt, 2.9 as BigInt)
+        }
+    };
+    assert_eq!(out, (BigInt::from(42), BigInt::from(97), BigInt::from(2)));
+
+    let out: (Option<i64>, Option<i64>) =
+        rune_s!("pub fn main() { (42n.to::<i64>(), 18446744073709551616n.to::<i64>()) }");
+    assert_eq!(out, (Some(42), None));
+
+    let out: String = rune_s!(r#"pub fn main() { `${2n.pow(64)}` }"#);
+    assert_eq!(out, "18446744073709551616");
+
+    let out: BigInt =
+        rune_s!(r#"pub fn main() { BigInt::parse("-123456789012345678901234567890")? }"#);
+    assert_eq!(out, big("-123456789012345678901234567890"));
+}
+
+#[test]
+fn bigint_hash() {
+    let out: i64 = rune! {
+        use std::collections::HashMap;
+
+        pub fn main() {
+            let map = HashMap::new();
+            map.insert(1n << 64, 1);
+            map.insert(7n, 2);
+            map[1n << 64] + map[7]
+        }
+    };
+    assert_eq!(out, 3);
+}
+
+#[test]
+fn bigint_serde() -> Result<()> {
+    let value: Value = serde_json::from_str("18446744073709551615").unwrap();
+    let out = BigInt::from_value(value).into_result()?;
+    assert_eq!(out, BigInt::from(u64::MAX));
+
+    let value: Value = serde_json::from_str("-42").unwrap();
+    assert!(matches!(value, Value::Integer(-42)));
+
+    let value = to_value(BigInt::from(u64::MAX))?;
+    assert_eq!(
+        serde_json::to_string(&value).unwrap(),
+        "18446744073709551615"
+    );
+
+    let value = to_value(BigInt::from(1) << 200)?;
+    assert!(serde_json::to_string(&value).is_err());
+    Ok(())
+}
+
+#[test]
+fn bigint_memory_limit() -> Result<()> {
+    let context = Context::with_default_modules()?;
+
+    let mut sources = sources! {
+        entry => {
+            pub fn shl(n) {
+                1n << n
+            }
+
+            pub fn shl_assign(n) {
+                let value = 1n;
+                value <<= n;
+                value
+            }
+
+            pub fn square(n) {
+                let value = 3n;
+
+                for _ in 0..n {
+                    value = value * value;
+                }
+
+                value.bits()
+            }
+
+            pub fn pow(n) {
+                3n.pow(n)
+            }
+
+            pub fn collect(n) {
+                let values = [];
+
+                for _ in 0..n {
+                    values.push(1n << 8000);
+                }
+
+                values.len()
+            }
+
+            pub fn discard(n) {
+                for _ in 0..n {
+                    let value = 1n << 8000;
+                }
+
+                n
+            }
+        }
+    };
+
+    let unit = prepare(&mut sources).with_context(&context).build()?;
+    let mut vm = Vm::new(Arc::new(context.runtime()), Arc::new(unit));
+
+    // Operations whose results wouldn't fit within the memory limit are
+    // denied before they're computed.
+    for (function, n) in [
+        ("shl", 4_000_000_000i64),
+        ("shl_assign", 4_000_000_000),
+        ("square", 64),
+        ("pow", 4_000_000_000),
+    ] {
+        let mut execution = vm.execute([function], (n,))?.with_memory_limit(1024);
+        let error = execution.complete().into_result().unwrap_err();
+        assert!(error.is_memory_limit_exceeded(), "{function}: {error}");
+    }
+
+    let mut execution = vm.execute(["shl"], (100,))?.with_memory_limit(1024);
+    let value = execution.complete().into_result()?;
+    assert_eq!(
+        BigInt::from_value(value).into_result()?,
+        BigInt::from(1) << 100
+    );
+
+    let mut execution = vm.execute(["square"], (4,))?.with_memory_limit(1024);
+    let value = execution.complete().into_result()?;
+    assert_eq!(u64::from_value(value).into_result()?, 26);
+
+    // Big integers which are kept alive are charged against the limit, and
+    // given back to it once they're dropped.
+    let mut execution = vm.execute(["collect"], (100,))?.with_memory_limit(65536);
+    let error = execution.complete().into_result().unwrap_err();
+    assert!(error.is_memory_limit_exceeded(), "{error}");
+
+    let mut execution = vm.execute(["collect"], (10,))?.with_memory_limit(65536);
+    let value = execution.complete().into_result()?;
+    assert_eq!(u64::from_value(value).into_result()?, 10);
+
+    let mut execution = vm.execute(["discard"], (1000,))?.with_memory_limit(65536);
+    let value = execution.complete().into_result()?;
+    assert_eq!(u64::from_value(value).into_result()?, 1000);
+    Ok(())
+}
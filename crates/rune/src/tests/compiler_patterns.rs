@@ -30,3 +30,38 @@ fn illegal_pattern_in_match() {
         }
     };
 }
+
+#[test]
+fn illegal_or_pattern() {
+    assert_errors! {
+        r#"pub fn main() { match 1 { a | 2 => a } }"#,
+        span!(30, 31), PatternOrBindings { name } => {
+            assert_eq!(&*name, "a");
+        }
+    };
+
+    assert_errors! {
+        r#"pub fn main() { match (1, 2) { (a, 1) | (b, 2) => a } }"#,
+        span!(40, 46), PatternOrBindings { name } => {
+            assert_eq!(&*name, "a");
+        }
+    };
+}
+
+#[test]
+fn illegal_range_pattern() {
+    assert_errors! {
+        r#"pub fn main() { match 1 { 1..1 => 0 } }"#,
+        span!(26, 30), EmptyPatternRange
+    };
+
+    assert_errors! {
+        r#"pub fn main() { match 1 { 5..=1 => 0 } }"#,
+        span!(26, 31), EmptyPatternRange
+    };
+
+    assert_errors! {
+        r#"pub fn main() { match 1 { 1..='a' => 0 } }"#,
+        span!(26, 33), UnsupportedPatternRange
+    };
+}
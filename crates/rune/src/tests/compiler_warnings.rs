@@ -25,3 +25,19 @@ fn test_remove_variant_parens() {
         span!(20, 22), RemoveTupleCallParams { variant: span!(16, 20), .. }
     };
 }
+
+#[test]
+fn test_unused_function() {
+    assert_warnings! {
+        r#"fn unused() {} pub fn main() {}"#,
+        span!(0, 14), NotUsed { context: None, .. }
+    };
+}
+
+#[test]
+fn test_unused_function_with_underscore() {
+    let mut diagnostics = Default::default();
+    let _ = crate::tests::compile_helper(r#"fn _unused() {} pub fn main() {}"#, &mut diagnostics)
+        .expect("source should compile");
+    assert!(!diagnostics.has_warning());
+}
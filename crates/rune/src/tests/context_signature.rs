@@ -0,0 +1,104 @@
+prelude!();
+
+use crate::compile::ContextSignature;
+use crate::no_std::sync::Arc;
+use crate::Diagnostics;
+
+fn context() -> Result<Context> {
+    let mut module = Module::with_item(["app"]);
+    module.function(["add"], |a: i64, b: i64| a + b)?;
+
+    let mut context = Context::with_default_modules()?;
+    context.install(module)?;
+    Ok(context)
+}
+
+/// Export the signature of the context and read it back.
+fn signature(context: &Context) -> Result<ContextSignature> {
+    let json = serde_json::to_string(&context.signature())?;
+    Ok(serde_json::from_str(&json)?)
+}
+
+fn build(signature: &ContextSignature, source: &str) -> (Diagnostics, Option<crate::Unit>) {
+    let mut sources = Sources::new();
+    sources.insert(Source::new("entry", source));
+
+    let mut diagnostics = Diagnostics::new();
+
+    let unit = prepare(&mut sources)
+        .with_context_signature(signature)
+        .with_diagnostics(&mut diagnostics)
+        .build();
+
+    (diagnostics, unit.ok())
+}
+
+#[test]
+fn test_signature_link_check() -> Result<()> {
+    let context = context()?;
+    let signature = signature(&context)?;
+
+    let (diagnostics, unit) = build(
+        &signature,
+        r#"
+        pub fn main() {
+            let values = [1, 2];
+            println!("{}", values.len());
+            app::add(values[0], std::i64::max(values[1], 40))
+        }
+        "#,
+    );
+
+    assert!(!diagnostics.has_error());
+
+    // The unit runs with the real context.
+    let mut vm = Vm::new(Arc::new(context.runtime()), Arc::new(unit.expect("unit")));
+    let output: i64 = from_value(vm.call(["main"], ())?)?;
+    assert_eq!(output, 41);
+
+    let (diagnostics, unit) = build(&signature, "pub fn main() { app::sub(1, 2) }");
+    assert!(diagnostics.has_error());
+    assert!(unit.is_none());
+    Ok(())
+}
+
+#[test]
+fn test_signature_natives_missing() -> Result<()> {
+    let context = context()?;
+    let signature = signature(&context)?;
+
+    let (_, unit) = build(&signature, "pub fn main() { app::add(1, 2) }");
+
+    // Native functions are not available in a context constructed from a
+    // signature.
+    let context = Context::from_signature(&signature)?;
+    let mut vm = Vm::new(Arc::new(context.runtime()), Arc::new(unit.expect("unit")));
+    assert!(vm.call(["main"], ()).is_err());
+    Ok(())
+}
+
+#[test]
+fn test_signature_version() -> Result<()> {
+    let context = context()?;
+    let json = serde_json::to_string(&context.signature())?;
+    let json = json.replacen("\"version\":1", "\"version\":2", 1);
+    let signature: ContextSignature = serde_json::from_str(&json)?;
+
+    let (diagnostics, unit) = build(&signature, "pub fn main() { 42 }");
+    assert!(unit.is_none());
+
+    let message = diagnostics
+        .diagnostics()
+        .iter()
+        .flat_map(|d| match d {
+            crate::diagnostics::Diagnostic::Fatal(fatal) => Some(fatal.to_string()),
+            _ => None,
+        })
+        .collect::<Vec<_>>();
+
+    assert_eq!(
+        message,
+        ["Context signature has version 2, but only version 1 is supported"]
+    );
+    Ok(())
+}
@@ -0,0 +1,92 @@
+prelude!();
+
+#[derive(Any)]
+#[rune(item = ::app)]
+struct Counter {
+    value: i64,
+}
+
+impl Counter {
+    fn get(&self) -> i64 {
+        self.value
+    }
+}
+
+fn context() -> Result<Context> {
+    let mut module = Module::with_crate("app");
+    module.ty::<Counter>()?;
+
+    module
+        .function(["add"], |a: i64, b: i64| a + b)?
+        .docs(["Add two numbers."])
+        .deprecated("Use `+` instead");
+
+    module.associated_function("get", Counter::get)?;
+    module.field_function(Protocol::GET, "value", |c: &Counter| c.value)?;
+    module.constant(["LIMIT"], 10i64)?;
+
+    let mut context = Context::with_default_modules()?;
+    context.install(module)?;
+    Ok(context)
+}
+
+#[test]
+fn test_declaration_stubs() -> Result<()> {
+    let context = context()?;
+    let declarations = crate::doc::declarations(&context, &[])?;
+    let stubs = declarations.to_stubs()?;
+
+    let expected = [
+        "pub mod app {",
+        "    pub const LIMIT = 10;",
+        "    /// Add two numbers.",
+        "    #[deprecated = \"Use `+` instead\"]",
+        "    pub fn add(value: ::std::i64, value1: ::std::i64) -> ::std::i64;",
+        "    pub struct Counter;",
+        "    impl Counter {",
+        "        pub fn get(self) -> ::std::i64;",
+        "        #[protocol(get, field = \"value\")]",
+        "        pub fn get_value(self) -> ::std::i64;",
+    ];
+
+    for line in expected {
+        assert!(
+            stubs.lines().any(|l| l == line),
+            "missing `{line}` in:\n{stubs}"
+        );
+    }
+
+    Ok(())
+}
+
+#[test]
+fn test_declaration_json() -> Result<()> {
+    let context = context()?;
+    let declarations = crate::doc::declarations(&context, &[])?;
+    let json: serde_json::Value = serde_json::from_str(&declarations.to_json()?)?;
+
+    assert_eq!(json["version"], 1);
+
+    let module = json["modules"]
+        .as_array()
+        .and_then(|modules| modules.iter().find(|m| m["item"] == "::app"))
+        .expect("missing module");
+
+    let add = &module["functions"][0];
+    assert_eq!(add["item"], "::app::add");
+    assert_eq!(add["kind"], "function");
+    assert_eq!(add["deprecated"], "Use `+` instead");
+    assert_eq!(add["args"][1]["name"], "value1");
+    assert_eq!(add["args"][1]["type"], "::std::i64");
+    assert_eq!(add["return_type"], "::std::i64");
+
+    let counter = &module["types"][0];
+    assert_eq!(counter["item"], "::app::Counter");
+
+    let get = &counter["functions"][0];
+    assert_eq!(get["item"], "::app::Counter::get");
+    assert_eq!(get["kind"], "associated");
+    assert_eq!(get["associated"]["type"], "instance");
+    assert_eq!(get["args"][0]["name"], "self");
+    Ok(())
+}
@@ -0,0 +1,270 @@
+prelude!();
+
+use crate::no_std::sync::Arc;
+use crate::to_value;
+
+use ErrorKind::*;
+
+#[test]
+fn default_values() {
+    let out: (i64, i64, i64) = rune! {
+        const BASE = 1000;
+
+        fn connect(host, port = 8080, timeout = BASE + 10) {
+            (host, port, timeout)
+        }
+
+        pub fn main() {
+            let (_, a, b) = connect("localhost");
+            let (_, c, _) = connect("localhost", 9000);
+            (a, b, c)
+        }
+    };
+    assert_eq!(out, (8080, 1010, 9000));
+
+    let out: (i64, i64) = rune! {
+        struct Server;
+
+        impl Server {
+            fn listen(self, port = 80) {
+                port
+            }
+        }
+
+        pub fn main() {
+            let server = Server;
+            (server.listen(), Server::listen(server))
+        }
+    };
+    assert_eq!(out, (80, 80));
+
+    let out: i64 = rune! {
+        fn add(a, b = 2) {
+            a + b
+        }
+
+        pub fn main() {
+            let add = add;
+            add(40)
+        }
+    };
+    assert_eq!(out, 42);
+}
+
+#[test]
+fn named_arguments() {
+    let out: (i64, i64, bool) = rune! {
+        fn connect(host, port = 8080, secure = false) {
+            (host, port, secure)
+        }
+
+        pub fn main() {
+            let (_, port, _) = connect("localhost", port: 9000);
+            let (_, _, secure) = connect(secure: true, host: "localhost");
+            let (host, _, _) = connect(port: 1, host: 2);
+            (host, port, secure)
+        }
+    };
+    assert_eq!(out, (2, 9000, true));
+
+    // Arguments are evaluated in the order they are passed.
+    let out: Vec<i64> = rune! {
+        fn sub(a, b) {
+            a - b
+        }
+
+        pub fn main() {
+            let order = [];
+            let value = sub(b: { order.push(1); 1 }, a: { order.push(2); 10 });
+            order.push(value);
+            order
+        }
+    };
+    assert_eq!(out, [1, 2, 9]);
+
+    let out: (i64, i64, i64) = rune! {
+        struct Point { x, y }
+
+        impl Point {
+            fn translate(self, dx = 0, dy = 0) {
+                Point { x: self.x + dx, y: self.y + dy }
+            }
+        }
+
+        fn scale(value, by = 2) {
+            value * by
+        }
+
+        pub fn main() {
+            let point = Point { x: 1, y: 2 }.translate(dy: 10);
+            let scale = scale;
+            let sub = |a, b| a - b;
+            (point.y, scale(4, by: 3), sub(b: 1, a: 10))
+        }
+    };
+    assert_eq!(out, (12, 12, 9));
+}
+
+#[test]
+fn argument_errors() {
+    assert_errors! {
+        r#"fn f(a = 1, b) { a + b }"#,
+        span!(12, 13), ArgumentDefaultOrder
+    };
+
+    assert_errors! {
+        r#"pub fn main() { let f = |a, b = 1| a + b; }"#,
+        span!(30, 31), UnsupportedArgumentDefault
+    };
+
+    assert_errors! {
+        r#"fn f(a, b) { a + b } pub fn main() { f(a: 1, 2) }"#,
+        span!(45, 46), NamedArgumentOrder
+    };
+
+    assert_errors! {
+        r#"fn f(a, b) { a + b } pub fn main() { f(a: 1, a: 2) }"#,
+        span!(45, 46), DuplicateArgument { name } => {
+            assert_eq!(&*name, "a");
+        }
+    };
+
+    assert_errors! {
+        r#"fn f(a, b) { a + b } pub fn main() { f(1, a: 2) }"#,
+        span!(42, 43), DuplicateArgument { name } => {
+            assert_eq!(&*name, "a");
+        }
+    };
+
+    assert_errors! {
+        r#"fn f(a, b) { a + b } pub fn main() { f(1, c: 2) }"#,
+        span!(42, 43), NoSuchArgument { name } => {
+            assert_eq!(&*name, "c");
+        }
+    };
+
+    assert_errors! {
+        r#"fn f(a, b, c = 3) { a + b + c } pub fn main() { f(a: 1, c: 2) }"#,
+        span!(48, 61), MissingArgument { name } => {
+            assert_eq!(&*name, "b");
+        }
+    };
+
+    assert_errors! {
+        r#"pub fn main() { std::i64::max(a: 1, b: 2) }"#,
+        span!(30, 31), UnsupportedNamedArguments
+    };
+}
+
+#[test]
+fn runtime_argument_errors() {
+    assert_vm_error!(
+        r#"
+        fn f(a, b = 2) { a + b }
+
+        pub fn main() {
+            let f = f;
+            f(c: 1)
+        }
+        "#,
+        VmErrorKind::NoSuchArgument { name } => {
+            assert_eq!(name, "c");
+        }
+    );
+
+    assert_vm_error!(
+        r#"
+        fn f(a, b = 2) { a + b }
+
+        pub fn main() {
+            let f = f;
+            f(b: 1)
+        }
+        "#,
+        VmErrorKind::MissingArgument { name } => {
+            assert_eq!(name, "a");
+        }
+    );
+
+    assert_vm_error!(
+        r#"
+        fn f(a, b = 2) { a + b }
+
+        pub fn main() {
+            let f = f;
+            f(1, a: 1)
+        }
+        "#,
+        VmErrorKind::DuplicateArgument { name } => {
+            assert_eq!(name, "a");
+        }
+    );
+
+    assert_vm_error!(
+        r#"
+        pub fn main() {
+            let max = std::i64::max;
+            max(a: 1, b: 2)
+        }
+        "#,
+        VmErrorKind::UnsupportedNamedArguments => {}
+    );
+
+    assert_vm_error!(
+        r#"
+        fn f(a, b = 2) { a + b }
+
+        pub fn main() {
+            let f = f;
+            f()
+        }
+        "#,
+        VmErrorKind::MissingArgument { name } => {
+            assert_eq!(name, "a");
+        }
+    );
+}
+
+#[test]
+fn host_named_arguments() -> Result<()> {
+    let mut sources = sources! {
+        entry => {
+            pub fn connect(host, port = 8080, secure = false) {
+                (host, port, secure)
+            }
+        }
+    };
+
+    let unit = prepare(&mut sources).build()?;
+    let mut vm = Vm::without_runtime(Arc::new(unit));
+
+    let output = vm.call(["connect"], ("localhost",))?;
+    let output: (String, i64, bool) = from_value(output)?;
+    assert_eq!(output, (String::from("localhost"), 8080, false));
+
+    let output = vm.call_named(["connect"], ("localhost",), [("secure", true)])?;
+    let output: (String, i64, bool) = from_value(output)?;
+    assert_eq!(output, (String::from("localhost"), 8080, true));
+
+    let output = vm.call_named(
+        ["connect"],
+        (),
+        [
+            ("port", to_value(9000)?),
+            ("host", to_value("example.com")?),
+        ],
+    )?;
+    let output: (String, i64, bool) = from_value(output)?;
+    assert_eq!(output, (String::from("example.com"), 9000, false));
+
+    let error = vm
+        .call_named(["connect"], (), [("port", 9000)])
+        .unwrap_err();
+
+    assert!(matches!(
+        error.into_kind(),
+        VmErrorKind::MissingArgument { name } if name == "host"
+    ));
+
+    Ok(())
+}
@@ -0,0 +1,178 @@
+prelude!();
+
+use ErrorKind::*;
+
+#[test]
+fn test_trait_default_functions() {
+    let out: (String, String) = rune! {
+        trait Shape {
+            fn area(self);
+
+            fn name(self) {
+                "shape"
+            }
+
+            fn describe(self) {
+                format!("{} {}", self.name(), self.area())
+            }
+        }
+
+        struct Square { side }
+        struct Circle { r }
+
+        impl Shape for Square {
+            fn area(self) {
+                self.side * self.side
+            }
+
+            fn name(self) {
+                "square"
+            }
+        }
+
+        impl Shape for Circle {
+            fn area(self) {
+                3 * self.r * self.r
+            }
+        }
+
+        pub fn main() {
+            (Square { side: 2 }.describe(), Circle { r: 1 }.describe())
+        }
+    };
+
+    assert_eq!(out, (String::from("square 4"), String::from("shape 3")));
+}
+
+#[test]
+fn test_trait_native_type() {
+    let out: (String, i64) = rune! {
+        trait Shout {
+            fn shout(self) {
+                self + "!"
+            }
+
+            fn len(self);
+        }
+
+        impl Shout for String {}
+
+        pub fn main() {
+            let s = String::from("hello");
+            (s.shout(), s.len())
+        }
+    };
+
+    assert_eq!(out, (String::from("hello!"), 5));
+}
+
+#[test]
+fn test_trait_in_module() {
+    let out: i64 = rune! {
+        mod shapes {
+            pub trait Area {
+                async fn area(self);
+
+                fn double(self) {
+                    2
+                }
+            }
+        }
+
+        struct Square { side }
+
+        impl shapes::Area for Square {
+            async fn area(self) {
+                self.side * self.side
+            }
+        }
+
+        pub async fn main() {
+            let square = Square { side: 3 };
+            square.area().await * square.double()
+        }
+    };
+
+    assert_eq!(out, 18);
+}
+
+#[test]
+fn test_trait_default_in_trait_module() {
+    let out: i64 = rune! {
+        mod a {
+            fn helper() {
+                40
+            }
+
+            pub trait Answer {
+                fn answer(self) {
+                    helper() + self.extra()
+                }
+
+                fn extra(self);
+            }
+        }
+
+        fn helper() {
+            0
+        }
+
+        struct Foo;
+
+        impl a::Answer for Foo {
+            fn extra(self) {
+                helper() + 2
+            }
+        }
+
+        pub fn main() {
+            Foo.answer()
+        }
+    };
+
+    assert_eq!(out, 42);
+}
+
+#[test]
+fn test_trait_errors() {
+    assert_errors! {
+        r#"
+        trait Foo { fn foo(self); }
+        struct Bar;
+        impl Foo for Bar {}
+        "#,
+        span!(78, 81), MissingTraitFunction { name, .. } => {
+            assert_eq!(&*name, "foo");
+        }
+    };
+
+    assert_errors! {
+        r#"
+        trait Foo { fn foo(self) {} }
+        struct Bar;
+        impl Foo for Bar { fn bar(self) {} }
+        "#,
+        span!(89, 92), NotTraitFunction { name, .. } => {
+            assert_eq!(&*name, "bar");
+        }
+    };
+
+    assert_errors! {
+        r#"
+        trait Foo { fn foo(self, a); }
+        struct Bar;
+        impl Foo for Bar { fn foo(self) {} }
+        "#,
+        span!(93, 99), TraitFunctionArguments { expected: 2, actual: 1, .. }
+    };
+
+    assert_errors! {
+        r#"trait Foo { fn foo(self); fn foo(self) {} }"#,
+        span!(29, 32), TraitFunctionConflict { .. }
+    };
+
+    assert_errors! {
+        r#"struct Bar; impl Bar for Bar {}"#,
+        span!(17, 20), ExpectedMeta { expected: "trait", .. }
+    };
+}
@@ -0,0 +1,159 @@
+prelude!();
+
+use ErrorKind::*;
+
+#[test]
+fn annotations_are_optional() {
+    let out: i64 = rune! {
+        fn add(a: i64, b) -> i64 {
+            a + b
+        }
+
+        fn untyped(a, b) {
+            a + b
+        }
+
+        pub fn main() {
+            let a: i64 = add(1, 2);
+            let b = untyped(3, 4);
+            a + b
+        }
+    };
+    assert_eq!(out, 10);
+
+    let out: String = rune! {
+        struct Person {
+            name: String,
+            age,
+        }
+
+        impl Person {
+            fn greeting(self) -> String {
+                "Hello " + self.name
+            }
+        }
+
+        pub fn main() {
+            let person = Person { name: "John", age: "unknown" };
+            let greeting: String = person.greeting();
+            greeting
+        }
+    };
+    assert_eq!(out, "Hello John");
+
+    let out: i64 = rune! {
+        pub fn main() {
+            let (a, b): (i64, i64) = (1, 2);
+            let s: String = "abc";
+            let n: i64 = s.len();
+            a + b + n
+        }
+    };
+    assert_eq!(out, 6);
+
+    let out: i64 = rune! {
+        fn count() -> i64 {
+            yield 1;
+            yield 2;
+        }
+
+        pub async fn main() {
+            let add = |a: i64, b: i64| a + b;
+            let value: i64 = async { 40 }.await;
+            let sum: i64 = add(value, 2);
+            sum
+        }
+    };
+    assert_eq!(out, 42);
+}
+
+#[test]
+fn type_mismatch() {
+    assert_errors! {
+        r#"pub fn main() { let s: String = 42; }"#,
+        span!(32, 34), TypeMismatch { expected, actual } => {
+            assert_eq!(&*expected, "::std::string::String");
+            assert_eq!(&*actual, "::std::i64");
+        }
+    };
+
+    assert_errors! {
+        r#"fn add(a: i64, b: i64) { a + b } pub fn main() { add(1, "two") }"#,
+        span!(56, 61), TypeMismatch { expected, actual } => {
+            assert_eq!(&*expected, "::std::i64");
+            assert_eq!(&*actual, "::std::string::String");
+        }
+    };
+
+    assert_errors! {
+        r#"pub fn main() { let x: i64 = 1; x = "a"; }"#,
+        span!(36, 39), TypeMismatch { expected, actual } => {
+            assert_eq!(&*expected, "::std::i64");
+            assert_eq!(&*actual, "::std::string::String");
+        }
+    };
+
+    assert_errors! {
+        r#"struct Point { x: i64, y } pub fn main() { Point { x: 1.0, y: 2 } }"#,
+        span!(54, 57), TypeMismatch { expected, actual } => {
+            assert_eq!(&*expected, "::std::i64");
+            assert_eq!(&*actual, "::std::f64");
+        }
+    };
+}
+
+#[test]
+fn return_type_mismatch() {
+    assert_errors! {
+        r#"fn f() -> String { 42 }"#,
+        span!(19, 21), TypeMismatch { expected, actual } => {
+            assert_eq!(&*expected, "::std::string::String");
+            assert_eq!(&*actual, "::std::i64");
+        }
+    };
+
+    assert_errors! {
+        r#"fn f(a: bool) -> String { if a { return 1.0; } "yes" }"#,
+        span!(40, 43), TypeMismatch { expected, actual } => {
+            assert_eq!(&*expected, "::std::string::String");
+            assert_eq!(&*actual, "::std::f64");
+        }
+    };
+
+    assert_errors! {
+        r#"fn len() -> i64 { 1 } pub fn main() { let b: bool = len(); }"#,
+        span!(52, 57), TypeMismatch { expected, actual } => {
+            assert_eq!(&*expected, "::std::bool");
+            assert_eq!(&*actual, "::std::i64");
+        }
+    };
+}
+
+#[test]
+fn native_type_mismatch() {
+    assert_errors! {
+        r#"pub fn main() { let s: String = "a"; let b: bool = s.len(); }"#,
+        span!(51, 58), TypeMismatch { expected, actual } => {
+            assert_eq!(&*expected, "::std::bool");
+            assert_eq!(&*actual, "::std::i64");
+        }
+    };
+
+    assert_errors! {
+        r#"pub fn main() { let s: String = "a"; std::i64::max(s, 1) }"#,
+        span!(51, 52), TypeMismatch { expected, actual } => {
+            assert_eq!(&*expected, "::std::i64");
+            assert_eq!(&*actual, "::std::string::String");
+        }
+    };
+}
+
+#[test]
+fn missing_type() {
+    assert_errors! {
+        r#"fn f(a: Missing) { a } pub fn main() { f(1) }"#,
+        span!(8, 15), MissingItem { item } => {
+            assert_eq!(item.to_string(), "Missing");
+        }
+    };
+}
@@ -0,0 +1,191 @@
+prelude!();
+
+use crate::hash;
+use crate::no_std::sync::Arc;
+use crate::runtime::unit::{ArrayUnit, UnitEncoder};
+use crate::runtime::{Call, Inst, RuntimeContext, Unit, UnitFn};
+
+/// Construct a unit with a single function `main` consisting of the given
+/// instructions.
+fn single_function(instructions: impl IntoIterator<Item = Inst>) -> Result<Unit> {
+    let mut storage = ArrayUnit::default();
+
+    for inst in instructions {
+        storage.encode(inst)?;
+    }
+
+    let mut functions = hash::Map::default();
+
+    functions.insert(
+        Hash::type_hash(["main"]),
+        UnitFn::Offset {
+            offset: 0,
+            call: Call::Immediate,
+            args: 1,
+        },
+    );
+
+    Ok(Unit::new(
+        storage,
+        functions,
+        Vec::new(),
+        Vec::new(),
+        Vec::new(),
+        hash::Map::default(),
+        hash::Map::default(),
+        None,
+        hash::Map::default(),
+        hash::Map::default(),
+    ))
+}
+
+fn compile(source: &str) -> Result<(Context, Unit)> {
+    let context = Context::with_default_modules()?;
+
+    let mut sources = Sources::new();
+    sources.insert(Source::new("entry", source));
+
+    let unit = prepare(&mut sources).with_context(&context).build()?;
+    Ok((context, unit))
+}
+
+#[test]
+fn test_verify_compiled() -> Result<()> {
+    let (context, unit) = compile(
+        r#"
+        struct Point { x, y }
+
+        pub fn main(n) {
+            let p = Point { x: n, y: "hello" };
+            let out = [];
+
+            for i in 0..n {
+                out.push(match i { 0 => p.x, _ => i });
+            }
+
+            (out, p.y, b"bytes", #{ a: 1 })
+        }
+        "#,
+    )?;
+
+    unit.verify(&context.runtime())?;
+    Ok(())
+}
+
+#[test]
+fn test_verify_bad_jump() -> Result<()> {
+    let unit = single_function([Inst::Jump { jump: 2 }, Inst::ReturnUnit])?;
+    let error = unit.verify(&RuntimeContext::default()).unwrap_err();
+    assert_eq!(error.to_string(), "Bad jump 2 at 0");
+    Ok(())
+}
+
+#[test]
+fn test_verify_bad_stack_offset() -> Result<()> {
+    let unit = single_function([Inst::Copy { offset: 0 }, Inst::ReturnUnit])?;
+    unit.verify(&RuntimeContext::default())?;
+
+    let unit = single_function([Inst::Copy { offset: 100 }, Inst::ReturnUnit])?;
+    let error = unit.verify(&RuntimeContext::default()).unwrap_err();
+
+    assert_eq!(
+        error.to_string(),
+        "Stack offset 100 at 0 is out of bounds, the function uses at most 5 stack slots"
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_verify_missing_constants() -> Result<()> {
+    let unit = single_function([Inst::String { slot: 0 }, Inst::ReturnUnit])?;
+    let error = unit.verify(&RuntimeContext::default()).unwrap_err();
+    assert_eq!(error.to_string(), "Missing static string for slot 0 at 0");
+
+    let unit = single_function([Inst::Object { slot: 3 }, Inst::ReturnUnit])?;
+    let error = unit.verify(&RuntimeContext::default()).unwrap_err();
+    assert_eq!(
+        error.to_string(),
+        "Missing static object keys for slot 3 at 0"
+    );
+    Ok(())
+}
+
+#[test]
+fn test_verify_missing_function() -> Result<()> {
+    let (context, unit) = compile("pub fn main() { std::i64::max(1, 2) }")?;
+    unit.verify(&context.runtime())?;
+
+    let error = unit.verify(&RuntimeContext::default()).unwrap_err();
+    assert!(error.to_string().starts_with("Missing function"));
+
+    let unit = single_function([
+        Inst::Call {
+            hash: Hash::type_hash(["missing"]),
+            args: 0,
+        },
+        Inst::ReturnUnit,
+    ])?;
+
+    assert!(unit.verify(&RuntimeContext::default()).is_err());
+    Ok(())
+}
+
+#[test]
+#[cfg(feature = "unit-format")]
+fn test_format_roundtrip() -> Result<()> {
+    use crate::runtime::unit::{UnitHeader, FORMAT_VERSION};
+
+    let (context, unit) = compile("pub fn main(n) { std::i64::max(n, 2) + 40 }")?;
+    let bytes = unit.encode()?;
+
+    let header = UnitHeader::read(&bytes)?;
+    assert_eq!(header.version, FORMAT_VERSION);
+    assert_eq!(header.requirements, unit.requirements()?);
+
+    let runtime = Arc::new(context.runtime());
+    let unit = Unit::decode(&bytes, &runtime)?;
+
+    let mut vm = Vm::new(runtime, Arc::new(unit));
+    let output: i64 = from_value(vm.call(["main"], (1i64,))?)?;
+    assert_eq!(output, 42);
+
+    // The requirements only depend on the natives which are used.
+    let (_, other) = compile("pub fn main() { std::i64::max(3, 4) }")?;
+    assert_eq!(other.requirements()?, header.requirements);
+
+    let (_, other) = compile("pub fn main() { std::i64::min(3, 4) }")?;
+    assert_ne!(other.requirements()?, header.requirements);
+    Ok(())
+}
+
+#[test]
+#[cfg(feature = "unit-format")]
+fn test_format_rejected() -> Result<()> {
+    let (context, unit) = compile("pub fn main() { std::i64::max(1, 2) }")?;
+    let runtime = context.runtime();
+    let bytes = unit.encode()?;
+
+    let error = <Unit>::decode(&bytes[..10], &runtime).unwrap_err();
+    assert_eq!(error.to_string(), "Unit is missing a header");
+
+    let mut bad = bytes.clone();
+    bad[0] = b'X';
+    let error = <Unit>::decode(&bad, &runtime).unwrap_err();
+    assert_eq!(error.to_string(), "Not an encoded unit");
+
+    let mut bad = bytes.clone();
+    bad[4] = 0xff;
+    let error = <Unit>::decode(&bad, &runtime).unwrap_err();
+    assert!(error.to_string().starts_with("Unit has format version 255"));
+
+    let mut bad = bytes.clone();
+    bad[12] ^= 0xff;
+    let error = <Unit>::decode(&bad, &runtime).unwrap_err();
+    assert!(error.to_string().starts_with("Unit requirements"));
+
+    // The unit requires a native function which isn't available.
+    let error = <Unit>::decode(&bytes, &RuntimeContext::default()).unwrap_err();
+    assert!(error.to_string().starts_with("Missing function"));
+    Ok(())
+}
@@ -0,0 +1,162 @@
+prelude!();
+
+use crate::no_std::sync::Arc;
+use crate::runtime::coverage::{Coverage, CoverageSummary};
+
+const SOURCE: &str = r#"pub fn main(n) {
+    let out = 0;
+
+    for i in 0..n {
+        out += i;
+    }
+
+    match n {
+        0 => "none",
+        1 => "one",
+        _ => "many",
+    }
+}
+"#;
+
+fn run(inputs: &[i64]) -> Result<Coverage> {
+    let context = Context::with_default_modules()?;
+    let runtime = Arc::new(context.runtime());
+
+    let mut sources = Sources::new();
+    sources.insert(Source::new("test", SOURCE));
+
+    let unit = Arc::new(prepare(&mut sources).with_context(&context).build()?);
+
+    let mut coverage = Coverage::new();
+    coverage.insert(unit.clone(), &sources);
+
+    let mut vm = Vm::new(runtime, unit);
+
+    for &n in inputs {
+        vm.execute(["main"], (n,))?
+            .complete_with_coverage(&mut coverage)
+            .into_result()?;
+    }
+
+    Ok(coverage)
+}
+
+fn lcov(coverage: &Coverage) -> Result<String> {
+    let mut out = Vec::new();
+    coverage.write_lcov(&mut out)?;
+    Ok(String::from_utf8(out)?)
+}
+
+#[test]
+fn test_coverage_lines() -> Result<()> {
+    let coverage = run(&[0])?;
+    let lcov = lcov(&coverage)?;
+
+    assert!(lcov.starts_with("TN:\nSF:test\n"));
+    assert!(lcov.contains("DA:4,1\n"));
+    assert!(lcov.contains("DA:5,0\n"));
+    assert!(lcov.contains("DA:9,1\n"));
+    assert!(lcov.contains("DA:10,0\n"));
+    assert!(lcov.contains("DA:11,0\n"));
+    assert!(lcov.ends_with("end_of_record\n"));
+
+    let summary = coverage.summary();
+    assert!(summary.lines_hit < summary.lines_found);
+    Ok(())
+}
+
+#[test]
+fn test_coverage_branches() -> Result<()> {
+    let partial = run(&[0])?.summary();
+    let full = run(&[0, 1, 2])?.summary();
+
+    assert_eq!(partial.branches_found, full.branches_found);
+    assert!(partial.branches_hit < full.branches_hit);
+    assert_eq!(full.branches_hit, full.branches_found);
+    assert_eq!(full.lines_hit, full.lines_found);
+    assert_eq!(full.line_percent(), 100.0);
+    Ok(())
+}
+
+#[test]
+fn test_coverage_html() -> Result<()> {
+    let coverage = run(&[1])?;
+
+    let mut out = Vec::new();
+    coverage.write_html(&mut out)?;
+    let html = String::from_utf8(out)?;
+
+    assert!(html.contains("<tr class=\"hit\"><td class=\"line\">10</td>"));
+    assert!(html.contains("<tr class=\"miss\"><td class=\"line\">11</td>"));
+    assert!(html.contains("1 =&gt; &quot;one&quot;,"));
+    Ok(())
+}
+
+#[test]
+fn test_coverage_ignores_other_units() -> Result<()> {
+    let context = Context::with_default_modules()?;
+    let runtime = Arc::new(context.runtime());
+
+    let mut sources = sources! {
+        entry => {
+            pub fn main() {
+                1 + 2
+            }
+        }
+    };
+
+    let unit = Arc::new(prepare(&mut sources).with_context(&context).build()?);
+
+    let mut coverage = Coverage::new();
+
+    let mut vm = Vm::new(runtime, unit);
+    let value = vm
+        .execute(["main"], ())?
+        .complete_with_coverage(&mut coverage)
+        .into_result()?;
+
+    assert_eq!(from_value::<i64>(value)?, 3);
+    assert_eq!(coverage.summary(), CoverageSummary::default());
+    Ok(())
+}
+
+#[test]
+fn test_coverage_closure() -> Result<()> {
+    let context = Context::with_default_modules()?;
+    let runtime = Arc::new(context.runtime());
+
+    let mut sources = Sources::new();
+    sources.insert(Source::new(
+        "test",
+        r#"fn inc(n) {
+    n + 1
+}
+
+pub fn main() {
+    [1, 2].iter().map(|n| {
+        inc(n)
+    }).collect::<Vec>()
+}
+"#,
+    ));
+
+    let unit = Arc::new(prepare(&mut sources).with_context(&context).build()?);
+
+    let mut coverage = Coverage::new();
+    coverage.insert(unit.clone(), &sources);
+
+    let mut vm = Vm::new(runtime, unit);
+    let value = vm
+        .execute(["main"], ())?
+        .complete_with_coverage(&mut coverage)
+        .into_result()?;
+
+    assert_eq!(from_value::<Vec<i64>>(value)?, [2, 3]);
+    let lcov = lcov(&coverage)?;
+    assert!(lcov.contains("DA:2,2\n"));
+    assert!(lcov.contains("DA:7,2\n"));
+
+    let summary = coverage.summary();
+    assert_eq!(summary.lines_hit, summary.lines_found);
+    Ok(())
+}
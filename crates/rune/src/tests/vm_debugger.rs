@@ -0,0 +1,205 @@
+prelude!();
+
+use crate::no_std::sync::Arc;
+use crate::runtime::debugger::{DebugState, Debugger, Resume};
+use crate::{SourceId, Unit};
+
+const SOURCE: &str = r#"fn add(a, b) {
+    let c = a + b;
+    c
+}
+
+pub fn main() {
+    let x = 1;
+    let y = add(x, 2);
+    let z = x + y;
+    z * 2
+}
+"#;
+
+fn setup() -> Result<(Context, Arc<Unit>, Debugger, SourceId)> {
+    let context = Context::with_default_modules()?;
+
+    let mut sources = Sources::new();
+    let source_id = sources.insert(Source::memory(SOURCE));
+
+    let unit = Arc::new(prepare(&mut sources).with_context(&context).build()?);
+    let debugger = Debugger::new(unit.clone(), &sources);
+    Ok((context, unit, debugger, source_id))
+}
+
+/// The zero-based line of the innermost frame.
+fn line(debugger: &Debugger, vm: &Vm) -> Option<usize> {
+    Some(debugger.location(vm.ip())?.line)
+}
+
+fn locals(debugger: &Debugger, vm: &Vm, frame: usize) -> Vec<(String, i64)> {
+    let frames = debugger.frames(vm);
+
+    debugger
+        .locals(vm, &frames[frame])
+        .into_iter()
+        .map(|(name, value)| (name.to_owned(), from_value(value).unwrap()))
+        .collect()
+}
+
+#[test]
+fn test_breakpoints() -> Result<()> {
+    let (context, unit, mut debugger, source_id) = setup()?;
+
+    // Line 4 is empty, so the breakpoint is moved to the next line with
+    // code in it.
+    let placed = debugger.set_breakpoints(source_id, [1, 4, 8, 100]);
+    assert_eq!(placed, [Some(1), Some(6), Some(8), None]);
+
+    let mut vm = Vm::new(Arc::new(context.runtime()), unit);
+    let mut execution = vm.execute(["main"], ())?;
+
+    let state = execution.debug(&debugger, Resume::Continue).into_result()?;
+    assert!(matches!(state, DebugState::Breakpoint));
+    assert_eq!(line(&debugger, execution.vm()), Some(1));
+    assert_eq!(debugger.frames(execution.vm()).len(), 2);
+
+    let state = execution.debug(&debugger, Resume::Continue).into_result()?;
+    assert!(matches!(state, DebugState::Breakpoint));
+    assert_eq!(line(&debugger, execution.vm()), Some(8));
+
+    assert_eq!(
+        locals(&debugger, execution.vm(), 0),
+        [(String::from("x"), 1), (String::from("y"), 3)]
+    );
+
+    let state = execution.debug(&debugger, Resume::Continue).into_result()?;
+    let DebugState::Complete(value) = state else {
+        panic!("expected completion, got {state:?}");
+    };
+
+    assert_eq!(from_value::<i64>(value)?, 8);
+    Ok(())
+}
+
+#[test]
+fn test_stepping() -> Result<()> {
+    let (context, unit, debugger, _) = setup()?;
+
+    let mut vm = Vm::new(Arc::new(context.runtime()), unit);
+    let mut execution = vm.execute(["main"], ())?;
+    assert_eq!(line(&debugger, execution.vm()), Some(6));
+
+    let state = execution.debug(&debugger, Resume::StepOver).into_result()?;
+    assert!(matches!(state, DebugState::Step));
+    assert_eq!(line(&debugger, execution.vm()), Some(7));
+
+    // Step into `add` until we reach its body.
+    while debugger.frames(execution.vm()).len() == 1 || line(&debugger, execution.vm()) != Some(1) {
+        execution.debug(&debugger, Resume::StepInto).into_result()?;
+    }
+
+    assert_eq!(
+        locals(&debugger, execution.vm(), 0),
+        [(String::from("a"), 1), (String::from("b"), 2)]
+    );
+    assert_eq!(
+        locals(&debugger, execution.vm(), 1),
+        [(String::from("x"), 1)]
+    );
+
+    let state = execution.debug(&debugger, Resume::StepOut).into_result()?;
+    assert!(matches!(state, DebugState::Step));
+    assert_eq!(debugger.frames(execution.vm()).len(), 1);
+
+    let state = execution.debug(&debugger, Resume::StepOver).into_result()?;
+    assert!(matches!(state, DebugState::Step));
+    assert_eq!(line(&debugger, execution.vm()), Some(9));
+
+    assert_eq!(
+        locals(&debugger, execution.vm(), 0),
+        [
+            (String::from("x"), 1),
+            (String::from("y"), 3),
+            (String::from("z"), 4)
+        ]
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_evaluate() -> Result<()> {
+    let (context, unit, mut debugger, source_id) = setup()?;
+    debugger.set_breakpoints(source_id, [9]);
+
+    let mut vm = Vm::new(Arc::new(context.runtime()), unit);
+    let mut execution = vm.execute(["main"], ())?;
+
+    let state = execution.debug(&debugger, Resume::Continue).into_result()?;
+    assert!(matches!(state, DebugState::Breakpoint));
+
+    let frames = debugger.frames(execution.vm());
+
+    let value = debugger.evaluate(&context, execution.vm(), &frames[0], "add(x, z) * y")?;
+    assert_eq!(from_value::<i64>(value)?, 15);
+
+    // The second evaluation reuses the unit compiled by the first.
+    let value = debugger.evaluate(&context, execution.vm(), &frames[0], "add(x, z) * y")?;
+    assert_eq!(from_value::<i64>(value)?, 15);
+
+    assert!(debugger
+        .evaluate(&context, execution.vm(), &frames[0], "missing + 1")
+        .is_err());
+
+    // Evaluating more expressions than are cached evicts the least recently
+    // used ones, which are compiled again when evaluated.
+    for n in 0..64 {
+        let value = debugger.evaluate(&context, execution.vm(), &frames[0], &format!("x + {n}"))?;
+        assert_eq!(from_value::<i64>(value)?, 1 + n);
+    }
+
+    let value = debugger.evaluate(&context, execution.vm(), &frames[0], "add(x, z) * y")?;
+    assert_eq!(from_value::<i64>(value)?, 15);
+
+    Ok(())
+}
+
+#[test]
+fn test_native_closure() -> Result<()> {
+    let context = Context::with_default_modules()?;
+
+    let mut sources = Sources::new();
+    let source_id = sources.insert(Source::memory(
+        r#"pub fn main() {
+    let values = [1, 2].iter().map(|n| n * 2).collect::<Vec>();
+    let total = values[0] + values[1];
+    total
+}
+"#,
+    ));
+
+    let unit = Arc::new(prepare(&mut sources).with_context(&context).build()?);
+    let mut debugger = Debugger::new(unit.clone(), &sources);
+    debugger.set_breakpoints(source_id, [3]);
+
+    let mut vm = Vm::new(Arc::new(context.runtime()), unit);
+    let mut execution = vm.execute(["main"], ())?;
+    assert_eq!(line(&debugger, execution.vm()), Some(1));
+
+    // Stepping over the line runs the closure to completion.
+    let state = execution.debug(&debugger, Resume::StepOver).into_result()?;
+    assert!(matches!(state, DebugState::Step));
+    assert_eq!(line(&debugger, execution.vm()), Some(2));
+
+    let state = execution.debug(&debugger, Resume::Continue).into_result()?;
+    assert!(matches!(state, DebugState::Breakpoint));
+    assert_eq!(line(&debugger, execution.vm()), Some(3));
+
+    let frames = debugger.frames(execution.vm());
+    let value = debugger.evaluate(&context, execution.vm(), &frames[0], "values")?;
+    assert_eq!(from_value::<Vec<i64>>(value)?, [2, 4]);
+
+    let state = execution.debug(&debugger, Resume::Continue).into_result()?;
+    let DebugState::Complete(value) = state else {
+        panic!("expected execution to complete");
+    };
+    assert_eq!(from_value::<i64>(value)?, 6);
+    Ok(())
+}
@@ -0,0 +1,155 @@
+prelude!();
+
+use std::time::{Duration, Instant};
+
+use crate::no_std::sync::Arc;
+use crate::runtime::CancelToken;
+
+fn vm() -> Result<Vm> {
+    let mut sources = sources! {
+        entry => {
+            fn spin() {
+                loop {}
+            }
+
+            pub fn main() {
+                spin()
+            }
+
+            pub async fn async_main() {
+                spin()
+            }
+
+            pub fn add(a, b) {
+                a + b
+            }
+        }
+    };
+
+    let unit = prepare(&mut sources).build()?;
+    Ok(Vm::without_runtime(Arc::new(unit)))
+}
+
+#[test]
+fn test_cancelled() -> Result<()> {
+    let mut vm = vm()?;
+
+    let token = CancelToken::new();
+    token.cancel();
+
+    let mut execution = vm.execute(["main"], ())?.with_cancel_token(token);
+    let error = execution.complete().into_result().unwrap_err();
+    assert!(error.is_cancelled());
+    assert!(!error.is_deadline_exceeded());
+    Ok(())
+}
+
+#[test]
+fn test_cancelled_from_thread() -> Result<()> {
+    let mut vm = vm()?;
+
+    let token = CancelToken::new();
+
+    let handle = std::thread::spawn({
+        let token = token.clone();
+
+        move || {
+            std::thread::sleep(Duration::from_millis(10));
+            token.cancel();
+        }
+    });
+
+    let mut execution = vm.execute(["async_main"], ())?.with_cancel_token(token);
+    let error = block_on(execution.async_complete())
+        .into_result()
+        .unwrap_err();
+    assert!(error.is_cancelled());
+
+    // Execution was interrupted inside of `spin`, which is still inspectable.
+    assert_eq!(execution.vm().call_frames().len(), 1);
+    handle.join().unwrap();
+    Ok(())
+}
+
+#[test]
+fn test_deadline_exceeded() -> Result<()> {
+    let mut vm = vm()?;
+
+    let deadline = Instant::now() + Duration::from_millis(10);
+    let mut execution = vm.execute(["main"], ())?.with_deadline(deadline);
+    let error = execution.complete().into_result().unwrap_err();
+    assert!(error.is_deadline_exceeded());
+    assert!(!error.is_cancelled());
+    assert_eq!(execution.vm().call_frames().len(), 1);
+    Ok(())
+}
+
+#[test]
+fn test_not_interrupted() -> Result<()> {
+    let mut vm = vm()?;
+
+    let deadline = Instant::now() + Duration::from_secs(60);
+
+    let mut execution = vm
+        .execute(["add"], (1, 2))?
+        .with_cancel_token(CancelToken::new())
+        .with_deadline(deadline);
+
+    let value = execution.complete().into_result()?;
+    assert_eq!(from_value::<i64>(value)?, 3);
+    Ok(())
+}
+
+#[test]
+fn test_nested_cancelled() -> Result<()> {
+    let mut sources = sources! {
+        entry => {
+            pub fn main() {
+                [1].iter().map(|x| loop {}).collect::<Vec>()
+            }
+        }
+    };
+
+    let context = Context::with_default_modules()?;
+    let unit = prepare(&mut sources).with_context(&context).build()?;
+    let mut vm = Vm::new(Arc::new(context.runtime()), Arc::new(unit));
+
+    let token = CancelToken::new();
+
+    let handle = std::thread::spawn({
+        let token = token.clone();
+
+        move || {
+            std::thread::sleep(Duration::from_millis(10));
+            token.cancel();
+        }
+    });
+
+    let mut execution = vm.execute(["main"], ())?.with_cancel_token(token);
+    let error = execution.complete().into_result().unwrap_err();
+    assert!(error.is_cancelled());
+    handle.join().unwrap();
+    Ok(())
+}
+
+#[test]
+fn test_nested_deadline_exceeded() -> Result<()> {
+    let mut sources = sources! {
+        entry => {
+            pub fn main() {
+                let it = (0..1).iter().map(|x| loop {});
+                it.next()
+            }
+        }
+    };
+
+    let context = Context::with_default_modules()?;
+    let unit = prepare(&mut sources).with_context(&context).build()?;
+    let mut vm = Vm::new(Arc::new(context.runtime()), Arc::new(unit));
+
+    let deadline = Instant::now() + Duration::from_millis(10);
+    let mut execution = vm.execute(["main"], ())?.with_deadline(deadline);
+    let error = execution.complete().into_result().unwrap_err();
+    assert!(error.is_deadline_exceeded());
+    Ok(())
+}
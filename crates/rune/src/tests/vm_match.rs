@@ -121,3 +121,153 @@ fn test_struct_matching() {
     };
     assert_eq!(out, 3);
 }
+
+#[test]
+fn test_match_or_patterns() {
+    let out: (String, String, String) = rune! {
+        fn classify(c) {
+            match c {
+                'a' | 'e' | 'i' | 'o' | 'u' => "vowel",
+                ' ' | '\n' => "space",
+                _ => "other",
+            }
+        }
+
+        pub fn main() {
+            (classify('e'), classify('\n'), classify('x'))
+        }
+    };
+    assert_eq!(
+        out,
+        (
+            String::from("vowel"),
+            String::from("space"),
+            String::from("other")
+        )
+    );
+
+    let out: (i64, i64, i64) = rune! {
+        enum Shape { Circle(r), Square(s), Rect(w, h) }
+
+        fn size(shape) {
+            match shape {
+                Shape::Circle(x) | Shape::Square(x) => x,
+                Shape::Rect(w, h) => w * h,
+            }
+        }
+
+        pub fn main() {
+            (size(Shape::Circle(3)), size(Shape::Square(4)), size(Shape::Rect(2, 5)))
+        }
+    };
+    assert_eq!(out, (3, 4, 10));
+
+    let out: (i64, i64, i64, i64) = rune! {
+        fn pick(pair) {
+            match pair {
+                (a, 1 | 2) | (1 | 2, a) => a,
+                (a, b) if a == b => 0,
+                _ => -1,
+            }
+        }
+
+        pub fn main() {
+            (pick((7, 2)), pick((1, 9)), pick((5, 5)), pick((5, 6)))
+        }
+    };
+    assert_eq!(out, (7, 9, 0, -1));
+
+    let out: bool = rune! {
+        pub fn main() {
+            if let Some(1 | 3) | None = Some(3) { true } else { false }
+        }
+    };
+    assert!(out);
+}
+
+#[test]
+fn test_match_range_patterns() {
+    let out: (String, String, String, String, String) = rune! {
+        fn size(n) {
+            match n {
+                ..=-1 => "negative",
+                0 => "zero",
+                1..10 => "small",
+                10..=99 => "medium",
+                100.. => "large",
+            }
+        }
+
+        pub fn main() {
+            (size(-5), size(0), size(9), size(99), size(100))
+        }
+    };
+    assert_eq!(
+        out,
+        (
+            String::from("negative"),
+            String::from("zero"),
+            String::from("small"),
+            String::from("medium"),
+            String::from("large")
+        )
+    );
+
+    let out: (bool, bool, bool, bool) = rune! {
+        fn is_hex(b) {
+            match b {
+                b'0'..=b'9' | b'a'..=b'f' | b'A'..=b'F' => true,
+                _ => false,
+            }
+        }
+
+        pub fn main() {
+            (is_hex(b'7'), is_hex(b'c'), is_hex(b'g'), is_hex('7'))
+        }
+    };
+    assert_eq!(out, (true, true, false, false));
+
+    let out: (i64, i64, i64) = rune! {
+        fn kind(c) {
+            match c {
+                'a'..='z' => 1,
+                'A'..'[' => 2,
+                _ => 3,
+            }
+        }
+
+        pub fn main() {
+            (kind('q'), kind('Z'), kind(1))
+        }
+    };
+    assert_eq!(out, (1, 2, 3));
+
+    let out: (bool, bool, bool) = rune! {
+        fn test(value) {
+            match value {
+                (0..=5, 'a'..='c', n) => n,
+                _ => false,
+            }
+        }
+
+        pub fn main() {
+            (test((5, 'c', true)), test((6, 'a', true)), test((0, "a", true)))
+        }
+    };
+    assert_eq!(out, (true, false, false));
+
+    let out: (i64, i64, i64, i64, i64) = rune! {
+        fn kind(value) {
+            match value {
+                5..=5 | b'x'..b'y' | 'a'..='a' => 1,
+                b'\0'.. => 2,
+                _ => 3,
+            }
+        }
+
+        pub fn main() {
+            (kind(5), kind(b'x'), kind('a'), kind(b'y'), kind(6))
+        }
+    };
+    assert_eq!(out, (1, 1, 1, 2, 3));
+}
@@ -0,0 +1,97 @@
+prelude!();
+
+use crate::no_std::sync::Arc;
+
+fn vm() -> Result<Vm> {
+    let context = Context::with_default_modules()?;
+
+    let mut sources = sources! {
+        entry => {
+            pub async fn grow(n) {
+                let values = [];
+
+                for n in 0..n {
+                    values.push(n);
+                }
+
+                values.len()
+            }
+
+            pub fn churn(n) {
+                for n in 0..n {
+                    let values = [];
+
+                    for n in 0..16 {
+                        values.push(n);
+                    }
+                }
+
+                n
+            }
+
+            pub fn nested(n) {
+                let lengths = [1].iter().map(|_| {
+                    let values = [];
+
+                    for n in 0..n {
+                        values.push(n);
+                    }
+
+                    values.len()
+                });
+
+                lengths.collect::<Vec>()
+            }
+        }
+    };
+
+    let unit = prepare(&mut sources).with_context(&context).build()?;
+    Ok(Vm::new(Arc::new(context.runtime()), Arc::new(unit)))
+}
+
+#[test]
+fn test_memory_limit_exceeded() -> Result<()> {
+    let mut vm = vm()?;
+
+    let mut execution = vm.execute(["grow"], (100_000,))?.with_memory_limit(1024);
+    let error = block_on(execution.async_complete())
+        .into_result()
+        .unwrap_err();
+    assert!(error.is_memory_limit_exceeded());
+
+    // The virtual machine can still be used after the limit was exceeded.
+    let mut execution = vm.execute(["grow"], (10,))?.with_memory_limit(1024);
+    let value = block_on(execution.async_complete()).into_result()?;
+    assert_eq!(from_value::<i64>(value)?, 10);
+    Ok(())
+}
+
+#[test]
+fn test_memory_limit_released() -> Result<()> {
+    let mut vm = vm()?;
+
+    // Memory which is freed is returned to the limit, so repeatedly
+    // allocating and dropping small collections stays within it.
+    let mut execution = vm.execute(["churn"], (1000,))?.with_memory_limit(4096);
+    let value = execution.complete().into_result()?;
+    assert_eq!(from_value::<i64>(value)?, 1000);
+    assert_eq!(alloc::limit::get(), usize::MAX);
+    Ok(())
+}
+
+#[test]
+fn test_memory_limit_nested() -> Result<()> {
+    let mut vm = vm()?;
+
+    // Closures called from native functions run in nested executions, which
+    // allocate against the limit of the execution that called them.
+    let mut execution = vm.execute(["nested"], (100_000,))?.with_memory_limit(1024);
+    let error = execution.complete().into_result().unwrap_err();
+    assert!(error.is_memory_limit_exceeded());
+
+    let mut execution = vm.execute(["nested"], (10,))?.with_memory_limit(1024);
+    let value = execution.complete().into_result()?;
+    assert_eq!(from_value::<Vec<i64>>(value)?, [10]);
+    assert_eq!(alloc::limit::get(), usize::MAX);
+    Ok(())
+}
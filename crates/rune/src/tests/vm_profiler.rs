@@ -0,0 +1,156 @@
+prelude!();
+
+use crate::no_std::sync::Arc;
+use crate::runtime::profiler::{Metric, Profiler};
+
+const SOURCE: &str = r#"fn fib(n) {
+    if n < 2 {
+        n
+    } else {
+        fib(n - 1) + fib(n - 2)
+    }
+}
+
+pub fn main() {
+    let out = [];
+
+    for n in 0..4 {
+        out.push(fib(n));
+    }
+
+    out
+}
+"#;
+
+fn profile() -> Result<(Profiler, Vec<i64>)> {
+    let context = Context::with_default_modules()?;
+    let runtime = Arc::new(context.runtime());
+
+    let mut sources = Sources::new();
+    sources.insert(Source::new("test", SOURCE));
+
+    let unit = Arc::new(prepare(&mut sources).with_context(&context).build()?);
+    let mut profiler = Profiler::new(unit.clone(), &sources).with_context(&context);
+
+    let mut vm = Vm::new(runtime, unit);
+    let value = vm.profile(["main"], (), &mut profiler)?;
+    Ok((profiler, from_value(value)?))
+}
+
+#[test]
+fn test_profile_functions() -> Result<()> {
+    let (profiler, value) = profile()?;
+    assert_eq!(value, [0, 1, 1, 2]);
+
+    let total = profiler.total();
+    let functions = profiler.functions();
+
+    let names = functions.iter().map(|f| f.name).collect::<Vec<_>>();
+    assert!(names.contains(&"main"));
+    assert!(names.contains(&"fib"));
+    assert!(names.contains(&"std::vec::Vec::push"));
+    assert!(names.contains(&"Iterator::next"));
+
+    let main = functions.iter().find(|f| f.name == "main").unwrap();
+    assert_eq!(main.inclusive, total);
+
+    let exclusive = functions
+        .iter()
+        .map(|f| f.exclusive.instructions)
+        .sum::<u64>();
+    assert_eq!(exclusive, total.instructions);
+
+    for f in &functions {
+        assert!(f.exclusive.instructions <= f.inclusive.instructions);
+    }
+
+    let push = functions
+        .iter()
+        .find(|f| f.name == "std::vec::Vec::push")
+        .unwrap();
+    assert_eq!(push.exclusive.instructions, 4);
+    Ok(())
+}
+
+#[test]
+fn test_profile_lines() -> Result<()> {
+    let (profiler, _) = profile()?;
+
+    let lines = profiler.lines();
+    let names = lines.iter().map(|l| l.name).collect::<Vec<_>>();
+
+    assert!(names.contains(&"test:2"));
+    assert!(names.contains(&"test:13"));
+    assert!(!names.contains(&"test:8"));
+
+    let instructions = lines.iter().map(|l| l.exclusive.instructions).sum::<u64>();
+    assert_eq!(instructions, profiler.total().instructions);
+    Ok(())
+}
+
+#[test]
+fn test_profile_folded() -> Result<()> {
+    let (profiler, _) = profile()?;
+
+    let mut folded = Vec::new();
+    profiler.write_folded(&mut folded, Metric::Instructions)?;
+    let folded = String::from_utf8(folded)?;
+
+    let mut stacks = Vec::new();
+
+    for line in folded.lines() {
+        let (stack, weight) = line.rsplit_once(' ').unwrap();
+        assert!(weight.parse::<u64>()? > 0);
+        stacks.push(stack);
+    }
+
+    assert!(stacks.contains(&"main"));
+    assert!(stacks.contains(&"main;fib"));
+    assert!(stacks.contains(&"main;fib;fib"));
+    assert!(stacks.contains(&"main;std::vec::Vec::push"));
+    Ok(())
+}
+
+#[test]
+fn test_profile_closure() -> Result<()> {
+    let context = Context::with_default_modules()?;
+    let runtime = Arc::new(context.runtime());
+
+    let mut sources = Sources::new();
+    sources.insert(Source::new(
+        "test",
+        r#"fn square(n) {
+    n * n
+}
+
+pub fn main() {
+    [1, 2, 3].iter().map(|n| square(n)).collect::<Vec>()
+}
+"#,
+    ));
+
+    let unit = Arc::new(prepare(&mut sources).with_context(&context).build()?);
+    let mut profiler = Profiler::new(unit.clone(), &sources).with_context(&context);
+
+    let mut vm = Vm::new(runtime, unit);
+    let value = vm.profile(["main"], (), &mut profiler)?;
+    assert_eq!(from_value::<Vec<i64>>(value)?, [1, 4, 9]);
+
+    let mut folded = Vec::new();
+    profiler.write_folded(&mut folded, Metric::Instructions)?;
+    let folded = String::from_utf8(folded)?;
+
+    // The closure is called by `collect`, and calls `square` in turn.
+    assert!(folded.lines().any(|line| {
+        line.starts_with("main;std::iter::Iterator::collect;") && line.ends_with(";square 9")
+    }));
+
+    let total = profiler.total();
+    let main = profiler
+        .functions()
+        .into_iter()
+        .find(|f| f.name == "main")
+        .unwrap();
+    assert_eq!(main.inclusive, total);
+    Ok(())
+}
@@ -63,3 +63,44 @@ fn test_resume() {
     };
     assert_eq!(out, 6);
 }
+
+#[test]
+fn test_native_stream() {
+    fn numbers(n: i64) -> runtime::Stream<Vm> {
+        runtime::Stream::from_stream(futures_util::stream::iter((1..=n).map(VmResult::Ok)))
+    }
+
+    let mut module = Module::new();
+    module.function(["numbers"], numbers).unwrap();
+
+    let out: i64 = rune_n! {
+        &module,
+        (),
+        i64 =>
+        use std::ops::GeneratorState;
+
+        pub async fn main() {
+            let stream = numbers(3);
+            let result = 0;
+
+            while let Some(value) = stream.next().await {
+                result += value;
+            }
+
+            let stream = numbers(1);
+
+            // Values passed to a native stream are ignored.
+            if let GeneratorState::Yielded(value) = stream.resume(10).await {
+                result += value;
+            }
+
+            if let GeneratorState::Complete(()) = stream.resume(()).await {
+                result += 100;
+            }
+
+            result
+        }
+    };
+
+    assert_eq!(out, 107);
+}
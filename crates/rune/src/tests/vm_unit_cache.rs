@@ -0,0 +1,173 @@
+prelude!();
+
+use std::collections::HashMap;
+use std::io;
+use std::path::Path;
+
+use crate::ast::Spanned;
+use crate::build::prepare_with;
+use crate::compile::{SourceLoader, UnitCache};
+use crate::no_std::sync::Arc;
+use crate::runtime::unit::{ArrayUnit, UnitEncoder};
+use crate::BuildError;
+
+#[derive(Default)]
+struct MemoryCache {
+    entries: HashMap<Hash, Vec<u8>>,
+    hits: usize,
+}
+
+impl UnitCache for MemoryCache {
+    fn load(&mut self, key: Hash) -> io::Result<Option<Vec<u8>>> {
+        let entry = self.entries.get(&key).cloned();
+        self.hits += usize::from(entry.is_some());
+        Ok(entry)
+    }
+
+    fn store(&mut self, key: Hash, entry: &[u8]) -> io::Result<()> {
+        self.entries.insert(key, entry.to_vec());
+        Ok(())
+    }
+}
+
+/// A source loader which serves the `foo` module from memory.
+struct MemoryLoader {
+    foo: &'static str,
+}
+
+impl SourceLoader for MemoryLoader {
+    fn load(&mut self, _: &Path, _: &Item, _: &dyn Spanned) -> compile::Result<Source> {
+        Ok(Source::with_path("foo", self.foo, "foo.rn"))
+    }
+}
+
+fn build(cache: &mut MemoryCache, main: &str, foo: &'static str) -> Result<(Sources, crate::Unit)> {
+    let context = Context::with_default_modules()?;
+
+    let mut sources = Sources::new();
+    sources.insert(Source::with_path("main", main, "main.rn"));
+
+    let mut loader = MemoryLoader { foo };
+
+    let unit = prepare(&mut sources)
+        .with_context(&context)
+        .with_source_loader(&mut loader)
+        .with_cache(cache)
+        .build()?;
+
+    Ok((sources, unit))
+}
+
+fn call(unit: crate::Unit) -> Result<i64> {
+    let context = Context::with_default_modules()?;
+    let mut vm = Vm::new(Arc::new(context.runtime()), Arc::new(unit));
+    Ok(from_value(vm.call(["main"], ())?)?)
+}
+
+const MAIN: &str = "mod foo; pub fn main() { foo::value() }";
+
+#[test]
+fn test_cache_hit() -> Result<()> {
+    let mut cache = MemoryCache::default();
+
+    let (_, unit) = build(&mut cache, MAIN, "pub fn value() { 1 }")?;
+    assert_eq!(call(unit)?, 1);
+    assert_eq!(cache.hits, 0);
+
+    let (sources, unit) = build(&mut cache, MAIN, "pub fn value() { 1 }")?;
+    assert_eq!(call(unit)?, 1);
+    assert_eq!(cache.hits, 1);
+    assert_eq!(cache.entries.len(), 1);
+
+    // Loaded modules are added to the sources on a hit.
+    assert_eq!(sources.iter().count(), 2);
+    Ok(())
+}
+
+#[test]
+fn test_cache_source_changed() -> Result<()> {
+    let mut cache = MemoryCache::default();
+
+    let (_, unit) = build(&mut cache, MAIN, "pub fn value() { 1 }")?;
+    assert_eq!(call(unit)?, 1);
+
+    let main = "mod foo; pub fn main() { foo::value() + 1 }";
+    let (_, unit) = build(&mut cache, main, "pub fn value() { 1 }")?;
+    assert_eq!(call(unit)?, 2);
+    assert_eq!(cache.hits, 0);
+    assert_eq!(cache.entries.len(), 2);
+    Ok(())
+}
+
+#[test]
+fn test_cache_module_changed() -> Result<()> {
+    let mut cache = MemoryCache::default();
+
+    let (_, unit) = build(&mut cache, MAIN, "pub fn value() { 1 }")?;
+    assert_eq!(call(unit)?, 1);
+
+    let (_, unit) = build(&mut cache, MAIN, "pub fn value() { 2 }")?;
+    assert_eq!(call(unit)?, 2);
+
+    // The entry is found, but is rejected since `foo` changed.
+    assert_eq!(cache.hits, 1);
+    assert_eq!(cache.entries.len(), 1);
+
+    let (_, unit) = build(&mut cache, MAIN, "pub fn value() { 2 }")?;
+    assert_eq!(call(unit)?, 2);
+    assert_eq!(cache.hits, 2);
+    Ok(())
+}
+
+#[test]
+fn test_cache_constant_changed() -> Result<()> {
+    fn build(cache: &mut MemoryCache, limit: i64) -> Result<i64> {
+        let mut module = Module::with_crate("host");
+        module.constant(["LIMIT"], limit)?;
+
+        let mut context = Context::with_default_modules()?;
+        context.install(module)?;
+
+        let mut sources = Sources::new();
+        sources.insert(Source::new("main", "pub fn main() { host::LIMIT }"));
+
+        let unit = prepare(&mut sources)
+            .with_context(&context)
+            .with_cache(cache)
+            .build()?;
+
+        let mut vm = Vm::new(Arc::new(context.runtime()), Arc::new(unit));
+        Ok(from_value(vm.call(["main"], ())?)?)
+    }
+
+    let mut cache = MemoryCache::default();
+    assert_eq!(build(&mut cache, 1)?, 1);
+
+    // Constants are inlined, so changing the value of one can't use the
+    // unit which was compiled with the old value.
+    assert_eq!(build(&mut cache, 2)?, 2);
+    assert_eq!(cache.hits, 0);
+    assert_eq!(cache.entries.len(), 2);
+
+    assert_eq!(build(&mut cache, 2)?, 2);
+    assert_eq!(cache.hits, 1);
+    Ok(())
+}
+
+/// Building without a cache only requires the bounds needed to compile.
+fn build_with<S>(sources: &mut Sources) -> Result<crate::Unit<S>, BuildError>
+where
+    S: Default + UnitEncoder,
+{
+    prepare_with(sources).build()
+}
+
+#[test]
+fn test_build_without_cache() -> Result<()> {
+    let mut sources = Sources::new();
+    sources.insert(Source::new("main", "pub fn main() { 42 }"));
+
+    let unit = build_with::<ArrayUnit>(&mut sources)?;
+    assert_eq!(call(unit)?, 42);
+    Ok(())
+}
@@ -2,17 +2,19 @@
 
 use crate::no_std::prelude::*;
 
-use crate::no_std::collections::HashMap;
 use crate::no_std::collections::VecDeque;
+use crate::no_std::collections::{HashMap, HashSet};
+use crate::no_std::rc::Rc;
 
 use crate::ast;
 use crate::ast::Span;
-use crate::compile::{self, ModId};
+use crate::compile::{self, meta, Location, ModId, WithSpan};
 use crate::indexing::index;
 use crate::indexing::items::Items;
 use crate::indexing::{IndexItem, Indexer, Scopes};
-use crate::query::{GenericsParameters, Query, Used};
-use crate::SourceId;
+use crate::parse::Resolve;
+use crate::query::{GenericsParameters, Query, QueryImplFn, QueryTrait, Used};
+use crate::{Hash, SourceId};
 
 mod import;
 mod task;
@@ -199,6 +201,28 @@ impl<'a, 'arena> Worker<'a, 'arena> {
                     // point.
                     self.q.inner.items.insert(meta.item_meta.id, meta.item_meta);
 
+                    let removed = self
+                        .q
+                        .inner
+                        .impl_functions
+                        .remove(&entry.id)
+                        .unwrap_or_default();
+
+                    let query_trait = match &entry.trait_path {
+                        Some(trait_path) => Some(self.lookup_trait(&entry.location, trait_path)?),
+                        None => None,
+                    };
+
+                    let defaults = match &query_trait {
+                        Some(query_trait) => self.check_trait_impl(
+                            entry.location.span,
+                            meta.type_hash_of(),
+                            query_trait,
+                            &removed,
+                        )?,
+                        None => Vec::new(),
+                    };
+
                     let item = self.q.pool.item(meta.item_meta.item);
                     let items = Items::new(item, meta.item_meta.id, self.q.gen);
 
@@ -215,17 +239,39 @@ impl<'a, 'arena> Worker<'a, 'arena> {
                         queue: Some(&mut self.queue),
                     };
 
-                    let removed = idx
-                        .q
-                        .inner
-                        .impl_functions
-                        .remove(&entry.id)
-                        .unwrap_or_default();
-
                     for f in removed {
                         index::item_fn_immediate(&mut idx, *f.ast)?;
                     }
 
+                    // Default functions are indexed as if they were declared
+                    // in the impl block, using the source and module of the
+                    // trait so that names in them resolve where they were
+                    // written.
+                    if let Some(query_trait) = query_trait.filter(|_| !defaults.is_empty()) {
+                        let item = self.q.pool.item(meta.item_meta.item);
+                        let items = Items::new(item, meta.item_meta.id, self.q.gen);
+
+                        let mut idx = Indexer {
+                            q: self.q.borrow(),
+                            root: query_trait.root.clone(),
+                            source_id: query_trait.item_meta.location.source_id,
+                            items,
+                            scopes: Scopes::default(),
+                            item: IndexItem::with_impl_item(
+                                query_trait.item_meta.module,
+                                meta.item_meta.id,
+                            ),
+                            nested_item: None,
+                            macro_depth: 0,
+                            loaded: Some(&mut self.loaded),
+                            queue: Some(&mut self.queue),
+                        };
+
+                        for f in defaults {
+                            index::item_fn_immediate(&mut idx, f)?;
+                        }
+                    }
+
                     Ok::<_, compile::Error>(())
                 };
 
@@ -237,6 +283,129 @@ impl<'a, 'arena> Worker<'a, 'arena> {
     }
 }
 
+impl Worker<'_, '_> {
+    /// Look up the trait referenced by an impl block.
+    fn lookup_trait(
+        &mut self,
+        location: &Location,
+        path: &ast::Path,
+    ) -> compile::Result<Rc<QueryTrait>> {
+        let named = self
+            .q
+            .convert_path_with(path, true, Used::Used, Used::Used)?;
+
+        if let Some((spanned, _)) = named.parameters.into_iter().flatten().next() {
+            return Err(compile::Error::new(
+                spanned.span(),
+                compile::ErrorKind::UnsupportedGenerics,
+            ));
+        }
+
+        let meta = self
+            .q
+            .lookup_meta(location, named.item, GenericsParameters::default())?;
+
+        let meta::Kind::Trait { id } = meta.kind else {
+            return Err(compile::Error::expected_meta(
+                path,
+                meta.info(self.q.pool),
+                "trait",
+            ));
+        };
+
+        Ok(self.q.trait_for(id).with_span(path)?)
+    }
+
+    /// Check that the functions of an impl block match the trait it
+    /// implements, returning the default functions which need to be added to
+    /// the implementation.
+    fn check_trait_impl(
+        &self,
+        span: Span,
+        type_hash: Option<Hash>,
+        query_trait: &QueryTrait,
+        functions: &[QueryImplFn],
+    ) -> compile::Result<Vec<ast::ItemFn>> {
+        let cx = resolve_context!(self.q);
+        let trait_item = self.q.pool.item(query_trait.item_meta.item);
+
+        let mut declared = HashMap::new();
+
+        for item in &query_trait.ast.items {
+            declared.insert(item.name().resolve(cx)?, item);
+        }
+
+        let mut implemented = HashSet::new();
+
+        for f in functions {
+            let name = f.ast.name.resolve(cx)?;
+
+            let Some(item) = declared.get(name) else {
+                return Err(compile::Error::new(
+                    &f.ast.name,
+                    compile::ErrorKind::NotTraitFunction {
+                        name: name.into(),
+                        trait_item: trait_item.to_owned(),
+                    },
+                ));
+            };
+
+            if item.args().len() != f.ast.args.len() {
+                return Err(compile::Error::new(
+                    &f.ast.args,
+                    compile::ErrorKind::TraitFunctionArguments {
+                        name: name.into(),
+                        trait_item: trait_item.to_owned(),
+                        expected: item.args().len(),
+                        actual: f.ast.args.len(),
+                    },
+                ));
+            }
+
+            implemented.insert(name);
+        }
+
+        let mut defaults = Vec::new();
+
+        for item in &query_trait.ast.items {
+            let name = item.name().resolve(cx)?;
+
+            if implemented.contains(name) {
+                continue;
+            }
+
+            // Native types might already provide the function.
+            let is_native = type_hash.is_some_and(|hash| {
+                self.q
+                    .context
+                    .lookup_function(Hash::associated_function(hash, name))
+                    .is_some()
+            });
+
+            if is_native {
+                continue;
+            }
+
+            match item {
+                ast::TraitItem::Required(..) => {
+                    return Err(compile::Error::new(
+                        span,
+                        compile::ErrorKind::MissingTraitFunction {
+                            name: name.into(),
+                            trait_item: trait_item.to_owned(),
+                        },
+                    ));
+                }
+                ast::TraitItem::Provided(f) => {
+                    defaults.push(f.clone());
+                }
+            }
+        }
+
+        Ok(defaults)
+    }
+}
+
 #[derive(Debug, Clone, Copy)]
 pub(crate) enum ImportKind {
     /// The import is in-place.
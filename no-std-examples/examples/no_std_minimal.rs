@@ -31,6 +31,8 @@ use rune::no_std::RawEnv;
 use rune::{Diagnostics, Vm};
 
 static mut BUDGET: usize = usize::MAX;
+static mut MEMORY: usize = usize::MAX;
+static mut MEMORY_LIMIT: usize = usize::MAX;
 static mut RAW_ENV: RawEnv = RawEnv::null();
 
 /// Necessary hook to abort the current process.
@@ -64,6 +66,30 @@ extern "C" fn __rune_budget_get() -> usize {
     unsafe { BUDGET }
 }
 
+#[no_mangle]
+extern "C" fn __rune_alloc_memory_replace(value: usize) -> usize {
+    // SAFETY: this is only ever executed in a singlethreaded environment.
+    unsafe { replace(&mut MEMORY, value) }
+}
+
+#[no_mangle]
+extern "C" fn __rune_alloc_memory_get() -> usize {
+    // SAFETY: this is only ever executed in a singlethreaded environment.
+    unsafe { MEMORY }
+}
+
+#[no_mangle]
+extern "C" fn __rune_alloc_memory_limit_replace(value: usize) -> usize {
+    // SAFETY: this is only ever executed in a singlethreaded environment.
+    unsafe { replace(&mut MEMORY_LIMIT, value) }
+}
+
+#[no_mangle]
+extern "C" fn __rune_alloc_memory_limit_get() -> usize {
+    // SAFETY: this is only ever executed in a singlethreaded environment.
+    unsafe { MEMORY_LIMIT }
+}
+
 #[no_mangle]
 extern "C" fn __rune_env_get() -> RawEnv {
     // SAFETY: this is only ever executed in a singlethreaded environment.